@@ -0,0 +1,804 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use jility_core::policy::ProjectPolicy;
+use jility_core::search::{SearchFilters, SearchService};
+use jility_core::workflow::{self, Workflow};
+use jility_core::{
+    comment, project, sprint, sprint_ticket, ticket, ticket_assignee, ticket_change, ticket_label,
+    user, workspace, workspace_member, ChangeType, Comment, DatabaseConfig,
+    Project as ProjectEntity, Sprint as SprintEntity, SprintStatus, SprintTicket,
+    Ticket as TicketEntity, TicketAssignee, TicketLabel, User, Workspace,
+    WorkspaceRole,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::{
+    Backend, Comment as CommentView, NewSprint, NewTicket, Project, SearchHit, Sprint, Ticket,
+    TicketDetail, TicketEdit, TicketFilter,
+};
+
+/// Reads and writes `.jility/data.db` directly
+pub struct LocalBackend {
+    db: Arc<DatabaseConnection>,
+    project: Project,
+    actor: String,
+}
+
+async fn open_database(path: &Path) -> Result<DatabaseConnection> {
+    let db = jility_core::connect(&DatabaseConfig::sqlite(path.display().to_string()))
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    jility_core::run_migrations(&db).await?;
+    Ok(db)
+}
+
+impl LocalBackend {
+    /// Open the database at `path` and select `project` (a key or ID), or the
+    /// only project if none is given
+    pub async fn open(path: &Path, project: Option<&str>, actor: String) -> Result<Self> {
+        if !path.exists() {
+            bail!(
+                "No local database at {}. Run `jility init` first",
+                path.display()
+            );
+        }
+        let db = open_database(path).await?;
+
+        let projects = ProjectEntity::find()
+            .order_by_asc(project::Column::CreatedAt)
+            .all(&db)
+            .await?;
+        let project = match project {
+            Some(wanted) => projects
+                .into_iter()
+                .find(|p| {
+                    p.id.to_string() == wanted
+                        || p.key.as_deref().is_some_and(|k| k.eq_ignore_ascii_case(wanted))
+                })
+                .ok_or_else(|| anyhow!("Project not found: {}", wanted))?,
+            None => match projects.len() {
+                1 => projects.into_iter().next().unwrap(),
+                0 => bail!("No projects found. Run `jility init` first"),
+                _ => bail!("Several projects found; choose one with --project <KEY>"),
+            },
+        };
+
+        Ok(Self {
+            db: Arc::new(db),
+            project: project.into(),
+            actor,
+        })
+    }
+
+    /// Create the database at `path` with a local user, workspace and a
+    /// project keyed `key`. Existing data is kept.
+    pub async fn init(path: &Path, key: &str, name: &str, actor: &str) -> Result<Project> {
+        let db = open_database(path).await?;
+
+        if let Some(existing) = ProjectEntity::find()
+            .filter(project::Column::Key.eq(key))
+            .one(&db)
+            .await?
+        {
+            return Ok(existing.into());
+        }
+
+        let now = Utc::now();
+        let txn = db.begin().await?;
+
+        let user = match User::find()
+            .filter(user::Column::Username.eq(actor))
+            .one(&txn)
+            .await?
+        {
+            Some(user) => user,
+            None => {
+                user::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    email: Set(format!("{}@localhost", actor)),
+                    username: Set(actor.to_string()),
+                    // Local users never log in
+                    password_hash: Set("!".to_string()),
+                    full_name: Set(None),
+                    avatar_url: Set(None),
+                    is_active: Set(true),
+                    is_verified: Set(false),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                    last_login_at: Set(None),
+                }
+                .insert(&txn)
+                .await?
+            }
+        };
+
+        let workspace = match Workspace::find().one(&txn).await? {
+            Some(workspace) => workspace,
+            None => {
+                let workspace = workspace::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    name: Set("Local".to_string()),
+                    slug: Set(jility_core::slug::generate_slug("local")),
+                    created_by_user_id: Set(user.id),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                }
+                .insert(&txn)
+                .await?;
+                workspace_member::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    workspace_id: Set(workspace.id),
+                    user_id: Set(user.id),
+                    role: Set(WorkspaceRole::Admin),
+                    invited_by_user_id: Set(None),
+                    invited_at: Set(None),
+                    joined_at: Set(now.into()),
+                }
+                .insert(&txn)
+                .await?;
+                workspace
+            }
+        };
+
+        let project = project::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            workspace_id: Set(workspace.id),
+            name: Set(name.to_string()),
+            description: Set(None),
+            key: Set(Some(key.to_string())),
+            color: Set(None),
+            ai_planning_enabled: Set(false),
+            auto_link_git: Set(false),
+            require_story_points: Set(false),
+            ticket_sequence: Set(0),
+            git_webhook_secret: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok(project.into())
+    }
+
+    fn number(&self, ticket: &ticket::Model) -> String {
+        format!("{}-{}", self.project.prefix(), ticket.ticket_number)
+    }
+
+    /// Find a ticket in this project by number (`JIL-42`) or UUID
+    async fn resolve(&self, id: &str) -> Result<ticket::Model> {
+        let query = TicketEntity::find()
+            .filter(ticket::Column::ProjectId.eq(self.project.id))
+            .filter(ticket::Column::DeletedAt.is_null());
+
+        let query = match Uuid::parse_str(id) {
+            Ok(uuid) => query.filter(ticket::Column::Id.eq(uuid)),
+            Err(_) => {
+                let number = id
+                    .rsplit_once('-')
+                    .filter(|(prefix, _)| prefix.eq_ignore_ascii_case(self.project.prefix()))
+                    .and_then(|(_, n)| n.parse::<i32>().ok())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Invalid ticket '{}': expected {}-<number>",
+                            id,
+                            self.project.prefix()
+                        )
+                    })?;
+                query.filter(ticket::Column::TicketNumber.eq(number))
+            }
+        };
+
+        query
+            .one(self.db.as_ref())
+            .await?
+            .ok_or_else(|| anyhow!("Ticket not found: {}", id))
+    }
+
+    /// Attach assignees and labels, loading them for all tickets at once
+    async fn to_tickets(&self, models: Vec<ticket::Model>) -> Result<Vec<Ticket>> {
+        let ids: Vec<Uuid> = models.iter().map(|t| t.id).collect();
+
+        let mut assignees: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for row in TicketAssignee::find()
+            .filter(ticket_assignee::Column::TicketId.is_in(ids.clone()))
+            .order_by_asc(ticket_assignee::Column::AssignedAt)
+            .all(self.db.as_ref())
+            .await?
+        {
+            assignees.entry(row.ticket_id).or_default().push(row.assignee);
+        }
+
+        let mut labels: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for row in TicketLabel::find()
+            .filter(ticket_label::Column::TicketId.is_in(ids))
+            .order_by_asc(ticket_label::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await?
+        {
+            labels.entry(row.ticket_id).or_default().push(row.label);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|t| Ticket {
+                number: self.number(&t),
+                assignees: assignees.remove(&t.id).unwrap_or_default(),
+                labels: labels.remove(&t.id).unwrap_or_default(),
+                id: t.id,
+                title: t.title,
+                description: t.description,
+                status: t.status,
+                story_points: t.story_points,
+                created_by: t.created_by,
+                created_at: t.created_at,
+                updated_at: t.updated_at,
+            })
+            .collect())
+    }
+
+    async fn to_ticket(&self, model: ticket::Model) -> Result<Ticket> {
+        Ok(self.to_tickets(vec![model]).await?.remove(0))
+    }
+
+    async fn record_change<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        ticket_id: Uuid,
+        change_type: ChangeType,
+        field_name: Option<&str>,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Result<()> {
+        ticket_change::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            ticket_id: Set(ticket_id),
+            change_type: Set(change_type.as_str().to_string()),
+            field_name: Set(field_name.map(str::to_string)),
+            old_value: Set(old_value),
+            new_value: Set(new_value),
+            changed_by: Set(self.actor.clone()),
+            changed_at: Set(Utc::now()),
+            message: Set(None),
+        }
+        .insert(db)
+        .await?;
+        Ok(())
+    }
+
+    async fn sprint(&self, sprint_id: Uuid) -> Result<sprint::Model> {
+        SprintEntity::find_by_id(sprint_id)
+            .filter(sprint::Column::ProjectId.eq(self.project.id))
+            .one(self.db.as_ref())
+            .await?
+            .ok_or_else(|| anyhow!("Sprint not found: {}", sprint_id))
+    }
+
+    async fn workflow(&self) -> Result<Workflow> {
+        Ok(workflow::load(self.db.as_ref(), self.project.id).await?)
+    }
+
+    async fn set_sprint_status(
+        &self,
+        sprint: sprint::Model,
+        status: SprintStatus,
+        dates: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ) -> Result<Sprint> {
+        let mut active: sprint::ActiveModel = sprint.into();
+        active.status = Set(status.as_str().to_string());
+        if let Some((start, end)) = dates {
+            active.start_date = Set(Some(start));
+            active.end_date = Set(Some(end));
+        }
+        active.updated_at = Set(Utc::now());
+        Ok(active.update(self.db.as_ref()).await?.into())
+    }
+}
+
+#[async_trait]
+impl Backend for LocalBackend {
+    fn project(&self) -> &Project {
+        &self.project
+    }
+
+    async fn create_ticket(&self, new: NewTicket) -> Result<Ticket> {
+        let workflow = self.workflow().await?;
+        let status = match new.status {
+            Some(status) => {
+                workflow.check_status(&status)?;
+                status
+            }
+            None => workflow.initial_status.clone(),
+        };
+        ProjectPolicy::load(self.db.as_ref(), self.project.id)
+            .await?
+            .check_status_change(&workflow, None, &status, new.story_points)?;
+
+        let now = Utc::now();
+        let txn = self.db.begin().await?;
+
+        let ticket_number = project::next_ticket_number(&txn, self.project.id).await?;
+
+        let ticket = ticket::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            project_id: Set(self.project.id),
+            ticket_number: Set(ticket_number),
+            title: Set(new.title),
+            description: Set(new.description.unwrap_or_default()),
+            status: Set(status),
+            story_points: Set(new.story_points),
+            epic_id: Set(None),
+            parent_id: Set(None),
+            is_epic: Set(false),
+            epic_color: Set(None),
+            parent_epic_id: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+            created_by: Set(self.actor.clone()),
+        }
+        .insert(&txn)
+        .await?;
+
+        self.record_change(
+            &txn,
+            ticket.id,
+            ChangeType::Created,
+            None,
+            None,
+            Some(serde_json::to_string(&ticket)?),
+        )
+        .await?;
+
+        for assignee in new.assignees {
+            ticket_assignee::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                ticket_id: Set(ticket.id),
+                assignee: Set(assignee.clone()),
+                assigned_at: Set(now),
+                assigned_by: Set(self.actor.clone()),
+            }
+            .insert(&txn)
+            .await?;
+            self.record_change(
+                &txn,
+                ticket.id,
+                ChangeType::AssigneeAdded,
+                Some("assignee"),
+                None,
+                Some(assignee),
+            )
+            .await?;
+        }
+
+        for label in new.labels {
+            ticket_label::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                ticket_id: Set(ticket.id),
+                label: Set(label.clone()),
+                created_at: Set(now),
+            }
+            .insert(&txn)
+            .await?;
+            self.record_change(
+                &txn,
+                ticket.id,
+                ChangeType::LabelAdded,
+                Some("label"),
+                None,
+                Some(label),
+            )
+            .await?;
+        }
+
+        txn.commit().await?;
+        self.to_ticket(ticket).await
+    }
+
+    async fn list_tickets(&self, filter: TicketFilter) -> Result<Vec<Ticket>> {
+        let mut query = TicketEntity::find()
+            .filter(ticket::Column::ProjectId.eq(self.project.id))
+            .filter(ticket::Column::DeletedAt.is_null())
+            .order_by_asc(ticket::Column::TicketNumber);
+
+        if !filter.status.is_empty() {
+            let workflow = self.workflow().await?;
+            for status in &filter.status {
+                workflow.check_status(status)?;
+            }
+            query = query.filter(ticket::Column::Status.is_in(filter.status));
+        }
+
+        if let Some(assignee) = filter.assignee {
+            let ids: Vec<Uuid> = TicketAssignee::find()
+                .filter(ticket_assignee::Column::Assignee.eq(assignee))
+                .all(self.db.as_ref())
+                .await?
+                .into_iter()
+                .map(|row| row.ticket_id)
+                .collect();
+            query = query.filter(ticket::Column::Id.is_in(ids));
+        }
+
+        let models = query.all(self.db.as_ref()).await?;
+        self.to_tickets(models).await
+    }
+
+    async fn get_ticket(&self, id: &str) -> Result<TicketDetail> {
+        let model = self.resolve(id).await?;
+        let comments = Comment::find()
+            .filter(comment::Column::TicketId.eq(model.id))
+            .order_by_asc(comment::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await?
+            .into_iter()
+            .map(CommentView::from)
+            .collect();
+
+        Ok(TicketDetail {
+            ticket: self.to_ticket(model).await?,
+            comments,
+        })
+    }
+
+    async fn edit_ticket(&self, id: &str, edit: TicketEdit) -> Result<Ticket> {
+        let old = self.resolve(id).await?;
+        let txn = self.db.begin().await?;
+        let mut active: ticket::ActiveModel = old.clone().into();
+
+        if let Some(title) = edit.title.filter(|t| *t != old.title) {
+            self.record_change(
+                &txn,
+                old.id,
+                ChangeType::TitleChanged,
+                Some("title"),
+                Some(old.title.clone()),
+                Some(title.clone()),
+            )
+            .await?;
+            active.title = Set(title);
+        }
+
+        if let Some(description) = edit.description.filter(|d| *d != old.description) {
+            self.record_change(
+                &txn,
+                old.id,
+                ChangeType::DescriptionChanged,
+                Some("description"),
+                Some(old.description.clone()),
+                Some(description.clone()),
+            )
+            .await?;
+            active.description = Set(description);
+        }
+
+        if let Some(points) = edit.story_points.filter(|p| Some(*p) != old.story_points) {
+            self.record_change(
+                &txn,
+                old.id,
+                ChangeType::StoryPointsChanged,
+                Some("story_points"),
+                old.story_points.map(|p| p.to_string()),
+                Some(points.to_string()),
+            )
+            .await?;
+            active.story_points = Set(Some(points));
+        }
+
+        let model = if active.is_changed() {
+            active.updated_at = Set(Utc::now());
+            active.update(&txn).await?
+        } else {
+            old
+        };
+
+        txn.commit().await?;
+        self.to_ticket(model).await
+    }
+
+    async fn move_ticket(&self, id: &str, status: &str) -> Result<Ticket> {
+        let old = self.resolve(id).await?;
+        let workflow = self.workflow().await?;
+        workflow.check_transition(&old.status, status)?;
+        if old.status == status {
+            return self.to_ticket(old).await;
+        }
+        ProjectPolicy::load(self.db.as_ref(), self.project.id)
+            .await?
+            .check_status_change(&workflow, Some(&old.status), status, old.story_points)?;
+
+        let txn = self.db.begin().await?;
+        self.record_change(
+            &txn,
+            old.id,
+            ChangeType::StatusChanged,
+            Some("status"),
+            Some(old.status.clone()),
+            Some(status.to_string()),
+        )
+        .await?;
+        let mut active: ticket::ActiveModel = old.into();
+        active.status = Set(status.to_string());
+        active.updated_at = Set(Utc::now());
+        let model = active.update(&txn).await?;
+        txn.commit().await?;
+
+        self.to_ticket(model).await
+    }
+
+    async fn assign_ticket(&self, id: &str, assignee: &str) -> Result<Ticket> {
+        let model = self.resolve(id).await?;
+        let existing = TicketAssignee::find()
+            .filter(ticket_assignee::Column::TicketId.eq(model.id))
+            .filter(ticket_assignee::Column::Assignee.eq(assignee))
+            .one(self.db.as_ref())
+            .await?;
+
+        if existing.is_none() {
+            let txn = self.db.begin().await?;
+            ticket_assignee::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                ticket_id: Set(model.id),
+                assignee: Set(assignee.to_string()),
+                assigned_at: Set(Utc::now()),
+                assigned_by: Set(self.actor.clone()),
+            }
+            .insert(&txn)
+            .await?;
+            self.record_change(
+                &txn,
+                model.id,
+                ChangeType::AssigneeAdded,
+                Some("assignee"),
+                None,
+                Some(assignee.to_string()),
+            )
+            .await?;
+            txn.commit().await?;
+        }
+
+        self.to_ticket(model).await
+    }
+
+    async fn unassign_ticket(&self, id: &str, assignee: &str) -> Result<Ticket> {
+        let model = self.resolve(id).await?;
+        let txn = self.db.begin().await?;
+        let result = TicketAssignee::delete_many()
+            .filter(ticket_assignee::Column::TicketId.eq(model.id))
+            .filter(ticket_assignee::Column::Assignee.eq(assignee))
+            .exec(&txn)
+            .await?;
+        if result.rows_affected == 0 {
+            bail!("{} is not assigned to {}", assignee, self.number(&model));
+        }
+        self.record_change(
+            &txn,
+            model.id,
+            ChangeType::AssigneeRemoved,
+            Some("assignee"),
+            Some(assignee.to_string()),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
+
+        self.to_ticket(model).await
+    }
+
+    async fn add_comment(&self, id: &str, content: &str) -> Result<CommentView> {
+        let model = self.resolve(id).await?;
+        let comment = comment::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            ticket_id: Set(model.id),
+            parent_id: Set(None),
+            author: Set(self.actor.clone()),
+            author_user_id: Set(None),
+            content: Set(content.to_string()),
+            created_at: Set(Utc::now()),
+            updated_at: Set(None),
+            deleted_at: Set(None),
+            deleted_by: Set(None),
+            source_id: Set(None),
+        };
+
+        let txn = self.db.begin().await?;
+        let comment = comment.insert(&txn).await?;
+        self.record_change(
+            &txn,
+            model.id,
+            ChangeType::CommentAdded,
+            Some("comment"),
+            None,
+            Some(comment.id.to_string()),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(comment.into())
+    }
+
+    async fn list_sprints(&self) -> Result<Vec<Sprint>> {
+        Ok(SprintEntity::find()
+            .filter(sprint::Column::ProjectId.eq(self.project.id))
+            .order_by_asc(sprint::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await?
+            .into_iter()
+            .map(Sprint::from)
+            .collect())
+    }
+
+    async fn create_sprint(&self, new: NewSprint) -> Result<Sprint> {
+        let now = Utc::now();
+        let sprint = sprint::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            project_id: Set(self.project.id),
+            name: Set(new.name),
+            goal: Set(new.goal),
+            start_date: Set(None),
+            end_date: Set(None),
+            status: Set(SprintStatus::Planning.as_str().to_string()),
+            capacity: Set(new.capacity),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(self.db.as_ref())
+        .await?;
+
+        Ok(sprint.into())
+    }
+
+    async fn add_to_sprint(&self, sprint_id: Uuid, id: &str) -> Result<()> {
+        let sprint = self.sprint(sprint_id).await?;
+        let model = self.resolve(id).await?;
+
+        let existing = SprintTicket::find()
+            .filter(sprint_ticket::Column::SprintId.eq(sprint.id))
+            .filter(sprint_ticket::Column::TicketId.eq(model.id))
+            .one(self.db.as_ref())
+            .await?;
+        if existing.is_some() {
+            bail!("{} is already in {}", self.number(&model), sprint.name);
+        }
+
+        let txn = self.db.begin().await?;
+        sprint_ticket::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            sprint_id: Set(sprint.id),
+            ticket_id: Set(model.id),
+            added_at: Set(Utc::now()),
+            added_by: Set(self.actor.clone()),
+        }
+        .insert(&txn)
+        .await?;
+        self.record_change(
+            &txn,
+            model.id,
+            ChangeType::AddedToSprint,
+            None,
+            None,
+            Some(sprint.id.to_string()),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn start_sprint(
+        &self,
+        sprint_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Sprint> {
+        let sprint = self.sprint(sprint_id).await?;
+        if sprint.status != SprintStatus::Planning.as_str() {
+            bail!("Sprint has already been started");
+        }
+        self.set_sprint_status(sprint, SprintStatus::Active, Some((start, end)))
+            .await
+    }
+
+    async fn complete_sprint(&self, sprint_id: Uuid) -> Result<Sprint> {
+        let sprint = self.sprint(sprint_id).await?;
+        if sprint.status != SprintStatus::Active.as_str() {
+            bail!("Only active sprints can be completed");
+        }
+        self.set_sprint_status(sprint, SprintStatus::Completed, None)
+            .await
+    }
+
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<SearchHit>> {
+        let filters = SearchFilters {
+            query: query.to_string(),
+            status: None,
+            assignees: None,
+            labels: None,
+            created_by: None,
+            created_after: None,
+            created_before: None,
+            updated_after: None,
+            updated_before: None,
+            min_points: None,
+            max_points: None,
+            has_comments: None,
+            has_commits: None,
+            has_dependencies: None,
+            epic_id: None,
+            parent_id: None,
+            project_id: Some(self.project.id),
+            project_ids: None,
+            search_in: vec![
+                "title".to_string(),
+                "description".to_string(),
+                "comments".to_string(),
+            ],
+        };
+
+        let response = SearchService::new(self.db.clone())
+            .search_tickets(filters, Some(limit), None)
+            .await?;
+
+        Ok(response
+            .tickets
+            .into_iter()
+            .map(|t| SearchHit {
+                number: format!("{}-{}", self.project.prefix(), t.ticket_number),
+                title: t.title,
+                status: t.status,
+            })
+            .collect())
+    }
+}
+
+impl From<project::Model> for Project {
+    fn from(project: project::Model) -> Self {
+        Self {
+            id: project.id,
+            name: project.name,
+            key: project.key,
+        }
+    }
+}
+
+impl From<comment::Model> for CommentView {
+    fn from(comment: comment::Model) -> Self {
+        Self {
+            id: comment.id,
+            parent_id: comment.parent_id,
+            author: comment.author,
+            // Removed comments are tombstones
+            content: if comment.deleted_at.is_some() {
+                String::new()
+            } else {
+                comment.content
+            },
+            created_at: comment.created_at,
+            deleted_at: comment.deleted_at,
+        }
+    }
+}
+
+impl From<sprint::Model> for Sprint {
+    fn from(sprint: sprint::Model) -> Self {
+        Self {
+            id: sprint.id,
+            name: sprint.name,
+            goal: sprint.goal,
+            status: sprint.status,
+            start_date: sprint.start_date,
+            end_date: sprint.end_date,
+            capacity: sprint.capacity,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Where the CLI reads and writes tickets
+//!
+//! [`LocalBackend`] talks to `.jility/data.db` directly through `jility-core`;
+//! [`RemoteBackend`] goes through a Jility server's REST API. Both return the
+//! same types, shaped like the server's JSON responses so remote payloads
+//! deserialize into them directly.
+
+mod local;
+mod remote;
+
+pub use local::LocalBackend;
+pub use remote::RemoteBackend;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: Uuid,
+    pub name: String,
+    pub key: Option<String>,
+}
+
+impl Project {
+    /// Prefix of this project's ticket numbers
+    pub fn prefix(&self) -> &str {
+        self.key.as_deref().unwrap_or("TASK")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ticket {
+    pub id: Uuid,
+    /// e.g. "JIL-42"
+    pub number: String,
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    pub story_points: Option<i32>,
+    pub assignees: Vec<String>,
+    pub labels: Vec<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: Uuid,
+    /// The comment this one replies to
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    pub author: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    /// Set on the tombstone of a removed comment
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketDetail {
+    pub ticket: Ticket,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sprint {
+    pub id: Uuid,
+    pub name: String,
+    pub goal: Option<String>,
+    pub status: String,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub capacity: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub number: String,
+    pub title: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NewTicket {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub story_points: Option<i32>,
+    pub assignees: Vec<String>,
+    pub labels: Vec<String>,
+}
+
+/// Fields to change on a ticket; `None` leaves a field untouched
+#[derive(Debug, Clone, Default)]
+pub struct TicketEdit {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub story_points: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TicketFilter {
+    pub status: Vec<String>,
+    pub assignee: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NewSprint {
+    pub name: String,
+    pub goal: Option<String>,
+    pub capacity: Option<i32>,
+}
+
+/// Ticket and sprint operations for the current project. Tickets are
+/// identified by number (`JIL-42`) or UUID.
+#[async_trait]
+pub trait Backend: Send + Sync {
+    fn project(&self) -> &Project;
+
+    async fn create_ticket(&self, ticket: NewTicket) -> Result<Ticket>;
+    async fn list_tickets(&self, filter: TicketFilter) -> Result<Vec<Ticket>>;
+    async fn get_ticket(&self, id: &str) -> Result<TicketDetail>;
+    async fn edit_ticket(&self, id: &str, edit: TicketEdit) -> Result<Ticket>;
+    async fn move_ticket(&self, id: &str, status: &str) -> Result<Ticket>;
+    async fn assign_ticket(&self, id: &str, assignee: &str) -> Result<Ticket>;
+    async fn unassign_ticket(&self, id: &str, assignee: &str) -> Result<Ticket>;
+    async fn add_comment(&self, id: &str, content: &str) -> Result<Comment>;
+
+    async fn list_sprints(&self) -> Result<Vec<Sprint>>;
+    async fn create_sprint(&self, sprint: NewSprint) -> Result<Sprint>;
+    async fn add_to_sprint(&self, sprint_id: Uuid, ticket: &str) -> Result<()>;
+    async fn start_sprint(
+        &self,
+        sprint_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Sprint>;
+    async fn complete_sprint(&self, sprint_id: Uuid) -> Result<Sprint>;
+
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<SearchHit>>;
+}
@@ -0,0 +1,323 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{
+    Backend, Comment, NewSprint, NewTicket, Project, SearchHit, Sprint, Ticket, TicketDetail,
+    TicketEdit, TicketFilter,
+};
+
+/// Talks to a Jility server's REST API
+pub struct RemoteBackend {
+    client: Client,
+    api_base_url: String,
+    auth_token: Option<String>,
+    project: Project,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct TicketPage {
+    tickets: Vec<Ticket>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchBody {
+    tickets: Vec<SearchTicket>,
+}
+
+#[derive(Deserialize)]
+struct SearchTicket {
+    ticket_number: i32,
+    title: String,
+    status: String,
+}
+
+impl RemoteBackend {
+    /// Connect to `api_base_url` and select `project` (a key or ID), or the
+    /// only project visible to the caller if none is given
+    pub async fn connect(
+        api_base_url: String,
+        auth_token: Option<String>,
+        project: Option<&str>,
+    ) -> Result<Self> {
+        let mut backend = Self {
+            client: Client::new(),
+            api_base_url: api_base_url.trim_end_matches('/').to_string(),
+            auth_token,
+            project: Project {
+                id: Uuid::nil(),
+                name: String::new(),
+                key: None,
+            },
+        };
+
+        let projects: Vec<Project> = backend.get("/projects").await?;
+        backend.project = match project {
+            Some(wanted) => projects
+                .into_iter()
+                .find(|p| {
+                    p.id.to_string() == wanted
+                        || p.key.as_deref().is_some_and(|k| k.eq_ignore_ascii_case(wanted))
+                })
+                .ok_or_else(|| anyhow!("Project not found: {}", wanted))?,
+            None => match projects.len() {
+                1 => projects.into_iter().next().unwrap(),
+                0 => bail!("No projects are visible to this account"),
+                _ => bail!("Several projects found; choose one with --project <KEY>"),
+            },
+        };
+
+        Ok(backend)
+    }
+
+    /// Build a request with authentication if available
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.api_base_url, path));
+        if let Some(token) = &self.auth_token {
+            // API keys and JWTs use different schemes
+            let scheme = if token.starts_with("jil_") {
+                "ApiKey"
+            } else {
+                "Bearer"
+            };
+            request = request.header("Authorization", format!("{} {}", scheme, token));
+        }
+        request
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let response = self.check(request).await?;
+        response
+            .json()
+            .await
+            .context("Failed to parse server response")
+    }
+
+    async fn check(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", self.api_base_url))?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<ErrorBody>(&text)
+            .map(|body| body.message)
+            .unwrap_or(text);
+        bail!("Server returned {}: {}", status, message)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.request(Method::GET, path)).await
+    }
+
+    /// The UUID of a ticket given by number or UUID
+    async fn ticket_id(&self, id: &str) -> Result<Uuid> {
+        Ok(self.get_ticket(id).await?.ticket.id)
+    }
+}
+
+#[async_trait]
+impl Backend for RemoteBackend {
+    fn project(&self) -> &Project {
+        &self.project
+    }
+
+    async fn create_ticket(&self, new: NewTicket) -> Result<Ticket> {
+        self.send(self.request(Method::POST, "/tickets").json(&json!({
+            "project_id": self.project.id,
+            "title": new.title,
+            "description": new.description,
+            "status": new.status,
+            "story_points": new.story_points,
+            "assignees": new.assignees,
+            "labels": new.labels,
+        })))
+        .await
+    }
+
+    async fn list_tickets(&self, filter: TicketFilter) -> Result<Vec<Ticket>> {
+        let mut query = vec![
+            ("project_id", self.project.id.to_string()),
+            ("limit", "500".to_string()),
+        ];
+        query.extend(filter.status.into_iter().map(|s| ("status", s)));
+        if let Some(assignee) = filter.assignee {
+            query.push(("assignee", assignee));
+        }
+
+        let mut tickets = Vec::new();
+        let mut cursor = None;
+        loop {
+            let mut request = self.request(Method::GET, "/tickets").query(&query);
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("cursor", cursor)]);
+            }
+            let page: TicketPage = self.send(request).await?;
+            tickets.extend(page.tickets);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(tickets),
+            }
+        }
+    }
+
+    async fn get_ticket(&self, id: &str) -> Result<TicketDetail> {
+        self.get(&format!("/tickets/{}", id)).await
+    }
+
+    async fn edit_ticket(&self, id: &str, edit: TicketEdit) -> Result<Ticket> {
+        let ticket_id = self.ticket_id(id).await?;
+
+        let mut ticket = None;
+        if edit.title.is_some() || edit.story_points.is_some() {
+            ticket = Some(
+                self.send(
+                    self.request(Method::PUT, &format!("/tickets/{}", ticket_id))
+                        .json(&json!({
+                            "title": edit.title,
+                            "story_points": edit.story_points,
+                        })),
+                )
+                .await?,
+            );
+        }
+        if let Some(description) = edit.description {
+            ticket = Some(
+                self.send(
+                    self.request(Method::PATCH, &format!("/tickets/{}/description", ticket_id))
+                        .json(&json!({ "description": description })),
+                )
+                .await?,
+            );
+        }
+
+        match ticket {
+            Some(ticket) => Ok(ticket),
+            None => Ok(self.get_ticket(id).await?.ticket),
+        }
+    }
+
+    async fn move_ticket(&self, id: &str, status: &str) -> Result<Ticket> {
+        let ticket_id = self.ticket_id(id).await?;
+        self.send(
+            self.request(Method::PATCH, &format!("/tickets/{}/status", ticket_id))
+                .json(&json!({ "status": status })),
+        )
+        .await
+    }
+
+    async fn assign_ticket(&self, id: &str, assignee: &str) -> Result<Ticket> {
+        let ticket_id = self.ticket_id(id).await?;
+        self.send(
+            self.request(Method::POST, &format!("/tickets/{}/assign", ticket_id))
+                .json(&json!({ "assignee": assignee })),
+        )
+        .await
+    }
+
+    async fn unassign_ticket(&self, id: &str, assignee: &str) -> Result<Ticket> {
+        let ticket_id = self.ticket_id(id).await?;
+        self.send(
+            self.request(Method::POST, &format!("/tickets/{}/unassign", ticket_id))
+                .json(&json!({ "assignee": assignee })),
+        )
+        .await
+    }
+
+    async fn add_comment(&self, id: &str, content: &str) -> Result<Comment> {
+        let ticket_id = self.ticket_id(id).await?;
+        self.send(
+            self.request(Method::POST, &format!("/tickets/{}/comments", ticket_id))
+                .json(&json!({ "content": content })),
+        )
+        .await
+    }
+
+    async fn list_sprints(&self) -> Result<Vec<Sprint>> {
+        self.get(&format!("/projects/{}/sprints", self.project.id))
+            .await
+    }
+
+    async fn create_sprint(&self, new: NewSprint) -> Result<Sprint> {
+        self.send(
+            self.request(
+                Method::POST,
+                &format!("/projects/{}/sprints", self.project.id),
+            )
+            .json(&json!({
+                "name": new.name,
+                "goal": new.goal,
+                "capacity": new.capacity,
+            })),
+        )
+        .await
+    }
+
+    async fn add_to_sprint(&self, sprint_id: Uuid, ticket: &str) -> Result<()> {
+        self.check(self.request(
+            Method::POST,
+            &format!("/sprints/{}/tickets/{}", sprint_id, ticket),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    async fn start_sprint(
+        &self,
+        sprint_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Sprint> {
+        self.send(
+            self.request(Method::POST, &format!("/sprints/{}/start", sprint_id))
+                .json(&json!({
+                    "start_date": start.to_rfc3339(),
+                    "end_date": end.to_rfc3339(),
+                })),
+        )
+        .await
+    }
+
+    async fn complete_sprint(&self, sprint_id: Uuid) -> Result<Sprint> {
+        self.send(self.request(Method::POST, &format!("/sprints/{}/complete", sprint_id)))
+            .await
+    }
+
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<SearchHit>> {
+        let body: SearchBody = self
+            .send(self.request(Method::GET, "/search").query(&[
+                ("q", query.to_string()),
+                ("project_id", self.project.id.to_string()),
+                ("limit", limit.to_string()),
+            ]))
+            .await?;
+
+        Ok(body
+            .tickets
+            .into_iter()
+            .map(|t| SearchHit {
+                number: format!("{}-{}", self.project.prefix(), t.ticket_number),
+                title: t.title,
+                status: t.status,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use super::GlobalArgs;
+use crate::backend::{Backend, LocalBackend, RemoteBackend};
+use crate::config::{self, Config, Workspace, JILITY_DIR};
+use crate::output::{print_info, print_success};
+
+#[derive(Debug, clap::Args)]
+pub struct InitArgs {
+    /// Project key used as the ticket prefix (e.g. JIL for JIL-1)
+    #[arg(long, default_value = "TASK")]
+    pub key: String,
+
+    /// Project name; defaults to the current directory's name
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+/// Create `.jility/` in the current directory. Without `--server` this also
+/// creates the local database and project; with it, the server project is
+/// checked and remembered.
+pub async fn run(args: InitArgs, global: GlobalArgs) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let key = args.key.to_uppercase();
+    let mut workspace = Workspace::open(cwd.join(JILITY_DIR))?;
+
+    let project = match &global.server {
+        Some(server) => {
+            let project = global.project.as_deref().unwrap_or(&key);
+            let backend =
+                RemoteBackend::connect(server.clone(), global.token.clone(), Some(project)).await?;
+            backend.project().clone()
+        }
+        None => {
+            std::fs::create_dir_all(&workspace.dir)?;
+            let name = args.name.unwrap_or_else(|| {
+                cwd.file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| key.clone())
+            });
+            LocalBackend::init(&workspace.database_path(), &key, &name, &config::local_actor())
+                .await?
+        }
+    };
+
+    workspace.config = Config {
+        project: Some(project.prefix().to_string()),
+        server: global.server,
+    };
+    workspace.save()?;
+
+    print_success(&format!(
+        "Initialized {} for project {} ({})",
+        workspace.dir.display(),
+        project.name.bold(),
+        project.prefix()
+    ));
+    print_info(&format!(
+        "Create your first ticket with `jility ticket new \"Title\"`; tickets will be numbered {}-1, {}-2, …",
+        project.prefix(),
+        project.prefix()
+    ));
+    Ok(())
+}
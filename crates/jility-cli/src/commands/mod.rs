@@ -0,0 +1,60 @@
+pub mod init;
+pub mod search;
+pub mod sprint;
+pub mod ticket;
+
+use anyhow::{bail, Result};
+
+use crate::backend::{Backend, LocalBackend, RemoteBackend};
+use crate::config::{self, Workspace};
+use crate::output::Format;
+
+/// Options shared by every command
+#[derive(Debug, Clone, clap::Args)]
+pub struct GlobalArgs {
+    /// Jility server API URL, e.g. http://localhost:3900/api. Without it the
+    /// local `.jility/` database is used.
+    #[arg(long, env = "JILITY_API_URL", global = true)]
+    pub server: Option<String>,
+
+    /// JWT or API key used to authenticate with the server
+    #[arg(long, env = "JILITY_API_TOKEN", global = true, hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// Project key (e.g. JIL) or ID
+    #[arg(long, env = "JILITY_PROJECT", global = true)]
+    pub project: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Table, global = true)]
+    pub format: Format,
+}
+
+/// Open the backend selected by the flags, falling back to `.jility/config.json`
+pub async fn open_backend(args: &GlobalArgs) -> Result<Box<dyn Backend>> {
+    let cwd = std::env::current_dir()?;
+    let workspace = Workspace::discover(&cwd)?;
+    let config = workspace
+        .as_ref()
+        .map(|w| w.config.clone())
+        .unwrap_or_default();
+
+    let project = args.project.clone().or(config.project);
+    let server = args.server.clone().or(config.server);
+
+    if let Some(server) = server {
+        let backend = RemoteBackend::connect(server, args.token.clone(), project.as_deref()).await?;
+        return Ok(Box::new(backend));
+    }
+
+    let Some(workspace) = workspace else {
+        bail!("No .jility directory found. Run `jility init` or pass --server <URL>");
+    };
+    let backend = LocalBackend::open(
+        &workspace.database_path(),
+        project.as_deref(),
+        config::local_actor(),
+    )
+    .await?;
+    Ok(Box::new(backend))
+}
@@ -0,0 +1,18 @@
+use anyhow::Result;
+
+use crate::backend::Backend;
+use crate::output::{self, Format};
+
+#[derive(Debug, clap::Args)]
+pub struct SearchArgs {
+    /// Words to look for in titles, descriptions and comments
+    pub query: String,
+
+    #[arg(short, long, default_value_t = 20)]
+    pub limit: u64,
+}
+
+pub async fn run(args: SearchArgs, backend: &dyn Backend, format: Format) -> Result<()> {
+    let hits = backend.search(&args.query, args.limit).await?;
+    output::print_search_hits(&hits, format)
+}
@@ -0,0 +1,116 @@
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Utc};
+use clap::Subcommand;
+use uuid::Uuid;
+
+use crate::backend::{Backend, NewSprint, Sprint};
+use crate::output::{self, print_success, Format};
+
+#[derive(Debug, Subcommand)]
+pub enum SprintCommand {
+    /// List sprints in the project
+    List,
+    /// Create a sprint (if it doesn't exist yet) and add tickets to it
+    Plan {
+        /// Sprint name or ID
+        sprint: String,
+        /// Tickets to add, e.g. JIL-1 JIL-2
+        tickets: Vec<String>,
+        #[arg(short, long)]
+        goal: Option<String>,
+        /// Story point capacity
+        #[arg(short, long)]
+        capacity: Option<i32>,
+    },
+    /// Start a planned sprint today
+    Start {
+        /// Sprint name or ID
+        sprint: String,
+        /// Sprint length in days
+        #[arg(long, default_value_t = 14)]
+        days: i64,
+    },
+    /// Complete the active sprint
+    Complete {
+        /// Sprint name or ID
+        sprint: String,
+    },
+}
+
+/// Find a sprint by ID or name, preferring one that isn't completed when
+/// several share a name
+async fn find_sprint(backend: &dyn Backend, sprint: &str) -> Result<Option<Sprint>> {
+    let sprints = backend.list_sprints().await?;
+    if let Ok(id) = Uuid::parse_str(sprint) {
+        return Ok(sprints.into_iter().find(|s| s.id == id));
+    }
+
+    let mut matches: Vec<Sprint> = sprints
+        .into_iter()
+        .filter(|s| s.name.eq_ignore_ascii_case(sprint))
+        .collect();
+    matches.sort_by_key(|s| s.status == "completed");
+    Ok(matches.into_iter().next())
+}
+
+async fn require_sprint(backend: &dyn Backend, sprint: &str) -> Result<Sprint> {
+    find_sprint(backend, sprint)
+        .await?
+        .ok_or_else(|| anyhow!("Sprint not found: {}", sprint))
+}
+
+pub async fn run(command: SprintCommand, backend: &dyn Backend, format: Format) -> Result<()> {
+    match command {
+        SprintCommand::List => {
+            let sprints = backend.list_sprints().await?;
+            output::print_sprints(&sprints, format)
+        }
+        SprintCommand::Plan {
+            sprint,
+            tickets,
+            goal,
+            capacity,
+        } => {
+            let sprint = match find_sprint(backend, &sprint).await? {
+                Some(existing) => existing,
+                None => {
+                    let created = backend
+                        .create_sprint(NewSprint {
+                            name: sprint,
+                            goal,
+                            capacity,
+                        })
+                        .await?;
+                    if format == Format::Table {
+                        print_success(&format!("Created sprint {}", created.name));
+                    }
+                    created
+                }
+            };
+
+            for ticket in &tickets {
+                backend.add_to_sprint(sprint.id, ticket).await?;
+                if format == Format::Table {
+                    print_success(&format!("Added {} to {}", ticket, sprint.name));
+                }
+            }
+            if format == Format::Json {
+                return output::print_json(&sprint);
+            }
+            Ok(())
+        }
+        SprintCommand::Start { sprint, days } => {
+            let sprint = require_sprint(backend, &sprint).await?;
+            let start = Utc::now();
+            let sprint = backend
+                .start_sprint(sprint.id, start, start + Duration::days(days))
+                .await?;
+            output::print_sprint(&sprint, format)
+        }
+        SprintCommand::Complete { sprint } => {
+            let sprint = require_sprint(backend, &sprint).await?;
+            let sprint = backend.complete_sprint(sprint.id).await?;
+            output::print_sprint(&sprint, format)
+        }
+    }
+}
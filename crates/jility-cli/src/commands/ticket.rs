@@ -0,0 +1,204 @@
+use std::io::Write;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+
+use crate::backend::{Backend, NewTicket, TicketEdit, TicketFilter};
+use crate::output::{self, print_info, print_success, Format};
+
+#[derive(Debug, Subcommand)]
+pub enum TicketCommand {
+    /// Create a ticket
+    New {
+        title: String,
+        #[arg(short, long)]
+        description: Option<String>,
+        /// A status from the project workflow (default: its initial status)
+        #[arg(short, long)]
+        status: Option<String>,
+        #[arg(short, long)]
+        points: Option<i32>,
+        /// May be repeated to pair several assignees
+        #[arg(short, long = "assignee")]
+        assignees: Vec<String>,
+        /// May be repeated
+        #[arg(short, long = "label")]
+        labels: Vec<String>,
+    },
+    /// List tickets in the project
+    List {
+        /// May be repeated
+        #[arg(short, long)]
+        status: Vec<String>,
+        #[arg(short, long)]
+        assignee: Option<String>,
+    },
+    /// Show a ticket with its comments
+    Show { id: String },
+    /// Change a ticket's title, description or points. With no options the
+    /// description is opened in $EDITOR.
+    Edit {
+        id: String,
+        #[arg(short, long)]
+        title: Option<String>,
+        #[arg(short, long)]
+        description: Option<String>,
+        #[arg(short, long)]
+        points: Option<i32>,
+    },
+    /// Change a ticket's status
+    Move { id: String, status: String },
+    /// Add (or with --remove, remove) an assignee
+    Assign {
+        id: String,
+        assignee: String,
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Add a comment
+    Comment { id: String, content: String },
+}
+
+/// Accept `in-progress` and `In_Progress` as well as `in_progress`
+fn normalize_status(status: &str) -> String {
+    status.trim().to_lowercase().replace('-', "_")
+}
+
+pub async fn run(command: TicketCommand, backend: &dyn Backend, format: Format) -> Result<()> {
+    match command {
+        TicketCommand::New {
+            title,
+            description,
+            status,
+            points,
+            assignees,
+            labels,
+        } => {
+            let ticket = backend
+                .create_ticket(NewTicket {
+                    title,
+                    description,
+                    status: status.as_deref().map(normalize_status),
+                    story_points: points,
+                    assignees,
+                    labels,
+                })
+                .await?;
+            if format == Format::Table {
+                print_success(&format!("Created {}", ticket.number));
+            }
+            output::print_ticket(&ticket, format)
+        }
+        TicketCommand::List { status, assignee } => {
+            let tickets = backend
+                .list_tickets(TicketFilter {
+                    status: status.iter().map(|s| normalize_status(s)).collect(),
+                    assignee,
+                })
+                .await?;
+            output::print_tickets(&tickets, format)
+        }
+        TicketCommand::Show { id } => {
+            let detail = backend.get_ticket(&id).await?;
+            output::print_ticket_detail(&detail, format)
+        }
+        TicketCommand::Edit {
+            id,
+            title,
+            description,
+            points,
+        } => {
+            let mut edit = TicketEdit {
+                title,
+                description,
+                story_points: points,
+            };
+            if edit.title.is_none() && edit.description.is_none() && edit.story_points.is_none() {
+                let current = backend.get_ticket(&id).await?.ticket;
+                match edit_in_editor(&current.description)? {
+                    Some(description) => edit.description = Some(description),
+                    None => {
+                        print_info("Description unchanged");
+                        return Ok(());
+                    }
+                }
+            }
+
+            let ticket = backend.edit_ticket(&id, edit).await?;
+            if format == Format::Table {
+                print_success(&format!("Updated {}", ticket.number));
+            }
+            output::print_ticket(&ticket, format)
+        }
+        TicketCommand::Move { id, status } => {
+            let ticket = backend.move_ticket(&id, &normalize_status(&status)).await?;
+            output::print_ticket(&ticket, format)
+        }
+        TicketCommand::Assign {
+            id,
+            assignee,
+            remove,
+        } => {
+            let ticket = if remove {
+                backend.unassign_ticket(&id, &assignee).await?
+            } else {
+                backend.assign_ticket(&id, &assignee).await?
+            };
+            if format == Format::Table {
+                let assignees = if ticket.assignees.is_empty() {
+                    "nobody".to_string()
+                } else {
+                    ticket.assignees.join(", ")
+                };
+                print_success(&format!("{} is assigned to {}", ticket.number, assignees));
+                return Ok(());
+            }
+            output::print_ticket(&ticket, format)
+        }
+        TicketCommand::Comment { id, content } => {
+            let comment = backend.add_comment(&id, &content).await?;
+            if format == Format::Table {
+                print_success(&format!("Commented on {}", id));
+            }
+            output::print_comment(&comment, format)
+        }
+    }
+}
+
+/// Open `text` in `$EDITOR` (default `vi`); `None` if it was left unchanged
+fn edit_in_editor(text: &str) -> Result<Option<String>> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    let mut file = tempfile::Builder::new()
+        .prefix("jility-")
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    // $EDITOR may carry arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("$EDITOR is empty")?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(file.path())
+        .status()
+        .with_context(|| format!("Failed to launch {}", editor))?;
+    if !status.success() {
+        bail!("{} exited with {}", editor, status);
+    }
+
+    let edited = std::fs::read_to_string(file.path())?;
+    Ok((edited != text).then_some(edited))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_status() {
+        assert_eq!(normalize_status("in-progress"), "in_progress");
+        assert_eq!(normalize_status(" Done "), "done");
+    }
+}
@@ -0,0 +1,97 @@
+//! `.jility/` directory discovery and settings
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const JILITY_DIR: &str = ".jility";
+const CONFIG_FILE: &str = "config.json";
+const DATABASE_FILE: &str = "data.db";
+
+/// Settings written by `jility init` to `.jility/config.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// Project key (or ID) that commands operate on
+    pub project: Option<String>,
+    /// Jility server API URL; when unset the local database is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+}
+
+/// A `.jility/` directory and its settings
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub dir: PathBuf,
+    pub config: Config,
+}
+
+impl Workspace {
+    /// Find the nearest `.jility/` directory in `start` or one of its parents
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let candidate = dir.join(JILITY_DIR);
+            if candidate.is_dir() {
+                return Self::open(candidate).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn open(dir: PathBuf) -> Result<Self> {
+        let path = dir.join(CONFIG_FILE);
+        let config = if path.exists() {
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&raw)
+                .with_context(|| format!("Invalid config file {}", path.display()))?
+        } else {
+            Config::default()
+        };
+        Ok(Self { dir, config })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(CONFIG_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(&self.config)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn database_path(&self) -> PathBuf {
+        self.dir.join(DATABASE_FILE)
+    }
+}
+
+/// Name recorded as the author of local changes: `$JILITY_USER`, then `$USER`
+pub fn local_actor() -> String {
+    std::env::var("JILITY_USER")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "local".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_walks_up_to_jility_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let workspace = Workspace {
+            dir: root.path().join(JILITY_DIR),
+            config: Config {
+                project: Some("JIL".to_string()),
+                server: None,
+            },
+        };
+        workspace.save().unwrap();
+
+        let nested = root.path().join("src/deeply/nested");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let found = Workspace::discover(&nested).unwrap().unwrap();
+        assert_eq!(found.dir, workspace.dir);
+        assert_eq!(found.config.project.as_deref(), Some("JIL"));
+        assert_eq!(found.database_path(), root.path().join(".jility/data.db"));
+    }
+}
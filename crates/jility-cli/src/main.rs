@@ -0,0 +1,74 @@
+//! `jility` command-line client
+//!
+//! Works against the local `.jility/data.db` created by `jility init`, or
+//! against a Jility server when `--server` (or `JILITY_API_URL`) is set.
+
+mod backend;
+mod commands;
+mod config;
+mod output;
+
+use clap::{Parser, Subcommand};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::commands::{init, search, sprint, ticket, GlobalArgs};
+
+#[derive(Debug, Parser)]
+#[command(name = "jility", version, about = "File and manage Jility tickets from the terminal")]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Set up a .jility/ directory in the current directory
+    Init(init::InitArgs),
+    /// Create, list and update tickets
+    #[command(subcommand)]
+    Ticket(ticket::TicketCommand),
+    /// Plan, start and complete sprints
+    #[command(subcommand)]
+    Sprint(sprint::SprintCommand),
+    /// Full-text search across tickets and comments
+    Search(search::SearchArgs),
+}
+
+#[tokio::main]
+async fn main() {
+    // Quiet by default; set RUST_LOG to see database and HTTP activity
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "warn,sqlx=error".into()),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    if let Err(e) = run(Cli::parse()).await {
+        output::print_error(&format!("{:#}", e));
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    let format = cli.global.format;
+    match cli.command {
+        Command::Init(args) => init::run(args, cli.global).await,
+        Command::Ticket(command) => {
+            let backend = commands::open_backend(&cli.global).await?;
+            ticket::run(command, backend.as_ref(), format).await
+        }
+        Command::Sprint(command) => {
+            let backend = commands::open_backend(&cli.global).await?;
+            sprint::run(command, backend.as_ref(), format).await
+        }
+        Command::Search(args) => {
+            let backend = commands::open_backend(&cli.global).await?;
+            search::run(args, backend.as_ref(), format).await
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Terminal and JSON output
+
+use std::io::Write;
+
+use clap::ValueEnum;
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::backend::{Comment, SearchHit, Sprint, Ticket, TicketDetail};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Tabled)]
+struct TicketRow {
+    #[tabled(rename = "ID")]
+    number: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Assignees")]
+    assignees: String,
+    #[tabled(rename = "Points")]
+    points: String,
+}
+
+#[derive(Tabled)]
+struct SprintRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Dates")]
+    dates: String,
+    #[tabled(rename = "Capacity")]
+    capacity: String,
+    #[tabled(rename = "Goal")]
+    goal: String,
+}
+
+#[derive(Tabled)]
+struct SearchRow {
+    #[tabled(rename = "ID")]
+    number: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+pub fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, value)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+pub fn print_success(message: &str) {
+    println!("{} {}", "✓".green().bold(), message);
+}
+
+pub fn print_info(message: &str) {
+    println!("{} {}", "→".blue().bold(), message);
+}
+
+pub fn print_error(message: &str) {
+    eprintln!("{} {}", "✗".red().bold(), message);
+}
+
+pub fn status_badge(status: &str) -> ColoredString {
+    match status {
+        "done" => status.green(),
+        "in_progress" | "active" => status.yellow(),
+        "review" => status.magenta(),
+        "blocked" => status.red(),
+        "todo" | "planning" => status.cyan(),
+        _ => status.dimmed(),
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max - 1).collect::<String>())
+    }
+}
+
+fn points(points: Option<i32>) -> String {
+    points.map(|p| p.to_string()).unwrap_or_default()
+}
+
+pub fn print_tickets(tickets: &[Ticket], format: Format) -> anyhow::Result<()> {
+    if format == Format::Json {
+        return print_json(&tickets);
+    }
+    if tickets.is_empty() {
+        print_info("No tickets found");
+        return Ok(());
+    }
+
+    let rows = tickets.iter().map(|t| TicketRow {
+        number: t.number.clone(),
+        title: truncate(&t.title, 50),
+        status: t.status.clone(),
+        assignees: t.assignees.join(", "),
+        points: points(t.story_points),
+    });
+    println!("{}", Table::new(rows).with(Style::rounded()));
+    Ok(())
+}
+
+pub fn print_ticket(ticket: &Ticket, format: Format) -> anyhow::Result<()> {
+    if format == Format::Json {
+        return print_json(ticket);
+    }
+
+    println!(
+        "{} {} [{}]",
+        ticket.number.bold(),
+        ticket.title,
+        status_badge(&ticket.status)
+    );
+    Ok(())
+}
+
+pub fn print_ticket_detail(detail: &TicketDetail, format: Format) -> anyhow::Result<()> {
+    if format == Format::Json {
+        return print_json(detail);
+    }
+
+    let ticket = &detail.ticket;
+    let rule = "─".repeat(60).dimmed();
+
+    println!("{} {}", ticket.number.bold(), ticket.title.bold());
+    println!("{}", rule);
+    println!("{:<12} {}", "Status:".dimmed(), status_badge(&ticket.status));
+    if let Some(p) = ticket.story_points {
+        println!("{:<12} {}", "Points:".dimmed(), p);
+    }
+    if !ticket.assignees.is_empty() {
+        println!("{:<12} {}", "Assignees:".dimmed(), ticket.assignees.join(", "));
+    }
+    if !ticket.labels.is_empty() {
+        println!("{:<12} {}", "Labels:".dimmed(), ticket.labels.join(", "));
+    }
+    println!(
+        "{:<12} {} on {}",
+        "Created:".dimmed(),
+        ticket.created_by,
+        ticket.created_at.format("%Y-%m-%d %H:%M")
+    );
+    println!(
+        "{:<12} {}",
+        "Updated:".dimmed(),
+        ticket.updated_at.format("%Y-%m-%d %H:%M")
+    );
+
+    if !ticket.description.is_empty() {
+        println!("{}", rule);
+        println!("{}", ticket.description);
+    }
+
+    if !detail.comments.is_empty() {
+        println!("{}", rule);
+        println!("{}", format!("Comments ({})", detail.comments.len()).bold());
+        for comment in &detail.comments {
+            print_comment_body(comment);
+        }
+    }
+    Ok(())
+}
+
+fn print_comment_body(comment: &Comment) {
+    println!();
+    if comment.deleted_at.is_some() {
+        println!("{}", "comment removed".dimmed());
+        return;
+    }
+    println!(
+        "{}{} {}",
+        if comment.parent_id.is_some() { "↳ " } else { "" },
+        comment.author.bold(),
+        comment
+            .created_at
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+            .dimmed()
+    );
+    println!("{}", comment.content);
+}
+
+pub fn print_comment(comment: &Comment, format: Format) -> anyhow::Result<()> {
+    if format == Format::Json {
+        return print_json(comment);
+    }
+    print_comment_body(comment);
+    Ok(())
+}
+
+pub fn print_sprints(sprints: &[Sprint], format: Format) -> anyhow::Result<()> {
+    if format == Format::Json {
+        return print_json(&sprints);
+    }
+    if sprints.is_empty() {
+        print_info("No sprints found");
+        return Ok(());
+    }
+
+    let rows = sprints.iter().map(|s| SprintRow {
+        name: s.name.clone(),
+        status: s.status.clone(),
+        dates: match (s.start_date, s.end_date) {
+            (Some(start), Some(end)) => {
+                format!("{} → {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"))
+            }
+            _ => String::new(),
+        },
+        capacity: points(s.capacity),
+        goal: truncate(s.goal.as_deref().unwrap_or(""), 40),
+    });
+    println!("{}", Table::new(rows).with(Style::rounded()));
+    Ok(())
+}
+
+pub fn print_sprint(sprint: &Sprint, format: Format) -> anyhow::Result<()> {
+    if format == Format::Json {
+        return print_json(sprint);
+    }
+    println!("{} [{}]", sprint.name.bold(), status_badge(&sprint.status));
+    Ok(())
+}
+
+pub fn print_search_hits(hits: &[SearchHit], format: Format) -> anyhow::Result<()> {
+    if format == Format::Json {
+        return print_json(&hits);
+    }
+    if hits.is_empty() {
+        print_info("No matching tickets");
+        return Ok(());
+    }
+
+    let rows = hits.iter().map(|h| SearchRow {
+        number: h.number.clone(),
+        title: truncate(&h.title, 60),
+        status: h.status.clone(),
+    });
+    println!("{}", Table::new(rows).with(Style::rounded()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a longer title", 8), "a longe…");
+    }
+}
@@ -0,0 +1,321 @@
+//! Portable workspace archives
+//!
+//! An archive is JSON Lines: a [`Header`], one [`Record`] per row of the
+//! workspace's data, and an [`End`] line counting the records, so a
+//! truncated file is caught before anything is restored. Records keep the
+//! IDs they had where they were exported; a restore gives every row a new
+//! one through an [`IdMap`], so an archive can be restored next to the
+//! workspace it came from or on a server with another database engine.
+//!
+//! Secrets stay on the server: password hashes, sessions, API keys, invites,
+//! webhooks and push secrets aren't archived. Attachments are archived as
+//! metadata; their content stays in the blob store under its SHA-256.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entities::{
+    attachment, comment, comment_reaction, comment_revision, commit_link, project,
+    project_workflow, saved_view, sprint, sprint_ticket, ticket, ticket_alias, ticket_assignee,
+    ticket_change, ticket_dependency, ticket_label, ticket_template, ticket_watcher, workspace,
+    WorkspaceRole,
+};
+use crate::error::{CoreError, CoreResult};
+
+/// `format` of every archive's header
+pub const FORMAT: &str = "jility-workspace";
+
+/// Version written by this build. Bumped whenever a record changes shape;
+/// archives of a newer version are refused.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Who made the export
+    pub exported_by: String,
+}
+
+impl Header {
+    pub fn new(exported_by: &str) -> Self {
+        Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            exported_at: Utc::now(),
+            exported_by: exported_by.to_string(),
+        }
+    }
+}
+
+/// A workspace member. Restores match members to accounts by email.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub user_id: Uuid,
+    pub email: String,
+    pub username: String,
+    pub full_name: Option<String>,
+    pub role: WorkspaceRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Last line of an archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct End {
+    /// Records between the header and this line
+    pub records: usize,
+}
+
+/// One line of an archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum Record {
+    Header(Header),
+    Workspace(workspace::Model),
+    Member(Member),
+    Project(project::Model),
+    Workflow(project_workflow::Model),
+    Template(ticket_template::Model),
+    Sprint(sprint::Model),
+    Ticket(ticket::Model),
+    TicketAlias(ticket_alias::Model),
+    Assignee(ticket_assignee::Model),
+    Label(ticket_label::Model),
+    Dependency(ticket_dependency::Model),
+    SprintTicket(sprint_ticket::Model),
+    Comment(comment::Model),
+    CommentRevision(comment_revision::Model),
+    CommentReaction(comment_reaction::Model),
+    Attachment(attachment::Model),
+    CommitLink(commit_link::Model),
+    Watcher(ticket_watcher::Model),
+    Change(ticket_change::Model),
+    SavedView(saved_view::Model),
+    End(End),
+}
+
+impl Record {
+    /// The record's `type`, e.g. `ticket`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Record::Header(_) => "header",
+            Record::Workspace(_) => "workspace",
+            Record::Member(_) => "member",
+            Record::Project(_) => "project",
+            Record::Workflow(_) => "workflow",
+            Record::Template(_) => "template",
+            Record::Sprint(_) => "sprint",
+            Record::Ticket(_) => "ticket",
+            Record::TicketAlias(_) => "ticket_alias",
+            Record::Assignee(_) => "assignee",
+            Record::Label(_) => "label",
+            Record::Dependency(_) => "dependency",
+            Record::SprintTicket(_) => "sprint_ticket",
+            Record::Comment(_) => "comment",
+            Record::CommentRevision(_) => "comment_revision",
+            Record::CommentReaction(_) => "comment_reaction",
+            Record::Attachment(_) => "attachment",
+            Record::CommitLink(_) => "commit_link",
+            Record::Watcher(_) => "watcher",
+            Record::Change(_) => "change",
+            Record::SavedView(_) => "saved_view",
+            Record::End(_) => "end",
+        }
+    }
+}
+
+/// Write `records` as an archive
+pub fn write(header: Header, records: &[Record]) -> String {
+    let line = |record: &Record| serde_json::to_string(record).unwrap() + "\n";
+
+    let mut out = line(&Record::Header(header));
+    for record in records {
+        out.push_str(&line(record));
+    }
+    out.push_str(&line(&Record::End(End {
+        records: records.len(),
+    })));
+    out
+}
+
+/// The header and records of an archive, checked to be complete and of a
+/// version this build reads
+pub fn read(input: &str) -> CoreResult<(Header, Vec<Record>)> {
+    let invalid = |line: usize, message: String| {
+        CoreError::InvalidInput(format!("Archive line {}: {}", line, message))
+    };
+
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| (i + 1, text.trim()))
+        .filter(|(_, text)| !text.is_empty());
+    let parse = |(number, text): (usize, &str)| {
+        serde_json::from_str::<Record>(text).map_err(|e| invalid(number, e.to_string()))
+    };
+
+    let header = match lines.next().map(parse).transpose()? {
+        Some(Record::Header(header)) => header,
+        _ => {
+            return Err(CoreError::InvalidInput(
+                "Not a workspace archive: the first line isn't a header".to_string(),
+            ))
+        }
+    };
+    if header.format != FORMAT {
+        return Err(invalid(
+            1,
+            format!("format '{}' isn't '{}'", header.format, FORMAT),
+        ));
+    }
+    if header.version > VERSION {
+        return Err(invalid(
+            1,
+            format!(
+                "archive version {} is newer than this server reads ({})",
+                header.version, VERSION
+            ),
+        ));
+    }
+
+    let mut records = Vec::new();
+    while let Some((number, text)) = lines.next() {
+        match parse((number, text))? {
+            Record::Header(_) => return Err(invalid(number, "a second header".to_string())),
+            Record::End(end) if end.records != records.len() => {
+                return Err(invalid(
+                    number,
+                    format!(
+                        "the archive should have {} records but has {}",
+                        end.records,
+                        records.len()
+                    ),
+                ))
+            }
+            Record::End(_) => {
+                if let Some((number, _)) = lines.next() {
+                    return Err(invalid(number, "lines after the end".to_string()));
+                }
+                return Ok((header, records));
+            }
+            record => records.push(record),
+        }
+    }
+    Err(CoreError::InvalidInput(
+        "The archive is truncated: it has no end line".to_string(),
+    ))
+}
+
+/// New IDs for the rows of a restored archive
+#[derive(Debug, Default)]
+pub struct IdMap {
+    ids: HashMap<Uuid, Uuid>,
+}
+
+impl IdMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new ID for the row exported as `old`, the same one every time
+    pub fn assign(&mut self, old: Uuid) -> Uuid {
+        *self.ids.entry(old).or_insert_with(Uuid::new_v4)
+    }
+
+    /// The new ID of the row exported as `old`, if it was assigned one
+    pub fn get(&self, old: Uuid) -> Option<Uuid> {
+        self.ids.get(&old).copied()
+    }
+
+    /// `text` with every assigned ID in it replaced by the new one, for
+    /// values such as a change's ticket snapshot that embed IDs
+    pub fn rewrite(&self, text: &str) -> String {
+        const LEN: usize = 36;
+
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while rest.len() >= LEN {
+            let candidate = rest
+                .get(..LEN)
+                .and_then(|s| Uuid::try_parse(s).ok())
+                .and_then(|id| self.get(id));
+            match candidate {
+                Some(id) => {
+                    out.push_str(&id.to_string());
+                    rest = &rest[LEN..];
+                }
+                None => {
+                    let c = rest.chars().next().unwrap_or_default();
+                    out.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(ticket_id: Uuid, label: &str) -> Record {
+        Record::Label(ticket_label::Model {
+            id: Uuid::new_v4(),
+            ticket_id,
+            label: label.to_string(),
+            created_at: Utc::now(),
+        })
+    }
+
+    #[test]
+    fn test_read_checks_the_archive_is_whole() {
+        let ticket_id = Uuid::new_v4();
+        let records = vec![label(ticket_id, "bug"), label(ticket_id, "ui")];
+        let archive = write(Header::new("alice"), &records);
+        assert!(archive
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("{\"type\":\"label\""));
+
+        let (header, read_back) = read(&archive).unwrap();
+        assert_eq!(header.version, VERSION);
+        assert_eq!(read_back, records);
+
+        let truncated: Vec<&str> = archive.lines().take(2).collect();
+        assert!(read(&truncated.join("\n")).is_err());
+        let short = archive.replacen(&serde_json::to_string(&records[1]).unwrap(), "", 1);
+        assert!(read(&short).is_err());
+        let newer = archive.replacen("\"version\":1", "\"version\":99", 1);
+        assert!(read(&newer).is_err());
+        assert!(read("{\"type\":\"end\",\"data\":{\"records\":0}}").is_err());
+    }
+
+    #[test]
+    fn test_rewrite_replaces_assigned_ids() {
+        let (ticket, comment, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut ids = IdMap::new();
+        let new_ticket = ids.assign(ticket);
+        let new_comment = ids.assign(comment);
+        assert_eq!(ids.assign(ticket), new_ticket);
+
+        let text = format!(
+            "{{\"id\":\"{}\",\"note\":\"é {}\"}}{}",
+            ticket, other, comment
+        );
+        assert_eq!(
+            ids.rewrite(&text),
+            format!(
+                "{{\"id\":\"{}\",\"note\":\"é {}\"}}{}",
+                new_ticket, other, new_comment
+            )
+        );
+        assert_eq!(ids.rewrite("short é"), "short é");
+    }
+}
@@ -0,0 +1,280 @@
+//! Ticket dependency graph
+//!
+//! An in-memory view of `ticket_dependency` rows used to validate new edges
+//! before they are inserted and to plan work across an epic or sprint. Edges
+//! point from a ticket to the ticket it depends on.
+
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// ticket -> tickets it depends on
+    depends_on: HashMap<Uuid, Vec<Uuid>>,
+    /// ticket -> tickets that depend on it
+    dependents: HashMap<Uuid, Vec<Uuid>>,
+}
+
+impl DependencyGraph {
+    /// Build a graph from `(ticket_id, depends_on_id)` pairs
+    pub fn new(edges: impl IntoIterator<Item = (Uuid, Uuid)>) -> Self {
+        let mut depends_on: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        let mut dependents: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for (ticket_id, depends_on_id) in edges {
+            depends_on.entry(ticket_id).or_default().push(depends_on_id);
+            dependents.entry(depends_on_id).or_default().push(ticket_id);
+        }
+        Self { depends_on, dependents }
+    }
+
+    /// All `(ticket_id, depends_on_id)` edges with both ends in `nodes`
+    pub fn edges_within(&self, nodes: &HashSet<Uuid>) -> Vec<(Uuid, Uuid)> {
+        let mut edges: Vec<(Uuid, Uuid)> = self
+            .depends_on
+            .iter()
+            .filter(|(ticket_id, _)| nodes.contains(ticket_id))
+            .flat_map(|(&ticket_id, deps)| {
+                deps.iter()
+                    .filter(|dep| nodes.contains(dep))
+                    .map(move |&dep| (ticket_id, dep))
+            })
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    /// Tickets reachable from `root` in either direction within `depth` hops, with
+    /// their distance from `root`
+    pub fn neighborhood(&self, root: Uuid, depth: usize) -> HashMap<Uuid, usize> {
+        let mut distances = HashMap::from([(root, 0)]);
+        let mut queue = VecDeque::from([root]);
+
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[&node];
+            if distance >= depth {
+                continue;
+            }
+
+            let neighbors = self
+                .depends_on
+                .get(&node)
+                .into_iter()
+                .chain(self.dependents.get(&node))
+                .flatten();
+            for &next in neighbors {
+                if let Entry::Vacant(entry) = distances.entry(next) {
+                    entry.insert(distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Order `nodes` so every ticket comes after the tickets it depends on.
+    ///
+    /// Only edges inside `nodes` are considered. Ties are broken by input order.
+    /// Returns the tickets left on a cycle as the error.
+    pub fn topological_order(&self, nodes: &[Uuid]) -> Result<Vec<Uuid>, Vec<Uuid>> {
+        let in_scope: HashSet<Uuid> = nodes.iter().copied().collect();
+        let mut remaining: HashMap<Uuid, usize> = nodes
+            .iter()
+            .map(|node| {
+                let deps = self
+                    .depends_on
+                    .get(node)
+                    .map_or(0, |deps| deps.iter().filter(|d| in_scope.contains(d)).count());
+                (*node, deps)
+            })
+            .collect();
+
+        let mut ready: VecDeque<Uuid> = nodes
+            .iter()
+            .filter(|node| remaining[*node] == 0)
+            .copied()
+            .collect();
+        let mut order = Vec::with_capacity(nodes.len());
+
+        while let Some(node) = ready.pop_front() {
+            order.push(node);
+            for dependent in self.dependents.get(&node).into_iter().flatten() {
+                if let Some(count) = remaining.get_mut(dependent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push_back(*dependent);
+                    }
+                }
+            }
+        }
+
+        if order.len() == nodes.len() {
+            Ok(order)
+        } else {
+            let done: HashSet<Uuid> = order.into_iter().collect();
+            Err(nodes.iter().filter(|n| !done.contains(n)).copied().collect())
+        }
+    }
+
+    /// Heaviest chain of dependencies among `nodes`, dependencies first, and its
+    /// total weight
+    pub fn critical_path(
+        &self,
+        nodes: &[Uuid],
+        weight: impl Fn(Uuid) -> i32,
+    ) -> Result<(Vec<Uuid>, i32), Vec<Uuid>> {
+        let order = self.topological_order(nodes)?;
+        let in_scope: HashSet<Uuid> = nodes.iter().copied().collect();
+
+        // Heaviest path ending at each node
+        let mut best: HashMap<Uuid, (i32, Option<Uuid>)> = HashMap::new();
+        for &node in &order {
+            let previous = self
+                .depends_on
+                .get(&node)
+                .into_iter()
+                .flatten()
+                .filter(|dep| in_scope.contains(dep))
+                .map(|dep| (best[dep].0, *dep))
+                .max();
+            let total = weight(node) + previous.map_or(0, |(w, _)| w);
+            best.insert(node, (total, previous.map(|(_, dep)| dep)));
+        }
+
+        let Some((&end, &(total, _))) = order
+            .iter()
+            .map(|node| (node, &best[node]))
+            .max_by_key(|(_, (total, _))| *total)
+        else {
+            return Ok((Vec::new(), 0));
+        };
+
+        let mut path = vec![end];
+        let mut current = end;
+        while let Some(previous) = best[&current].1 {
+            path.push(previous);
+            current = previous;
+        }
+        path.reverse();
+
+        Ok((path, total))
+    }
+
+    pub fn contains_edge(&self, ticket_id: Uuid, depends_on_id: Uuid) -> bool {
+        self.depends_on
+            .get(&ticket_id)
+            .is_some_and(|deps| deps.contains(&depends_on_id))
+    }
+
+    /// Shortest chain of dependencies leading from `from` to `to`, inclusive
+    pub fn path(&self, from: Uuid, to: Uuid) -> Option<Vec<Uuid>> {
+        let mut previous: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut visited: HashSet<Uuid> = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while let Some(&prev) = previous.get(&current) {
+                    path.push(prev);
+                    current = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for &next in self.depends_on.get(&node).into_iter().flatten() {
+                if visited.insert(next) {
+                    previous.insert(next, node);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The cycle that adding `ticket_id -> depends_on_id` would create, starting and
+    /// ending at `ticket_id`
+    pub fn cycle_if_added(&self, ticket_id: Uuid, depends_on_id: Uuid) -> Option<Vec<Uuid>> {
+        if ticket_id == depends_on_id {
+            return Some(vec![ticket_id, ticket_id]);
+        }
+
+        let path = self.path(depends_on_id, ticket_id)?;
+        let mut cycle = Vec::with_capacity(path.len() + 1);
+        cycle.push(ticket_id);
+        cycle.extend(path);
+        Some(cycle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    #[test]
+    fn test_cycle_if_added_reports_path() {
+        let t = ids(3);
+        let graph = DependencyGraph::new([(t[0], t[1]), (t[1], t[2])]);
+
+        assert_eq!(graph.cycle_if_added(t[2], t[0]), Some(vec![t[2], t[0], t[1], t[2]]));
+        assert_eq!(graph.cycle_if_added(t[0], t[2]), None);
+    }
+
+    #[test]
+    fn test_self_dependency_is_a_cycle() {
+        let t = ids(1);
+        let graph = DependencyGraph::default();
+
+        assert_eq!(graph.cycle_if_added(t[0], t[0]), Some(vec![t[0], t[0]]));
+    }
+
+    #[test]
+    fn test_neighborhood_respects_depth() {
+        let t = ids(4);
+        let graph = DependencyGraph::new([(t[0], t[1]), (t[1], t[2]), (t[3], t[0])]);
+
+        let near = graph.neighborhood(t[0], 1);
+        assert_eq!(near.len(), 3);
+        assert_eq!(near[&t[3]], 1);
+
+        let all = graph.neighborhood(t[0], 5);
+        assert_eq!(all[&t[2]], 2);
+    }
+
+    #[test]
+    fn test_topological_order_puts_dependencies_first() {
+        let t = ids(3);
+        let graph = DependencyGraph::new([(t[0], t[1]), (t[1], t[2])]);
+
+        assert_eq!(graph.topological_order(&t), Ok(vec![t[2], t[1], t[0]]));
+    }
+
+    #[test]
+    fn test_topological_order_reports_cycle() {
+        let t = ids(3);
+        let graph = DependencyGraph::new([(t[0], t[1]), (t[1], t[0])]);
+
+        assert_eq!(graph.topological_order(&t), Err(vec![t[0], t[1]]));
+    }
+
+    #[test]
+    fn test_critical_path_follows_heaviest_chain() {
+        let t = ids(4);
+        // t0 depends on t1 (3 pts) and t2 (1 pt); t1 depends on t3
+        let graph = DependencyGraph::new([(t[0], t[1]), (t[0], t[2]), (t[1], t[3])]);
+        let points = [2, 3, 1, 5];
+        let weight = |id: Uuid| points[t.iter().position(|&x| x == id).unwrap()];
+
+        let (path, total) = graph.critical_path(&t, weight).unwrap();
+        assert_eq!(path, vec![t[3], t[1], t[0]]);
+        assert_eq!(total, 10);
+    }
+}
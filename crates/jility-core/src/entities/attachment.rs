@@ -0,0 +1,64 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A file uploaded to a ticket or one of its comments. The bytes live in the
+/// server's blob store, keyed by their SHA-256 so identical uploads share
+/// one blob.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "attachment")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    pub ticket_id: Uuid,
+
+    /// Set when the file was attached to a comment on the ticket
+    #[sea_orm(nullable)]
+    pub comment_id: Option<Uuid>,
+
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+
+    /// Hex SHA-256 of the content, also its blob store key
+    pub sha256: String,
+
+    /// Who uploaded the file ("alice", "alice (ci-agent)", etc.)
+    pub uploaded_by: String,
+
+    /// The user who uploaded it, directly or through an API key
+    #[sea_orm(nullable)]
+    pub uploaded_by_user_id: Option<Uuid>,
+
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ticket::Entity",
+        from = "Column::TicketId",
+        to = "super::ticket::Column::Id"
+    )]
+    Ticket,
+    #[sea_orm(
+        belongs_to = "super::comment::Entity",
+        from = "Column::CommentId",
+        to = "super::comment::Column::Id"
+    )]
+    Comment,
+}
+
+impl Related<super::ticket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ticket.def()
+    }
+}
+
+impl Related<super::comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
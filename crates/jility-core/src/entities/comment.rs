@@ -9,9 +9,18 @@ pub struct Model {
 
     pub ticket_id: Uuid,
 
+    /// The comment this one replies to, on the same ticket
+    #[sea_orm(nullable)]
+    pub parent_id: Option<Uuid>,
+
     /// Who wrote the comment ("agent-1", "alice", etc.)
     pub author: String,
 
+    /// The user who wrote it, directly or through an API key; unset for
+    /// imported comments
+    #[sea_orm(nullable)]
+    pub author_user_id: Option<Uuid>,
+
     /// Markdown content (can include @mentions)
     #[sea_orm(column_type = "Text")]
     pub content: String,
@@ -20,6 +29,18 @@ pub struct Model {
 
     #[sea_orm(nullable)]
     pub updated_at: Option<DateTimeUtc>,
+
+    /// Soft delete: removed comments stay in their thread as a tombstone
+    #[sea_orm(nullable)]
+    pub deleted_at: Option<DateTimeUtc>,
+
+    #[sea_orm(nullable)]
+    pub deleted_by: Option<String>,
+
+    /// ID of the comment in the tracker it was imported from, so importing
+    /// again adds only the comments made since
+    #[sea_orm(nullable)]
+    pub source_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -30,6 +51,10 @@ pub enum Relation {
         to = "super::ticket::Column::Id"
     )]
     Ticket,
+    #[sea_orm(has_many = "super::comment_reaction::Entity")]
+    Reactions,
+    #[sea_orm(has_many = "super::comment_revision::Entity")]
+    Revisions,
 }
 
 impl Related<super::ticket::Entity> for Entity {
@@ -38,4 +63,16 @@ impl Related<super::ticket::Entity> for Entity {
     }
 }
 
+impl Related<super::comment_reaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reactions.def()
+    }
+}
+
+impl Related<super::comment_revision::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Revisions.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
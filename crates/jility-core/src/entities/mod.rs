@@ -1,48 +1,76 @@
 pub mod api_key;
+pub mod attachment;
 pub mod comment;
+pub mod comment_reaction;
+pub mod comment_revision;
 pub mod commit_link;
+pub mod notification;
 pub mod project;
+pub mod project_workflow;
 pub mod saved_view;
 pub mod session;
 pub mod sprint;
 pub mod sprint_ticket;
 pub mod ticket;
+pub mod ticket_alias;
 pub mod ticket_assignee;
 pub mod ticket_change;
 pub mod ticket_dependency;
 pub mod ticket_label;
+pub mod ticket_template;
+pub mod ticket_watcher;
 pub mod user;
+pub mod webhook;
+pub mod webhook_delivery;
 pub mod workspace;
 pub mod workspace_invite;
 pub mod workspace_member;
 
 pub use api_key::Entity as ApiKey;
+pub use attachment::Entity as Attachment;
 pub use comment::Entity as Comment;
+pub use comment_reaction::Entity as CommentReaction;
+pub use comment_revision::Entity as CommentRevision;
 pub use commit_link::Entity as CommitLink;
+pub use notification::Entity as Notification;
 pub use project::Entity as Project;
+pub use project_workflow::Entity as ProjectWorkflow;
 pub use saved_view::Entity as SavedView;
 pub use session::Entity as Session;
 pub use sprint::Entity as Sprint;
 pub use sprint_ticket::Entity as SprintTicket;
 pub use ticket::Entity as Ticket;
+pub use ticket_alias::Entity as TicketAlias;
 pub use ticket_assignee::Entity as TicketAssignee;
 pub use ticket_change::Entity as TicketChange;
 pub use ticket_dependency::Entity as TicketDependency;
 pub use ticket_label::Entity as TicketLabel;
+pub use ticket_template::Entity as TicketTemplate;
+pub use ticket_watcher::Entity as TicketWatcher;
 pub use user::Entity as User;
+pub use webhook::Entity as Webhook;
+pub use webhook_delivery::Entity as WebhookDelivery;
 pub use workspace::Entity as Workspace;
 pub use workspace_invite::Entity as WorkspaceInvite;
 pub use workspace_member::Entity as WorkspaceMember;
 
 // Re-export commonly used types
 pub use api_key::Model as ApiKeyModel;
+pub use attachment::Model as AttachmentModel;
+pub use notification::{Model as NotificationModel, NotificationKind};
 pub use project::Model as ProjectModel;
+pub use project_workflow::Model as ProjectWorkflowModel;
 pub use saved_view::Model as SavedViewModel;
 pub use session::Model as SessionModel;
 pub use sprint::{Model as SprintModel, SprintStatus};
 pub use ticket::{Model as TicketModel, TicketStatus};
+pub use ticket_alias::Model as TicketAliasModel;
 pub use ticket_change::{ChangeType, Model as TicketChangeModel};
+pub use ticket_template::{Model as TicketTemplateModel, TemplateSubtask};
+pub use ticket_watcher::{Model as TicketWatcherModel, WatchReason};
 pub use user::Model as UserModel;
+pub use webhook::Model as WebhookModel;
+pub use webhook_delivery::{DeliveryStatus, Model as WebhookDeliveryModel};
 pub use workspace::Model as WorkspaceModel;
 pub use workspace_invite::Model as WorkspaceInviteModel;
 pub use workspace_member::Model as WorkspaceMemberModel;
@@ -0,0 +1,86 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An entry in a user's inbox about activity on a ticket
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "notification")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Recipient
+    pub user_id: Uuid,
+    pub ticket_id: Uuid,
+
+    /// `mentioned`, `assigned`, `commented` or `status_changed`
+    pub kind: String,
+    /// Who caused it, as recorded in `changed_by`
+    pub actor: String,
+    /// Short description, e.g. the start of the comment
+    #[sea_orm(column_type = "Text")]
+    pub message: String,
+    pub comment_id: Option<Uuid>,
+
+    pub read_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ticket::Entity",
+        from = "Column::TicketId",
+        to = "super::ticket::Column::Id"
+    )]
+    Ticket,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::ticket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ticket.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Rust enum for notification kinds (converted to/from string in DB)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// `@username` in a comment
+    Mentioned,
+    Assigned,
+    /// A comment on a watched ticket
+    Commented,
+    /// A watched ticket changed status
+    StatusChanged,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mentioned => "mentioned",
+            Self::Assigned => "assigned",
+            Self::Commented => "commented",
+            Self::StatusChanged => "status_changed",
+        }
+    }
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
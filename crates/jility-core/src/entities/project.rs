@@ -1,4 +1,5 @@
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Query};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
@@ -32,6 +33,15 @@ pub struct Model {
     #[sea_orm(default_value = false)]
     pub require_story_points: bool,
 
+    /// Last ticket number handed out; see [`next_ticket_number`]
+    #[sea_orm(default_value = 0)]
+    pub ticket_sequence: i32,
+
+    /// Verifies pushes sent to `POST /api/projects/:id/git/push`; pushes are
+    /// refused until one is generated
+    #[serde(skip_serializing)]
+    pub git_webhook_secret: Option<String>,
+
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -72,3 +82,92 @@ impl Related<super::workspace::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+/// Reserve the next ticket number of `project_id`.
+///
+/// Increments the project's counter in a single `UPDATE .. RETURNING`, so
+/// concurrent callers always get distinct numbers. Call it inside the
+/// transaction that inserts the ticket, before anything else the transaction
+/// reads, so an SQLite writer waits for the lock instead of failing as busy.
+pub async fn next_ticket_number<C: ConnectionTrait>(db: &C, project_id: Uuid) -> Result<i32, DbErr> {
+    let update = Query::update()
+        .table(Entity)
+        .value(
+            Column::TicketSequence,
+            Expr::col(Column::TicketSequence).add(1),
+        )
+        .and_where(Column::Id.eq(project_id))
+        .returning_col(Column::TicketSequence)
+        .to_owned();
+
+    db.query_one(db.get_database_backend().build(&update))
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("Project not found: {}", project_id)))?
+        .try_get("", Column::TicketSequence.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::ticket;
+    use crate::migration::Migrator;
+    use chrono::Utc;
+    use sea_orm::{ActiveValue::Set, ConnectOptions, Database, TransactionTrait};
+    use sea_orm_migration::MigratorTrait;
+
+    #[tokio::test]
+    async fn test_concurrent_ticket_numbers_are_distinct() {
+        // A file, so the writers get connections of their own
+        let dir = tempfile::tempdir().unwrap();
+        let mut options = ConnectOptions::new(format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("jility.db").display()
+        ));
+        options.max_connections(8);
+        let db = Database::connect(options).await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        let now = Utc::now();
+        let project = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            workspace_id: Set(Uuid::new_v4()),
+            name: Set("Numbers".to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let creates = (0..20).map(|i| {
+            let db = db.clone();
+            tokio::spawn(async move {
+                let txn = db.begin().await?;
+                let number = next_ticket_number(&txn, project.id).await?;
+                tokio::task::yield_now().await;
+                ticket::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    project_id: Set(project.id),
+                    ticket_number: Set(number),
+                    title: Set(format!("Ticket {}", i)),
+                    created_by: Set("alice".to_string()),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?;
+                txn.commit().await?;
+                Ok::<_, DbErr>(number)
+            })
+        });
+        let mut numbers = Vec::new();
+        for create in creates.collect::<Vec<_>>() {
+            numbers.push(create.await.unwrap().unwrap());
+        }
+
+        numbers.sort();
+        assert_eq!(numbers, (1..=20).collect::<Vec<_>>());
+    }
+}
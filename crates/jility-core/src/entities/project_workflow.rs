@@ -0,0 +1,65 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::workflow::{Workflow, WorkflowStatus, WorkflowTransition};
+
+/// A project's custom workflow. Projects without a row use `Workflow::default()`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "project_workflow")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    #[sea_orm(unique)]
+    pub project_id: Uuid,
+
+    /// Status given to tickets created without one
+    pub initial_status: String,
+
+    /// JSON array of `WorkflowStatus`, in board order
+    #[sea_orm(column_type = "Text")]
+    pub statuses: String,
+
+    /// JSON array of `WorkflowTransition`
+    #[sea_orm(column_type = "Text")]
+    pub transitions: String,
+
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+impl Model {
+    pub fn statuses(&self) -> Vec<WorkflowStatus> {
+        serde_json::from_str(&self.statuses).unwrap_or_default()
+    }
+
+    pub fn transitions(&self) -> Vec<WorkflowTransition> {
+        serde_json::from_str(&self.transitions).unwrap_or_default()
+    }
+
+    pub fn workflow(&self) -> Workflow {
+        Workflow {
+            statuses: self.statuses(),
+            transitions: self.transitions(),
+            initial_status: self.initial_status.clone(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Another name a ticket answers to, such as the key it had in the tracker
+/// it was imported from. Aliases are unique within a project.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ticket_alias")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    pub project_id: Uuid,
+
+    pub ticket_id: Uuid,
+
+    /// e.g. "PROJ-123"
+    pub alias: String,
+
+    /// Where the alias came from: "jira", etc.
+    pub source: String,
+
+    /// ID of the issue in that source, e.g. Jira's issue id, which stays
+    /// the same when the issue's key changes
+    #[sea_orm(nullable)]
+    pub source_id: Option<String>,
+
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ticket::Entity",
+        from = "Column::TicketId",
+        to = "super::ticket::Column::Id"
+    )]
+    Ticket,
+}
+
+impl Related<super::ticket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ticket.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
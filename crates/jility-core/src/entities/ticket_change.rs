@@ -59,6 +59,8 @@ pub enum ChangeType {
     // Lifecycle
     Created,
     Deleted,
+    /// Created by an import; carries the ticket like `Created`
+    Imported,
 
     // Field updates
     TitleChanged,
@@ -78,6 +80,8 @@ pub enum ChangeType {
 
     // Collaboration
     CommentAdded,
+    CommentEdited,
+    CommentDeleted,
     CommitLinked,
 
     // Sprint
@@ -90,6 +94,7 @@ impl ChangeType {
         match self {
             Self::Created => "created",
             Self::Deleted => "deleted",
+            Self::Imported => "imported",
             Self::TitleChanged => "title_changed",
             Self::DescriptionChanged => "description_changed",
             Self::StatusChanged => "status_changed",
@@ -103,6 +108,8 @@ impl ChangeType {
             Self::ParentChanged => "parent_changed",
             Self::EpicChanged => "epic_changed",
             Self::CommentAdded => "comment_added",
+            Self::CommentEdited => "comment_edited",
+            Self::CommentDeleted => "comment_deleted",
             Self::CommitLinked => "commit_linked",
             Self::AddedToSprint => "added_to_sprint",
             Self::RemovedFromSprint => "removed_from_sprint",
@@ -113,6 +120,7 @@ impl ChangeType {
         match s {
             "created" => Ok(Self::Created),
             "deleted" => Ok(Self::Deleted),
+            "imported" => Ok(Self::Imported),
             "title_changed" => Ok(Self::TitleChanged),
             "description_changed" => Ok(Self::DescriptionChanged),
             "status_changed" => Ok(Self::StatusChanged),
@@ -126,6 +134,8 @@ impl ChangeType {
             "parent_changed" => Ok(Self::ParentChanged),
             "epic_changed" => Ok(Self::EpicChanged),
             "comment_added" => Ok(Self::CommentAdded),
+            "comment_edited" => Ok(Self::CommentEdited),
+            "comment_deleted" => Ok(Self::CommentDeleted),
             "commit_linked" => Ok(Self::CommitLinked),
             "added_to_sprint" => Ok(Self::AddedToSprint),
             "removed_from_sprint" => Ok(Self::RemovedFromSprint),
@@ -0,0 +1,99 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ticket_template")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    pub workspace_id: Uuid,
+
+    /// Project the template belongs to (None = available to every project in the workspace)
+    #[sea_orm(nullable)]
+    pub project_id: Option<Uuid>,
+
+    /// Unique name within its scope (e.g., "bug-report")
+    pub name: String,
+
+    /// What the template is for
+    #[sea_orm(nullable)]
+    pub description: Option<String>,
+
+    /// Ticket title with `{{variable}}` placeholders
+    pub title_template: String,
+
+    /// Ticket description with `{{variable}}` placeholders
+    #[sea_orm(column_type = "Text")]
+    pub description_template: String,
+
+    #[sea_orm(nullable)]
+    pub default_status: Option<String>,
+
+    #[sea_orm(nullable)]
+    pub default_story_points: Option<i32>,
+
+    /// JSON array of label names
+    #[sea_orm(column_type = "Text")]
+    pub default_labels: String,
+
+    /// JSON array of `TemplateSubtask`
+    #[sea_orm(column_type = "Text")]
+    pub subtasks: String,
+
+    pub created_by: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+/// Sub-task created under the ticket when a template is instantiated
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemplateSubtask {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub story_points: Option<i32>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl Model {
+    pub fn default_labels(&self) -> Vec<String> {
+        serde_json::from_str(&self.default_labels).unwrap_or_default()
+    }
+
+    pub fn subtasks(&self) -> Vec<TemplateSubtask> {
+        serde_json::from_str(&self.subtasks).unwrap_or_default()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id"
+    )]
+    Workspace,
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::policy::PolicyViolation;
+
 #[derive(Debug, Error)]
 pub enum CoreError {
     #[error("Resource not found: {0}")]
@@ -11,6 +13,10 @@ pub enum CoreError {
     #[error("Database error: {0}")]
     Database(#[from] sea_orm::DbErr),
 
+    /// A change breaks one of the project's policies
+    #[error("Policy violation: {0}")]
+    PolicyViolation(PolicyViolation),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 }
@@ -0,0 +1,433 @@
+//! Ticket history replay
+//!
+//! Rebuilds the state of a ticket at any point in time from its `ticket_change`
+//! rows. The `created` change carries a JSON snapshot of the ticket row; every
+//! later change is applied on top of it in chronological order.
+//!
+//! Sprint reporting works the other way round: [`SprintTimeline`] starts from the
+//! ticket's current state and undoes newer changes, so tickets that predate change
+//! tracking still have a well-defined past.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::entities::{ticket, ticket_change, ChangeType};
+use crate::workflow::{StatusCategory, Workflow};
+
+/// The revertable fields of a ticket at a given point in its history
+#[derive(Debug, Clone, PartialEq)]
+pub struct TicketSnapshot {
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    pub story_points: Option<i32>,
+    pub assignees: BTreeSet<String>,
+    pub labels: BTreeSet<String>,
+    pub parent_id: Option<Uuid>,
+    pub epic_id: Option<Uuid>,
+}
+
+impl TicketSnapshot {
+    /// Build a snapshot from the current ticket row and its related rows
+    pub fn from_ticket(
+        ticket: &ticket::Model,
+        assignees: impl IntoIterator<Item = String>,
+        labels: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self {
+            title: ticket.title.clone(),
+            description: ticket.description.clone(),
+            status: ticket.status.clone(),
+            story_points: ticket.story_points,
+            assignees: assignees.into_iter().collect(),
+            labels: labels.into_iter().collect(),
+            parent_id: ticket.parent_id,
+            epic_id: ticket.epic_id,
+        }
+    }
+
+    /// Replay changes (sorted by `changed_at`) into a snapshot.
+    ///
+    /// Creating a ticket records its assignees and labels at the same instant
+    /// as the `created` entry, and engines order such ties differently, so
+    /// changes sharing the `created` entry's timestamp count as after it
+    /// wherever they were sorted.
+    ///
+    /// Returns `None` if the changes do not start with a parseable `created`
+    /// (or `imported`) entry.
+    pub fn replay<'a>(
+        changes: impl IntoIterator<Item = &'a ticket_change::Model>,
+    ) -> Option<Self> {
+        let changes: Vec<&ticket_change::Model> = changes.into_iter().collect();
+
+        let position = changes.iter().position(|c| {
+            c.change_type == ChangeType::Created.as_str()
+                || c.change_type == ChangeType::Imported.as_str()
+        })?;
+        let created = changes[position];
+        let initial: ticket::Model = serde_json::from_str(created.new_value.as_deref()?).ok()?;
+        let mut snapshot = Self::from_ticket(&initial, Vec::new(), Vec::new());
+
+        let tied = changes[..position]
+            .iter()
+            .filter(|c| c.changed_at == created.changed_at);
+        for change in tied.chain(&changes[position + 1..]) {
+            snapshot.apply(change);
+        }
+
+        Some(snapshot)
+    }
+
+    /// Apply a single change on top of this snapshot
+    pub fn apply(&mut self, change: &ticket_change::Model) {
+        let Ok(change_type) = ChangeType::from_str(&change.change_type) else {
+            return;
+        };
+        let new_value = change.new_value.clone();
+
+        match change_type {
+            ChangeType::TitleChanged => {
+                if let Some(title) = new_value {
+                    self.title = title;
+                }
+            }
+            ChangeType::DescriptionChanged => {
+                self.description = new_value.unwrap_or_default();
+            }
+            ChangeType::StatusChanged => {
+                if let Some(status) = new_value {
+                    self.status = status;
+                }
+            }
+            ChangeType::StoryPointsChanged => {
+                self.story_points = new_value.and_then(|v| v.parse().ok());
+            }
+            ChangeType::AssigneeAdded => {
+                if let Some(assignee) = new_value {
+                    self.assignees.insert(assignee);
+                }
+            }
+            ChangeType::AssigneeRemoved => {
+                if let Some(assignee) = &change.old_value {
+                    self.assignees.remove(assignee);
+                }
+            }
+            ChangeType::LabelAdded => {
+                if let Some(label) = new_value {
+                    self.labels.insert(label);
+                }
+            }
+            ChangeType::LabelRemoved => {
+                if let Some(label) = &change.old_value {
+                    self.labels.remove(label);
+                }
+            }
+            ChangeType::ParentChanged => {
+                self.parent_id = new_value.and_then(|v| Uuid::parse_str(&v).ok());
+            }
+            ChangeType::EpicChanged => {
+                self.epic_id = new_value.and_then(|v| Uuid::parse_str(&v).ok());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sprint-relevant state of a ticket at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SprintTicketState {
+    pub in_sprint: bool,
+    pub done: bool,
+    pub story_points: Option<i32>,
+}
+
+/// Status, story point and sprint membership history of one ticket for one sprint
+#[derive(Debug, Clone)]
+pub struct SprintTimeline {
+    sprint_id: String,
+    /// Statuses in the project workflow's done category
+    done_statuses: Vec<String>,
+    current: SprintTicketState,
+    deleted_at: Option<DateTime<Utc>>,
+    /// Relevant changes, newest first
+    changes: Vec<ticket_change::Model>,
+}
+
+impl SprintTimeline {
+    /// Build a timeline from the current ticket row, whether it is currently in the
+    /// sprint, and its `ticket_change` rows (in any order)
+    pub fn new(
+        sprint_id: Uuid,
+        workflow: &Workflow,
+        ticket: &ticket::Model,
+        in_sprint: bool,
+        changes: impl IntoIterator<Item = ticket_change::Model>,
+    ) -> Self {
+        let relevant = [
+            ChangeType::StatusChanged.as_str(),
+            ChangeType::StoryPointsChanged.as_str(),
+            ChangeType::AddedToSprint.as_str(),
+            ChangeType::RemovedFromSprint.as_str(),
+        ];
+
+        let mut changes: Vec<_> = changes
+            .into_iter()
+            .filter(|c| c.ticket_id == ticket.id && relevant.contains(&c.change_type.as_str()))
+            .collect();
+        changes.sort_by_key(|c| Reverse(c.changed_at));
+
+        Self {
+            sprint_id: sprint_id.to_string(),
+            done_statuses: workflow
+                .statuses
+                .iter()
+                .filter(|s| s.category == StatusCategory::Done)
+                .map(|s| s.key.clone())
+                .collect(),
+            current: SprintTicketState {
+                in_sprint,
+                done: workflow.is_done(&ticket.status),
+                story_points: ticket.story_points,
+            },
+            deleted_at: ticket.deleted_at,
+            changes,
+        }
+    }
+
+    /// State of the ticket as of `at`, undoing every change recorded after it
+    pub fn state_at(&self, at: DateTime<Utc>) -> SprintTicketState {
+        let mut state = self.current;
+
+        for change in self.changes.iter().take_while(|c| c.changed_at > at) {
+            self.undo(&mut state, change);
+        }
+
+        if self.deleted_at.is_some_and(|deleted_at| deleted_at <= at) {
+            state.in_sprint = false;
+        }
+
+        state
+    }
+
+    /// Whether the ticket was completed for this sprint within `start..=end`: it
+    /// moved to done inside the window and was still done and in the sprint at `end`
+    pub fn completed_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        let at_end = self.state_at(end);
+        if !at_end.in_sprint || !at_end.done {
+            return false;
+        }
+
+        self.changes.iter().any(|c| {
+            c.changed_at > start
+                && c.changed_at <= end
+                && c.change_type == ChangeType::StatusChanged.as_str()
+                && c.new_value.as_ref().is_some_and(|status| self.is_done(status))
+        })
+    }
+
+    fn is_done(&self, status: &str) -> bool {
+        self.done_statuses.iter().any(|s| s == status)
+    }
+
+    fn undo(&self, state: &mut SprintTicketState, change: &ticket_change::Model) {
+        let Ok(change_type) = ChangeType::from_str(&change.change_type) else {
+            return;
+        };
+
+        match change_type {
+            ChangeType::StatusChanged => {
+                if let Some(old) = &change.old_value {
+                    state.done = self.is_done(old);
+                }
+            }
+            ChangeType::StoryPointsChanged => {
+                state.story_points = change.old_value.as_ref().and_then(|v| v.parse().ok());
+            }
+            ChangeType::AddedToSprint
+                if change.new_value.as_deref() == Some(self.sprint_id.as_str()) =>
+            {
+                state.in_sprint = false;
+            }
+            ChangeType::RemovedFromSprint
+                if change.old_value.as_deref() == Some(self.sprint_id.as_str()) =>
+            {
+                state.in_sprint = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn change(
+        change_type: ChangeType,
+        old_value: Option<&str>,
+        new_value: Option<&str>,
+        offset: i64,
+    ) -> ticket_change::Model {
+        ticket_change::Model {
+            id: Uuid::new_v4(),
+            ticket_id: Uuid::nil(),
+            change_type: change_type.as_str().to_string(),
+            field_name: None,
+            old_value: old_value.map(str::to_string),
+            new_value: new_value.map(str::to_string),
+            changed_by: "alice".to_string(),
+            changed_at: Utc::now() + Duration::seconds(offset),
+            message: None,
+        }
+    }
+
+    fn created() -> ticket_change::Model {
+        let now = Utc::now();
+        let ticket = ticket::Model {
+            id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            ticket_number: 1,
+            title: "Original".to_string(),
+            description: "First draft".to_string(),
+            status: "backlog".to_string(),
+            story_points: None,
+            epic_id: None,
+            parent_id: None,
+            is_epic: false,
+            epic_color: None,
+            parent_epic_id: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            created_by: "alice".to_string(),
+        };
+        change(
+            ChangeType::Created,
+            None,
+            Some(&serde_json::to_string(&ticket).unwrap()),
+            0,
+        )
+    }
+
+    #[test]
+    fn test_replay_applies_changes_in_order() {
+        let changes = vec![
+            created(),
+            change(ChangeType::TitleChanged, Some("Original"), Some("Renamed"), 1),
+            change(ChangeType::StatusChanged, Some("backlog"), Some("in_progress"), 2),
+            change(ChangeType::StoryPointsChanged, None, Some("5"), 3),
+            change(ChangeType::AssigneeAdded, None, Some("bob"), 4),
+            change(ChangeType::LabelAdded, None, Some("bug"), 5),
+            change(ChangeType::AssigneeRemoved, Some("bob"), None, 6),
+        ];
+
+        let snapshot = TicketSnapshot::replay(&changes).unwrap();
+        assert_eq!(snapshot.title, "Renamed");
+        assert_eq!(snapshot.description, "First draft");
+        assert_eq!(snapshot.status, "in_progress");
+        assert_eq!(snapshot.story_points, Some(5));
+        assert!(snapshot.assignees.is_empty());
+        assert!(snapshot.labels.contains("bug"));
+    }
+
+    #[test]
+    fn test_replay_stops_at_prefix() {
+        let changes = [
+            created(),
+            change(ChangeType::DescriptionChanged, Some("First draft"), Some("Good"), 1),
+            change(ChangeType::DescriptionChanged, Some("Good"), Some("Broken"), 2),
+        ];
+
+        let snapshot = TicketSnapshot::replay(&changes[..2]).unwrap();
+        assert_eq!(snapshot.description, "Good");
+    }
+
+    #[test]
+    fn test_replay_keeps_changes_tied_with_created() {
+        let created = created();
+        let mut assigned = change(ChangeType::AssigneeAdded, None, Some("bob"), 0);
+        let mut labelled = change(ChangeType::LabelAdded, None, Some("bug"), 0);
+        assigned.changed_at = created.changed_at;
+        labelled.changed_at = created.changed_at;
+
+        let snapshot = TicketSnapshot::replay(&[assigned, labelled, created]).unwrap();
+        assert!(snapshot.assignees.contains("bob"));
+        assert!(snapshot.labels.contains("bug"));
+    }
+
+    #[test]
+    fn test_replay_requires_created_entry() {
+        let changes = vec![change(ChangeType::TitleChanged, None, Some("x"), 0)];
+        assert!(TicketSnapshot::replay(&changes).is_none());
+    }
+
+    fn sprint_ticket(status: &str, story_points: Option<i32>) -> ticket::Model {
+        let mut ticket: ticket::Model =
+            serde_json::from_str(created().new_value.as_deref().unwrap()).unwrap();
+        ticket.status = status.to_string();
+        ticket.story_points = story_points;
+        ticket
+    }
+
+    #[test]
+    fn test_sprint_timeline_undoes_later_changes() {
+        let sprint_id = Uuid::new_v4();
+        let sprint = sprint_id.to_string();
+        let start = Utc::now();
+        let ticket = sprint_ticket("done", Some(8));
+
+        let changes = vec![
+            change(ChangeType::AddedToSprint, None, Some(&sprint), 1),
+            change(ChangeType::StoryPointsChanged, Some("3"), Some("8"), 2),
+            change(ChangeType::StatusChanged, Some("in_progress"), Some("done"), 3),
+        ];
+        let timeline = SprintTimeline::new(sprint_id, &Workflow::default(), &ticket, true, changes);
+
+        let before = timeline.state_at(start);
+        assert!(!before.in_sprint);
+        assert_eq!(before.story_points, Some(3));
+        assert!(!before.done);
+
+        let mid = timeline.state_at(start + Duration::milliseconds(2500));
+        assert!(mid.in_sprint);
+        assert_eq!(mid.story_points, Some(8));
+        assert!(!mid.done);
+
+        assert!(timeline.state_at(start + Duration::seconds(10)).done);
+    }
+
+    #[test]
+    fn test_completed_between_ignores_work_outside_window() {
+        let sprint_id = Uuid::new_v4();
+        let sprint = sprint_id.to_string();
+        let start = Utc::now();
+        let ticket = sprint_ticket("done", Some(5));
+
+        let changes = vec![
+            change(ChangeType::AddedToSprint, None, Some(&sprint), -10),
+            change(ChangeType::StatusChanged, Some("todo"), Some("done"), 20),
+        ];
+        let timeline = SprintTimeline::new(sprint_id, &Workflow::default(), &ticket, true, changes);
+
+        assert!(!timeline.completed_between(start, start + Duration::seconds(10)));
+        assert!(timeline.completed_between(start, start + Duration::seconds(30)));
+    }
+
+    #[test]
+    fn test_sprint_timeline_restores_removed_ticket() {
+        let sprint_id = Uuid::new_v4();
+        let sprint = sprint_id.to_string();
+        let start = Utc::now();
+        let ticket = sprint_ticket("todo", Some(2));
+
+        let changes = vec![change(ChangeType::RemovedFromSprint, Some(&sprint), None, 5)];
+        let timeline = SprintTimeline::new(sprint_id, &Workflow::default(), &ticket, false, changes);
+
+        assert!(timeline.state_at(start).in_sprint);
+        assert!(!timeline.state_at(start + Duration::seconds(10)).in_sprint);
+    }
+}
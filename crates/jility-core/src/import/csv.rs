@@ -0,0 +1,146 @@
+//! Minimal CSV reader
+//!
+//! Reads the RFC 4180 dialect trackers export: comma-separated, fields
+//! optionally wrapped in double quotes, `""` for a quote inside a quoted
+//! field, and line breaks allowed inside quoted fields. Stray quotes in an
+//! unquoted field are kept as text.
+
+use std::fmt;
+
+/// One row of a CSV file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Line the record starts on, counting from 1
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+impl Record {
+    /// The field at `index`, trimmed; empty when the row is short
+    pub fn get(&self, index: usize) -> &str {
+        self.fields.get(index).map(|f| f.trim()).unwrap_or_default()
+    }
+
+    fn is_blank(&self) -> bool {
+        self.fields.iter().all(|f| f.trim().is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Every non-blank record of `input`, header included
+pub fn parse(input: &str) -> Result<Vec<Record>, CsvError> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut records = Vec::new();
+    let mut record = Record {
+        line: 1,
+        fields: Vec::new(),
+    };
+    let mut field = String::new();
+    let mut line = 1;
+    // Inside a quoted field, and where it started
+    let mut quoted: Option<usize> = None;
+    // The current field started with a quote
+    let mut was_quoted = false;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted.is_some() {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = None,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !was_quoted => {
+                quoted = Some(line);
+                was_quoted = true;
+            }
+            ',' => {
+                record.fields.push(std::mem::take(&mut field));
+                was_quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.fields.push(std::mem::take(&mut field));
+                was_quoted = false;
+                line += 1;
+                let next = Record {
+                    line,
+                    fields: Vec::new(),
+                };
+                let done = std::mem::replace(&mut record, next);
+                if !done.is_blank() {
+                    records.push(done);
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if let Some(start) = quoted {
+        return Err(CsvError {
+            line: start,
+            message: "quoted field is never closed".to_string(),
+        });
+    }
+    if !field.is_empty() || was_quoted || !record.fields.is_empty() {
+        record.fields.push(field);
+    }
+    if !record.is_blank() {
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quotes_and_line_breaks() {
+        let input = "\u{feff}Summary,Issue key,Description\r\n\
+                     Plain,PROJ-1,\r\n\
+                     \"With, comma\",PROJ-2,\"Line one\nline \"\"two\"\"\"\r\n\
+                     \r\n\
+                     Trailing,PROJ-3,say \"hi\"";
+        let records = parse(input).unwrap();
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].fields, ["Summary", "Issue key", "Description"]);
+        assert_eq!(records[1].fields, ["Plain", "PROJ-1", ""]);
+        assert_eq!(records[2].line, 3);
+        assert_eq!(records[2].get(0), "With, comma");
+        assert_eq!(records[2].get(2), "Line one\nline \"two\"");
+        assert_eq!(records[3].line, 6);
+        assert_eq!(records[3].get(2), "say \"hi\"");
+        assert_eq!(records[3].get(7), "");
+
+        let err = parse("a,b\n\"open,c").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}
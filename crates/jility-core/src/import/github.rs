@@ -0,0 +1,655 @@
+//! GitHub Issues exports
+//!
+//! Reads the JSON the REST API returns for a repository's issues and issue
+//! comments, as saved by `gh api`:
+//!
+//! ```text
+//! gh api --paginate 'repos/OWNER/REPO/issues?state=all' > issues.json
+//! gh api --paginate 'repos/OWNER/REPO/issues/comments' > comments.json
+//! ```
+//!
+//! A file may hold one array, the pages `--paginate` prints one after
+//! another, or the array of pages `--slurp` makes; issues and comments may
+//! share a file. Issues are keyed `OWNER/REPO#NUMBER`, so the backlogs of
+//! several repositories can go into one project; an issue without the URLs
+//! naming its repository is an error. Pull requests, which the issues
+//! endpoint lists too, are skipped along with their comments.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{ImportPlan, ImportedComment, ImportedIssue, ImportedSprint, Origin, Severity};
+use crate::workflow::{StatusCategory, Workflow};
+
+/// Source recorded on aliases and changes of issues imported from GitHub
+pub const SOURCE: &str = "github";
+
+/// Author of comments whose GitHub account was deleted
+const GHOST: &str = "ghost";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GithubMapping {
+    /// Status of open issues; the workflow's initial status by default
+    pub open: Option<String>,
+    /// Status of closed issues; the workflow's first done status by default
+    pub closed: Option<String>,
+    /// Labels that put an open issue in a status, e.g.
+    /// `{"in progress": "in_progress"}`. They aren't kept as labels.
+    pub status_labels: HashMap<String, String>,
+    /// GitHub label to Jility label; `null` drops the label. Labels not
+    /// listed are kept as is.
+    pub labels: HashMap<String, Option<String>>,
+    /// GitHub login to Jility username. Logins not listed are kept as is.
+    pub users: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+/// The API gives label objects; other tools save just the names
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Label {
+    Name(String),
+    Object { name: String },
+}
+
+impl Label {
+    fn name(&self) -> &str {
+        match self {
+            Label::Name(name) | Label::Object { name } => name.trim(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Milestone {
+    title: String,
+    description: Option<String>,
+    state: Option<String>,
+    due_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    number: u64,
+    id: Option<u64>,
+    #[serde(default)]
+    title: String,
+    body: Option<String>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    labels: Vec<Label>,
+    milestone: Option<Milestone>,
+    #[serde(default)]
+    assignees: Vec<User>,
+    assignee: Option<User>,
+    user: Option<User>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    pull_request: Option<Value>,
+    repository_url: Option<String>,
+    html_url: Option<String>,
+}
+
+impl Issue {
+    fn repository(&self) -> Option<String> {
+        self.repository_url
+            .as_deref()
+            .and_then(api_repository)
+            .or_else(|| self.html_url.as_deref().and_then(web_repository))
+    }
+
+    fn key(&self) -> String {
+        format!("{}#{}", self.repository().unwrap_or_default(), self.number)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Comment {
+    id: Option<u64>,
+    issue_url: String,
+    user: Option<User>,
+    body: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    /// Key of the issue the comment is on
+    fn issue_key(&self) -> String {
+        let number = self.issue_url.rsplit('/').next().unwrap_or_default();
+        let repository = api_repository(&self.issue_url).unwrap_or_default();
+        format!("{}#{}", repository, number)
+    }
+}
+
+/// `OWNER/REPO` of an API URL, e.g. `https://api.github.com/repos/OWNER/REPO/issues/1`
+fn api_repository(url: &str) -> Option<String> {
+    let mut parts = url.split_once("/repos/")?.1.split('/');
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    (!owner.is_empty() && !repo.is_empty()).then(|| format!("{}/{}", owner, repo))
+}
+
+/// `OWNER/REPO` of a web URL, e.g. `https://github.com/OWNER/REPO/issues/1`
+fn web_repository(url: &str) -> Option<String> {
+    let mut parts = url.split("://").nth(1)?.split('/').skip(1);
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    (!owner.is_empty() && !repo.is_empty()).then(|| format!("{}/{}", owner, repo))
+}
+
+/// Every item of a file: the values of its arrays, however they're nested
+fn items(content: &str) -> Result<Vec<Value>, serde_json::Error> {
+    fn flatten(value: Value, items: &mut Vec<Value>) {
+        match value {
+            Value::Array(values) => values.into_iter().for_each(|v| flatten(v, items)),
+            value => items.push(value),
+        }
+    }
+
+    let mut items = Vec::new();
+    for value in serde_json::Deserializer::from_str(content).into_iter::<Value>() {
+        flatten(value?, &mut items);
+    }
+    Ok(items)
+}
+
+/// Read GitHub issue and comment exports, given as `(file name, content)`,
+/// into a plan for a project using `workflow`
+pub fn plan<'a>(
+    files: impl IntoIterator<Item = (&'a str, &'a str)>,
+    mapping: &GithubMapping,
+    workflow: &Workflow,
+) -> ImportPlan {
+    let files: Vec<(&str, &str)> = files.into_iter().collect();
+    let mut plan = ImportPlan::new(SOURCE);
+    let mut reader = Reader::new(&mut plan, mapping, workflow);
+    for (name, content) in &files {
+        let file = (files.len() > 1).then(|| name.to_string());
+        reader.read(&mut plan, file, content);
+    }
+    reader.finish(&mut plan);
+    plan
+}
+
+/// Collects the issues and comments of every file, so comments can find
+/// their issue whichever file it's in
+struct Reader<'a> {
+    workflow: &'a Workflow,
+    open: String,
+    closed: String,
+    /// The mapping's tables keyed by lowercase name
+    status_labels: HashMap<String, &'a str>,
+    labels: HashMap<String, Option<&'a str>>,
+    users: HashMap<String, &'a str>,
+    /// Issues by key, each with where it was read
+    issues: HashMap<String, (Issue, Origin)>,
+    pull_requests: HashSet<String>,
+    comments: Vec<Comment>,
+    comment_ids: HashSet<u64>,
+    /// Issues read more than once
+    duplicates: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(plan: &mut ImportPlan, mapping: &'a GithubMapping, workflow: &'a Workflow) -> Self {
+        let mut status = |setting: &str, mapped: Option<&String>, default: Option<&str>| {
+            let wanted = mapped.map(String::as_str).or(default);
+            match wanted {
+                Some(status) if workflow.status(status).is_some() => status.to_string(),
+                Some(status) => {
+                    plan.general(
+                        Severity::Error,
+                        format!(
+                            "\"{}\" is '{}', which isn't a workflow status",
+                            setting, status
+                        ),
+                    );
+                    workflow.initial_status.clone()
+                }
+                None => {
+                    plan.general(
+                        Severity::Error,
+                        format!(
+                            "The workflow has no done status; set \"{}\" to one of: {}",
+                            setting,
+                            workflow.keys().join(", ")
+                        ),
+                    );
+                    workflow.initial_status.clone()
+                }
+            }
+        };
+        let open = status(
+            "open",
+            mapping.open.as_ref(),
+            Some(&workflow.initial_status),
+        );
+        let closed = status(
+            "closed",
+            mapping.closed.as_ref(),
+            workflow.first_in(StatusCategory::Done),
+        );
+
+        let mut status_labels = HashMap::new();
+        for (label, status) in &mapping.status_labels {
+            if workflow.status(status).is_none() {
+                plan.general(
+                    Severity::Error,
+                    format!(
+                        "\"status_labels\" maps \"{}\" to '{}', which isn't a workflow status",
+                        label, status
+                    ),
+                );
+            }
+            status_labels.insert(label.trim().to_lowercase(), status.as_str());
+        }
+
+        Self {
+            workflow,
+            open,
+            closed,
+            status_labels,
+            labels: mapping
+                .labels
+                .iter()
+                .map(|(k, v)| (k.trim().to_lowercase(), v.as_deref()))
+                .collect(),
+            users: mapping
+                .users
+                .iter()
+                .map(|(k, v)| (k.trim().to_lowercase(), v.as_str()))
+                .collect(),
+            issues: HashMap::new(),
+            pull_requests: HashSet::new(),
+            comments: Vec::new(),
+            comment_ids: HashSet::new(),
+            duplicates: 0,
+        }
+    }
+
+    fn read(&mut self, plan: &mut ImportPlan, file: Option<String>, content: &str) {
+        let origin = Origin { file, line: None };
+        let items = match items(content) {
+            Ok(items) => items,
+            Err(e) => {
+                let origin = Origin {
+                    line: Some(e.line()),
+                    ..origin
+                };
+                plan.problem(
+                    Severity::Error,
+                    &origin,
+                    None,
+                    format!("Invalid JSON: {}", e),
+                );
+                return;
+            }
+        };
+        if items.is_empty() {
+            plan.problem(
+                Severity::Error,
+                &origin,
+                None,
+                "The file has no issues or comments",
+            );
+            return;
+        }
+
+        for (i, item) in items.into_iter().enumerate() {
+            let is_issue = item.get("number").is_some();
+            let is_comment = !is_issue && item.get("issue_url").is_some();
+            if is_issue {
+                match serde_json::from_value::<Issue>(item) {
+                    Ok(issue) => self.issue(plan, issue, &origin),
+                    Err(e) => plan.problem(
+                        Severity::Error,
+                        &origin,
+                        None,
+                        format!("Item {} isn't a GitHub issue: {}", i + 1, e),
+                    ),
+                }
+            } else if is_comment {
+                match serde_json::from_value::<Comment>(item) {
+                    Ok(comment) => self.comment(comment),
+                    Err(e) => plan.problem(
+                        Severity::Error,
+                        &origin,
+                        None,
+                        format!("Item {} isn't a GitHub comment: {}", i + 1, e),
+                    ),
+                }
+            } else {
+                plan.problem(
+                    Severity::Error,
+                    &origin,
+                    None,
+                    format!("Item {} is neither an issue nor a comment", i + 1),
+                );
+            }
+        }
+    }
+
+    /// Keep an issue, or its latest copy when exports overlap
+    fn issue(&mut self, plan: &mut ImportPlan, issue: Issue, origin: &Origin) {
+        let key = issue.key();
+        if issue.pull_request.is_some() {
+            self.pull_requests.insert(key);
+            return;
+        }
+        // `#NUMBER` alone would collide across repositories
+        if issue.repository().is_none() {
+            plan.problem(
+                Severity::Error,
+                origin,
+                Some(&key),
+                "No repository_url or html_url, so the issue's repository is unknown",
+            );
+        }
+        if let Some((seen, _)) = self.issues.get(&key) {
+            self.duplicates += 1;
+            if seen.updated_at >= issue.updated_at {
+                return;
+            }
+        }
+        self.issues.insert(key, (issue, origin.clone()));
+    }
+
+    fn comment(&mut self, comment: Comment) {
+        if let Some(id) = comment.id {
+            if !self.comment_ids.insert(id) {
+                return;
+            }
+        }
+        self.comments.push(comment);
+    }
+
+    fn user(&self, login: &str) -> String {
+        self.users
+            .get(&login.to_lowercase())
+            .map(|user| user.to_string())
+            .unwrap_or_else(|| login.to_string())
+    }
+
+    fn finish(mut self, plan: &mut ImportPlan) {
+        let mut comments: HashMap<String, Vec<ImportedComment>> = HashMap::new();
+        let mut orphans = 0;
+        for comment in std::mem::take(&mut self.comments) {
+            let key = comment.issue_key();
+            if !self.issues.contains_key(&key) {
+                if !self.pull_requests.contains(&key) {
+                    orphans += 1;
+                }
+                continue;
+            }
+            let body = comment.body.unwrap_or_default();
+            if body.trim().is_empty() {
+                continue;
+            }
+            comments.entry(key).or_default().push(ImportedComment {
+                source_id: comment.id.map(|id| id.to_string()),
+                author: comment
+                    .user
+                    .map(|u| self.user(&u.login))
+                    .unwrap_or_else(|| GHOST.to_string()),
+                body,
+                created_at: comment.created_at,
+                updated_at: comment.updated_at,
+            });
+        }
+
+        let mut issues: Vec<(String, Issue, Origin)> = std::mem::take(&mut self.issues)
+            .into_iter()
+            .map(|(key, (issue, origin))| (key, issue, origin))
+            .collect();
+        issues.sort_by(|(a_key, a, _), (b_key, b, _)| {
+            (a.created_at, a.number, a_key).cmp(&(b.created_at, b.number, b_key))
+        });
+
+        for (key, issue, origin) in issues {
+            if issue.title.trim().is_empty() {
+                plan.problem(Severity::Error, &origin, Some(&key), "Missing title");
+            }
+
+            let mut status = None;
+            let mut labels: Vec<String> = Vec::new();
+            for label in &issue.labels {
+                let name = label.name();
+                let wanted = name.to_lowercase();
+                if let Some(mapped) = self.status_labels.get(&wanted) {
+                    status = status.or(Some(mapped.to_string()));
+                    continue;
+                }
+                let label = match self.labels.get(&wanted) {
+                    Some(Some(mapped)) => mapped.to_string(),
+                    Some(None) => continue,
+                    None => name.to_string(),
+                };
+                if !label.is_empty() && !labels.contains(&label) {
+                    labels.push(label);
+                }
+            }
+            let status = if issue.state.eq_ignore_ascii_case("closed") {
+                self.closed.clone()
+            } else {
+                status
+                    .filter(|s| self.workflow.status(s).is_some())
+                    .unwrap_or_else(|| self.open.clone())
+            };
+
+            let mut assignees: Vec<String> = Vec::new();
+            for user in issue.assignees.iter().chain(&issue.assignee) {
+                let user = self.user(&user.login);
+                if !assignees.contains(&user) {
+                    assignees.push(user);
+                }
+            }
+
+            // Each issue repeats its milestone; keep whatever any copy has
+            let sprint = issue.milestone.map(|milestone| {
+                let goal = milestone.description.filter(|d| !d.trim().is_empty());
+                let completed = milestone
+                    .state
+                    .is_some_and(|s| s.eq_ignore_ascii_case("closed"));
+                match plan.sprints.iter_mut().find(|s| s.name == milestone.title) {
+                    Some(sprint) => {
+                        sprint.goal = sprint.goal.take().or(goal);
+                        sprint.end_date = sprint.end_date.or(milestone.due_on);
+                        sprint.completed |= completed;
+                    }
+                    None => plan.sprints.push(ImportedSprint {
+                        name: milestone.title.clone(),
+                        goal,
+                        end_date: milestone.due_on,
+                        completed,
+                    }),
+                }
+                milestone.title
+            });
+
+            let mut issue_comments = comments.remove(&key).unwrap_or_default();
+            issue_comments.sort_by_key(|c| c.created_at);
+
+            plan.issues.push(ImportedIssue {
+                source_id: issue.id.map(|id| id.to_string()),
+                origin,
+                title: issue.title.trim().to_string(),
+                description: issue.body.unwrap_or_default(),
+                status,
+                story_points: None,
+                is_epic: false,
+                assignees,
+                labels,
+                reporter: issue.user.map(|u| self.user(&u.login)),
+                epic: None,
+                parent: None,
+                sprint,
+                created_at: issue.created_at,
+                updated_at: issue.updated_at,
+                comments: issue_comments,
+                key,
+            });
+        }
+
+        if self.duplicates > 0 {
+            plan.general(
+                Severity::Warning,
+                format!(
+                    "{} issue(s) appear in more than one file; the most recently updated copy is kept",
+                    self.duplicates
+                ),
+            );
+        }
+        if orphans > 0 {
+            plan.general(
+                Severity::Warning,
+                format!(
+                    "{} comment(s) belong to issues that aren't in the export; skipped",
+                    orphans
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUES: &str = r#"[
+      {"number": 2, "id": 902, "title": "Crash on save", "body": null, "state": "open",
+       "repository_url": "https://api.github.com/repos/acme/app",
+       "labels": [{"name": "bug"}, {"name": "status: doing"}, {"name": "wontfix"}],
+       "milestone": {"title": "v1.0", "description": "First release", "state": "closed", "due_on": "2024-04-01T07:00:00Z"},
+       "assignees": [{"login": "Octocat"}], "user": {"login": "hubot"},
+       "created_at": "2024-03-02T10:00:00Z", "updated_at": "2024-03-05T10:00:00Z"},
+      {"number": 3, "title": "Add linting", "state": "open", "pull_request": {},
+       "repository_url": "https://api.github.com/repos/acme/app"}
+    ][
+      {"number": 1, "id": 901, "title": "Write docs", "body": "Start with the API", "state": "closed",
+       "html_url": "https://github.com/acme/app/issues/1", "labels": ["docs"],
+       "milestone": {"title": "v1.0"},
+       "created_at": "2024-03-01T10:00:00Z", "updated_at": "2024-03-01T10:00:00Z"}
+    ]"#;
+
+    const COMMENTS: &str = r#"[[
+      {"id": 11, "issue_url": "https://api.github.com/repos/acme/app/issues/2",
+       "user": {"login": "octocat"}, "body": "Second", "created_at": "2024-03-04T10:00:00Z"},
+      {"id": 10, "issue_url": "https://api.github.com/repos/acme/app/issues/2",
+       "user": null, "body": "First", "created_at": "2024-03-03T10:00:00Z"},
+      {"id": 12, "issue_url": "https://api.github.com/repos/acme/app/issues/3",
+       "user": {"login": "octocat"}, "body": "On the PR"},
+      {"id": 13, "issue_url": "https://api.github.com/repos/acme/app/issues/7",
+       "user": {"login": "octocat"}, "body": "Lost"}
+    ]]"#;
+
+    #[test]
+    fn test_plan_reads_issues_and_comments() {
+        let mut mapping = GithubMapping::default();
+        mapping
+            .status_labels
+            .insert("Status: Doing".to_string(), "in_progress".to_string());
+        mapping.labels.insert("wontfix".to_string(), None);
+        mapping
+            .users
+            .insert("octocat".to_string(), "alice".to_string());
+        let plan = plan(
+            [("issues.json", ISSUES), ("comments.json", COMMENTS)],
+            &mapping,
+            &Workflow::default(),
+        );
+
+        let keys: Vec<&str> = plan.issues.iter().map(|i| i.key.as_str()).collect();
+        assert_eq!(keys, ["acme/app#1", "acme/app#2"]);
+
+        let docs = &plan.issues[0];
+        assert_eq!(docs.status, "done");
+        assert_eq!(docs.labels, ["docs"]);
+        assert_eq!(docs.source_id.as_deref(), Some("901"));
+
+        let crash = &plan.issues[1];
+        assert_eq!(crash.status, "in_progress");
+        assert_eq!(crash.labels, ["bug"]);
+        assert_eq!(crash.assignees, ["alice"]);
+        assert_eq!(crash.reporter.as_deref(), Some("hubot"));
+        assert_eq!(crash.description, "");
+        assert_eq!(crash.sprint.as_deref(), Some("v1.0"));
+        let comments: Vec<(Option<&str>, &str, &str)> = crash
+            .comments
+            .iter()
+            .map(|c| (c.source_id.as_deref(), c.author.as_str(), c.body.as_str()))
+            .collect();
+        assert_eq!(
+            comments,
+            [
+                (Some("10"), "ghost", "First"),
+                (Some("11"), "alice", "Second")
+            ]
+        );
+
+        assert_eq!(plan.sprints.len(), 1);
+        assert_eq!(plan.sprints[0].goal.as_deref(), Some("First release"));
+        assert!(plan.sprints[0].completed);
+
+        let messages: Vec<&str> = plan.problems.iter().map(|p| p.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["1 comment(s) belong to issues that aren't in the export; skipped"]
+        );
+    }
+
+    #[test]
+    fn test_plan_reports_bad_input() {
+        let mapping = GithubMapping {
+            closed: Some("shipped".to_string()),
+            ..GithubMapping::default()
+        };
+        let plan = plan(
+            [
+                (
+                    "a.json",
+                    r#"[{"number": 1, "title": " ", "state": "open"}, {"x": 1}]"#,
+                ),
+                ("b.json", "[{\"number\": 2,\n \"title\": }]"),
+            ],
+            &mapping,
+            &Workflow::default(),
+        );
+
+        let messages: Vec<String> = plan
+            .problems
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} {:?} {}",
+                    p.file.as_deref().unwrap_or(""),
+                    p.line,
+                    p.message
+                )
+            })
+            .collect();
+        assert_eq!(
+            messages[0],
+            " None \"closed\" is 'shipped', which isn't a workflow status"
+        );
+        assert_eq!(
+            messages[1],
+            "a.json None No repository_url or html_url, so the issue's repository is unknown"
+        );
+        assert_eq!(plan.problems[1].key.as_deref(), Some("#1"));
+        assert_eq!(
+            messages[2],
+            "a.json None Item 2 is neither an issue nor a comment"
+        );
+        assert!(messages[3].starts_with("b.json Some(2) Invalid JSON"));
+        assert_eq!(messages[4], "a.json None Missing title");
+        assert!(plan.has_errors());
+    }
+}
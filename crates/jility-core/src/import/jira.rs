@@ -0,0 +1,640 @@
+//! Jira CSV exports
+//!
+//! Reads the CSV Jira produces from an issue search (Export > CSV). Jira
+//! repeats a column once per value of a multi-valued field such as `Labels`
+//! or `Sprint`; every copy is read. A [`JiraMapping`] says which columns feed
+//! which ticket fields and how Jira statuses and users translate to Jility.
+//! Large projects can be exported in batches and read together, so links
+//! between batches resolve.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Deserialize;
+
+use super::{csv, ImportPlan, ImportedIssue, Origin, Severity};
+use crate::workflow::Workflow;
+
+/// Source recorded on aliases and changes of issues imported from Jira
+pub const SOURCE: &str = "jira";
+
+/// Formats of Jira's `Created` and `Updated` columns, tried after RFC 3339
+const DATE_FORMATS: [&str; 4] = [
+    "%d/%b/%y %I:%M %p",
+    "%d/%b/%Y %I:%M %p",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+];
+
+/// Common Jira statuses and the built-in status each one matches
+const STATUS_SYNONYMS: [(&str, &str); 12] = [
+    ("open", "todo"),
+    ("new", "todo"),
+    ("reopened", "todo"),
+    ("selected for development", "todo"),
+    ("in review", "review"),
+    ("code review", "review"),
+    ("in qa", "review"),
+    ("testing", "review"),
+    ("closed", "done"),
+    ("resolved", "done"),
+    ("won't do", "done"),
+    ("on hold", "blocked"),
+];
+
+/// Column names to read each field from. Unset fields use Jira's default
+/// column names; a column named here must exist in every file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct JiraColumns {
+    pub key: Option<String>,
+    pub id: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub issue_type: Option<String>,
+    pub status: Option<String>,
+    pub assignee: Option<String>,
+    pub reporter: Option<String>,
+    /// Every column whose values become labels, e.g. `["Labels", "Component/s"]`
+    pub labels: Option<Vec<String>>,
+    pub story_points: Option<String>,
+    pub epic_link: Option<String>,
+    pub parent: Option<String>,
+    pub sprint: Option<String>,
+    pub created: Option<String>,
+    pub updated: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JiraMapping {
+    pub columns: JiraColumns,
+    /// Jira status to workflow status key. Statuses not listed are matched
+    /// to a workflow status of the same name.
+    pub statuses: HashMap<String, String>,
+    /// Jira user, as the export shows them, to Jility username. Users not
+    /// listed are kept as exported.
+    pub users: HashMap<String, String>,
+    /// Issue types imported as epics
+    pub epic_types: Vec<String>,
+    /// `strftime` format of dates, when the export doesn't use Jira's default
+    pub date_format: Option<String>,
+}
+
+impl Default for JiraMapping {
+    fn default() -> Self {
+        Self {
+            columns: JiraColumns::default(),
+            statuses: HashMap::new(),
+            users: HashMap::new(),
+            epic_types: vec!["Epic".to_string()],
+            date_format: None,
+        }
+    }
+}
+
+/// Read Jira CSV exports, given as `(file name, content)`, into a plan for
+/// a project using `workflow`
+pub fn plan<'a>(
+    files: impl IntoIterator<Item = (&'a str, &'a str)>,
+    mapping: &JiraMapping,
+    workflow: &Workflow,
+) -> ImportPlan {
+    let files: Vec<(&str, &str)> = files.into_iter().collect();
+    let mut plan = ImportPlan::new(SOURCE);
+    let mut reader = Reader::new(mapping, workflow);
+    for (name, content) in &files {
+        let file = (files.len() > 1).then(|| name.to_string());
+        reader.read(&mut plan, file, content);
+    }
+    reader.finish(&mut plan);
+    plan.link();
+    plan
+}
+
+/// Column indexes of one file
+struct Columns {
+    key: usize,
+    summary: usize,
+    id: Option<usize>,
+    description: Option<usize>,
+    issue_type: Option<usize>,
+    status: Option<usize>,
+    assignee: Option<usize>,
+    reporter: Option<usize>,
+    labels: Vec<usize>,
+    story_points: Option<usize>,
+    epic_link: Option<usize>,
+    parent: Option<usize>,
+    sprint: Vec<usize>,
+    created: Option<usize>,
+    updated: Option<usize>,
+}
+
+impl Columns {
+    fn find(header: &csv::Record, columns: &JiraColumns) -> Result<Self, Vec<String>> {
+        let mut missing = Vec::new();
+        let all = |name: &str| -> Vec<usize> {
+            header
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| f.trim().eq_ignore_ascii_case(name))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        // Every copy of the named column, or of the first default present
+        let mut lookup = |named: &Option<String>, defaults: &[&str], required: bool| {
+            if let Some(name) = named {
+                let found = all(name);
+                if found.is_empty() {
+                    missing.push(format!("Column \"{}\" not found", name));
+                }
+                return found;
+            }
+            let found = defaults
+                .iter()
+                .map(|name| all(name))
+                .find(|found| !found.is_empty())
+                .unwrap_or_default();
+            if found.is_empty() && required {
+                missing.push(format!("Column \"{}\" not found", defaults[0]));
+            }
+            found
+        };
+
+        let key = lookup(&columns.key, &["Issue key"], true);
+        let summary = lookup(&columns.summary, &["Summary"], true);
+        let id = lookup(&columns.id, &["Issue id"], false);
+        let description = lookup(&columns.description, &["Description"], false);
+        let issue_type = lookup(&columns.issue_type, &["Issue Type"], false);
+        let status = lookup(&columns.status, &["Status"], false);
+        let assignee = lookup(&columns.assignee, &["Assignee"], false);
+        let reporter = lookup(&columns.reporter, &["Reporter"], false);
+        let labels = match &columns.labels {
+            Some(names) => names
+                .iter()
+                .flat_map(|name| lookup(&Some(name.clone()), &[], false))
+                .collect(),
+            None => lookup(&None, &["Labels"], false),
+        };
+        let story_points = lookup(
+            &columns.story_points,
+            &[
+                "Custom field (Story Points)",
+                "Custom field (Story point estimate)",
+            ],
+            false,
+        );
+        let epic_link = lookup(&columns.epic_link, &["Custom field (Epic Link)"], false);
+        let parent = lookup(&columns.parent, &["Parent id", "Parent"], false);
+        let sprint = lookup(&columns.sprint, &["Sprint"], false);
+        let created = lookup(&columns.created, &["Created"], false);
+        let updated = lookup(&columns.updated, &["Updated"], false);
+
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+        Ok(Self {
+            key: key[0],
+            summary: summary[0],
+            id: id.first().copied(),
+            description: description.first().copied(),
+            issue_type: issue_type.first().copied(),
+            status: status.first().copied(),
+            assignee: assignee.first().copied(),
+            reporter: reporter.first().copied(),
+            labels,
+            story_points: story_points.first().copied(),
+            epic_link: epic_link.first().copied(),
+            parent: parent.first().copied(),
+            sprint,
+            created: created.first().copied(),
+            updated: updated.first().copied(),
+        })
+    }
+}
+
+/// Reads files into a plan, tallying the problems that repeat across issues
+/// so each is reported once
+struct Reader<'a> {
+    mapping: &'a JiraMapping,
+    workflow: &'a Workflow,
+    /// `mapping.statuses` and `mapping.users` keyed by lowercase name
+    statuses: HashMap<String, &'a str>,
+    users: HashMap<String, &'a str>,
+    /// Where each key was first seen
+    keys: HashMap<String, Origin>,
+    /// Jira statuses with no workflow status, and how many issues have each
+    unmapped_statuses: BTreeMap<String, usize>,
+    /// Mapped statuses the workflow doesn't have
+    unknown_statuses: BTreeMap<String, String>,
+    unmapped_users: BTreeMap<String, usize>,
+    /// Unreadable dates: how many, and the first one with its origin
+    bad_dates: Option<(usize, String, Origin)>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(mapping: &'a JiraMapping, workflow: &'a Workflow) -> Self {
+        let lowercase = |map: &'a HashMap<String, String>| {
+            map.iter()
+                .map(|(k, v)| (k.trim().to_lowercase(), v.as_str()))
+                .collect()
+        };
+        Self {
+            mapping,
+            workflow,
+            statuses: lowercase(&mapping.statuses),
+            users: lowercase(&mapping.users),
+            keys: HashMap::new(),
+            unmapped_statuses: BTreeMap::new(),
+            unknown_statuses: BTreeMap::new(),
+            unmapped_users: BTreeMap::new(),
+            bad_dates: None,
+        }
+    }
+
+    fn read(&mut self, plan: &mut ImportPlan, file: Option<String>, content: &str) {
+        let records = match csv::parse(content) {
+            Ok(records) => records,
+            Err(e) => {
+                let origin = Origin {
+                    file,
+                    line: Some(e.line),
+                };
+                plan.problem(
+                    Severity::Error,
+                    &origin,
+                    None,
+                    format!("Invalid CSV: {}", e.message),
+                );
+                return;
+            }
+        };
+        let file_origin = Origin {
+            file: file.clone(),
+            line: None,
+        };
+        let Some((header, rows)) = records.split_first() else {
+            plan.problem(Severity::Error, &file_origin, None, "The file is empty");
+            return;
+        };
+        let columns = match Columns::find(header, &self.mapping.columns) {
+            Ok(columns) => columns,
+            Err(missing) => {
+                for message in missing {
+                    plan.problem(Severity::Error, &file_origin, None, message);
+                }
+                return;
+            }
+        };
+        if columns.status.is_none() {
+            plan.problem(
+                Severity::Warning,
+                &file_origin,
+                None,
+                format!(
+                    "No status column; issues start in '{}'",
+                    self.workflow.initial_status
+                ),
+            );
+        }
+
+        for row in rows {
+            let origin = Origin {
+                file: file.clone(),
+                line: Some(row.line),
+            };
+            if let Some(issue) = self.issue(plan, &columns, row, origin) {
+                plan.issues.push(issue);
+            }
+        }
+    }
+
+    fn issue(
+        &mut self,
+        plan: &mut ImportPlan,
+        columns: &Columns,
+        row: &csv::Record,
+        origin: Origin,
+    ) -> Option<ImportedIssue> {
+        let optional = |index: Option<usize>| {
+            index
+                .map(|i| row.get(i))
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+
+        let key = row.get(columns.key).to_string();
+        if key.is_empty() {
+            plan.problem(Severity::Error, &origin, None, "Missing issue key");
+            return None;
+        }
+        if let Some(first) = self.keys.get(&key) {
+            let message = match (&first.file, first.line) {
+                (Some(file), Some(line)) => {
+                    format!("Duplicate issue key, first seen in {} line {}", file, line)
+                }
+                (None, Some(line)) => format!("Duplicate issue key, first seen on line {}", line),
+                _ => "Duplicate issue key".to_string(),
+            };
+            plan.problem(Severity::Error, &origin, Some(&key), message);
+            return None;
+        }
+        self.keys.insert(key.clone(), origin.clone());
+
+        let title = row.get(columns.summary).to_string();
+        if title.is_empty() {
+            plan.problem(Severity::Error, &origin, Some(&key), "Missing summary");
+        }
+
+        let is_epic = optional(columns.issue_type).is_some_and(|kind| {
+            self.mapping
+                .epic_types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&kind))
+        });
+
+        let status = match optional(columns.status) {
+            Some(status) => self.status(&status),
+            None => self.workflow.initial_status.clone(),
+        };
+
+        let story_points = match optional(columns.story_points) {
+            Some(points) => match points.parse::<f64>() {
+                Ok(points) if points >= 0.0 && points <= i32::MAX as f64 => {
+                    Some(points.round() as i32)
+                }
+                _ => {
+                    plan.problem(
+                        Severity::Warning,
+                        &origin,
+                        Some(&key),
+                        format!("Story points \"{}\" aren't a number; left empty", points),
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut labels: Vec<String> = Vec::new();
+        for &i in &columns.labels {
+            for label in row.get(i).split_whitespace() {
+                if !labels.iter().any(|l| l == label) {
+                    labels.push(label.to_string());
+                }
+            }
+        }
+
+        let assignees = optional(columns.assignee)
+            .map(|user| vec![self.user(&user)])
+            .unwrap_or_default();
+        let reporter = optional(columns.reporter).map(|user| self.user(&user));
+        let sprint = columns
+            .sprint
+            .iter()
+            .rev()
+            .map(|&i| row.get(i))
+            .find(|s| !s.is_empty())
+            .map(str::to_string);
+        let created_at = optional(columns.created).and_then(|d| self.date(&d, &origin));
+        let updated_at = optional(columns.updated).and_then(|d| self.date(&d, &origin));
+
+        Some(ImportedIssue {
+            source_id: optional(columns.id),
+            origin,
+            title,
+            description: optional(columns.description).unwrap_or_default(),
+            status,
+            story_points,
+            is_epic,
+            assignees,
+            labels,
+            reporter,
+            epic: optional(columns.epic_link),
+            parent: optional(columns.parent),
+            sprint,
+            created_at,
+            updated_at,
+            comments: Vec::new(),
+            key,
+        })
+    }
+
+    /// The workflow status for a Jira status; unmapped statuses are tallied
+    /// and stand in as the initial status
+    fn status(&mut self, jira: &str) -> String {
+        let wanted = jira.to_lowercase();
+        if let Some(mapped) = self.statuses.get(&wanted) {
+            if self.workflow.status(mapped).is_none() {
+                self.unknown_statuses
+                    .insert(jira.to_string(), mapped.to_string());
+                return self.workflow.initial_status.clone();
+            }
+            return mapped.to_string();
+        }
+
+        let matched = self.workflow.suggest(jira).or_else(|| {
+            STATUS_SYNONYMS
+                .iter()
+                .find(|(name, _)| *name == wanted)
+                .and_then(|(_, builtin)| self.workflow.suggest(builtin))
+        });
+        match matched {
+            Some(status) => status.to_string(),
+            None => {
+                *self.unmapped_statuses.entry(jira.to_string()).or_default() += 1;
+                self.workflow.initial_status.clone()
+            }
+        }
+    }
+
+    fn user(&mut self, jira: &str) -> String {
+        match self.users.get(&jira.to_lowercase()) {
+            Some(user) => user.to_string(),
+            None => {
+                *self.unmapped_users.entry(jira.to_string()).or_default() += 1;
+                jira.to_string()
+            }
+        }
+    }
+
+    fn date(&mut self, value: &str, origin: &Origin) -> Option<DateTime<Utc>> {
+        if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+            return Some(date.with_timezone(&Utc));
+        }
+        let custom = self.mapping.date_format.as_deref();
+        let parsed = match custom {
+            Some(format) => NaiveDateTime::parse_from_str(value, format).ok(),
+            None => DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok()),
+        };
+        if parsed.is_none() {
+            match &mut self.bad_dates {
+                Some((count, _, _)) => *count += 1,
+                None => self.bad_dates = Some((1, value.to_string(), origin.clone())),
+            }
+        }
+        parsed.map(|date| date.and_utc())
+    }
+
+    /// Report the tallied problems
+    fn finish(self, plan: &mut ImportPlan) {
+        let issues = |count: usize| match count {
+            1 => "1 issue".to_string(),
+            n => format!("{} issues", n),
+        };
+
+        for (jira, count) in &self.unmapped_statuses {
+            plan.general(
+                Severity::Error,
+                format!(
+                    "Status \"{}\" ({}) doesn't match a workflow status; map it in \"statuses\" to one of: {}",
+                    jira,
+                    issues(*count),
+                    self.workflow.keys().join(", ")
+                ),
+            );
+        }
+        for (jira, mapped) in &self.unknown_statuses {
+            plan.general(
+                Severity::Error,
+                format!(
+                    "\"statuses\" maps \"{}\" to '{}', which isn't a workflow status",
+                    jira, mapped
+                ),
+            );
+        }
+        for (jira, count) in &self.unmapped_users {
+            plan.general(
+                Severity::Warning,
+                format!(
+                    "User \"{}\" ({}) isn't mapped in \"users\"; kept as is",
+                    jira,
+                    issues(*count)
+                ),
+            );
+        }
+        if let Some((count, example, origin)) = self.bad_dates {
+            let format = self
+                .mapping
+                .date_format
+                .clone()
+                .unwrap_or_else(|| DATE_FORMATS[0].to_string());
+            plan.problem(
+                Severity::Warning,
+                &origin,
+                None,
+                format!(
+                    "{} date(s) don't match \"{}\", e.g. \"{}\"; set \"date_format\", or they are dated at import time",
+                    count, format, example
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "\
+Summary,Issue key,Issue id,Issue Type,Status,Assignee,Reporter,Labels,Labels,Sprint,Sprint,Custom field (Story Points),Custom field (Epic Link),Parent id,Created
+Checkout revamp,SHOP-1,10001,Epic,In Progress,Alice Smith,Bob,,,,,,,,05/Mar/24 2:17 PM
+Pay with card,SHOP-2,10002,Story,Code Review,Alice Smith,Bob,payments,ui,Sprint 1,Sprint 2,3.0,SHOP-1,,06/Mar/24 9:00 AM
+Validate CVC,SHOP-3,10003,Sub-task,Waiting,,Bob,,,,,,,10002,2024-03-07 10:00
+Old epic link,SHOP-4,10004,Story,Done,Carol,Bob,,,,,x,,10001,yesterday
+";
+
+    #[test]
+    fn test_plan_maps_and_links_issues() {
+        let mut mapping = JiraMapping::default();
+        mapping
+            .users
+            .insert("alice smith".to_string(), "alice".to_string());
+        let plan = plan([("shop.csv", EXPORT)], &mapping, &Workflow::default());
+
+        let keys: Vec<&str> = plan.issues.iter().map(|i| i.key.as_str()).collect();
+        assert_eq!(keys, ["SHOP-1", "SHOP-2", "SHOP-4", "SHOP-3"]);
+
+        let epic = &plan.issues[0];
+        assert!(epic.is_epic);
+        assert_eq!(epic.status, "in_progress");
+        assert_eq!(epic.assignees, ["alice"]);
+        assert_eq!(
+            epic.created_at.unwrap().to_rfc3339(),
+            "2024-03-05T14:17:00+00:00"
+        );
+
+        let story = &plan.issues[1];
+        assert_eq!(story.status, "review");
+        assert_eq!(story.labels, ["payments", "ui"]);
+        assert_eq!(story.sprint.as_deref(), Some("Sprint 2"));
+        assert_eq!(story.story_points, Some(3));
+        assert_eq!(story.epic.as_deref(), Some("SHOP-1"));
+
+        // A parent that is an epic is the epic; other parents resolve by id
+        assert_eq!(plan.issues[2].epic.as_deref(), Some("SHOP-1"));
+        assert_eq!(plan.issues[2].parent, None);
+        assert_eq!(plan.issues[3].parent.as_deref(), Some("SHOP-2"));
+
+        let messages: Vec<(Severity, &str)> = plan
+            .problems
+            .iter()
+            .map(|p| (p.severity, p.message.as_str()))
+            .collect();
+        assert!(plan.has_errors());
+        assert!(messages
+            .iter()
+            .any(|(s, m)| *s == Severity::Error && m.starts_with("Status \"Waiting\" (1 issue)")));
+        assert!(messages
+            .iter()
+            .any(|(s, m)| *s == Severity::Warning && m.starts_with("User \"Carol\" (1 issue)")));
+        assert!(messages
+            .iter()
+            .any(|(_, m)| m.starts_with("Story points \"x\"")));
+        assert!(messages.iter().any(|(_, m)| m.starts_with("1 date(s)")));
+        assert!(!messages.iter().any(|(_, m)| m.contains("Alice")));
+    }
+
+    #[test]
+    fn test_plan_reports_bad_files() {
+        let mut mapping = JiraMapping::default();
+        mapping.columns.story_points = Some("Points".to_string());
+        let plan = plan(
+            [
+                ("a.csv", "Summary,Issue key\nOne,A-1\n"),
+                (
+                    "b.csv",
+                    "Summary,Issue key,Points\nOne,A-1,1\n,A-2,1\nTwo,A-1,2\n",
+                ),
+                ("c.csv", "Title\nx\n"),
+            ],
+            &mapping,
+            &Workflow::default(),
+        );
+
+        let messages: Vec<String> = plan
+            .problems
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} {:?} {}",
+                    p.file.as_deref().unwrap_or(""),
+                    p.line,
+                    p.message
+                )
+            })
+            .collect();
+        assert!(messages.contains(&"a.csv None Column \"Points\" not found".to_string()));
+        assert!(messages.contains(&"b.csv Some(3) Missing summary".to_string()));
+        assert!(messages.contains(
+            &"b.csv Some(4) Duplicate issue key, first seen in b.csv line 2".to_string()
+        ));
+        assert!(messages.contains(&"c.csv None Column \"Issue key\" not found".to_string()));
+        assert!(plan.has_errors());
+        assert_eq!(plan.issues.len(), 2);
+    }
+}
@@ -0,0 +1,271 @@
+//! Importing tickets from other trackers
+//!
+//! An importer reads an export into an [`ImportPlan`]: the issues to create,
+//! with their statuses already mapped onto the project's workflow, and the
+//! problems found along the way. Nothing here touches the database, so a plan
+//! can be reported as a dry run before anything is written.
+
+pub mod csv;
+pub mod github;
+pub mod jira;
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Nothing is imported while a plan has errors
+    Error,
+    /// Imported anyway, with the noted loss
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportProblem {
+    pub severity: Severity,
+    /// Export file the problem is in, when several were given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Line of the export the issue starts on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Key of the issue in the source tracker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub message: String,
+}
+
+/// Where in the export an issue came from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Origin {
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+/// An issue read from an export, ready to become a ticket
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedIssue {
+    /// Key in the source tracker, e.g. `PROJ-123`; kept as the ticket's alias
+    pub key: String,
+    /// Other identifier the source refers to the issue by, e.g. Jira's issue id
+    pub source_id: Option<String>,
+    pub origin: Origin,
+    pub title: String,
+    pub description: String,
+    /// A status of the target project's workflow
+    pub status: String,
+    pub story_points: Option<i32>,
+    pub is_epic: bool,
+    pub assignees: Vec<String>,
+    pub labels: Vec<String>,
+    pub reporter: Option<String>,
+    /// Key of the epic the issue belongs to
+    pub epic: Option<String>,
+    /// Key of the issue's parent
+    pub parent: Option<String>,
+    /// Name of the sprint the issue was last planned in
+    pub sprint: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Oldest first
+    pub comments: Vec<ImportedComment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedComment {
+    /// ID of the comment in the source tracker
+    pub source_id: Option<String>,
+    pub author: String,
+    pub body: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// What the source knows about a sprint its issues name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSprint {
+    pub name: String,
+    pub goal: Option<String>,
+    pub end_date: Option<DateTime<Utc>>,
+    /// Closed in the source; created as `completed` rather than `planning`
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportPlan {
+    /// Source the issues came from, e.g. `jira`
+    pub source: String,
+    /// Epics first, then every issue after its parent
+    pub issues: Vec<ImportedIssue>,
+    /// Details of the sprints the issues name, when the source has them
+    pub sprints: Vec<ImportedSprint>,
+    pub problems: Vec<ImportProblem>,
+}
+
+impl ImportPlan {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.problems.iter().any(|p| p.severity == Severity::Error)
+    }
+
+    pub fn sprint(&self, name: &str) -> Option<&ImportedSprint> {
+        self.sprints.iter().find(|s| s.name == name)
+    }
+
+    pub fn problem(
+        &mut self,
+        severity: Severity,
+        origin: &Origin,
+        key: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        self.problems.push(ImportProblem {
+            severity,
+            file: origin.file.clone(),
+            line: origin.line,
+            key: key.map(str::to_string),
+            message: message.into(),
+        });
+    }
+
+    /// Problem that isn't about one issue
+    pub fn general(&mut self, severity: Severity, message: impl Into<String>) {
+        self.problem(severity, &Origin::default(), None, message);
+    }
+
+    /// Resolve epic and parent references between the plan's issues and put
+    /// the issues in creation order.
+    ///
+    /// References may use an issue's key or source id; those that match an
+    /// issue of the plan are rewritten to its key, the rest are left for the
+    /// importer to find among earlier imports. A parent that is an epic
+    /// becomes the issue's epic. Epics can't belong to an epic or have a
+    /// parent, and parent links can't loop; such links are dropped with a
+    /// warning.
+    pub fn link(&mut self) {
+        let mut by_reference: HashMap<String, usize> = HashMap::new();
+        for (i, issue) in self.issues.iter().enumerate() {
+            if let Some(id) = &issue.source_id {
+                by_reference.insert(id.clone(), i);
+            }
+        }
+        for (i, issue) in self.issues.iter().enumerate() {
+            by_reference.insert(issue.key.clone(), i);
+        }
+
+        let mut dropped = Vec::new();
+        for i in 0..self.issues.len() {
+            let resolve = |reference: &Option<String>| {
+                reference
+                    .as_ref()
+                    .and_then(|r| by_reference.get(r))
+                    .map(|&j| (self.issues[j].key.clone(), self.issues[j].is_epic))
+            };
+            let epic = resolve(&self.issues[i].epic);
+            let parent = resolve(&self.issues[i].parent);
+
+            let issue = &mut self.issues[i];
+            if let Some((key, _)) = epic {
+                issue.epic = Some(key);
+            }
+            if let Some((key, is_epic)) = parent {
+                if !is_epic {
+                    issue.parent = Some(key);
+                } else {
+                    issue.parent = None;
+                    match &issue.epic {
+                        None => issue.epic = Some(key),
+                        Some(epic) if *epic == key => {}
+                        Some(epic) => dropped.push((
+                            i,
+                            format!(
+                                "Parent epic {} differs from epic {}; parent dropped",
+                                key, epic
+                            ),
+                        )),
+                    }
+                }
+            }
+            if issue.is_epic {
+                if let Some(epic) = issue.epic.take() {
+                    dropped.push((
+                        i,
+                        format!("Epics can't belong to an epic; link to {} dropped", epic),
+                    ));
+                }
+                if let Some(parent) = issue.parent.take() {
+                    dropped.push((
+                        i,
+                        format!("Epics can't have a parent; link to {} dropped", parent),
+                    ));
+                }
+            }
+        }
+
+        let index: HashMap<String, usize> = self
+            .issues
+            .iter()
+            .enumerate()
+            .map(|(i, issue)| (issue.key.clone(), i))
+            .collect();
+        let parent_of = |issues: &[ImportedIssue], i: usize| {
+            issues[i]
+                .parent
+                .as_ref()
+                .and_then(|p| index.get(p))
+                .copied()
+        };
+
+        // Cut each loop at the first of its issues
+        for i in 0..self.issues.len() {
+            let mut seen = HashSet::from([i]);
+            let mut current = i;
+            while let Some(parent) = parent_of(&self.issues, current) {
+                if parent == i {
+                    let parent = self.issues[i].parent.take().unwrap_or_default();
+                    dropped.push((i, format!("Parent links loop; link to {} dropped", parent)));
+                    break;
+                }
+                if !seen.insert(parent) {
+                    break;
+                }
+                current = parent;
+            }
+        }
+
+        let depth: Vec<usize> = (0..self.issues.len())
+            .map(|i| {
+                let mut depth = 0;
+                let mut current = i;
+                while let Some(parent) = parent_of(&self.issues, current) {
+                    depth += 1;
+                    current = parent;
+                }
+                depth
+            })
+            .collect();
+
+        for (i, message) in dropped {
+            let origin = self.issues[i].origin.clone();
+            let key = self.issues[i].key.clone();
+            self.problem(Severity::Warning, &origin, Some(&key), message);
+        }
+
+        let mut order: Vec<usize> = (0..self.issues.len()).collect();
+        order.sort_by_key(|&i| (!self.issues[i].is_epic, depth[i]));
+        let mut issues: Vec<Option<ImportedIssue>> = std::mem::take(&mut self.issues)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.issues = order.into_iter().filter_map(|i| issues[i].take()).collect();
+    }
+}
@@ -5,13 +5,31 @@
 //! - SeaORM migrations
 //! - Database connection management
 //! - Business logic and domain types
+//! - Ticket history replay
+//! - Dependency graph analysis
+//! - Ticket template rendering
+//! - Per-project status workflows
+//! - Project policies checked on ticket changes
+//! - Ticket references and closing verbs in commit messages
+//! - `@username` mentions in comments
+//! - Reading exports of other trackers for import
+//! - Portable workspace archives for backup and restore
 
+pub mod archive;
 pub mod db;
+pub mod dependency_graph;
 pub mod entities;
 pub mod error;
+pub mod history;
+pub mod import;
+pub mod mentions;
 pub mod migration;
+pub mod policy;
 pub mod search;
+pub mod smart_commit;
+pub mod template;
 pub mod utils;
+pub mod workflow;
 
 // Re-export commonly used types
 pub use db::{connect, run_migrations, DatabaseConfig};
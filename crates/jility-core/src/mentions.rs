@@ -0,0 +1,61 @@
+//! `@username` mentions in comments
+//!
+//! A mention starts at an `@` that doesn't follow a word character, so email
+//! addresses don't count, and runs over letters, digits, `_`, `-` and `.`.
+//! Mentions inside backtick code spans and fenced blocks are ignored.
+
+/// Usernames mentioned in `text`, in order of first mention
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    let mut in_code = false;
+    let mut previous: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c == '`' {
+            in_code = !in_code;
+        } else if c == '@' && !in_code && !previous.is_some_and(is_username_char) {
+            let start = i + 1;
+            let mut end = start;
+            while let Some(&(j, next)) = chars.peek() {
+                if !is_username_char(next) {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            // `@alice.` at the end of a sentence mentions `alice`
+            let username = text[start..end].trim_end_matches(['.', '-']);
+            if !username.is_empty() && !mentions.iter().any(|m| m == username) {
+                mentions.push(username.to_string());
+            }
+            previous = text[..end].chars().next_back();
+            continue;
+        }
+        previous = Some(c);
+    }
+
+    mentions
+}
+
+fn is_username_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mentions() {
+        assert_eq!(
+            parse_mentions("@alice can you check this with @bob.smith? Thanks @alice."),
+            vec!["alice", "bob.smith"]
+        );
+        assert_eq!(
+            parse_mentions("(@agent-1) mail carol@example.com, run `@dave` or\n```\n@erin\n```\n@frank"),
+            vec!["agent-1", "frank"]
+        );
+        assert!(parse_mentions("@ alone, @@, @.").is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create ticket_template table
+        manager
+            .create_table(
+                Table::create()
+                    .table(TicketTemplate::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TicketTemplate::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(TicketTemplate::WorkspaceId).uuid().not_null())
+                    .col(ColumnDef::new(TicketTemplate::ProjectId).uuid())
+                    .col(ColumnDef::new(TicketTemplate::Name).string().not_null())
+                    .col(ColumnDef::new(TicketTemplate::Description).text())
+                    .col(ColumnDef::new(TicketTemplate::TitleTemplate).string().not_null())
+                    .col(
+                        ColumnDef::new(TicketTemplate::DescriptionTemplate)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(ColumnDef::new(TicketTemplate::DefaultStatus).string())
+                    .col(ColumnDef::new(TicketTemplate::DefaultStoryPoints).integer())
+                    .col(
+                        ColumnDef::new(TicketTemplate::DefaultLabels)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(
+                        ColumnDef::new(TicketTemplate::Subtasks)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(ColumnDef::new(TicketTemplate::CreatedBy).string().not_null())
+                    .col(ColumnDef::new(TicketTemplate::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(TicketTemplate::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TicketTemplate::Table, TicketTemplate::WorkspaceId)
+                            .to(Workspace::Table, Workspace::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TicketTemplate::Table, TicketTemplate::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE INDEX IF NOT EXISTS idx_ticket_template_workspace ON ticket_template (workspace_id)"
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TicketTemplate::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum TicketTemplate {
+    Table,
+    Id,
+    WorkspaceId,
+    ProjectId,
+    Name,
+    Description,
+    TitleTemplate,
+    DescriptionTemplate,
+    DefaultStatus,
+    DefaultStoryPoints,
+    DefaultLabels,
+    Subtasks,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Workspace {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Project {
+    Table,
+    Id,
+}
@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // API keys are now looked up by their keyed digest. Existing rows still
+        // hold salted bcrypt hashes, which are unique as well, and are rewritten
+        // to a digest the first time the key is used.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_api_keys_key_hash")
+                    .table(ApiKey::Table)
+                    .col(ApiKey::KeyHash)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_api_keys_key_hash")
+                    .table(ApiKey::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Identifier for ApiKey table
+#[derive(Iden)]
+enum ApiKey {
+    Table,
+    KeyHash,
+}
@@ -0,0 +1,135 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement, TransactionTrait};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Start of the CHECK the initial schema put on `ticket.status`
+const SQLITE_STATUS_CHECK: &str = "CHECK (\"status\" IN (";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectWorkflow::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ProjectWorkflow::Id).uuid().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(ProjectWorkflow::ProjectId)
+                            .uuid()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ProjectWorkflow::InitialStatus).string().not_null())
+                    .col(
+                        ColumnDef::new(ProjectWorkflow::Statuses)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectWorkflow::Transitions)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(ColumnDef::new(ProjectWorkflow::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(ProjectWorkflow::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ProjectWorkflow::Table, ProjectWorkflow::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Statuses now come from each project's workflow, so the fixed list
+        // the initial schema checked has to go
+        let db = manager.get_connection();
+        match manager.get_database_backend() {
+            DatabaseBackend::Sqlite => {
+                // SQLite can't drop a constraint, and rebuilding the table would
+                // cascade-delete every comment and link. Removing a CHECK doesn't
+                // change the on-disk format, so edit the stored schema instead
+                // (https://www.sqlite.org/lang_altertable.html#otheralter).
+                let txn = db.begin().await?;
+                let row = txn
+                    .query_one(Statement::from_string(
+                        DatabaseBackend::Sqlite,
+                        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'ticket'",
+                    ))
+                    .await?
+                    .ok_or_else(|| DbErr::Migration("ticket table not found".to_string()))?;
+                let sql: String = row.try_get("", "sql")?;
+
+                if let Some(stripped) = strip_status_check(&sql) {
+                    let version: i64 = txn
+                        .query_one(Statement::from_string(
+                            DatabaseBackend::Sqlite,
+                            "PRAGMA schema_version",
+                        ))
+                        .await?
+                        .ok_or_else(|| DbErr::Migration("schema_version unavailable".to_string()))?
+                        .try_get_by_index(0)?;
+
+                    txn.execute_unprepared("PRAGMA writable_schema = ON").await?;
+                    txn.execute(Statement::from_sql_and_values(
+                        DatabaseBackend::Sqlite,
+                        "UPDATE sqlite_master SET sql = ? WHERE type = 'table' AND name = 'ticket'",
+                        [stripped.into()],
+                    ))
+                    .await?;
+                    txn.execute_unprepared(&format!("PRAGMA schema_version = {}", version + 1))
+                        .await?;
+                    txn.execute_unprepared("PRAGMA writable_schema = OFF").await?;
+                }
+                txn.commit().await?;
+            }
+            DatabaseBackend::Postgres => {
+                db.execute_unprepared("ALTER TABLE ticket DROP CONSTRAINT IF EXISTS ticket_status_check")
+                    .await?;
+            }
+            DatabaseBackend::MySql => {}
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The status CHECK isn't restored: tickets may hold custom statuses by now
+        manager
+            .drop_table(Table::drop().table(ProjectWorkflow::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// `sql` without the status CHECK clause, or `None` if it has none
+fn strip_status_check(sql: &str) -> Option<String> {
+    let start = sql.find(SQLITE_STATUS_CHECK)?;
+    let end = start + sql[start..].find("))")? + 2;
+    Some(format!("{}{}", sql[..start].trim_end(), &sql[end..]))
+}
+
+#[derive(Iden)]
+enum ProjectWorkflow {
+    Table,
+    Id,
+    ProjectId,
+    InitialStatus,
+    Statuses,
+    Transitions,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Project {
+    Table,
+    Id,
+}
@@ -0,0 +1,217 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Project::Table)
+                    .add_column(
+                        ColumnDef::new(Project::TicketSequence)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Start each counter at the highest number in use, deleted tickets included
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "UPDATE project SET ticket_sequence = COALESCE(\
+                (SELECT MAX(ticket_number) FROM ticket WHERE ticket.project_id = project.id), 0)",
+        )
+        .await?;
+
+        // Databases that lost the unique index may already hold duplicate
+        // numbers. The oldest ticket keeps its number; the others are moved to
+        // the end of their project's sequence.
+        let backend = manager.get_database_backend();
+        let duplicates = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT t.id, t.project_id FROM ticket t \
+                 WHERE EXISTS (SELECT 1 FROM ticket o \
+                     WHERE o.project_id = t.project_id \
+                     AND o.ticket_number = t.ticket_number \
+                     AND (o.created_at < t.created_at \
+                         OR (o.created_at = t.created_at AND o.id < t.id))) \
+                 ORDER BY t.created_at",
+            ))
+            .await?;
+
+        for row in duplicates {
+            let id: Uuid = row.try_get("", "id")?;
+            let project_id: Uuid = row.try_get("", "project_id")?;
+
+            let sequence = Query::update()
+                .table(Project::Table)
+                .value(Project::TicketSequence, Expr::col(Project::TicketSequence).add(1))
+                .and_where(Expr::col(Project::Id).eq(project_id))
+                .to_owned();
+            db.execute(backend.build(&sequence)).await?;
+
+            let renumber = Query::update()
+                .table(Ticket::Table)
+                .value(
+                    Ticket::Number,
+                    SimpleExpr::SubQuery(
+                        None,
+                        Box::new(
+                            Query::select()
+                                .column(Project::TicketSequence)
+                                .from(Project::Table)
+                                .and_where(Expr::col(Project::Id).eq(project_id))
+                                .to_owned()
+                                .into_sub_query_statement(),
+                        ),
+                    ),
+                )
+                .and_where(Expr::col(Ticket::Id).eq(id))
+                .to_owned();
+            db.execute(backend.build(&renumber)).await?;
+        }
+
+        // The initial schema creates this index; make sure it's there
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tickets_number")
+                    .table(Ticket::Table)
+                    .col(Ticket::ProjectId)
+                    .col(Ticket::Number)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Project::Table)
+                    .drop_column(Project::TicketSequence)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Project {
+    Table,
+    Id,
+    TicketSequence,
+}
+
+#[derive(Iden)]
+enum Ticket {
+    Table,
+    Id,
+    ProjectId,
+    #[iden = "ticket_number"]
+    Number,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::Migrator;
+    use chrono::{Duration, Utc};
+    use sea_orm::Database;
+
+    #[tokio::test]
+    async fn test_duplicate_numbers_move_to_the_end() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let backend = db.get_database_backend();
+        let earlier = Migrator::migrations()
+            .iter()
+            .position(|m| m.name() == Migration.name())
+            .unwrap();
+        Migrator::up(&db, Some(earlier as u32)).await.unwrap();
+        // A database that lost the unique index
+        db.execute_unprepared("DROP INDEX idx_tickets_number").await.unwrap();
+
+        let project_id = Uuid::new_v4();
+        let start = Utc::now();
+        let insert = Query::insert()
+            .into_table(Project::Table)
+            .columns([
+                Project::Id.into_iden(),
+                Alias::new("name").into_iden(),
+                Alias::new("created_at").into_iden(),
+                Alias::new("updated_at").into_iden(),
+            ])
+            .values_panic([project_id.into(), "Numbers".into(), start.into(), start.into()])
+            .to_owned();
+        db.execute(backend.build(&insert)).await.unwrap();
+
+        // Created in this order; the second 2 and the second 3 are duplicates
+        let tickets: Vec<(Uuid, i32)> = [1, 2, 2, 3, 3].iter().map(|&n| (Uuid::new_v4(), n)).collect();
+        for (i, (id, number)) in tickets.iter().enumerate() {
+            let at = start + Duration::seconds(i as i64);
+            let insert = Query::insert()
+                .into_table(Ticket::Table)
+                .columns([
+                    Ticket::Id.into_iden(),
+                    Ticket::ProjectId.into_iden(),
+                    Ticket::Number.into_iden(),
+                    Alias::new("title").into_iden(),
+                    Alias::new("created_by").into_iden(),
+                    Alias::new("created_at").into_iden(),
+                    Alias::new("updated_at").into_iden(),
+                ])
+                .values_panic([
+                    (*id).into(),
+                    project_id.into(),
+                    (*number).into(),
+                    format!("Ticket {}", i).into(),
+                    "alice".into(),
+                    at.into(),
+                    at.into(),
+                ])
+                .to_owned();
+            db.execute(backend.build(&insert)).await.unwrap();
+        }
+
+        Migrator::up(&db, Some(1)).await.unwrap();
+
+        let number_of = |id: Uuid| {
+            let db = &db;
+            async move {
+                let select = Query::select()
+                    .column(Ticket::Number)
+                    .from(Ticket::Table)
+                    .and_where(Expr::col(Ticket::Id).eq(id))
+                    .to_owned();
+                let row = db.query_one(backend.build(&select)).await.unwrap().unwrap();
+                row.try_get::<i32>("", "ticket_number").unwrap()
+            }
+        };
+        let mut numbers = Vec::new();
+        for (id, _) in &tickets {
+            numbers.push(number_of(*id).await);
+        }
+        assert_eq!(numbers, vec![1, 2, 4, 3, 5]);
+
+        let select = Query::select()
+            .column(Project::TicketSequence)
+            .from(Project::Table)
+            .to_owned();
+        let row = db.query_one(backend.build(&select)).await.unwrap().unwrap();
+        assert_eq!(row.try_get::<i32>("", "ticket_sequence").unwrap(), 5);
+    }
+}
@@ -0,0 +1,158 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhook::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Webhook::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Webhook::WorkspaceId).uuid().not_null())
+                    .col(ColumnDef::new(Webhook::ProjectId).uuid().null())
+                    .col(ColumnDef::new(Webhook::Url).string().not_null())
+                    .col(ColumnDef::new(Webhook::Secret).string().not_null())
+                    .col(ColumnDef::new(Webhook::Events).text().not_null().default("[]"))
+                    .col(ColumnDef::new(Webhook::Active).boolean().not_null().default(true))
+                    .col(ColumnDef::new(Webhook::CreatedBy).string().not_null())
+                    .col(ColumnDef::new(Webhook::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Webhook::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Webhook::Table, Webhook::WorkspaceId)
+                            .to(Workspace::Table, Workspace::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Webhook::Table, Webhook::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_workspace")
+                    .table(Webhook::Table)
+                    .col(Webhook::WorkspaceId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(WebhookDelivery::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(WebhookDelivery::WebhookId).uuid().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Event).string().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Payload).text().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Status).string().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Attempts).integer().not_null().default(0))
+                    .col(ColumnDef::new(WebhookDelivery::NextAttemptAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(WebhookDelivery::ResponseCode).integer().null())
+                    .col(ColumnDef::new(WebhookDelivery::ResponseBody).text().null())
+                    .col(ColumnDef::new(WebhookDelivery::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::DeliveredAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(WebhookDelivery::Table, WebhookDelivery::WebhookId)
+                            .to(Webhook::Table, Webhook::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The delivery worker polls for due pending rows
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_delivery_queue")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::Status)
+                    .col(WebhookDelivery::NextAttemptAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_delivery_webhook")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::WebhookId)
+                    .col(WebhookDelivery::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Webhook::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Webhook {
+    Table,
+    Id,
+    WorkspaceId,
+    ProjectId,
+    Url,
+    Secret,
+    Events,
+    Active,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum WebhookDelivery {
+    Table,
+    Id,
+    WebhookId,
+    Event,
+    Payload,
+    Status,
+    Attempts,
+    NextAttemptAt,
+    ResponseCode,
+    ResponseBody,
+    CreatedAt,
+    DeliveredAt,
+}
+
+#[derive(Iden)]
+enum Workspace {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Project {
+    Table,
+    Id,
+}
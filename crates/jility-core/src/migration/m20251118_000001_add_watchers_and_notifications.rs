@@ -0,0 +1,140 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TicketWatcher::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TicketWatcher::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(TicketWatcher::TicketId).uuid().not_null())
+                    .col(ColumnDef::new(TicketWatcher::UserId).uuid().not_null())
+                    .col(ColumnDef::new(TicketWatcher::Reason).string().not_null())
+                    .col(ColumnDef::new(TicketWatcher::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TicketWatcher::Table, TicketWatcher::TicketId)
+                            .to(Ticket::Table, Ticket::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TicketWatcher::Table, TicketWatcher::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ticket_watcher_unique")
+                    .table(TicketWatcher::Table)
+                    .col(TicketWatcher::TicketId)
+                    .col(TicketWatcher::UserId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notification::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Notification::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Notification::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Notification::TicketId).uuid().not_null())
+                    .col(ColumnDef::new(Notification::Kind).string().not_null())
+                    .col(ColumnDef::new(Notification::Actor).string().not_null())
+                    .col(ColumnDef::new(Notification::Message).text().not_null())
+                    .col(ColumnDef::new(Notification::CommentId).uuid().null())
+                    .col(ColumnDef::new(Notification::ReadAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Notification::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Notification::Table, Notification::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Notification::Table, Notification::TicketId)
+                            .to(Ticket::Table, Ticket::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The inbox lists a user's newest notifications
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notification_inbox")
+                    .table(Notification::Table)
+                    .col(Notification::UserId)
+                    .col(Notification::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notification::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TicketWatcher::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum TicketWatcher {
+    Table,
+    Id,
+    TicketId,
+    UserId,
+    Reason,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Notification {
+    Table,
+    Id,
+    UserId,
+    TicketId,
+    Kind,
+    Actor,
+    Message,
+    CommentId,
+    ReadAt,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Ticket {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum User {
+    Table,
+    Id,
+}
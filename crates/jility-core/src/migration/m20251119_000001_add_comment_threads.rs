@@ -0,0 +1,153 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only takes one column per ALTER TABLE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .add_column(ColumnDef::new(Comment::ParentId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .add_column(ColumnDef::new(Comment::DeletedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .add_column(ColumnDef::new(Comment::DeletedBy).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CommentRevision::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(CommentRevision::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(CommentRevision::CommentId).uuid().not_null())
+                    .col(ColumnDef::new(CommentRevision::Content).text().not_null())
+                    .col(ColumnDef::new(CommentRevision::EditedBy).string().not_null())
+                    .col(ColumnDef::new(CommentRevision::EditedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CommentRevision::Table, CommentRevision::CommentId)
+                            .to(Comment::Table, Comment::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_comment_revision_comment")
+                    .table(CommentRevision::Table)
+                    .col(CommentRevision::CommentId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CommentReaction::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(CommentReaction::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(CommentReaction::CommentId).uuid().not_null())
+                    .col(ColumnDef::new(CommentReaction::Emoji).string().not_null())
+                    .col(ColumnDef::new(CommentReaction::ReactedBy).string().not_null())
+                    .col(ColumnDef::new(CommentReaction::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CommentReaction::Table, CommentReaction::CommentId)
+                            .to(Comment::Table, Comment::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Each actor reacts with a given emoji at most once
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_comment_reaction_unique")
+                    .table(CommentReaction::Table)
+                    .col(CommentReaction::CommentId)
+                    .col(CommentReaction::Emoji)
+                    .col(CommentReaction::ReactedBy)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CommentReaction::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CommentRevision::Table).to_owned())
+            .await?;
+        for column in [Comment::DeletedBy, Comment::DeletedAt, Comment::ParentId] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Comment::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Comment {
+    Table,
+    Id,
+    ParentId,
+    DeletedAt,
+    DeletedBy,
+}
+
+#[derive(Iden)]
+enum CommentRevision {
+    Table,
+    Id,
+    CommentId,
+    Content,
+    EditedBy,
+    EditedAt,
+}
+
+#[derive(Iden)]
+enum CommentReaction {
+    Table,
+    Id,
+    CommentId,
+    Emoji,
+    ReactedBy,
+    CreatedAt,
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Attachment::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Attachment::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Attachment::TicketId).uuid().not_null())
+                    .col(ColumnDef::new(Attachment::CommentId).uuid().null())
+                    .col(ColumnDef::new(Attachment::Filename).string().not_null())
+                    .col(ColumnDef::new(Attachment::ContentType).string().not_null())
+                    .col(ColumnDef::new(Attachment::SizeBytes).big_integer().not_null())
+                    .col(ColumnDef::new(Attachment::Sha256).string().not_null())
+                    .col(ColumnDef::new(Attachment::UploadedBy).string().not_null())
+                    .col(ColumnDef::new(Attachment::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Attachment::Table, Attachment::TicketId)
+                            .to(Ticket::Table, Ticket::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Attachment::Table, Attachment::CommentId)
+                            .to(Comment::Table, Comment::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_attachment_ticket")
+                    .table(Attachment::Table)
+                    .col(Attachment::TicketId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Blobs are shared by hash; deleting an attachment checks for others
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_attachment_sha256")
+                    .table(Attachment::Table)
+                    .col(Attachment::Sha256)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Attachment::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Attachment {
+    Table,
+    Id,
+    TicketId,
+    CommentId,
+    Filename,
+    ContentType,
+    SizeBytes,
+    Sha256,
+    UploadedBy,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Ticket {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Comment {
+    Table,
+    Id,
+}
@@ -0,0 +1,114 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TicketAlias::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TicketAlias::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TicketAlias::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(TicketAlias::TicketId).uuid().not_null())
+                    .col(ColumnDef::new(TicketAlias::Alias).string().not_null())
+                    .col(ColumnDef::new(TicketAlias::Source).string().not_null())
+                    .col(
+                        ColumnDef::new(TicketAlias::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TicketAlias::Table, TicketAlias::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TicketAlias::Table, TicketAlias::TicketId)
+                            .to(Ticket::Table, Ticket::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ticket_alias_unique")
+                    .table(TicketAlias::Table)
+                    .col(TicketAlias::ProjectId)
+                    .col(TicketAlias::Alias)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Resolving an alias doesn't know the project
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ticket_alias_alias")
+                    .table(TicketAlias::Table)
+                    .col(TicketAlias::Alias)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ticket_alias_ticket")
+                    .table(TicketAlias::Table)
+                    .col(TicketAlias::TicketId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TicketAlias::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum TicketAlias {
+    Table,
+    Id,
+    ProjectId,
+    TicketId,
+    Alias,
+    Source,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Project {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Ticket {
+    Table,
+    Id,
+}
@@ -0,0 +1,60 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Concurrent adds could link the same pair twice; keep the oldest link
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM ticket_dependency WHERE EXISTS (\
+                    SELECT 1 FROM ticket_dependency o \
+                    WHERE o.ticket_id = ticket_dependency.ticket_id \
+                    AND o.depends_on_id = ticket_dependency.depends_on_id \
+                    AND (o.created_at < ticket_dependency.created_at \
+                        OR (o.created_at = ticket_dependency.created_at \
+                            AND o.id < ticket_dependency.id)))",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ticket_dependency_pair")
+                    .table(TicketDependency::Table)
+                    .col(TicketDependency::TicketId)
+                    .col(TicketDependency::DependsOnId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_ticket_dependency_pair")
+                    .table(TicketDependency::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Identifier for TicketDependency table
+#[derive(Iden)]
+enum TicketDependency {
+    Table,
+    TicketId,
+    DependsOnId,
+}
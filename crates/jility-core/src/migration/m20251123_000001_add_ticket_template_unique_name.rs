@@ -0,0 +1,76 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Concurrent creates could reuse a name. The oldest template keeps it;
+        // the others get their ID's first block appended.
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let duplicates = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT t.id, t.name FROM ticket_template t \
+                 WHERE EXISTS (SELECT 1 FROM ticket_template o \
+                     WHERE o.workspace_id = t.workspace_id \
+                     AND (o.project_id = t.project_id \
+                         OR (o.project_id IS NULL AND t.project_id IS NULL)) \
+                     AND o.name = t.name \
+                     AND (o.created_at < t.created_at \
+                         OR (o.created_at = t.created_at AND o.id < t.id)))",
+            ))
+            .await?;
+
+        for row in duplicates {
+            let id: Uuid = row.try_get("", "id")?;
+            let name: String = row.try_get("", "name")?;
+
+            let rename = Query::update()
+                .table(TicketTemplate::Table)
+                .value(
+                    TicketTemplate::Name,
+                    format!("{}-{}", name, &id.simple().to_string()[..8]),
+                )
+                .and_where(Expr::col(TicketTemplate::Id).eq(id))
+                .to_owned();
+            db.execute(backend.build(&rename)).await?;
+        }
+
+        // NULLs never collide in a unique index, so workspace-wide templates
+        // need their own
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_ticket_template_project_name \
+             ON ticket_template (workspace_id, project_id, name)",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_ticket_template_workspace_name \
+             ON ticket_template (workspace_id, name) WHERE project_id IS NULL",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_ticket_template_project_name")
+            .await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_ticket_template_workspace_name")
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum TicketTemplate {
+    Table,
+    Id,
+    Name,
+}
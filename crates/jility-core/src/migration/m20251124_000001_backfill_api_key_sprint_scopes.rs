@@ -0,0 +1,63 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sprint routes used to need no scope, and keys were created with
+        // ticket scopes only. Give existing keys the sprint access matching
+        // their ticket access so they keep working.
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let keys = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT id, scopes FROM api_key",
+            ))
+            .await?;
+
+        for row in keys {
+            let id: Uuid = row.try_get("", "id")?;
+            let scopes: String = row.try_get("", "scopes")?;
+            let mut scopes: Vec<String> = serde_json::from_str(&scopes).unwrap_or_default();
+
+            let has = |scope: &str| scopes.iter().any(|s| s == scope);
+            let sprint_scope = if has("sprints:read") || has("sprints:write") || has("admin") {
+                continue;
+            } else if has("tickets:write") {
+                "sprints:write"
+            } else if has("tickets:read") {
+                "sprints:read"
+            } else {
+                continue;
+            };
+            scopes.push(sprint_scope.to_string());
+
+            let update = Query::update()
+                .table(ApiKey::Table)
+                .value(ApiKey::Scopes, serde_json::to_string(&scopes).unwrap())
+                .and_where(Expr::col(ApiKey::Id).eq(id))
+                .to_owned();
+            db.execute(backend.build(&update)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Backfilled scopes can't be told apart from chosen ones
+        Ok(())
+    }
+}
+
+/// Identifier for ApiKey table
+#[derive(Iden)]
+enum ApiKey {
+    Table,
+    Id,
+    Scopes,
+}
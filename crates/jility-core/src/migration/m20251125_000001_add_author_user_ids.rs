@@ -0,0 +1,79 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .add_column(ColumnDef::new(Comment::AuthorUserId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attachment::Table)
+                    .add_column(ColumnDef::new(Attachment::UploadedByUserId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing rows only recorded the actor, `alice` or `alice (key name)`.
+        // Rows whose actor isn't a user (imports, agents) stay unowned.
+        let db = manager.get_connection();
+        for (table, user_id, actor) in [
+            ("comment", "author_user_id", "author"),
+            ("attachment", "uploaded_by_user_id", "uploaded_by"),
+        ] {
+            db.execute_unprepared(&format!(
+                "UPDATE {table} SET {user_id} = (\
+                    SELECT u.id FROM \"user\" u \
+                    WHERE {table}.{actor} = u.username \
+                    OR substr({table}.{actor}, 1, length(u.username) + 2) = u.username || ' (' \
+                    ORDER BY length(u.username) DESC LIMIT 1)"
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attachment::Table)
+                    .drop_column(Attachment::UploadedByUserId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .drop_column(Comment::AuthorUserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Comment {
+    Table,
+    AuthorUserId,
+}
+
+#[derive(Iden)]
+enum Attachment {
+    Table,
+    UploadedByUserId,
+}
@@ -0,0 +1,222 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every row gets a user below, but SQLite can't make an added column
+        // NOT NULL afterwards
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CommentReaction::Table)
+                    .add_column(ColumnDef::new(CommentReaction::UserId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Reactions were keyed by actor, `alice` or `alice (key name)`, so one
+        // user could hold the same reaction once per key. Keep the earliest.
+        let db = manager.get_connection();
+        for sql in [
+            "UPDATE comment_reaction SET user_id = (\
+                SELECT u.id FROM \"user\" u \
+                WHERE comment_reaction.reacted_by = u.username \
+                OR substr(comment_reaction.reacted_by, 1, length(u.username) + 2) = u.username || ' (' \
+                ORDER BY length(u.username) DESC LIMIT 1)",
+            "DELETE FROM comment_reaction WHERE user_id IS NULL",
+            "DELETE FROM comment_reaction WHERE EXISTS (\
+                SELECT 1 FROM comment_reaction r \
+                WHERE r.comment_id = comment_reaction.comment_id \
+                AND r.emoji = comment_reaction.emoji \
+                AND r.user_id = comment_reaction.user_id \
+                AND (r.created_at < comment_reaction.created_at \
+                OR (r.created_at = comment_reaction.created_at AND r.id < comment_reaction.id)))",
+            "UPDATE comment_reaction SET reacted_by = (\
+                SELECT u.username FROM \"user\" u WHERE u.id = comment_reaction.user_id)",
+        ] {
+            db.execute_unprepared(sql).await?;
+        }
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_comment_reaction_unique")
+                    .table(CommentReaction::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_comment_reaction_user_unique")
+                    .table(CommentReaction::Table)
+                    .col(CommentReaction::CommentId)
+                    .col(CommentReaction::Emoji)
+                    .col(CommentReaction::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_comment_reaction_user_unique")
+                    .table(CommentReaction::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CommentReaction::Table)
+                    .drop_column(CommentReaction::UserId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_comment_reaction_unique")
+                    .table(CommentReaction::Table)
+                    .col(CommentReaction::CommentId)
+                    .col(CommentReaction::Emoji)
+                    .col(CommentReaction::ReactedBy)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum CommentReaction {
+    Table,
+    CommentId,
+    Emoji,
+    ReactedBy,
+    UserId,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::Migrator;
+    use chrono::{Duration, Utc};
+    use sea_orm::Database;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_reactions_move_to_their_user() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let backend = db.get_database_backend();
+        let earlier = Migrator::migrations()
+            .iter()
+            .position(|m| m.name() == Migration.name())
+            .unwrap();
+        Migrator::up(&db, Some(earlier as u32)).await.unwrap();
+        // Reactions only need a user here, not a comment to hang off
+        db.execute_unprepared("PRAGMA foreign_keys = OFF")
+            .await
+            .unwrap();
+
+        let alice = Uuid::new_v4();
+        let start = Utc::now();
+        let insert = Query::insert()
+            .into_table(Alias::new("user"))
+            .columns(
+                [
+                    "id",
+                    "email",
+                    "username",
+                    "password_hash",
+                    "is_active",
+                    "is_verified",
+                    "created_at",
+                    "updated_at",
+                ]
+                .map(Alias::new),
+            )
+            .values_panic([
+                alice.into(),
+                "alice@example.com".into(),
+                "alice".into(),
+                "".into(),
+                true.into(),
+                true.into(),
+                start.into(),
+                start.into(),
+            ])
+            .to_owned();
+        db.execute(backend.build(&insert)).await.unwrap();
+
+        // The second 👍 is alice again through a key; ghost has no account
+        let comment_id = Uuid::new_v4();
+        let reactions = [
+            ("👍", "alice"),
+            ("👍", "alice (ci)"),
+            ("🎉", "alice (ci)"),
+            ("🎉", "ghost"),
+        ];
+        let ids: Vec<Uuid> = reactions.iter().map(|_| Uuid::new_v4()).collect();
+        for (i, (emoji, reacted_by)) in reactions.iter().enumerate() {
+            let insert = Query::insert()
+                .into_table(CommentReaction::Table)
+                .columns([
+                    Alias::new("id").into_iden(),
+                    CommentReaction::CommentId.into_iden(),
+                    CommentReaction::Emoji.into_iden(),
+                    CommentReaction::ReactedBy.into_iden(),
+                    Alias::new("created_at").into_iden(),
+                ])
+                .values_panic([
+                    ids[i].into(),
+                    comment_id.into(),
+                    (*emoji).into(),
+                    (*reacted_by).into(),
+                    (start + Duration::seconds(i as i64)).into(),
+                ])
+                .to_owned();
+            db.execute(backend.build(&insert)).await.unwrap();
+        }
+
+        Migrator::up(&db, Some(1)).await.unwrap();
+
+        let select = Query::select()
+            .column(Alias::new("id"))
+            .columns([CommentReaction::ReactedBy, CommentReaction::UserId])
+            .from(CommentReaction::Table)
+            .order_by(Alias::new("created_at"), Order::Asc)
+            .to_owned();
+        let rows: Vec<(Uuid, String, Uuid)> = db
+            .query_all(backend.build(&select))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| {
+                (
+                    row.try_get("", "id").unwrap(),
+                    row.try_get("", "reacted_by").unwrap(),
+                    row.try_get("", "user_id").unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (ids[0], "alice".to_string(), alice),
+                (ids[2], "alice".to_string(), alice)
+            ]
+        );
+    }
+}
@@ -10,6 +10,22 @@ mod m20251108_000001_fix_comments_fts;
 mod m20251109_000001_add_ticket_soft_delete;
 mod m20250109_000001_add_epic_support;
 mod m20251110_000001_add_sprint_capacity;
+mod m20251112_000001_add_ticket_templates;
+mod m20251113_000001_add_api_key_hash_index;
+mod m20251114_000001_add_project_workflows;
+mod m20251115_000001_add_project_ticket_sequence;
+mod m20251116_000001_add_webhooks;
+mod m20251117_000001_add_project_git_webhook_secret;
+mod m20251118_000001_add_watchers_and_notifications;
+mod m20251119_000001_add_comment_threads;
+mod m20251120_000001_add_attachments;
+mod m20251121_000001_add_ticket_aliases;
+mod m20251122_000001_add_ticket_dependency_unique_index;
+mod m20251123_000001_add_ticket_template_unique_name;
+mod m20251124_000001_backfill_api_key_sprint_scopes;
+mod m20251125_000001_add_author_user_ids;
+mod m20251126_000001_key_comment_reactions_by_user;
+mod m20251127_000001_add_import_source_ids;
 
 pub struct Migrator;
 
@@ -27,6 +43,22 @@ impl MigratorTrait for Migrator {
             Box::new(m20251109_000001_add_ticket_soft_delete::Migration),
             Box::new(m20250109_000001_add_epic_support::Migration),
             Box::new(m20251110_000001_add_sprint_capacity::Migration),
+            Box::new(m20251112_000001_add_ticket_templates::Migration),
+            Box::new(m20251113_000001_add_api_key_hash_index::Migration),
+            Box::new(m20251114_000001_add_project_workflows::Migration),
+            Box::new(m20251115_000001_add_project_ticket_sequence::Migration),
+            Box::new(m20251116_000001_add_webhooks::Migration),
+            Box::new(m20251117_000001_add_project_git_webhook_secret::Migration),
+            Box::new(m20251118_000001_add_watchers_and_notifications::Migration),
+            Box::new(m20251119_000001_add_comment_threads::Migration),
+            Box::new(m20251120_000001_add_attachments::Migration),
+            Box::new(m20251121_000001_add_ticket_aliases::Migration),
+            Box::new(m20251122_000001_add_ticket_dependency_unique_index::Migration),
+            Box::new(m20251123_000001_add_ticket_template_unique_name::Migration),
+            Box::new(m20251124_000001_backfill_api_key_sprint_scopes::Migration),
+            Box::new(m20251125_000001_add_author_user_ids::Migration),
+            Box::new(m20251126_000001_key_comment_reactions_by_user::Migration),
+            Box::new(m20251127_000001_add_import_source_ids::Migration),
         ]
     }
 }
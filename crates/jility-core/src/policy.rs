@@ -0,0 +1,130 @@
+//! Project policies
+//!
+//! Rules a project opts into through its settings and that every ticket change
+//! must respect, whichever client makes it. A broken rule is reported as a
+//! [`PolicyViolation`] naming the setting behind it, so clients can tell it
+//! apart from malformed input and explain how to satisfy it.
+
+use std::fmt;
+
+use sea_orm::{ConnectionTrait, EntityTrait};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::entities::{project, Project};
+use crate::error::{CoreError, CoreResult};
+use crate::workflow::{StatusCategory, Workflow};
+
+/// Name of the policy enforced by `project.require_story_points`
+pub const REQUIRE_STORY_POINTS: &str = "require_story_points";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyViolation {
+    /// Project setting that enforces the rule, e.g. `require_story_points`
+    pub policy: String,
+    /// Ticket field that has to be filled in before the change is allowed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// The policies a project has switched on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProjectPolicy {
+    /// Tickets need story points before they move into an in-progress status
+    pub require_story_points: bool,
+}
+
+impl From<&project::Model> for ProjectPolicy {
+    fn from(project: &project::Model) -> Self {
+        Self {
+            require_story_points: project.require_story_points,
+        }
+    }
+}
+
+impl ProjectPolicy {
+    /// Policies of `project_id`
+    pub async fn load<C: ConnectionTrait>(db: &C, project_id: Uuid) -> CoreResult<Self> {
+        let project = Project::find_by_id(project_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::NotFound(format!("Project not found: {}", project_id)))?;
+        Ok(Self::from(&project))
+    }
+
+    /// Check a ticket with `story_points` moving from `from` (`None` while it
+    /// is being created) to `to`. Only entering the in-progress category is
+    /// checked, so tickets started before a policy was switched on can still
+    /// move between in-progress statuses.
+    pub fn check_status_change(
+        &self,
+        workflow: &Workflow,
+        from: Option<&str>,
+        to: &str,
+        story_points: Option<i32>,
+    ) -> CoreResult<()> {
+        let starts_work = workflow.category(to) == StatusCategory::InProgress
+            && from.is_none_or(|from| workflow.category(from) != StatusCategory::InProgress);
+
+        if self.require_story_points && starts_work && story_points.is_none() {
+            return Err(CoreError::PolicyViolation(PolicyViolation {
+                policy: REQUIRE_STORY_POINTS.to_string(),
+                field: Some("story_points".to_string()),
+                message: format!(
+                    "This project requires story points before a ticket moves to '{}'. Estimate the ticket first.",
+                    to
+                ),
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_story_points() {
+        let workflow = Workflow::default();
+        let policy = ProjectPolicy {
+            require_story_points: true,
+        };
+
+        let err = policy
+            .check_status_change(&workflow, Some("todo"), "in_progress", None)
+            .unwrap_err();
+        match err {
+            CoreError::PolicyViolation(violation) => {
+                assert_eq!(violation.policy, REQUIRE_STORY_POINTS);
+                assert_eq!(violation.field.as_deref(), Some("story_points"));
+            }
+            other => panic!("expected a policy violation, got {:?}", other),
+        }
+        assert!(policy
+            .check_status_change(&workflow, None, "review", None)
+            .is_err());
+
+        assert!(policy
+            .check_status_change(&workflow, Some("todo"), "in_progress", Some(3))
+            .is_ok());
+        assert!(policy
+            .check_status_change(&workflow, Some("todo"), "done", None)
+            .is_ok());
+        // Already started before the policy was switched on
+        assert!(policy
+            .check_status_change(&workflow, Some("in_progress"), "review", None)
+            .is_ok());
+        assert!(ProjectPolicy::default()
+            .check_status_change(&workflow, Some("todo"), "in_progress", None)
+            .is_ok());
+    }
+}
@@ -1,9 +1,9 @@
-use crate::entities::{ticket, Ticket};
+use crate::entities::{comment, ticket, Comment, Ticket};
 use crate::error::CoreResult;
 use chrono::{DateTime, Utc};
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
-    QuerySelect,
+    sea_query::Query, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -28,6 +28,12 @@ pub struct SearchFilters {
     pub epic_id: Option<Uuid>,
     pub parent_id: Option<Uuid>,
     pub project_id: Option<Uuid>,
+    /// Only match tickets in these projects (used to scope results to the
+    /// caller's workspaces)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_ids: Option<Vec<Uuid>>,
+    /// Where `query` is looked for: `title`, `description` and/or `comments`;
+    /// empty means all three
     pub search_in: Vec<String>,
 }
 
@@ -80,6 +86,9 @@ impl SearchService {
         if let Some(project_id) = filters.project_id {
             query = query.filter(ticket::Column::ProjectId.eq(project_id));
         }
+        if let Some(project_ids) = filters.project_ids {
+            query = query.filter(ticket::Column::ProjectId.is_in(project_ids));
+        }
 
         // Apply status filter
         if let Some(statuses) = filters.status {
@@ -123,14 +132,32 @@ impl SearchService {
             query = query.filter(ticket::Column::EpicId.eq(epic_id));
         }
 
-        // Apply text search if provided
+        // Apply text search if provided, to the fields asked for (all by default)
         if !filters.query.is_empty() {
             let search_pattern = format!("%{}%", filters.query);
-            query = query.filter(
-                ticket::Column::Title
-                    .contains(&search_pattern)
-                    .or(ticket::Column::Description.contains(&search_pattern)),
-            );
+            let searches = |field: &str| {
+                filters.search_in.is_empty() || filters.search_in.iter().any(|f| f == field)
+            };
+            let mut matches = Condition::any();
+            if searches("title") {
+                matches = matches.add(ticket::Column::Title.contains(&search_pattern));
+            }
+            if searches("description") {
+                matches = matches.add(ticket::Column::Description.contains(&search_pattern));
+            }
+            if searches("comments") {
+                matches = matches.add(
+                    ticket::Column::Id.in_subquery(
+                        Query::select()
+                            .column(comment::Column::TicketId)
+                            .from(Comment)
+                            .and_where(comment::Column::Content.contains(&search_pattern))
+                            .and_where(comment::Column::DeletedAt.is_null())
+                            .to_owned(),
+                    ),
+                );
+            }
+            query = query.filter(matches);
         }
 
         // Order by updated_at descending
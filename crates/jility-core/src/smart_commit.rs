@@ -0,0 +1,103 @@
+//! Ticket references in commit messages
+//!
+//! Commits mention tickets by number, e.g. `JIL-42`. A reference that follows
+//! a closing verb (`fixes JIL-42`, `Closes JIL-1, JIL-2 and JIL-3`) asks for
+//! the ticket to be moved to its workflow's done status.
+
+use std::collections::HashMap;
+
+/// Words that close the tickets referenced after them, compared lowercase
+pub const CLOSING_VERBS: [&str; 9] = [
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitReference {
+    /// Project key, e.g. `JIL`
+    pub key: String,
+    pub number: i32,
+    /// Preceded by a closing verb
+    pub closes: bool,
+}
+
+/// Ticket references in `message`, in order of first mention. A ticket
+/// mentioned several times closes if any mention does.
+pub fn parse_references(message: &str) -> Vec<CommitReference> {
+    let mut references: Vec<CommitReference> = Vec::new();
+    let mut seen: HashMap<(String, i32), usize> = HashMap::new();
+    let mut closing = false;
+
+    for word in message.split_whitespace() {
+        let word = word.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '-');
+        let word = word.trim_end_matches('-');
+
+        if CLOSING_VERBS.contains(&word.to_ascii_lowercase().as_str()) {
+            closing = true;
+        } else if let Some((key, number)) = ticket_number(word) {
+            match seen.get(&(key.clone(), number)) {
+                Some(&i) => references[i].closes |= closing,
+                None => {
+                    seen.insert((key.clone(), number), references.len());
+                    references.push(CommitReference {
+                        key,
+                        number,
+                        closes: closing,
+                    });
+                }
+            }
+        } else if !(word.eq_ignore_ascii_case("and") || word.is_empty()) {
+            // `fixes JIL-1, JIL-2 and JIL-3` closes all three; any other word
+            // ends the list
+            closing = false;
+        }
+    }
+
+    references
+}
+
+/// `("JIL", 42)` for `JIL-42`: an uppercase key starting with a letter, a
+/// dash and a positive number
+fn ticket_number(word: &str) -> Option<(String, i32)> {
+    let (key, number) = word.rsplit_once('-')?;
+    let mut chars = key.chars();
+    let starts_with_letter = chars.next().is_some_and(|c| c.is_ascii_uppercase());
+    if !starts_with_letter || !chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+        return None;
+    }
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let number: i32 = number.parse().ok()?;
+    (number > 0).then(|| (key.to_string(), number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_references() {
+        let refs = parse_references(
+            "Fix login redirect (JIL-12)\n\nFixes JIL-4, JIL-7 and OPS-3. See JIL-9; refs jil-10, JIL-0, UTF-8x",
+        );
+        let found: Vec<(&str, i32, bool)> = refs
+            .iter()
+            .map(|r| (r.key.as_str(), r.number, r.closes))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("JIL", 12, false),
+                ("JIL", 4, true),
+                ("JIL", 7, true),
+                ("OPS", 3, true),
+                ("JIL", 9, false),
+            ]
+        );
+
+        // A later closing mention closes an earlier plain one
+        let refs = parse_references("Start JIL-1\n\nresolved: JIL-1");
+        assert_eq!(refs.len(), 1);
+        assert!(refs[0].closes);
+    }
+}
@@ -0,0 +1,104 @@
+//! Ticket template rendering
+//!
+//! Templates use `{{variable}}` placeholders (whitespace inside the braces is
+//! ignored). Rendering fails if any placeholder has no value.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::error::{CoreError, CoreResult};
+
+/// Names of all placeholders in `template`, in first-seen order
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for (_, name, _) in scan(template) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Substitute every placeholder in `template` with its value from `variables`
+pub fn render(template: &str, variables: &HashMap<String, String>) -> CoreResult<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut missing = BTreeSet::new();
+    let mut last = 0;
+
+    for (start, name, end) in scan(template) {
+        output.push_str(&template[last..start]);
+        match variables.get(name) {
+            Some(value) => output.push_str(value),
+            None => {
+                missing.insert(name.to_string());
+            }
+        }
+        last = end;
+    }
+    output.push_str(&template[last..]);
+
+    if missing.is_empty() {
+        Ok(output)
+    } else {
+        Err(CoreError::InvalidInput(format!(
+            "Missing template variables: {}",
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        )))
+    }
+}
+
+/// `(start, name, end)` byte ranges of each `{{name}}` in `template`
+fn scan(template: &str) -> Vec<(usize, &str, usize)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some(open) = template[offset..].find("{{") {
+        let start = offset + open;
+        let Some(close) = template[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + close + 2;
+        let name = template[start + 2..end - 2].trim();
+
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            found.push((start, name, end));
+            offset = end;
+        } else {
+            offset = start + 2;
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let rendered = render(
+            "Bug: {{summary}} in {{ component }} ({{summary}})",
+            &vars(&[("summary", "crash"), ("component", "board")]),
+        )
+        .unwrap();
+        assert_eq!(rendered, "Bug: crash in board (crash)");
+    }
+
+    #[test]
+    fn test_render_reports_missing_variables() {
+        let err = render("{{a}} {{b}}", &vars(&[("a", "x")])).unwrap_err();
+        assert!(err.to_string().contains("b"));
+    }
+
+    #[test]
+    fn test_placeholders_ignores_non_identifiers() {
+        assert_eq!(
+            placeholders("{{one}} {{ two }} {{not valid}} {{one}} {{"),
+            vec!["one".to_string(), "two".to_string()]
+        );
+    }
+}
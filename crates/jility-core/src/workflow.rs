@@ -0,0 +1,314 @@
+//! Per-project ticket workflows
+//!
+//! A workflow is the ordered set of statuses a project's tickets move through,
+//! the category each status belongs to, and which moves between statuses are
+//! allowed. Projects that never configured one use [`Workflow::default`]: the
+//! built-in statuses with every transition allowed.
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entities::{project_workflow, ProjectWorkflow, TicketStatus};
+use crate::error::{CoreError, CoreResult};
+
+/// `from` value of a transition that applies to every status
+pub const ANY_STATUS: &str = "*";
+
+/// What a status means for reporting, independent of its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusCategory {
+    Todo,
+    InProgress,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowStatus {
+    /// Value stored on tickets, e.g. `qa_review`
+    pub key: String,
+    /// Column heading, e.g. "QA Review"
+    pub name: String,
+    pub category: StatusCategory,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowTransition {
+    /// Status key, or [`ANY_STATUS`]
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workflow {
+    pub statuses: Vec<WorkflowStatus>,
+    /// Allowed moves. An empty list allows every move between statuses.
+    pub transitions: Vec<WorkflowTransition>,
+    pub initial_status: String,
+}
+
+impl Default for Workflow {
+    fn default() -> Self {
+        let status = |status: TicketStatus, name: &str, category| WorkflowStatus {
+            key: status.as_str().to_string(),
+            name: name.to_string(),
+            category,
+        };
+        Self {
+            statuses: vec![
+                status(TicketStatus::Backlog, "Backlog", StatusCategory::Todo),
+                status(TicketStatus::Todo, "To Do", StatusCategory::Todo),
+                status(TicketStatus::InProgress, "In Progress", StatusCategory::InProgress),
+                status(TicketStatus::Review, "Review", StatusCategory::InProgress),
+                status(TicketStatus::Done, "Done", StatusCategory::Done),
+                status(TicketStatus::Blocked, "Blocked", StatusCategory::InProgress),
+            ],
+            transitions: Vec::new(),
+            initial_status: TicketStatus::Backlog.as_str().to_string(),
+        }
+    }
+}
+
+impl Workflow {
+    pub fn status(&self, key: &str) -> Option<&WorkflowStatus> {
+        self.statuses.iter().find(|s| s.key == key)
+    }
+
+    pub fn keys(&self) -> Vec<&str> {
+        self.statuses.iter().map(|s| s.key.as_str()).collect()
+    }
+
+    /// Category of `key`; statuses the workflow doesn't know count as to-do
+    pub fn category(&self, key: &str) -> StatusCategory {
+        self.status(key)
+            .map(|s| s.category)
+            .unwrap_or(StatusCategory::Todo)
+    }
+
+    pub fn is_done(&self, key: &str) -> bool {
+        self.category(key) == StatusCategory::Done
+    }
+
+    /// First status in `category`, in board order
+    pub fn first_in(&self, category: StatusCategory) -> Option<&str> {
+        self.statuses
+            .iter()
+            .find(|s| s.category == category)
+            .map(|s| s.key.as_str())
+    }
+
+    /// Statuses a ticket in `from` may move to
+    pub fn next_statuses(&self, from: &str) -> Vec<&str> {
+        self.statuses
+            .iter()
+            .map(|s| s.key.as_str())
+            .filter(|to| *to != from && self.allows(from, to))
+            .collect()
+    }
+
+    fn allows(&self, from: &str, to: &str) -> bool {
+        self.transitions.is_empty()
+            || self
+                .transitions
+                .iter()
+                .any(|t| t.to == to && (t.from == from || t.from == ANY_STATUS))
+    }
+
+    /// The workflow status a mistyped `status` most likely meant, e.g.
+    /// `in_progress` for `In-Progress`
+    pub fn suggest(&self, status: &str) -> Option<&str> {
+        let wanted = normalize(status);
+        self.statuses
+            .iter()
+            .find(|s| normalize(&s.key) == wanted || normalize(&s.name) == wanted)
+            .map(|s| s.key.as_str())
+    }
+
+    /// `InvalidInput` unless `status` is one of this workflow's statuses
+    pub fn check_status(&self, status: &str) -> CoreResult<()> {
+        if self.status(status).is_some() {
+            return Ok(());
+        }
+
+        let hint = match self.suggest(status) {
+            Some(key) => format!(" Did you mean '{}'?", key),
+            None => String::new(),
+        };
+        Err(CoreError::InvalidInput(format!(
+            "Unknown status '{}'.{} Valid statuses: {}",
+            status,
+            hint,
+            self.keys().join(", ")
+        )))
+    }
+
+    /// `InvalidInput` unless a ticket in `from` may move to `to`. Tickets whose
+    /// current status isn't part of the workflow may move anywhere, so they can
+    /// always be brought back onto the board.
+    pub fn check_transition(&self, from: &str, to: &str) -> CoreResult<()> {
+        self.check_status(to)?;
+        if from == to || self.status(from).is_none() || self.allows(from, to) {
+            return Ok(());
+        }
+
+        let next = self.next_statuses(from);
+        let options = if next.is_empty() {
+            format!("'{}' has no outgoing transitions", from)
+        } else {
+            format!("From '{}' it can move to: {}", from, next.join(", "))
+        };
+        Err(CoreError::InvalidInput(format!(
+            "Cannot move a ticket from '{}' to '{}'. {}",
+            from, to, options
+        )))
+    }
+
+    /// Check that the workflow is internally consistent before saving it
+    pub fn validate(&self) -> CoreResult<()> {
+        let invalid = |msg: String| Err(CoreError::InvalidInput(msg));
+
+        if self.statuses.is_empty() {
+            return invalid("A workflow needs at least one status".to_string());
+        }
+        for (i, status) in self.statuses.iter().enumerate() {
+            if status.key.is_empty()
+                || !status
+                    .key
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            {
+                return invalid(format!(
+                    "Invalid status key '{}': use lowercase letters, digits and underscores",
+                    status.key
+                ));
+            }
+            if status.name.trim().is_empty() {
+                return invalid(format!("Status '{}' needs a name", status.key));
+            }
+            if self.statuses[..i].iter().any(|s| s.key == status.key) {
+                return invalid(format!("Duplicate status '{}'", status.key));
+            }
+        }
+        if self.first_in(StatusCategory::Done).is_none() {
+            return invalid("A workflow needs at least one status in the done category".to_string());
+        }
+        if self.status(&self.initial_status).is_none() {
+            return invalid(format!(
+                "Initial status '{}' is not one of the workflow's statuses",
+                self.initial_status
+            ));
+        }
+        for transition in &self.transitions {
+            if transition.from != ANY_STATUS {
+                self.check_status(&transition.from)?;
+            }
+            self.check_status(&transition.to)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lowercase with separators dropped, so `In-Progress`, `in progress` and
+/// `inprogress` all compare equal to `in_progress`
+fn normalize(status: &str) -> String {
+    status
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The workflow for `project_id`, or the default if it has none
+pub async fn load<C: ConnectionTrait>(db: &C, project_id: Uuid) -> CoreResult<Workflow> {
+    Ok(ProjectWorkflow::find()
+        .filter(project_workflow::Column::ProjectId.eq(project_id))
+        .one(db)
+        .await?
+        .map(|row| row.workflow())
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review_workflow() -> Workflow {
+        let status = |key: &str, category| WorkflowStatus {
+            key: key.to_string(),
+            name: key.replace('_', " "),
+            category,
+        };
+        let transition = |from: &str, to: &str| WorkflowTransition {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        Workflow {
+            statuses: vec![
+                status("open", StatusCategory::Todo),
+                status("in_progress", StatusCategory::InProgress),
+                status("qa_review", StatusCategory::InProgress),
+                status("closed", StatusCategory::Done),
+            ],
+            transitions: vec![
+                transition("open", "in_progress"),
+                transition("in_progress", "qa_review"),
+                transition("qa_review", "closed"),
+                transition("qa_review", "in_progress"),
+                transition(ANY_STATUS, "open"),
+            ],
+            initial_status: "open".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_default_allows_every_move() {
+        let workflow = Workflow::default();
+        assert!(workflow.validate().is_ok());
+        assert!(workflow.check_transition("backlog", "done").is_ok());
+        assert!(workflow.check_transition("done", "blocked").is_ok());
+        assert!(workflow.is_done("done"));
+    }
+
+    #[test]
+    fn test_transitions() {
+        let workflow = review_workflow();
+        assert!(workflow.validate().is_ok());
+        assert!(workflow.check_transition("open", "in_progress").is_ok());
+        assert!(workflow.check_transition("closed", "open").is_ok());
+        assert_eq!(workflow.next_statuses("in_progress"), vec!["open", "qa_review"]);
+
+        let err = workflow.check_transition("open", "closed").unwrap_err().to_string();
+        assert!(err.contains("From 'open' it can move to: in_progress"), "{}", err);
+
+        // Tickets stranded in a removed status can always be moved
+        assert!(workflow.check_transition("backlog", "closed").is_ok());
+    }
+
+    #[test]
+    fn test_unknown_status_suggests_key() {
+        let workflow = review_workflow();
+        let err = workflow.check_status("In-Progress").unwrap_err().to_string();
+        assert!(err.contains("Did you mean 'in_progress'?"), "{}", err);
+        assert_eq!(workflow.suggest("QA Review"), Some("qa_review"));
+        assert_eq!(workflow.suggest("inprogress"), Some("in_progress"));
+        assert_eq!(workflow.suggest("shipped"), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_inconsistent_workflows() {
+        let mut workflow = review_workflow();
+        workflow.initial_status = "new".to_string();
+        assert!(workflow.validate().is_err());
+
+        let mut workflow = review_workflow();
+        workflow.statuses[1].key = "In Progress".to_string();
+        assert!(workflow.validate().is_err());
+
+        let mut workflow = review_workflow();
+        workflow.statuses.retain(|s| s.category != StatusCategory::Done);
+        workflow.transitions.clear();
+        assert!(workflow.validate().is_err());
+    }
+}
@@ -50,9 +50,20 @@ pub struct ListTicketsParams {
     #[serde(default)]
     pub parent_epic_id: Option<String>,
     #[serde(default)]
+    pub sprint_id: Option<String>,
+    #[serde(default)]
     pub unassigned: Option<bool>,
+    /// created, updated, number, points or status
+    #[serde(default)]
+    pub sort: Option<String>,
+    /// asc or desc
+    #[serde(default)]
+    pub order: Option<String>,
     #[serde(default = "default_limit")]
     pub limit: u64,
+    /// `next_cursor` of the previous page
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 fn default_limit() -> u64 {
@@ -67,6 +78,34 @@ pub struct ClaimTicketParams {
     pub message: Option<String>,
 }
 
+/// Parameters for changing many tickets at once
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct BulkUpdateTicketsParams {
+    /// Tickets to change, as UUIDs or numbers such as JIL-42
+    pub ticket_ids: Vec<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub add_assignees: Vec<String>,
+    #[serde(default)]
+    pub remove_assignees: Vec<String>,
+    #[serde(default)]
+    pub add_labels: Vec<String>,
+    #[serde(default)]
+    pub remove_labels: Vec<String>,
+    /// Epic UUID or number to move the tickets into
+    #[serde(default)]
+    pub epic_id: Option<String>,
+    #[serde(default)]
+    pub remove_epic: bool,
+    /// Sprint UUID
+    #[serde(default)]
+    pub add_to_sprint: Option<String>,
+    /// Sprint UUID
+    #[serde(default)]
+    pub remove_from_sprint: Option<String>,
+}
+
 /// Edit operation types for description updates
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -120,6 +159,9 @@ pub struct UpdateStatusParams {
 pub struct AddCommentParams {
     pub ticket_id: String,
     pub content: String,
+    /// Comment to reply to
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 /// Parameters for assigning a ticket
@@ -179,7 +221,9 @@ pub struct ListTemplatesParams {}
 /// Parameters for creating from template
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct CreateFromTemplateParams {
+    /// Template name or ID (see list_templates)
     pub template: String,
+    /// Values for the template's {{variable}} placeholders
     pub variables: serde_json::Value,
     #[serde(default)]
     pub assignee: Option<String>,
@@ -85,7 +85,7 @@ impl JilityService {
                 "title": title,
                 "description": description.unwrap_or_default(),
                 "story_points": story_points,
-                "status": status.unwrap_or_else(|| "backlog".to_string()),
+                "status": status,
                 "assignees": assignees.unwrap_or_default(),
                 "labels": labels.unwrap_or_default(),
                 "parent_id": parent_id,
@@ -97,7 +97,7 @@ impl JilityService {
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("API error: {}", error_text));
+            return Err(format!("API error: {}", error_message(&error_text)));
         }
 
         let ticket: serde_json::Value = response.json().await
@@ -148,7 +148,7 @@ impl JilityService {
                     "title": ticket_params.title,
                     "description": ticket_params.description.unwrap_or_default(),
                     "story_points": ticket_params.story_points,
-                    "status": ticket_params.status.unwrap_or_else(|| "backlog".to_string()),
+                    "status": ticket_params.status,
                     "assignees": ticket_params.assignees.unwrap_or_default(),
                     "labels": ticket_params.labels.unwrap_or_default(),
                     "parent_id": params.parent_id.clone(),
@@ -235,43 +235,45 @@ impl JilityService {
 
     /// Query tickets with filters
     #[tool(
-        description = "List tickets with optional filters for status, assignee, labels, etc. Returns a summary of matching tickets."
+        description = "List tickets with optional filters for status, assignee, labels (tickets must have all of them), parent, epic and sprint. Sort by created, updated, number, points or status (order asc or desc). Returns up to `limit` tickets (default 50); pass the returned cursor to get the next page."
     )]
     pub async fn list_tickets(
         &self,
-        #[tool(param)] status: Option<Vec<String>>,
-        #[tool(param)] assignee: Option<String>,
-        #[tool(param)] labels: Option<Vec<String>>,
-        #[tool(param)] parent_id: Option<String>,
-        #[tool(param)] parent_epic_id: Option<String>,
-        #[tool(param)] unassigned: Option<bool>,
-        #[tool(param)] limit: Option<u64>,
+        #[tool(aggr)] params: ListTicketsParams,
     ) -> Result<String, String> {
 
-        let mut url = format!("{}/tickets", self.api_base_url);
-        let mut query_params = Vec::new();
+        let url = format!("{}/tickets", self.api_base_url);
 
         // Always filter by the configured project ID
-        query_params.push(format!("project_id={}", self.project_id));
-
-        if let Some(status) = &status {
-            for s in status {
-                query_params.push(format!("status[]={}", s));
-            }
+        let mut query_params = vec![
+            ("project_id", self.project_id.clone()),
+            ("limit", params.limit.to_string()),
+        ];
+        for s in params.status.unwrap_or_default() {
+            query_params.push(("status", s));
         }
-        if let Some(assignee) = &assignee {
-            query_params.push(format!("assignee={}", assignee));
+        for label in params.labels.unwrap_or_default() {
+            query_params.push(("label", label));
         }
-
-        if !query_params.is_empty() {
-            url.push('?');
-            url.push_str(&query_params.join("&"));
+        let optional = [
+            ("assignee", params.assignee),
+            ("parent_id", params.parent_id),
+            ("epic_id", params.parent_epic_id),
+            ("sprint_id", params.sprint_id),
+            ("sort", params.sort),
+            ("order", params.order),
+            ("cursor", params.cursor),
+        ];
+        query_params.extend(optional.into_iter().filter_map(|(k, v)| v.map(|v| (k, v))));
+        if params.unassigned == Some(true) {
+            query_params.push(("unassigned", "true".to_string()));
         }
 
         let response = self.build_request(
             reqwest::Method::GET,
             url.clone()
         )
+            .query(&query_params)
             .send()
             .await
             .map_err(|e| format!("Failed to list tickets: {}", e))?;
@@ -279,11 +281,12 @@ impl JilityService {
         if !response.status().is_success() {
             let status = response.status();
             let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error".to_string());
-            return Err(format!("Failed to list tickets (HTTP {}): {} | URL: {}", status, error_body, url));
+            return Err(format!("Failed to list tickets (HTTP {}): {} | URL: {}", status, error_message(&error_body), url));
         }
 
-        let tickets: Vec<serde_json::Value> = response.json().await
+        let page: serde_json::Value = response.json().await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let tickets = page["tickets"].as_array().cloned().unwrap_or_default();
 
         if tickets.is_empty() {
             return Ok("📋 No tickets found".to_string());
@@ -291,7 +294,7 @@ impl JilityService {
 
         let mut output = format!("📋 Found {} tickets\n\n", tickets.len());
 
-        for ticket in tickets.iter().take(limit.unwrap_or(50) as usize) {
+        for ticket in &tickets {
             output.push_str(&format!(
                 "- {} [ID: {}]: {} ({})\n",
                 ticket["number"].as_str().unwrap_or("?"),
@@ -301,12 +304,19 @@ impl JilityService {
             ));
         }
 
+        if let Some(cursor) = page["next_cursor"].as_str() {
+            output.push_str(&format!(
+                "\nMore tickets match. Call list_tickets again with the same filters and cursor: {}\n",
+                cursor
+            ));
+        }
+
         Ok(output)
     }
 
     /// Agent claims an unassigned ticket
     #[tool(
-        description = "Claim an unassigned ticket and automatically assign it to the agent making the request. Moves ticket to 'in_progress' status."
+        description = "Claim an unassigned ticket and automatically assign it to the agent making the request. Moves ticket to the first in-progress status of the project's workflow."
     )]
     pub async fn claim_ticket(
         &self,
@@ -314,6 +324,33 @@ impl JilityService {
         #[tool(param)] message: Option<String>,
     ) -> Result<String, String> {
 
+        // Move it to the workflow's first in-progress status. This goes first
+        // so a ticket the project's policies won't let us start stays unclaimed.
+        let workflow = self.fetch_workflow().await?;
+        let status = workflow["statuses"]
+            .as_array()
+            .and_then(|statuses| statuses.iter().find(|s| s["category"] == "in_progress"))
+            .and_then(|s| s["key"].as_str())
+            .unwrap_or("in_progress")
+            .to_string();
+
+        let response = self.build_request(
+            reqwest::Method::PATCH,
+            format!("{}/tickets/{}/status", self.api_base_url, ticket_id)
+        )
+            .json(&json!({ "status": status }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to update status: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!(
+                "Failed to claim {}: {}",
+                ticket_id, error_message(&error_text)
+            ));
+        }
+
         // Assign to "agent"
         let response = self.build_request(
             reqwest::Method::POST,
@@ -325,18 +362,9 @@ impl JilityService {
             .map_err(|e| format!("Failed to claim ticket: {}", e))?;
 
         if !response.status().is_success() {
-            return Err(format!("Failed to claim ticket: {}", ticket_id));
+            return Err(format!("Moved {} to {} but failed to assign it to agent", ticket_id, status));
         }
 
-        // Update status to in_progress
-        let _ = self.build_request(
-            reqwest::Method::PATCH,
-            format!("{}/tickets/{}/status", self.api_base_url, ticket_id)
-        )
-            .json(&json!({ "status": "in_progress" }))
-            .send()
-            .await;
-
         Ok(format!("✅ Claimed {} and assigned to agent", ticket_id))
     }
 
@@ -372,9 +400,79 @@ impl JilityService {
         ))
     }
 
+    /// Get description history for a ticket
+    #[tool(
+        description = "Get the description history of a ticket. Version 1 is the most recent edit. Use with revert_ticket to roll back a broken description."
+    )]
+    pub async fn get_ticket_history(
+        &self,
+        #[tool(param)] ticket_id: String,
+    ) -> Result<String, String> {
+
+        let response = self.build_request(
+            reqwest::Method::GET,
+            format!("{}/tickets/{}/history", self.api_base_url, ticket_id)
+        )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get history: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to get history: {}", error_text));
+        }
+
+        let versions: Vec<serde_json::Value> = response.json().await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        if versions.is_empty() {
+            return Ok(format!("📜 No description history for {}", ticket_id));
+        }
+
+        let mut output = format!("📜 {} versions of {}\n\n", versions.len(), ticket_id);
+
+        for version in versions {
+            output.push_str(&format!(
+                "**Version {}** by {} ({})\n{}\n\n---\n\n",
+                version["version"].as_i64().unwrap_or(0),
+                version["user_name"].as_str().unwrap_or("unknown"),
+                version["changed_at"].as_str().unwrap_or(""),
+                version["description"].as_str().unwrap_or("")
+            ));
+        }
+
+        Ok(output)
+    }
+
+    /// Revert a ticket to an earlier version
+    #[tool(
+        description = "Revert a ticket to the state it had at a history version (see get_ticket_history). Restores title, description, status, story points, assignees, labels, parent and epic."
+    )]
+    pub async fn revert_ticket(
+        &self,
+        #[tool(param)] ticket_id: String,
+        #[tool(param)] version: i32,
+    ) -> Result<String, String> {
+
+        let response = self.build_request(
+            reqwest::Method::POST,
+            format!("{}/tickets/{}/revert/{}", self.api_base_url, ticket_id, version)
+        )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to revert ticket: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to revert ticket: {}", error_text));
+        }
+
+        Ok(format!("✅ Reverted {} to version {}", ticket_id, version))
+    }
+
     /// Move ticket through workflow states
     #[tool(
-        description = "Update ticket status. Valid statuses: backlog, todo, in_progress, review, done, blocked."
+        description = "Update ticket status. Statuses and the allowed moves between them come from the project's workflow (see get_workflow); the default workflow uses backlog, todo, in_progress, review, done and blocked."
     )]
     pub async fn update_status(
         &self,
@@ -394,20 +492,179 @@ impl JilityService {
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Failed to update status: {}", error_text));
+            return Err(format!("Failed to update status: {}", error_message(&error_text)));
         }
 
         Ok(format!("✅ Moved {} to {}", ticket_id, status))
     }
 
+    /// Set a ticket's story points
+    #[tool(
+        description = "Set a ticket's story point estimate. Projects with require_story_points switched on refuse to start unestimated tickets."
+    )]
+    pub async fn estimate_ticket(
+        &self,
+        #[tool(param)] ticket_id: String,
+        #[tool(param)] story_points: i32,
+    ) -> Result<String, String> {
+
+        // Updates need the ticket's UUID; `ticket_id` may be e.g. JIL-42
+        let response = self.build_request(
+            reqwest::Method::GET,
+            format!("{}/tickets/{}", self.api_base_url, ticket_id)
+        )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get ticket: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Ticket not found: {}", ticket_id));
+        }
+
+        let data: serde_json::Value = response.json().await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let id = data["ticket"]["id"].as_str()
+            .ok_or_else(|| format!("Ticket not found: {}", ticket_id))?;
+
+        let response = self.build_request(
+            reqwest::Method::PUT,
+            format!("{}/tickets/{}", self.api_base_url, id)
+        )
+            .json(&json!({ "story_points": story_points }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to estimate ticket: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to estimate ticket: {}", error_message(&error_text)));
+        }
+
+        Ok(format!("✅ Estimated {} at {} points", ticket_id, story_points))
+    }
+
+    /// Apply the same changes to several tickets at once
+    #[tool(
+        description = "Change several tickets in one step: set their status, add or remove assignees and labels, move them into or out of an epic or sprint. All tickets change or none do; changes a ticket already has are skipped."
+    )]
+    pub async fn bulk_update_tickets(
+        &self,
+        #[tool(aggr)] params: BulkUpdateTicketsParams,
+    ) -> Result<String, String> {
+        let operations = json!({
+            "status": params.status,
+            "add_assignees": params.add_assignees,
+            "remove_assignees": params.remove_assignees,
+            "add_labels": params.add_labels,
+            "remove_labels": params.remove_labels,
+            "epic_id": params.epic_id,
+            "remove_epic": params.remove_epic,
+            "add_to_sprint": params.add_to_sprint,
+            "remove_from_sprint": params.remove_from_sprint,
+        });
+
+        let response = self.build_request(
+            reqwest::Method::POST,
+            format!("{}/tickets/bulk", self.api_base_url)
+        )
+            .json(&json!({ "tickets": params.ticket_ids, "operations": operations }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to update tickets: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to update tickets: {}", error_message(&error_text)));
+        }
+
+        let result: serde_json::Value = response.json().await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        let mut output = format!(
+            "✅ Updated {} tickets ({} changes)\n\n",
+            result["matched"].as_u64().unwrap_or(0),
+            result["changes"].as_u64().unwrap_or(0)
+        );
+        for ticket in result["tickets"].as_array().into_iter().flatten() {
+            output.push_str(&format!(
+                "- {}: {} ({})\n",
+                ticket["number"].as_str().unwrap_or("?"),
+                ticket["title"].as_str().unwrap_or("?"),
+                ticket["status"].as_str().unwrap_or("?")
+            ));
+        }
+
+        Ok(output)
+    }
+
+    /// Fetch the configured project's workflow
+    async fn fetch_workflow(&self) -> Result<serde_json::Value, String> {
+        let response = self.build_request(
+            reqwest::Method::GET,
+            format!("{}/projects/{}/workflow", self.api_base_url, self.project_id)
+        )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get workflow: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to get workflow: {}", error_text));
+        }
+
+        response.json().await
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    }
+
+    /// Show the project's statuses and allowed transitions
+    #[tool(
+        description = "Show the project's workflow: its statuses in board order with their category (todo, in_progress, done) and which statuses each one can move to."
+    )]
+    pub async fn get_workflow(&self) -> Result<String, String> {
+        let workflow = self.fetch_workflow().await?;
+        let statuses = workflow["statuses"].as_array().cloned().unwrap_or_default();
+        let transitions = workflow["transitions"].as_array().cloned().unwrap_or_default();
+
+        let mut output = format!(
+            "🔀 Workflow ({} statuses, new tickets start in {})\n\n",
+            statuses.len(),
+            workflow["initial_status"].as_str().unwrap_or("?")
+        );
+
+        for status in &statuses {
+            let key = status["key"].as_str().unwrap_or("?");
+            let next: Vec<&str> = if transitions.is_empty() {
+                vec!["any status"]
+            } else {
+                transitions
+                    .iter()
+                    .filter(|t| t["from"] == key || t["from"] == "*")
+                    .filter_map(|t| t["to"].as_str())
+                    .filter(|to| *to != key)
+                    .collect()
+            };
+
+            output.push_str(&format!(
+                "- **{}** ({}, {}) → {}\n",
+                key,
+                status["name"].as_str().unwrap_or(key),
+                status["category"].as_str().unwrap_or("?"),
+                if next.is_empty() { "nothing".to_string() } else { next.join(", ") }
+            ));
+        }
+
+        Ok(output)
+    }
+
     /// Add comment to ticket
     #[tool(
-        description = "Add a markdown comment to a ticket. Supports @mentions for notifying team members."
+        description = "Add a markdown comment to a ticket. Supports @mentions for notifying team members. Pass parent_id (a comment ID from get_comments) to reply in that comment's thread."
     )]
     pub async fn add_comment(
         &self,
         #[tool(param)] ticket_id: String,
         #[tool(param)] content: String,
+        #[tool(param)] parent_id: Option<String>,
     ) -> Result<String, String> {
 
         let response = self.build_request(
@@ -415,8 +672,8 @@ impl JilityService {
             format!("{}/tickets/{}/comments", self.api_base_url, ticket_id)
         )
             .json(&json!({
-                "author": "agent",
-                "content": content
+                "content": content,
+                "parent_id": parent_id
             }))
             .send()
             .await
@@ -432,7 +689,7 @@ impl JilityService {
 
     /// Get comments for a ticket
     #[tool(
-        description = "Get all comments for a ticket. Returns array of comments with author, timestamp, and content. Useful for reading human discussion before working on a ticket."
+        description = "Get all comments for a ticket as threads, with each comment's ID, author, timestamp, reactions and content. Replies are indented under the comment they answer. Useful for reading human discussion before working on a ticket."
     )]
     pub async fn get_comments(
         &self,
@@ -460,15 +717,54 @@ impl JilityService {
 
         let mut output = format!("💬 {} comments on {}\n\n", comments.len(), ticket_id);
 
-        for comment in comments {
+        // Depth-first from the top-level comments; replies come after their
+        // parent in the oldest-first list
+        let parent_of = |c: &serde_json::Value| c["parent_id"].as_str().map(str::to_string);
+        let mut stack: Vec<(usize, &serde_json::Value)> = comments
+            .iter()
+            .rev()
+            .filter(|c| parent_of(c).is_none())
+            .map(|c| (0, c))
+            .collect();
+        let mut first = true;
+        while let Some((depth, comment)) = stack.pop() {
+            let id = comment["id"].as_str().unwrap_or("");
             let author = comment["author"].as_str().unwrap_or("unknown");
             let created_at = comment["created_at"].as_str().unwrap_or("");
-            let content = comment["content"].as_str().unwrap_or("");
+            let indent = "> ".repeat(depth);
+            if depth == 0 && !first {
+                output.push_str("---\n\n");
+            }
+            first = false;
 
-            output.push_str(&format!(
-                "**{}** ({})\n{}\n\n---\n\n",
-                author, created_at, content
-            ));
+            if comment["deleted_at"].is_string() {
+                output.push_str(&format!("{}_comment removed_ ({})\n\n", indent, id));
+            } else {
+                let content = comment["content"].as_str().unwrap_or("");
+                let edited = if comment["revision_count"].as_u64().unwrap_or(0) > 0 { ", edited" } else { "" };
+                output.push_str(&format!(
+                    "{}**{}** ({}{}) [{}]\n",
+                    indent, author, created_at, edited, id
+                ));
+                for line in content.lines() {
+                    output.push_str(&format!("{}{}\n", indent, line));
+                }
+                if let Some(reactions) = comment["reactions"].as_array().filter(|r| !r.is_empty()) {
+                    let summary: Vec<String> = reactions
+                        .iter()
+                        .map(|r| format!("{} {}", r["emoji"].as_str().unwrap_or("?"), r["count"]))
+                        .collect();
+                    output.push_str(&format!("{}{}\n", indent, summary.join("  ")));
+                }
+                output.push('\n');
+            }
+            stack.extend(
+                comments
+                    .iter()
+                    .rev()
+                    .filter(|c| parent_of(c).as_deref() == Some(id))
+                    .map(|c| (depth + 1, c)),
+            );
         }
 
         Ok(output)
@@ -524,8 +820,7 @@ impl JilityService {
         )
             .json(&json!({
                 "commit_hash": commit_hash,
-                "commit_message": commit_message,
-                "linked_by": "agent"
+                "commit_message": commit_message
             }))
             .send()
             .await
@@ -561,7 +856,8 @@ impl JilityService {
             .map_err(|e| format!("Failed to add dependency: {}", e))?;
 
         if !response.status().is_success() {
-            return Err("Failed to add dependency".to_string());
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to add dependency: {}", error_text));
         }
 
         Ok(format!(
@@ -603,40 +899,151 @@ impl JilityService {
 
     /// Get full dependency tree
     #[tool(
-        description = "Get the complete dependency graph for a ticket, showing what it depends on and what depends on it."
+        description = "Get the dependency graph for a ticket, showing what it depends on and what depends on it. Set depth to include transitive dependencies (default 1)."
     )]
     pub async fn get_dependency_graph(
         &self,
         #[tool(param)] ticket_id: String,
+        #[tool(param)] depth: Option<u32>,
     ) -> Result<String, String> {
 
         let response = self.build_request(
             reqwest::Method::GET,
-            format!("{}/tickets/{}", self.api_base_url, ticket_id)
+            format!("{}/tickets/{}/dependency-graph", self.api_base_url, ticket_id)
         )
+            .query(&[("depth", depth.unwrap_or(1))])
             .send()
             .await
-            .map_err(|e| format!("Failed to get ticket: {}", e))?;
+            .map_err(|e| format!("Failed to get dependency graph: {}", e))?;
 
         if !response.status().is_success() {
-            return Err("Ticket not found".to_string());
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to get dependency graph: {}", error_text));
         }
 
         let data: serde_json::Value = response.json().await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
 
         let empty_array = Vec::new();
-        let deps = data["dependencies"].as_array().unwrap_or(&empty_array);
-        let dependents = data["dependents"].as_array().unwrap_or(&empty_array);
+        let nodes = data["nodes"].as_array().unwrap_or(&empty_array);
+        let edges = data["edges"].as_array().unwrap_or(&empty_array);
+
+        let numbers: std::collections::HashMap<&str, &str> = nodes
+            .iter()
+            .map(|n| (n["id"].as_str().unwrap_or("?"), n["number"].as_str().unwrap_or("?")))
+            .collect();
+        let number = |id: &serde_json::Value| {
+            numbers.get(id.as_str().unwrap_or("?")).copied().unwrap_or("?")
+        };
 
-        Ok(format!(
-            "📊 Dependency graph for {}\n\n\
-             **Dependencies (blocks):** {}\n\
-             **Dependents (blocked by):** {}",
-            ticket_id,
-            deps.len(),
-            dependents.len()
-        ))
+        let mut output = format!(
+            "📊 Dependency graph for {} ({} tickets, {} links)\n\n",
+            data["ticket"]["number"].as_str().unwrap_or(&ticket_id),
+            nodes.len(),
+            edges.len()
+        );
+
+        for node in nodes {
+            output.push_str(&format!(
+                "- {} [{}] {} (depth {})\n",
+                node["number"].as_str().unwrap_or("?"),
+                node["status"].as_str().unwrap_or("?"),
+                node["title"].as_str().unwrap_or("?"),
+                node["depth"].as_u64().unwrap_or(0)
+            ));
+        }
+
+        if !edges.is_empty() {
+            output.push_str("\n**Links:**\n");
+            for edge in edges {
+                output.push_str(&format!(
+                    "- {} depends on {}\n",
+                    number(&edge["ticket_id"]),
+                    number(&edge["depends_on_id"])
+                ));
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Plan work order for an epic or sprint
+    #[tool(
+        description = "Get the execution order and critical path (weighted by story points) for the tickets in an epic or a sprint. Provide exactly one of epic_id or sprint_id."
+    )]
+    pub async fn get_execution_plan(
+        &self,
+        #[tool(param)] epic_id: Option<String>,
+        #[tool(param)] sprint_id: Option<String>,
+    ) -> Result<String, String> {
+
+        let url = match (epic_id, sprint_id) {
+            (Some(epic_id), None) => format!("{}/epics/{}/dependency-graph", self.api_base_url, epic_id),
+            (None, Some(sprint_id)) => format!("{}/sprints/{}/dependency-graph", self.api_base_url, sprint_id),
+            _ => return Err("Provide exactly one of epic_id or sprint_id".to_string()),
+        };
+
+        let response = self.build_request(reqwest::Method::GET, url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get execution plan: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to get execution plan: {}", error_text));
+        }
+
+        let data: serde_json::Value = response.json().await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        let empty_array = Vec::new();
+        let nodes = data["nodes"].as_array().unwrap_or(&empty_array);
+        let numbers: std::collections::HashMap<&str, (&str, &str)> = nodes
+            .iter()
+            .map(|n| (
+                n["id"].as_str().unwrap_or("?"),
+                (n["number"].as_str().unwrap_or("?"), n["title"].as_str().unwrap_or("?")),
+            ))
+            .collect();
+        let describe = |id: &serde_json::Value| {
+            let (number, title) = numbers.get(id.as_str().unwrap_or("?")).copied().unwrap_or(("?", "?"));
+            format!("{}: {}", number, title)
+        };
+
+        let mut output = String::from("🗺️ Execution order (dependencies first)\n\n");
+        for (i, id) in data["topological_order"].as_array().unwrap_or(&empty_array).iter().enumerate() {
+            output.push_str(&format!("{}. {}\n", i + 1, describe(id)));
+        }
+
+        output.push_str(&format!(
+            "\n**Critical path** ({} points)\n",
+            data["critical_path_points"].as_i64().unwrap_or(0)
+        ));
+        for id in data["critical_path"].as_array().unwrap_or(&empty_array) {
+            output.push_str(&format!("- {}\n", describe(id)));
+        }
+
+        Ok(output)
+    }
+
+    /// Fetch the templates available in the configured project
+    async fn fetch_templates(&self) -> Result<Vec<serde_json::Value>, String> {
+        let response = self.build_request(
+            reqwest::Method::GET,
+            format!("{}/templates", self.api_base_url)
+        )
+            .query(&[("project_id", &self.project_id)])
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list templates: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to list templates: {}", error_text));
+        }
+
+        response.json().await
+            .map_err(|e| format!("Failed to parse response: {}", e))
     }
 
     /// List available templates
@@ -644,8 +1051,33 @@ impl JilityService {
         description = "List all available ticket templates. Templates provide pre-filled structure for common ticket types."
     )]
     pub async fn list_templates(&self) -> Result<String, String> {
-        Ok("📋 Available Templates\n\n\
-            (Template system not yet implemented on backend)".to_string())
+        let templates = self.fetch_templates().await?;
+
+        if templates.is_empty() {
+            return Ok("📋 No templates available for this project".to_string());
+        }
+
+        let mut output = format!("📋 {} available templates\n\n", templates.len());
+
+        for template in templates {
+            let variables: Vec<&str> = template["variables"]
+                .as_array()
+                .map(|vars| vars.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            let subtasks = template["subtasks"].as_array().map_or(0, |s| s.len());
+
+            output.push_str(&format!(
+                "- **{}**: {}\n  Variables: {}\n",
+                template["name"].as_str().unwrap_or("?"),
+                template["description"].as_str().unwrap_or("No description"),
+                if variables.is_empty() { "none".to_string() } else { variables.join(", ") }
+            ));
+            if subtasks > 0 {
+                output.push_str(&format!("  Creates {} sub-tasks\n", subtasks));
+            }
+        }
+
+        Ok(output)
     }
 
     /// Create ticket from template
@@ -656,11 +1088,54 @@ impl JilityService {
         &self,
         #[tool(aggr)] params: CreateFromTemplateParams,
     ) -> Result<String, String> {
-        Ok(format!(
-            "📋 Template creation not yet supported\n\n\
-             Requested template: {}",
-            params.template
-        ))
+        let templates = self.fetch_templates().await?;
+        let template = templates
+            .iter()
+            .find(|t| {
+                t["id"].as_str() == Some(params.template.as_str())
+                    || t["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(&params.template))
+            })
+            .ok_or_else(|| format!("Template not found: {}", params.template))?;
+
+        let response = self.build_request(
+            reqwest::Method::POST,
+            format!(
+                "{}/templates/{}/instantiate",
+                self.api_base_url,
+                template["id"].as_str().unwrap_or_default()
+            )
+        )
+            .json(&json!({
+                "project_id": self.project_id,
+                "variables": params.variables,
+                "assignees": params.assignee.map(|a| vec![a]),
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to create from template: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to create from template: {}", error_message(&error_text)));
+        }
+
+        let created: serde_json::Value = response.json().await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        let mut output = format!(
+            "✅ Created {}: {}\n",
+            created["ticket"]["number"].as_str().unwrap_or("?"),
+            created["ticket"]["title"].as_str().unwrap_or("?")
+        );
+        for subtask in created["subtasks"].as_array().into_iter().flatten() {
+            output.push_str(&format!(
+                "  - {}: {}\n",
+                subtask["number"].as_str().unwrap_or("?"),
+                subtask["title"].as_str().unwrap_or("?")
+            ));
+        }
+
+        Ok(output)
     }
 
     /// Search tickets by text
@@ -755,7 +1230,6 @@ impl JilityService {
                 "description": description.unwrap_or_default(),
                 "is_epic": true,
                 "epic_color": epic_color,
-                "status": "backlog",
             }))
             .send()
             .await
@@ -878,12 +1352,18 @@ impl JilityService {
 
         let sprint_id = sprint["id"].as_str().unwrap_or("unknown");
         let sprint_name = sprint["name"].as_str().unwrap_or("unknown");
+        let capacity_note = if capacity.is_none() && !sprint["capacity"].is_null() {
+            " (suggested from recent velocity)"
+        } else {
+            ""
+        };
 
         Ok(format!(
-            "✅ Created sprint: {} (ID: {})\n\nCapacity: {} points\nStatus: planned\n\nUse add_ticket_to_sprint to add tickets.",
+            "✅ Created sprint: {} (ID: {})\n\nCapacity: {} points{}\nStatus: planned\n\nUse add_ticket_to_sprint to add tickets.",
             sprint_name,
             sprint_id,
-            capacity.unwrap_or(0)
+            sprint["capacity"].as_i64().unwrap_or(0),
+            capacity_note
         ))
     }
 
@@ -906,9 +1386,6 @@ impl JilityService {
                 reqwest::Method::POST,
                 url
             )
-                .json(&json!({
-                    "added_by": "agent",
-                }))
                 .send()
                 .await;
 
@@ -1129,6 +1606,25 @@ impl JilityService {
     }
 }
 
+/// The `message` of a JSON API error body, or the body itself. Project policy
+/// violations also name the tool that satisfies the policy.
+fn error_message(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+        return body.to_string();
+    };
+    let Some(message) = value["message"].as_str() else {
+        return body.to_string();
+    };
+
+    match value["details"]["policy"].as_str() {
+        Some("require_story_points") => format!(
+            "{} Pass story_points when creating a ticket, or use estimate_ticket on an existing one, then try again.",
+            message
+        ),
+        _ => message.to_string(),
+    }
+}
+
 // Use the tool_box! macro to generate list_tools and call_tool implementations
 tool_box!(JilityService {
     create_ticket,
@@ -1137,7 +1633,12 @@ tool_box!(JilityService {
     list_tickets,
     claim_ticket,
     update_description,
+    get_ticket_history,
+    revert_ticket,
     update_status,
+    estimate_ticket,
+    bulk_update_tickets,
+    get_workflow,
     add_comment,
     get_comments,
     assign_ticket,
@@ -1145,6 +1646,7 @@ tool_box!(JilityService {
     add_dependency,
     remove_dependency,
     get_dependency_graph,
+    get_execution_plan,
     list_templates,
     create_from_template,
     search_tickets,
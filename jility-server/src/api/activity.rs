@@ -1,20 +1,34 @@
-use axum::{extract::{Path, State}, Json};
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use axum::{extract::{Path, State}, Extension, Json};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
 use uuid::Uuid;
 
 use crate::{
+    api::tickets::{format_ticket_number, resolve_ticket_id},
+    auth::{access, AuthUser},
     error::{ApiError, ApiResult},
-    models::{ChangeEventResponse, HistoryVersionResponse},
+    models::{ChangeEventResponse, HistoryVersionResponse, TicketResponse},
     state::AppState,
+    websocket::publish_ticket_event,
+};
+use jility_core::entities::{
+    ticket, ticket_assignee, ticket_change, ticket_label, user, ChangeType, Ticket,
+    TicketAssignee, TicketChange, TicketLabel, User,
 };
-use jility_core::entities::{ticket_change, user, TicketChange, User};
+use jility_core::history::TicketSnapshot;
+use jility_core::policy::ProjectPolicy;
+use jility_core::workflow;
 
 pub async fn get_activity(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<ChangeEventResponse>>> {
-    let ticket_id = Uuid::parse_str(&id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", id)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
 
     let ticket_changes = TicketChange::find()
         .filter(ticket_change::Column::TicketId.eq(ticket_id))
@@ -72,10 +86,12 @@ pub async fn get_activity(
 
 pub async fn get_history(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<HistoryVersionResponse>>> {
-    let ticket_id = Uuid::parse_str(&id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", id)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
 
     let changes = TicketChange::find()
         .filter(ticket_change::Column::TicketId.eq(ticket_id))
@@ -131,10 +147,12 @@ pub async fn get_history(
 
 pub async fn get_version(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path((id, version)): Path<(String, i32)>,
 ) -> ApiResult<Json<HistoryVersionResponse>> {
-    let ticket_id = Uuid::parse_str(&id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", id)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
 
     let changes = TicketChange::find()
         .filter(ticket_change::Column::TicketId.eq(ticket_id))
@@ -170,10 +188,281 @@ pub async fn get_version(
     }))
 }
 
+/// Revert a ticket to the state it had at a description history version.
+///
+/// Versions are numbered as in `get_history` (1 = most recent edit). The ticket's
+/// title, description, status, story points, assignees, labels, parent and epic are
+/// rebuilt by replaying `ticket_change` rows up to that version, and the difference
+/// is applied in a single transaction and recorded as new changes.
 pub async fn revert_to_version(
-    State(_state): State<AppState>,
-    Path((_id, _version)): Path<(String, i32)>,
-) -> ApiResult<Json<serde_json::Value>> {
-    // TODO: Implement revert functionality
-    Ok(Json(serde_json::json!({ "success": true, "message": "Not implemented yet" })))
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((id, version)): Path<(String, i32)>,
+) -> ApiResult<Json<TicketResponse>> {
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
+
+    let ticket = Ticket::find_by_id(ticket_id)
+        .filter(ticket::Column::DeletedAt.is_null())
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
+
+    let changes = TicketChange::find()
+        .filter(ticket_change::Column::TicketId.eq(ticket_id))
+        .order_by_asc(ticket_change::Column::ChangedAt)
+        .all(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?;
+
+    // Same numbering as get_history: newest description edit is version 1
+    let target = (version >= 1)
+        .then(|| {
+            changes
+                .iter()
+                .rev()
+                .filter(|c| c.change_type == ChangeType::DescriptionChanged.as_str())
+                .nth((version - 1) as usize)
+        })
+        .flatten()
+        .ok_or_else(|| ApiError::NotFound(format!("Version {} not found", version)))?;
+
+    let cutoff = changes
+        .iter()
+        .position(|c| c.id == target.id)
+        .map(|idx| idx + 1)
+        .unwrap_or(changes.len());
+
+    let target_state = TicketSnapshot::replay(&changes[..cutoff]).ok_or_else(|| {
+        ApiError::InvalidInput(format!(
+            "Ticket history is incomplete; cannot rebuild version {}",
+            version
+        ))
+    })?;
+
+    let assignees: Vec<String> = TicketAssignee::find()
+        .filter(ticket_assignee::Column::TicketId.eq(ticket_id))
+        .all(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|a| a.assignee)
+        .collect();
+
+    let labels: Vec<String> = TicketLabel::find()
+        .filter(ticket_label::Column::TicketId.eq(ticket_id))
+        .all(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|l| l.label)
+        .collect();
+
+    let current_state = TicketSnapshot::from_ticket(&ticket, assignees, labels);
+
+    let now = Utc::now();
+    let message = format!("Reverted to version {}", version);
+    let mut reverted: Vec<ticket_change::ActiveModel> = Vec::new();
+    let mut record = |change_type: ChangeType, field: &str, old: Option<String>, new: Option<String>| {
+        reverted.push(ticket_change::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            ticket_id: Set(ticket_id),
+            change_type: Set(change_type.as_str().to_string()),
+            field_name: Set(Some(field.to_string())),
+            old_value: Set(old),
+            new_value: Set(new),
+            changed_by: Set(auth_user.actor()),
+            changed_at: Set(now),
+            message: Set(Some(message.clone())),
+        });
+    };
+
+    let mut active: ticket::ActiveModel = ticket.clone().into();
+
+    if current_state.title != target_state.title {
+        record(
+            ChangeType::TitleChanged,
+            "title",
+            Some(current_state.title.clone()),
+            Some(target_state.title.clone()),
+        );
+        active.title = Set(target_state.title.clone());
+    }
+    if current_state.description != target_state.description {
+        record(
+            ChangeType::DescriptionChanged,
+            "description",
+            Some(current_state.description.clone()),
+            Some(target_state.description.clone()),
+        );
+        active.description = Set(target_state.description.clone());
+    }
+    if current_state.status != target_state.status {
+        let workflow = workflow::load(state.db.as_ref(), ticket.project_id).await?;
+        workflow.check_transition(&current_state.status, &target_state.status)?;
+        // The reverted points are the ones the ticket will have
+        ProjectPolicy::load(state.db.as_ref(), ticket.project_id)
+            .await?
+            .check_status_change(
+                &workflow,
+                Some(&current_state.status),
+                &target_state.status,
+                target_state.story_points,
+            )?;
+        record(
+            ChangeType::StatusChanged,
+            "status",
+            Some(current_state.status.clone()),
+            Some(target_state.status.clone()),
+        );
+        active.status = Set(target_state.status.clone());
+    }
+    if current_state.story_points != target_state.story_points {
+        record(
+            ChangeType::StoryPointsChanged,
+            "story_points",
+            current_state.story_points.map(|p| p.to_string()),
+            target_state.story_points.map(|p| p.to_string()),
+        );
+        active.story_points = Set(target_state.story_points);
+    }
+    if current_state.parent_id != target_state.parent_id {
+        record(
+            ChangeType::ParentChanged,
+            "parent_id",
+            current_state.parent_id.map(|id| id.to_string()),
+            target_state.parent_id.map(|id| id.to_string()),
+        );
+        active.parent_id = Set(target_state.parent_id);
+    }
+    if current_state.epic_id != target_state.epic_id {
+        record(
+            ChangeType::EpicChanged,
+            "epic_id",
+            current_state.epic_id.map(|id| id.to_string()),
+            target_state.epic_id.map(|id| id.to_string()),
+        );
+        active.epic_id = Set(target_state.epic_id);
+    }
+
+    let removed_assignees: Vec<String> = current_state
+        .assignees
+        .difference(&target_state.assignees)
+        .cloned()
+        .collect();
+    let added_assignees: Vec<String> = target_state
+        .assignees
+        .difference(&current_state.assignees)
+        .cloned()
+        .collect();
+    let removed_labels: Vec<String> = current_state
+        .labels
+        .difference(&target_state.labels)
+        .cloned()
+        .collect();
+    let added_labels: Vec<String> = target_state
+        .labels
+        .difference(&current_state.labels)
+        .cloned()
+        .collect();
+
+    for assignee in &removed_assignees {
+        record(ChangeType::AssigneeRemoved, "assignee", Some(assignee.clone()), None);
+    }
+    for assignee in &added_assignees {
+        record(ChangeType::AssigneeAdded, "assignee", None, Some(assignee.clone()));
+    }
+    for label in &removed_labels {
+        record(ChangeType::LabelRemoved, "label", Some(label.clone()), None);
+    }
+    for label in &added_labels {
+        record(ChangeType::LabelAdded, "label", None, Some(label.clone()));
+    }
+
+    let result = if reverted.is_empty() {
+        ticket
+    } else {
+        let txn = state.db.begin().await.map_err(ApiError::from)?;
+
+        active.updated_at = Set(now);
+        let result = active.update(&txn).await.map_err(ApiError::from)?;
+
+        if !removed_assignees.is_empty() {
+            TicketAssignee::delete_many()
+                .filter(ticket_assignee::Column::TicketId.eq(ticket_id))
+                .filter(ticket_assignee::Column::Assignee.is_in(removed_assignees))
+                .exec(&txn)
+                .await
+                .map_err(ApiError::from)?;
+        }
+        for assignee in added_assignees {
+            ticket_assignee::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                ticket_id: Set(ticket_id),
+                assignee: Set(assignee),
+                assigned_at: Set(now),
+                assigned_by: Set(auth_user.actor()),
+            }
+            .insert(&txn)
+            .await
+            .map_err(ApiError::from)?;
+        }
+
+        if !removed_labels.is_empty() {
+            TicketLabel::delete_many()
+                .filter(ticket_label::Column::TicketId.eq(ticket_id))
+                .filter(ticket_label::Column::Label.is_in(removed_labels))
+                .exec(&txn)
+                .await
+                .map_err(ApiError::from)?;
+        }
+        for label in added_labels {
+            ticket_label::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                ticket_id: Set(ticket_id),
+                label: Set(label),
+                created_at: Set(now),
+            }
+            .insert(&txn)
+            .await
+            .map_err(ApiError::from)?;
+        }
+
+        for change in reverted {
+            change.insert(&txn).await.map_err(ApiError::from)?;
+        }
+
+        txn.commit().await.map_err(ApiError::from)?;
+        result
+    };
+
+    let number = format_ticket_number(state.db.as_ref(), &result).await?;
+
+    let response = TicketResponse {
+        id: result.id.to_string(),
+        number,
+        title: result.title,
+        description: result.description,
+        status: result.status,
+        story_points: result.story_points,
+        assignees: target_state.assignees.into_iter().collect(),
+        labels: target_state.labels.into_iter().collect(),
+        created_at: result.created_at.to_rfc3339(),
+        updated_at: result.updated_at.to_rfc3339(),
+        created_by: result.created_by,
+        parent_id: result.parent_id.map(|id| id.to_string()),
+        epic_id: result.epic_id.map(|id| id.to_string()),
+        is_epic: result.is_epic,
+        epic_color: result.epic_color,
+    };
+
+    // Broadcast update
+    let ws_message = crate::models::ServerMessage::TicketUpdated {
+        ticket: response.clone(),
+    };
+    publish_ticket_event(&state, ticket_id, &ws_message).await;
+
+    Ok(Json(response))
 }
@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::Utc;
+use sea_orm::TransactionTrait;
+
+use crate::{
+    archive,
+    auth::{access, AuthUser},
+    error::ApiResult,
+    models::{RestoreQuery, RestoreReport},
+    state::AppState,
+};
+
+/// Largest archive a restore accepts
+pub const MAX_ARCHIVE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Download a workspace as a JSON Lines archive. Workspace admins only.
+pub async fn export_workspace(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(slug): Path<String>,
+) -> ApiResult<Response> {
+    let workspace = access::workspace_for_admin(state.db.as_ref(), &auth_user, &slug).await?;
+    let filename = format!("{}-{}.jsonl", workspace.slug, Utc::now().format("%Y%m%d"));
+    let body = archive::export(state.db.as_ref(), workspace, &auth_user.actor()).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Restore an archive, sent as the request body, as a new workspace the
+/// caller administers
+pub async fn restore_workspace(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<RestoreQuery>,
+    body: String,
+) -> ApiResult<Json<RestoreReport>> {
+    let (header, records) = jility_core::archive::read(&body)?;
+
+    // Restored attachments reuse blobs already stored; keep them until the
+    // rows pointing at them are committed
+    let _guard = state.blob_lock.read().await;
+    let txn = state.db.begin().await?;
+    let restored = archive::restore(
+        &txn,
+        state.blob_store.as_ref(),
+        &state.attachment_limits,
+        records,
+        &auth_user,
+    )
+    .await?;
+    if query.dry_run {
+        txn.rollback().await?;
+    } else {
+        txn.commit().await?;
+    }
+
+    Ok(Json(RestoreReport {
+        dry_run: query.dry_run,
+        workspace_id: restored.workspace.id.to_string(),
+        name: restored.workspace.name,
+        slug: restored.workspace.slug,
+        version: header.version,
+        exported_at: header.exported_at,
+        records: restored.records,
+        invited: restored.invited,
+        missing_attachments: restored.missing_attachments,
+        warnings: restored.warnings,
+    }))
+}
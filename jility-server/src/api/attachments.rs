@@ -0,0 +1,235 @@
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    api::tickets::resolve_ticket_id,
+    attachments::{
+        attachment_response, content_type_for, is_inline, remove, sanitize_filename,
+        ticket_attachments,
+    },
+    auth::{access, AuthUser},
+    error::{ApiError, ApiResult},
+    models::AttachmentResponse,
+    state::AppState,
+};
+use jility_core::entities::{attachment, Attachment, Comment, WorkspaceRole};
+
+fn multipart_error(err: MultipartError) -> ApiError {
+    ApiError::BadRequest(err.body_text())
+}
+
+fn parse_attachment_id(id: &str) -> ApiResult<Uuid> {
+    Uuid::parse_str(id).map_err(|_| ApiError::InvalidInput(format!("Invalid attachment ID: {}", id)))
+}
+
+/// The attachment, if the caller can see its ticket, and the caller's role
+async fn attachment_for_member(
+    state: &AppState,
+    user: &AuthUser,
+    id: &str,
+) -> ApiResult<(attachment::Model, WorkspaceRole)> {
+    let attachment = Attachment::find_by_id(parse_attachment_id(id)?)
+        .one(state.db.as_ref())
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Attachment not found: {}", id)))?;
+    let role = access::require_ticket(state.db.as_ref(), user, attachment.ticket_id).await?;
+    Ok((attachment, role))
+}
+
+/// Upload a file to a ticket as `multipart/form-data`: the content in a
+/// `file` field and, to attach it to a comment, the comment's ID in a
+/// `comment_id` field
+pub async fn upload_attachment(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<AttachmentResponse>> {
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
+
+    let limits = &state.attachment_limits;
+    let mut file = None;
+    let mut comment_id = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        match field.name() {
+            Some("file") => {
+                let filename = sanitize_filename(field.file_name().unwrap_or_default());
+                let content_type = content_type_for(field.content_type(), &filename);
+                // Stop reading as soon as the upload is too big
+                let mut data = Vec::new();
+                while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+                    if data.len() + chunk.len() > limits.max_bytes {
+                        return Err(ApiError::InvalidInput(format!(
+                            "Attachments are limited to {} bytes",
+                            limits.max_bytes
+                        )));
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+                file = Some((filename, content_type, data));
+            }
+            Some("comment_id") => {
+                let value = field.text().await.map_err(multipart_error)?;
+                comment_id = Some(Uuid::parse_str(value.trim()).map_err(|_| {
+                    ApiError::InvalidInput(format!("Invalid comment ID: {}", value))
+                })?);
+            }
+            _ => {}
+        }
+    }
+
+    let (filename, content_type, data) =
+        file.ok_or_else(|| ApiError::InvalidInput("Missing 'file' field".to_string()))?;
+    if data.is_empty() {
+        return Err(ApiError::InvalidInput("File is empty".to_string()));
+    }
+    if !limits.allows(&content_type) {
+        return Err(ApiError::InvalidInput(format!(
+            "Attachments of type {} are not allowed",
+            content_type
+        )));
+    }
+
+    if let Some(comment_id) = comment_id {
+        let comment = Comment::find_by_id(comment_id)
+            .one(state.db.as_ref())
+            .await?
+            .filter(|c| c.ticket_id == ticket_id && c.deleted_at.is_none())
+            .ok_or_else(|| {
+                ApiError::InvalidInput(format!("Comment not found on this ticket: {}", comment_id))
+            })?;
+        if comment.author_user_id != Some(auth_user.id) {
+            return Err(ApiError::Forbidden(
+                "Only the comment's author can attach files to it".to_string(),
+            ));
+        }
+    }
+
+    // Identical content is stored once. The row goes in first and the blob
+    // is always written, so a removal that saw no row for this hash and
+    // deleted the blob can't leave this attachment without content.
+    let sha256 = format!("{:x}", Sha256::digest(&data));
+    let size_bytes = data.len() as i64;
+    let attachment = attachment::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        ticket_id: Set(ticket_id),
+        comment_id: Set(comment_id),
+        filename: Set(filename),
+        content_type: Set(content_type),
+        size_bytes: Set(size_bytes),
+        sha256: Set(sha256),
+        uploaded_by: Set(auth_user.actor()),
+        uploaded_by_user_id: Set(Some(auth_user.id)),
+        created_at: Set(Utc::now()),
+    }
+    .insert(state.db.as_ref())
+    .await?;
+
+    let stored = {
+        let _guard = state.blob_lock.read().await;
+        state.blob_store.put(&attachment.sha256, data).await
+    };
+    if let Err(e) = stored {
+        remove(&state, vec![attachment]).await?;
+        return Err(e.into());
+    }
+
+    Ok(Json(attachment_response(attachment)))
+}
+
+/// A ticket's attachments, oldest first, including those on its comments
+pub async fn list_attachments(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Vec<AttachmentResponse>>> {
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
+
+    Ok(Json(ticket_attachments(state.db.as_ref(), ticket_id).await?))
+}
+
+pub async fn get_attachment(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<AttachmentResponse>> {
+    let (attachment, _) = attachment_for_member(&state, &auth_user, &id).await?;
+    Ok(Json(attachment_response(attachment)))
+}
+
+/// `filename*` parameter value (RFC 5987) for a `Content-Disposition` header
+fn encode_filename(filename: &str) -> String {
+    filename
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// The attachment's content. Only raster images are shown inline.
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Response> {
+    let (attachment, _) = attachment_for_member(&state, &auth_user, &id).await?;
+    let data = state.blob_store.get(&attachment.sha256).await?.ok_or_else(|| {
+        ApiError::NotFound(format!("Content of attachment {} is missing from storage", id))
+    })?;
+
+    let fallback: String = attachment
+        .filename
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() || c == ' ') && c != '"' && c != '\\' { c } else { '_' })
+        .collect();
+    let disposition = format!(
+        "{}; filename=\"{}\"; filename*=UTF-8''{}",
+        if is_inline(&attachment.content_type) { "inline" } else { "attachment" },
+        fallback,
+        encode_filename(&attachment.filename)
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::ETAG, format!("\"{}\"", attachment.sha256)),
+            (header::CACHE_CONTROL, "private, max-age=3600".to_string()),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// Attachments can be deleted by their uploader or a workspace admin
+pub async fn delete_attachment(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let (attachment, role) = attachment_for_member(&state, &auth_user, &id).await?;
+    if attachment.uploaded_by_user_id != Some(auth_user.id) && role != WorkspaceRole::Admin {
+        return Err(ApiError::Forbidden(
+            "Only the uploader or a workspace admin can delete this attachment".to_string(),
+        ));
+    }
+
+    remove(&state, vec![attachment]).await?;
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
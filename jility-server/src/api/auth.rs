@@ -8,7 +8,7 @@ use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::auth::AuthUser;
+use crate::auth::{AuthUser, Scope};
 use crate::error::{ApiError, ApiResult};
 use crate::state::AppState;
 
@@ -305,6 +305,7 @@ pub async fn logout(
         .filter(jility_core::session::Column::RevokedAt.is_null())
         .exec(&*state.db)
         .await?;
+    state.ws_state.revoke_sessions(auth_user.id).await;
 
     Ok(Json(serde_json::json!({ "success": true })))
 }
@@ -333,8 +334,17 @@ pub async fn create_api_key(
         return Err(ApiError::Validation("API key name is required".to_string()));
     }
 
+    if req.scopes.is_empty() {
+        return Err(ApiError::Validation(
+            "At least one scope is required".to_string(),
+        ));
+    }
+    for scope in &req.scopes {
+        scope.parse::<Scope>().map_err(ApiError::Validation)?;
+    }
+
     // Generate API key
-    let (key, key_hash) = state
+    let (key, key_digest) = state
         .auth_service
         .generate_api_key()
         .map_err(|e| ApiError::Internal(format!("Failed to generate API key: {}", e)))?;
@@ -350,7 +360,7 @@ pub async fn create_api_key(
         id: Set(Uuid::new_v4()),
         user_id: Set(auth_user.id),
         name: Set(req.name.clone()),
-        key_hash: Set(key_hash),
+        key_hash: Set(key_digest),
         prefix: Set(prefix.clone()),
         scopes: Set(serde_json::to_string(&req.scopes).unwrap_or_else(|_| "[]".to_string())),
         expires_at: Set(expires_at.map(|t| t.into())),
@@ -428,6 +438,8 @@ pub async fn revoke_api_key(
     let mut active_key: jility_core::api_key::ActiveModel = api_key.into();
     active_key.revoked_at = Set(Some(chrono::Utc::now().into()));
     active_key.update(&*state.db).await?;
+    state.api_key_cache.invalidate(key_uuid).await;
+    state.ws_state.revoke_api_key(key_uuid).await;
 
     Ok(Json(serde_json::json!({ "success": true })))
 }
@@ -0,0 +1,851 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{extract::State, Extension, Json};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::tickets::{filter_tickets, resolve_ticket_id, ticket_responses},
+    auth::{access, AuthUser, Scope},
+    error::{ApiError, ApiResult},
+    models::{
+        format_uuid, BulkOperations, BulkTicketRequest, BulkTicketResponse, ServerMessage,
+        TicketFilter,
+    },
+    state::AppState,
+    websocket::publish_tickets_event,
+};
+use jility_core::entities::{
+    sprint_ticket, ticket, ticket_assignee, ticket_change, ticket_label, ChangeType, Sprint,
+    SprintTicket, Ticket, TicketAssignee, TicketLabel,
+};
+use jility_core::policy::ProjectPolicy;
+use jility_core::workflow::{self, Workflow};
+
+/// Most tickets a single bulk request may change
+const MAX_BULK_TICKETS: usize = 500;
+
+/// Apply one set of operations to many tickets of a project. Everything runs
+/// in one transaction: if any ticket can't take a change, nothing is written.
+/// Operations a ticket already satisfies (a label it already has, the status
+/// it is already in) are skipped for that ticket.
+pub async fn bulk_update(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<BulkTicketRequest>,
+) -> ApiResult<Json<BulkTicketResponse>> {
+    let ops = payload.operations;
+    check_operations(&ops)?;
+    if ops.delete {
+        auth_user.require_scope(Scope::TicketsDelete)?;
+    }
+    if ops.add_to_sprint.is_some() || ops.remove_from_sprint.is_some() {
+        auth_user.require_scope(Scope::SprintsWrite)?;
+    }
+
+    let db = state.db.as_ref();
+    let tickets = select_tickets(db, &auth_user, payload.tickets, payload.filter).await?;
+    let Some(first) = tickets.first() else {
+        return Ok(Json(BulkTicketResponse {
+            matched: 0,
+            changes: 0,
+            tickets: Vec::new(),
+        }));
+    };
+    let project_id = first.project_id;
+    if tickets.iter().any(|t| t.project_id != project_id) {
+        return Err(ApiError::InvalidInput(
+            "Bulk operations apply to tickets of one project at a time".to_string(),
+        ));
+    }
+    let (project, _) = access::project_for_member(db, &auth_user, project_id).await?;
+    let workspace_id = project.workspace_id;
+    let prefix = project.key.unwrap_or_else(|| "TASK".to_string());
+
+    let mut plan = Plan::load(db, project_id, &tickets, &ops).await?;
+
+    let now = Utc::now();
+    let actor = auth_user.actor();
+    let txn = db.begin().await?;
+    let mut changed = Vec::new();
+    let mut changes = 0;
+    for ticket in tickets.iter() {
+        let number = format!("{}-{}", prefix, ticket.ticket_number);
+        let written = plan
+            .apply(&txn, ticket, &ops, &actor, now)
+            .await
+            .map_err(|e| for_ticket(&number, e))?;
+        if written > 0 {
+            changed.push(ticket.id);
+            changes += written;
+        }
+    }
+    txn.commit().await?;
+
+    let ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+    let updated = if ops.delete {
+        Vec::new()
+    } else {
+        let updated = Ticket::find()
+            .filter(ticket::Column::Id.is_in(ids))
+            .order_by_asc(ticket::Column::TicketNumber)
+            .all(db)
+            .await?;
+        ticket_responses(db, updated).await?
+    };
+
+    // Hooks that follow `ticket.deleted` hear about each ticket, as they
+    // would had it been deleted on its own
+    if ops.delete {
+        for ticket in tickets.iter().filter(|t| changed.contains(&t.id)) {
+            let data = json!({
+                "ticket_id": ticket.id,
+                "number": format!("{}-{}", prefix, ticket.ticket_number),
+            });
+            state
+                .webhooks
+                .enqueue(db, workspace_id, Some(project_id), "ticket.deleted", data)
+                .await;
+        }
+    }
+
+    if !changed.is_empty() {
+        let message = ServerMessage::TicketsBulkUpdated {
+            project_id: project_id.to_string(),
+            ticket_ids: changed.iter().map(format_uuid).collect(),
+            operations: ops.clone(),
+        };
+        let left_sprints = ops.remove_from_sprint.into_iter().collect();
+        publish_tickets_event(&state, project_id, changed, left_sprints, &message).await;
+    }
+
+    Ok(Json(BulkTicketResponse {
+        matched: tickets.len(),
+        changes,
+        tickets: updated,
+    }))
+}
+
+fn check_operations(ops: &BulkOperations) -> ApiResult<()> {
+    let others = ops.status.is_some()
+        || !ops.add_assignees.is_empty()
+        || !ops.remove_assignees.is_empty()
+        || !ops.add_labels.is_empty()
+        || !ops.remove_labels.is_empty()
+        || ops.epic_id.is_some()
+        || ops.remove_epic
+        || ops.add_to_sprint.is_some()
+        || ops.remove_from_sprint.is_some();
+
+    if ops.delete && others {
+        return Err(ApiError::InvalidInput(
+            "delete can't be combined with other operations".to_string(),
+        ));
+    }
+    if !ops.delete && !others {
+        return Err(ApiError::InvalidInput("No operations given".to_string()));
+    }
+    if ops.epic_id.is_some() && ops.remove_epic {
+        return Err(ApiError::InvalidInput(
+            "epic_id and remove_epic can't be combined".to_string(),
+        ));
+    }
+    if ops.add_to_sprint.is_some() && ops.add_to_sprint == ops.remove_from_sprint {
+        return Err(ApiError::InvalidInput(
+            "add_to_sprint and remove_from_sprint name the same sprint".to_string(),
+        ));
+    }
+    let overlap =
+        |add: &[String], remove: &[String]| add.iter().find(|v| remove.contains(v)).cloned();
+    if let Some(label) = overlap(&ops.add_labels, &ops.remove_labels) {
+        return Err(ApiError::InvalidInput(format!(
+            "Label '{}' is both added and removed",
+            label
+        )));
+    }
+    if let Some(assignee) = overlap(&ops.add_assignees, &ops.remove_assignees) {
+        return Err(ApiError::InvalidInput(format!(
+            "Assignee '{}' is both added and removed",
+            assignee
+        )));
+    }
+
+    Ok(())
+}
+
+/// The non-deleted tickets named by `ids`, or matched by `filter`, in number order
+async fn select_tickets(
+    db: &DatabaseConnection,
+    auth_user: &AuthUser,
+    ids: Vec<String>,
+    filter: Option<TicketFilter>,
+) -> ApiResult<Vec<ticket::Model>> {
+    match (ids.is_empty(), filter) {
+        (false, Some(_)) => Err(ApiError::InvalidInput(
+            "Give either tickets or filter, not both".to_string(),
+        )),
+        (true, None) => Err(ApiError::InvalidInput(
+            "Give the tickets to change or a filter that selects them".to_string(),
+        )),
+        (true, Some(filter)) => {
+            if filter.project_id.is_none() {
+                return Err(ApiError::InvalidInput(
+                    "A bulk filter must name a project_id".to_string(),
+                ));
+            }
+            let (query, _) = filter_tickets(db, auth_user, &filter).await?;
+            let tickets = query
+                .order_by_asc(ticket::Column::TicketNumber)
+                .limit(MAX_BULK_TICKETS as u64 + 1)
+                .all(db)
+                .await?;
+            if tickets.len() > MAX_BULK_TICKETS {
+                return Err(ApiError::InvalidInput(format!(
+                    "The filter matches more than {} tickets; narrow it down",
+                    MAX_BULK_TICKETS
+                )));
+            }
+            Ok(tickets)
+        }
+        (false, None) => {
+            if ids.len() > MAX_BULK_TICKETS {
+                return Err(ApiError::InvalidInput(format!(
+                    "At most {} tickets can be changed at once",
+                    MAX_BULK_TICKETS
+                )));
+            }
+            let mut uuids = Vec::with_capacity(ids.len());
+            for id in &ids {
+                let uuid = resolve_ticket_id(db, id).await?;
+                if !uuids.contains(&uuid) {
+                    uuids.push(uuid);
+                }
+            }
+            let tickets = Ticket::find()
+                .filter(ticket::Column::Id.is_in(uuids.clone()))
+                .filter(ticket::Column::DeletedAt.is_null())
+                .order_by_asc(ticket::Column::TicketNumber)
+                .all(db)
+                .await?;
+            if let Some(missing) = uuids
+                .iter()
+                .find(|id| !tickets.iter().any(|t| t.id == **id))
+            {
+                return Err(ApiError::NotFound(format!("Ticket not found: {}", missing)));
+            }
+            Ok(tickets)
+        }
+    }
+}
+
+/// Type, field, old value, new value and message of a `ticket_change` row
+type Change = (
+    ChangeType,
+    Option<&'static str>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Everything needed to apply the operations, loaded before the transaction starts
+struct Plan {
+    workflow: Option<(Workflow, ProjectPolicy)>,
+    epic_id: Option<Uuid>,
+    assignees: HashMap<Uuid, HashSet<String>>,
+    labels: HashMap<Uuid, HashSet<String>>,
+    /// Tickets already in `add_to_sprint`
+    in_added_sprint: HashSet<Uuid>,
+    /// Tickets currently in `remove_from_sprint`
+    in_removed_sprint: HashSet<Uuid>,
+}
+
+impl Plan {
+    async fn load(
+        db: &DatabaseConnection,
+        project_id: Uuid,
+        tickets: &[ticket::Model],
+        ops: &BulkOperations,
+    ) -> ApiResult<Self> {
+        let ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+
+        let workflow = match &ops.status {
+            Some(status) => {
+                let workflow = workflow::load(db, project_id).await?;
+                workflow.check_status(status)?;
+                Some((workflow, ProjectPolicy::load(db, project_id).await?))
+            }
+            None => None,
+        };
+
+        let epic_id = match &ops.epic_id {
+            Some(epic_id) => {
+                let epic_id = resolve_ticket_id(db, epic_id).await?;
+                let epic = Ticket::find_by_id(epic_id)
+                    .filter(ticket::Column::DeletedAt.is_null())
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ApiError::NotFound(format!("Epic not found: {}", epic_id)))?;
+                if !epic.is_epic || epic.project_id != project_id {
+                    return Err(ApiError::InvalidInput(format!(
+                        "Ticket {} is not an epic of this project",
+                        epic_id
+                    )));
+                }
+                // Epics don't nest, which also keeps an epic out of itself
+                if let Some(nested) = tickets.iter().find(|t| t.is_epic) {
+                    return Err(ApiError::InvalidInput(format!(
+                        "Ticket {} is an epic; epics can't belong to other epics",
+                        nested.id
+                    )));
+                }
+                Some(epic_id)
+            }
+            None => None,
+        };
+
+        for sprint_id in ops
+            .add_to_sprint
+            .iter()
+            .chain(ops.remove_from_sprint.iter())
+        {
+            let sprint = Sprint::find_by_id(*sprint_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::NotFound(format!("Sprint {} not found", sprint_id)))?;
+            if sprint.project_id != project_id {
+                return Err(ApiError::InvalidInput(format!(
+                    "Sprint {} belongs to another project",
+                    sprint_id
+                )));
+            }
+        }
+
+        let mut assignees: HashMap<Uuid, HashSet<String>> = HashMap::new();
+        if !ops.add_assignees.is_empty() || !ops.remove_assignees.is_empty() {
+            for row in TicketAssignee::find()
+                .filter(ticket_assignee::Column::TicketId.is_in(ids.clone()))
+                .all(db)
+                .await?
+            {
+                assignees
+                    .entry(row.ticket_id)
+                    .or_default()
+                    .insert(row.assignee);
+            }
+        }
+
+        let mut labels: HashMap<Uuid, HashSet<String>> = HashMap::new();
+        if !ops.add_labels.is_empty() || !ops.remove_labels.is_empty() {
+            for row in TicketLabel::find()
+                .filter(ticket_label::Column::TicketId.is_in(ids.clone()))
+                .all(db)
+                .await?
+            {
+                labels.entry(row.ticket_id).or_default().insert(row.label);
+            }
+        }
+
+        let sprint_members = |sprint_id: Option<Uuid>| {
+            let ids = ids.clone();
+            async move {
+                let Some(sprint_id) = sprint_id else {
+                    return ApiResult::Ok(HashSet::new());
+                };
+                Ok(SprintTicket::find()
+                    .filter(sprint_ticket::Column::SprintId.eq(sprint_id))
+                    .filter(sprint_ticket::Column::TicketId.is_in(ids))
+                    .all(db)
+                    .await?
+                    .into_iter()
+                    .map(|row| row.ticket_id)
+                    .collect())
+            }
+        };
+        let in_added_sprint = sprint_members(ops.add_to_sprint).await?;
+        let in_removed_sprint = sprint_members(ops.remove_from_sprint).await?;
+
+        Ok(Self {
+            workflow,
+            epic_id,
+            assignees,
+            labels,
+            in_added_sprint,
+            in_removed_sprint,
+        })
+    }
+
+    /// Apply `ops` to one ticket, returning the number of changes recorded
+    async fn apply(
+        &mut self,
+        txn: &DatabaseTransaction,
+        ticket: &ticket::Model,
+        ops: &BulkOperations,
+        actor: &str,
+        now: DateTime<Utc>,
+    ) -> ApiResult<usize> {
+        let ticket_id = ticket.id;
+        let mut changes: Vec<Change> = Vec::new();
+        let mut model: ticket::ActiveModel = ticket.clone().into();
+
+        if let (Some(status), Some((workflow, policy))) = (&ops.status, &self.workflow) {
+            if *status != ticket.status {
+                workflow.check_transition(&ticket.status, status)?;
+                policy.check_status_change(
+                    workflow,
+                    Some(&ticket.status),
+                    status,
+                    ticket.story_points,
+                )?;
+                changes.push((
+                    ChangeType::StatusChanged,
+                    Some("status"),
+                    Some(ticket.status.clone()),
+                    Some(status.clone()),
+                    None,
+                ));
+                model.status = Set(status.clone());
+            }
+        }
+
+        let epic_id = if ops.remove_epic { None } else { self.epic_id };
+        if (ops.remove_epic || self.epic_id.is_some()) && epic_id != ticket.epic_id {
+            changes.push((
+                ChangeType::EpicChanged,
+                Some("epic_id"),
+                ticket.epic_id.map(|id| id.to_string()),
+                epic_id.map(|id| id.to_string()),
+                None,
+            ));
+            model.epic_id = Set(epic_id);
+        }
+
+        if ops.delete {
+            changes.push((
+                ChangeType::Deleted,
+                None,
+                None,
+                None,
+                Some("Ticket soft deleted".to_string()),
+            ));
+            model.deleted_at = Set(Some(now));
+        }
+
+        let assignees = self.assignees.entry(ticket_id).or_default();
+        for assignee in &ops.add_assignees {
+            if assignees.insert(assignee.clone()) {
+                ticket_assignee::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    ticket_id: Set(ticket_id),
+                    assignee: Set(assignee.clone()),
+                    assigned_at: Set(now),
+                    assigned_by: Set(actor.to_string()),
+                }
+                .insert(txn)
+                .await?;
+                changes.push((
+                    ChangeType::AssigneeAdded,
+                    Some("assignee"),
+                    None,
+                    Some(assignee.clone()),
+                    None,
+                ));
+            }
+        }
+        for assignee in &ops.remove_assignees {
+            if assignees.remove(assignee) {
+                TicketAssignee::delete_many()
+                    .filter(ticket_assignee::Column::TicketId.eq(ticket_id))
+                    .filter(ticket_assignee::Column::Assignee.eq(assignee.as_str()))
+                    .exec(txn)
+                    .await?;
+                changes.push((
+                    ChangeType::AssigneeRemoved,
+                    Some("assignee"),
+                    Some(assignee.clone()),
+                    None,
+                    None,
+                ));
+            }
+        }
+
+        let labels = self.labels.entry(ticket_id).or_default();
+        for label in &ops.add_labels {
+            if labels.insert(label.clone()) {
+                ticket_label::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    ticket_id: Set(ticket_id),
+                    label: Set(label.clone()),
+                    created_at: Set(now),
+                }
+                .insert(txn)
+                .await?;
+                changes.push((
+                    ChangeType::LabelAdded,
+                    Some("label"),
+                    None,
+                    Some(label.clone()),
+                    None,
+                ));
+            }
+        }
+        for label in &ops.remove_labels {
+            if labels.remove(label) {
+                TicketLabel::delete_many()
+                    .filter(ticket_label::Column::TicketId.eq(ticket_id))
+                    .filter(ticket_label::Column::Label.eq(label.as_str()))
+                    .exec(txn)
+                    .await?;
+                changes.push((
+                    ChangeType::LabelRemoved,
+                    Some("label"),
+                    Some(label.clone()),
+                    None,
+                    None,
+                ));
+            }
+        }
+
+        if let Some(sprint_id) = ops.add_to_sprint {
+            if self.in_added_sprint.insert(ticket_id) {
+                sprint_ticket::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    sprint_id: Set(sprint_id),
+                    ticket_id: Set(ticket_id),
+                    added_at: Set(now),
+                    added_by: Set(actor.to_string()),
+                }
+                .insert(txn)
+                .await?;
+                changes.push((
+                    ChangeType::AddedToSprint,
+                    None,
+                    None,
+                    Some(format_uuid(&sprint_id)),
+                    None,
+                ));
+            }
+        }
+        if let Some(sprint_id) = ops.remove_from_sprint {
+            if self.in_removed_sprint.remove(&ticket_id) {
+                SprintTicket::delete_many()
+                    .filter(sprint_ticket::Column::SprintId.eq(sprint_id))
+                    .filter(sprint_ticket::Column::TicketId.eq(ticket_id))
+                    .exec(txn)
+                    .await?;
+                changes.push((
+                    ChangeType::RemovedFromSprint,
+                    None,
+                    Some(format_uuid(&sprint_id)),
+                    None,
+                    None,
+                ));
+            }
+        }
+
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        model.updated_at = Set(now);
+        model.update(txn).await?;
+
+        let written = changes.len();
+        for (change_type, field_name, old_value, new_value, message) in changes {
+            ticket_change::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                ticket_id: Set(ticket_id),
+                change_type: Set(change_type.as_str().to_string()),
+                field_name: Set(field_name.map(str::to_string)),
+                old_value: Set(old_value),
+                new_value: Set(new_value),
+                changed_by: Set(actor.to_string()),
+                changed_at: Set(now),
+                message: Set(message),
+            }
+            .insert(txn)
+            .await?;
+        }
+
+        Ok(written)
+    }
+}
+
+/// Name the ticket a per-ticket check failed on, so the caller can find it
+/// among the hundreds in the request
+fn for_ticket(number: &str, err: ApiError) -> ApiError {
+    match err {
+        ApiError::InvalidInput(msg) => ApiError::InvalidInput(format!("{}: {}", number, msg)),
+        ApiError::PolicyViolation(mut violation) => {
+            violation.message = format!("{}: {}", number, violation.message);
+            ApiError::PolicyViolation(violation)
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+    use jility_core::entities::{
+        project_workflow, webhook, webhook_delivery, TicketChange, WebhookDelivery,
+    };
+    use sea_orm::PaginatorTrait;
+    use jility_core::workflow::WorkflowTransition;
+
+    async fn bulk(
+        fx: &Fixture,
+        tickets: &[&ticket::Model],
+        operations: serde_json::Value,
+    ) -> ApiResult<BulkTicketResponse> {
+        let ids: Vec<String> = tickets.iter().map(|t| t.id.to_string()).collect();
+        let request = serde_json::from_value(json!({ "tickets": ids, "operations": operations }))
+            .unwrap();
+        let Json(response) =
+            bulk_update(State(fx.state.clone()), Extension(fx.user.clone()), Json(request)).await?;
+        Ok(response)
+    }
+
+    async fn reload(fx: &Fixture, ticket: &ticket::Model) -> ticket::Model {
+        Ticket::find_by_id(ticket.id)
+            .one(fx.state.db.as_ref())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn changes(fx: &Fixture, ticket: &ticket::Model) -> Vec<ticket_change::Model> {
+        TicketChange::find()
+            .filter(ticket_change::Column::TicketId.eq(ticket.id))
+            .all(fx.state.db.as_ref())
+            .await
+            .unwrap()
+    }
+
+    async fn labels(fx: &Fixture, ticket: &ticket::Model) -> Vec<String> {
+        let mut labels: Vec<String> = TicketLabel::find()
+            .filter(ticket_label::Column::TicketId.eq(ticket.id))
+            .all(fx.state.db.as_ref())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.label)
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    #[tokio::test]
+    async fn test_failed_transition_writes_nothing() {
+        let fx = Fixture::new().await;
+        let workflow = Workflow {
+            transitions: vec![WorkflowTransition {
+                from: "todo".to_string(),
+                to: "in_progress".to_string(),
+            }],
+            ..Default::default()
+        };
+        let now = Utc::now();
+        project_workflow::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            project_id: Set(fx.project.id),
+            initial_status: Set(workflow.initial_status.clone()),
+            statuses: Set(serde_json::to_string(&workflow.statuses).unwrap()),
+            transitions: Set(serde_json::to_string(&workflow.transitions).unwrap()),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(fx.state.db.as_ref())
+        .await
+        .unwrap();
+        let ready = fx.ticket("Ready", |t| t.status = Set("todo".to_string())).await;
+        let stuck = fx.ticket("Stuck", |_| {}).await;
+
+        let result = bulk(
+            &fx,
+            &[&ready, &stuck],
+            json!({ "status": "in_progress", "add_labels": ["next"] }),
+        )
+        .await;
+
+        match result {
+            Err(ApiError::InvalidInput(msg)) => assert!(msg.starts_with("JIL-2: "), "{}", msg),
+            other => panic!("expected a transition error, got {:?}", other.map(|r| r.changes)),
+        }
+        for (ticket, status) in [(&ready, "todo"), (&stuck, "backlog")] {
+            assert_eq!(reload(&fx, ticket).await.status, status);
+            assert!(labels(&fx, ticket).await.is_empty());
+            assert!(changes(&fx, ticket).await.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_operations_a_ticket_satisfies_are_skipped() {
+        let fx = Fixture::new().await;
+        let done = fx.ticket("Done", |t| t.status = Set("todo".to_string())).await;
+        ticket_label::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            ticket_id: Set(done.id),
+            label: Set("next".to_string()),
+            created_at: Set(Utc::now()),
+        }
+        .insert(fx.state.db.as_ref())
+        .await
+        .unwrap();
+        let fresh = fx.ticket("Fresh", |_| {}).await;
+
+        let response = bulk(
+            &fx,
+            &[&done, &fresh],
+            json!({ "status": "todo", "add_labels": ["next"], "remove_labels": ["stale"] }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.matched, 2);
+        assert_eq!(response.changes, 2);
+        assert!(changes(&fx, &done).await.is_empty());
+        assert_eq!(reload(&fx, &done).await.updated_at, done.updated_at);
+        assert_eq!(labels(&fx, &done).await, ["next"]);
+        assert_eq!(reload(&fx, &fresh).await.status, "todo");
+        assert_eq!(labels(&fx, &fresh).await, ["next"]);
+    }
+
+    #[tokio::test]
+    async fn test_each_ticket_gets_its_own_change_rows() {
+        let fx = Fixture::new().await;
+        let first = fx.ticket("First", |_| {}).await;
+        let second = fx.ticket("Second", |t| t.status = Set("review".to_string())).await;
+
+        let response = bulk(
+            &fx,
+            &[&first, &second],
+            json!({ "status": "review", "add_assignees": ["bob"], "add_labels": ["ui"] }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.changes, 5);
+
+        let kinds = |rows: &[ticket_change::Model]| {
+            let mut kinds: Vec<String> = rows.iter().map(|r| r.change_type.clone()).collect();
+            kinds.sort();
+            kinds
+        };
+        let first_rows = changes(&fx, &first).await;
+        assert_eq!(
+            kinds(&first_rows),
+            [
+                ChangeType::AssigneeAdded.as_str(),
+                ChangeType::LabelAdded.as_str(),
+                ChangeType::StatusChanged.as_str(),
+            ]
+        );
+        let status = first_rows
+            .iter()
+            .find(|r| r.change_type == ChangeType::StatusChanged.as_str())
+            .unwrap();
+        assert_eq!(status.old_value.as_deref(), Some("backlog"));
+        assert_eq!(status.new_value.as_deref(), Some("review"));
+        let second_rows = changes(&fx, &second).await;
+        assert_eq!(
+            kinds(&second_rows),
+            [ChangeType::AssigneeAdded.as_str(), ChangeType::LabelAdded.as_str()]
+        );
+        assert!(first_rows.iter().chain(&second_rows).all(|r| r.changed_by == "alice"));
+    }
+
+    #[tokio::test]
+    async fn test_ticket_limit() {
+        let fx = Fixture::new().await;
+        let ids: Vec<String> = (0..=MAX_BULK_TICKETS).map(|_| Uuid::new_v4().to_string()).collect();
+        let request = serde_json::from_value(json!({
+            "tickets": ids,
+            "operations": { "add_labels": ["x"] },
+        }))
+        .unwrap();
+        let result =
+            bulk_update(State(fx.state.clone()), Extension(fx.user.clone()), Json(request)).await;
+        assert!(matches!(result, Err(ApiError::InvalidInput(_))));
+
+        for i in 0..=MAX_BULK_TICKETS {
+            fx.ticket(&format!("Ticket {}", i), |_| {}).await;
+        }
+        let request = serde_json::from_value(json!({
+            "filter": { "project_id": fx.project.id.to_string() },
+            "operations": { "add_labels": ["x"] },
+        }))
+        .unwrap();
+        let result =
+            bulk_update(State(fx.state.clone()), Extension(fx.user.clone()), Json(request)).await;
+        assert!(matches!(result, Err(ApiError::InvalidInput(_))));
+        let labelled = TicketLabel::find().count(fx.state.db.as_ref()).await.unwrap();
+        assert_eq!(labelled, 0);
+    }
+
+    #[tokio::test]
+    async fn test_epics_cant_be_moved_into_an_epic() {
+        let fx = Fixture::new().await;
+        let target = fx.ticket("Target", |t| t.is_epic = Set(true)).await;
+        let other = fx.ticket("Other epic", |t| t.is_epic = Set(true)).await;
+        let task = fx.ticket("Task", |_| {}).await;
+
+        let epic_id = target.id.to_string();
+        let result = bulk(&fx, &[&other, &task], json!({ "epic_id": epic_id })).await;
+        assert!(matches!(result, Err(ApiError::InvalidInput(_))));
+        let result = bulk(&fx, &[&target], json!({ "epic_id": epic_id })).await;
+        assert!(matches!(result, Err(ApiError::InvalidInput(_))));
+        assert_eq!(reload(&fx, &task).await.epic_id, None);
+
+        bulk(&fx, &[&task], json!({ "epic_id": epic_id })).await.unwrap();
+        assert_eq!(reload(&fx, &task).await.epic_id, Some(target.id));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_sends_ticket_deleted_per_ticket() {
+        let fx = Fixture::new().await;
+        let now = Utc::now();
+        webhook::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            workspace_id: Set(fx.project.workspace_id),
+            project_id: Set(None),
+            url: Set("https://93.184.216.34/hook".to_string()),
+            secret: Set("secret".to_string()),
+            events: Set(json!(["ticket.deleted"]).to_string()),
+            active: Set(true),
+            created_by: Set("alice".to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(fx.state.db.as_ref())
+        .await
+        .unwrap();
+        let first = fx.ticket("First", |_| {}).await;
+        let second = fx.ticket("Second", |_| {}).await;
+
+        bulk(&fx, &[&first, &second], json!({ "delete": true })).await.unwrap();
+
+        let mut deleted: Vec<(String, String)> = WebhookDelivery::find()
+            .all(fx.state.db.as_ref())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d: webhook_delivery::Model| {
+                let payload: serde_json::Value = serde_json::from_str(&d.payload).unwrap();
+                (d.event, payload["data"]["number"].as_str().unwrap().to_string())
+            })
+            .collect();
+        deleted.sort();
+        assert_eq!(
+            deleted,
+            [
+                ("ticket.deleted".to_string(), "JIL-1".to_string()),
+                ("ticket.deleted".to_string(), "JIL-2".to_string()),
+            ]
+        );
+    }
+}
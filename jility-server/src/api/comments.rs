@@ -1,18 +1,169 @@
+use std::collections::{HashMap, HashSet};
+
 use axum::{extract::{Path, State}, Extension, Json};
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    sea_query::{Expr, OnConflict}, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
 use uuid::Uuid;
 use chrono::Utc;
 
 use crate::{
-    auth::middleware::AuthUser,
+    attachments,
+    auth::{access, middleware::AuthUser},
     error::{ApiError, ApiResult},
-    models::{CreateCommentRequest, UpdateCommentRequest, CommentResponse},
+    models::{
+        AddReactionRequest, CreateCommentRequest, UpdateCommentRequest, CommentResponse,
+        CommentRevisionResponse, ReactionSummary, ServerMessage,
+    },
+    notifications::{self, excerpt},
     state::AppState,
+    websocket::publish_ticket_event,
+};
+use jility_core::entities::{
+    attachment, comment, comment_reaction, comment_revision, notification, ticket_change, Attachment,
+    ChangeType, Comment, CommentReaction, CommentRevision, Notification, project, Project, ticket,
+    Ticket, TicketChange, WorkspaceRole,
 };
-use jility_core::entities::{comment, Comment, project, Project, ticket, Ticket};
+
+/// Longest reaction accepted, in characters. Emoji joined into one glyph
+/// (families, flags, skin tones) take several.
+const MAX_EMOJI_CHARS: usize = 16;
+
+/// Comments can be edited or deleted by their author or a workspace admin
+fn ensure_can_modify(comment: &comment::Model, role: WorkspaceRole, user: &AuthUser) -> ApiResult<()> {
+    if comment.author_user_id == Some(user.id) || role == WorkspaceRole::Admin {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Only the author or a workspace admin can change this comment".to_string(),
+        ))
+    }
+}
+
+fn ensure_not_removed(comment: &comment::Model) -> ApiResult<()> {
+    match comment.deleted_at {
+        Some(_) => Err(ApiError::Conflict(format!("Comment was removed: {}", comment.id))),
+        None => Ok(()),
+    }
+}
+
+fn valid_emoji(emoji: &str) -> bool {
+    !emoji.is_empty()
+        && emoji.chars().count() <= MAX_EMOJI_CHARS
+        && !emoji.chars().any(char::is_whitespace)
+}
+
+/// `ticket_change` row for something done to a comment, pointing at it
+fn comment_change(
+    comment: &comment::Model,
+    change_type: ChangeType,
+    actor: &str,
+    message: Option<String>,
+) -> ticket_change::ActiveModel {
+    ticket_change::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        ticket_id: Set(comment.ticket_id),
+        change_type: Set(change_type.as_str().to_string()),
+        field_name: Set(Some("comment".to_string())),
+        old_value: Set(None),
+        new_value: Set(Some(comment.id.to_string())),
+        changed_by: Set(actor.to_string()),
+        changed_at: Set(Utc::now()),
+        message: Set(message),
+    }
+}
+
+/// Responses for comments in the given order, loading their reactions and
+/// revision counts in two queries. Removed comments become tombstones.
+pub async fn comment_responses<C: ConnectionTrait>(
+    db: &C,
+    comments: Vec<comment::Model>,
+) -> Result<Vec<CommentResponse>, DbErr> {
+    let ids: HashSet<Uuid> = comments.iter().map(|c| c.id).collect();
+
+    let mut reactions: HashMap<Uuid, Vec<ReactionSummary>> = HashMap::new();
+    for reaction in CommentReaction::find()
+        .filter(comment_reaction::Column::CommentId.is_in(ids.clone()))
+        .order_by_asc(comment_reaction::Column::CreatedAt)
+        .all(db)
+        .await?
+    {
+        let summaries = reactions.entry(reaction.comment_id).or_default();
+        match summaries.iter_mut().find(|r| r.emoji == reaction.emoji) {
+            Some(summary) => {
+                summary.count += 1;
+                summary.users.push(reaction.reacted_by);
+                summary.user_ids.push(reaction.user_id.to_string());
+            }
+            None => summaries.push(ReactionSummary {
+                emoji: reaction.emoji,
+                count: 1,
+                users: vec![reaction.reacted_by],
+                user_ids: vec![reaction.user_id.to_string()],
+            }),
+        }
+    }
+
+    let mut revision_counts: HashMap<Uuid, u64> = HashMap::new();
+    for revision in CommentRevision::find()
+        .filter(comment_revision::Column::CommentId.is_in(ids))
+        .all(db)
+        .await?
+    {
+        *revision_counts.entry(revision.comment_id).or_default() += 1;
+    }
+
+    Ok(comments
+        .into_iter()
+        .map(|c| {
+            let removed = c.deleted_at.is_some();
+            CommentResponse {
+                id: c.id.to_string(),
+                ticket_id: c.ticket_id.to_string(),
+                parent_id: c.parent_id.map(|id| id.to_string()),
+                author: c.author,
+                author_user_id: c.author_user_id.map(|id| id.to_string()),
+                content: if removed { String::new() } else { c.content },
+                created_at: c.created_at.to_rfc3339(),
+                updated_at: c.updated_at.map(|dt| dt.to_rfc3339()),
+                revision_count: if removed { 0 } else { revision_counts.get(&c.id).copied().unwrap_or(0) },
+                reactions: if removed { Vec::new() } else { reactions.remove(&c.id).unwrap_or_default() },
+                deleted_at: c.deleted_at.map(|dt| dt.to_rfc3339()),
+                deleted_by: c.deleted_by,
+            }
+        })
+        .collect())
+}
+
+/// A ticket's comments oldest first, tombstones included so replies keep
+/// their place in the thread
+pub async fn ticket_comments<C: ConnectionTrait>(
+    db: &C,
+    ticket_id: Uuid,
+) -> Result<Vec<CommentResponse>, DbErr> {
+    let comments = Comment::find()
+        .filter(comment::Column::TicketId.eq(ticket_id))
+        .order_by_asc(comment::Column::CreatedAt)
+        .all(db)
+        .await?;
+    comment_responses(db, comments).await
+}
+
+async fn comment_response<C: ConnectionTrait>(
+    db: &C,
+    comment: comment::Model,
+) -> ApiResult<CommentResponse> {
+    let id = comment.id;
+    comment_responses(db, vec![comment])
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::NotFound(format!("Comment not found: {}", id)))
+}
 
 pub async fn list_comments(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(ticket_id): Path<String>,
 ) -> ApiResult<Json<Vec<CommentResponse>>> {
     // Try to parse as UUID first
@@ -53,23 +204,9 @@ pub async fn list_comments(
         ticket.id
     };
 
-    let comments = Comment::find()
-        .filter(comment::Column::TicketId.eq(ticket_uuid))
-        .all(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?
-        .into_iter()
-        .map(|c| CommentResponse {
-            id: c.id.to_string(),
-            ticket_id: c.ticket_id.to_string(),
-            author: c.author,
-            content: c.content,
-            created_at: c.created_at.to_rfc3339(),
-            updated_at: c.updated_at.map(|dt| dt.to_rfc3339()),
-        })
-        .collect();
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_uuid).await?;
 
-    Ok(Json(comments))
+    Ok(Json(ticket_comments(state.db.as_ref(), ticket_uuid).await?))
 }
 
 pub async fn create_comment(
@@ -116,87 +253,403 @@ pub async fn create_comment(
         ticket.id
     };
 
-    let now = Utc::now();
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_uuid).await?;
+
+    if let Some(parent_id) = payload.parent_id {
+        let parent = Comment::find_by_id(parent_id)
+            .one(state.db.as_ref())
+            .await?
+            .filter(|p| p.ticket_id == ticket_uuid)
+            .ok_or_else(|| {
+                ApiError::InvalidInput(format!("Parent comment not found on this ticket: {}", parent_id))
+            })?;
+        if parent.deleted_at.is_some() {
+            return Err(ApiError::InvalidInput(format!(
+                "Cannot reply to a removed comment: {}",
+                parent_id
+            )));
+        }
+    }
+
+    let actor = auth_user.actor();
     let comment = comment::ActiveModel {
         id: Set(Uuid::new_v4()),
         ticket_id: Set(ticket_uuid),
-        author: Set(auth_user.username),
+        parent_id: Set(payload.parent_id),
+        author: Set(actor.clone()),
+        author_user_id: Set(Some(auth_user.id)),
         content: Set(payload.content),
-        created_at: Set(now),
+        created_at: Set(Utc::now()),
         updated_at: Set(None),
+        deleted_at: Set(None),
+        deleted_by: Set(None),
+        source_id: Set(None),
     };
 
-    let result = comment
-        .insert(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?;
-
-    let response = CommentResponse {
-        id: result.id.to_string(),
-        ticket_id: result.ticket_id.to_string(),
-        author: result.author,
-        content: result.content,
-        created_at: result.created_at.to_rfc3339(),
-        updated_at: result.updated_at.map(|dt| dt.to_rfc3339()),
-    };
+    let txn = state.db.begin().await?;
+    let result = comment.insert(&txn).await?;
+    comment_change(&result, ChangeType::CommentAdded, &actor, Some(excerpt(&result.content)))
+        .insert(&txn)
+        .await?;
+    txn.commit().await?;
+
+    notifications::comment_added(&state, &result, auth_user.id).await;
+
+    let response = comment_response(state.db.as_ref(), result).await?;
 
     // Broadcast WebSocket update
-    let ws_message = serde_json::to_string(&crate::models::ServerMessage::CommentAdded {
+    let ws_message = ServerMessage::CommentAdded {
         ticket_id: ticket_uuid.to_string(),
         comment: response.clone(),
-    })
-    .unwrap();
-    state.ws_state.broadcast(ws_message).await;
+    };
+    publish_ticket_event(&state, ticket_uuid, &ws_message).await;
 
     Ok(Json(response))
 }
 
 pub async fn update_comment(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateCommentRequest>,
 ) -> ApiResult<Json<CommentResponse>> {
     let comment_id = Uuid::parse_str(&id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid comment ID: {}", id)))?;
 
-    let comment = Comment::find_by_id(comment_id)
-        .one(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?
-        .ok_or_else(|| ApiError::NotFound(format!("Comment not found: {}", id)))?;
+    let (comment, role) =
+        access::comment_for_member(state.db.as_ref(), &auth_user, comment_id).await?;
+    ensure_can_modify(&comment, role, &auth_user)?;
 
-    let mut comment: comment::ActiveModel = comment.into();
+    ensure_not_removed(&comment)?;
+
+    if payload.content == comment.content {
+        return Ok(Json(comment_response(state.db.as_ref(), comment).await?));
+    }
+
+    // Keep the replaced text as a revision
+    let actor = auth_user.actor();
     let now = Utc::now();
+    let old_content = comment.content.clone();
+    let txn = state.db.begin().await?;
+    comment_revision::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        comment_id: Set(comment.id),
+        content: Set(old_content.clone()),
+        edited_by: Set(actor.clone()),
+        edited_at: Set(now),
+    }
+    .insert(&txn)
+    .await?;
 
+    let mut comment: comment::ActiveModel = comment.into();
     comment.content = Set(payload.content);
     comment.updated_at = Set(Some(now));
+    let result = comment.update(&txn).await?;
 
-    let result = comment
-        .update(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?;
+    comment_change(&result, ChangeType::CommentEdited, &actor, Some(excerpt(&result.content)))
+        .insert(&txn)
+        .await?;
+    txn.commit().await?;
 
-    Ok(Json(CommentResponse {
-        id: result.id.to_string(),
-        ticket_id: result.ticket_id.to_string(),
-        author: result.author,
-        content: result.content,
-        created_at: result.created_at.to_rfc3339(),
-        updated_at: result.updated_at.map(|dt| dt.to_rfc3339()),
-    }))
+    notifications::comment_edited(&state, &result, &old_content).await;
+
+    let ticket_id = result.ticket_id;
+    let response = comment_response(state.db.as_ref(), result).await?;
+    let ws_message = ServerMessage::CommentUpdated {
+        ticket_id: ticket_id.to_string(),
+        comment: response.clone(),
+    };
+    publish_ticket_event(&state, ticket_id, &ws_message).await;
+
+    Ok(Json(response))
 }
 
+/// Remove a comment, leaving a tombstone in its thread. Its text and
+/// revisions are no longer served, excerpts of it in the activity feed and
+/// notifications are cleared, and its attachments are deleted.
 pub async fn delete_comment(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let comment_id = Uuid::parse_str(&id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid comment ID: {}", id)))?;
 
-    Comment::delete_by_id(comment_id)
+    let (comment, role) =
+        access::comment_for_member(state.db.as_ref(), &auth_user, comment_id).await?;
+    ensure_can_modify(&comment, role, &auth_user)?;
+
+    if comment.deleted_at.is_some() {
+        return Ok(Json(serde_json::json!({ "success": true })));
+    }
+
+    let actor = auth_user.actor();
+    let txn = state.db.begin().await?;
+    let mut comment: comment::ActiveModel = comment.into();
+    comment.deleted_at = Set(Some(Utc::now()));
+    comment.deleted_by = Set(Some(actor.clone()));
+    let result = comment.update(&txn).await?;
+
+    comment_change(&result, ChangeType::CommentDeleted, &actor, None)
+        .insert(&txn)
+        .await?;
+
+    // Activity entries and notifications quote the comment; its text
+    // shouldn't outlive it there
+    TicketChange::update_many()
+        .col_expr(ticket_change::Column::Message, Expr::value(Option::<String>::None))
+        .filter(ticket_change::Column::TicketId.eq(result.ticket_id))
+        .filter(ticket_change::Column::NewValue.eq(comment_id.to_string()))
+        .filter(ticket_change::Column::ChangeType.is_in([
+            ChangeType::CommentAdded.as_str(),
+            ChangeType::CommentEdited.as_str(),
+        ]))
+        .exec(&txn)
+        .await?;
+    Notification::update_many()
+        .col_expr(notification::Column::Message, Expr::value(String::new()))
+        .filter(notification::Column::CommentId.eq(comment_id))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+
+    let files = Attachment::find()
+        .filter(attachment::Column::CommentId.eq(comment_id))
+        .all(state.db.as_ref())
+        .await?;
+    attachments::remove(&state, files).await?;
+
+    let ticket_id = result.ticket_id;
+    let ws_message = ServerMessage::CommentDeleted {
+        ticket_id: ticket_id.to_string(),
+        comment: comment_response(state.db.as_ref(), result).await?,
+    };
+    publish_ticket_event(&state, ticket_id, &ws_message).await;
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Earlier versions of a comment, newest first
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Vec<CommentRevisionResponse>>> {
+    let comment_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid comment ID: {}", id)))?;
+
+    let (comment, _) =
+        access::comment_for_member(state.db.as_ref(), &auth_user, comment_id).await?;
+    ensure_not_removed(&comment)?;
+
+    let revisions = CommentRevision::find()
+        .filter(comment_revision::Column::CommentId.eq(comment_id))
+        .order_by_desc(comment_revision::Column::EditedAt)
+        .all(state.db.as_ref())
+        .await?
+        .into_iter()
+        .map(|r| CommentRevisionResponse {
+            id: r.id.to_string(),
+            content: r.content,
+            edited_by: r.edited_by,
+            edited_at: r.edited_at.to_rfc3339(),
+        })
+        .collect();
+
+    Ok(Json(revisions))
+}
+
+/// Any member can react; reacting twice with the same emoji is a no-op
+pub async fn add_reaction(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    Json(payload): Json<AddReactionRequest>,
+) -> ApiResult<Json<CommentResponse>> {
+    let comment_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid comment ID: {}", id)))?;
+
+    let (comment, _) =
+        access::comment_for_member(state.db.as_ref(), &auth_user, comment_id).await?;
+    ensure_not_removed(&comment)?;
+
+    let emoji = payload.emoji.trim();
+    if !valid_emoji(emoji) {
+        return Err(ApiError::InvalidInput(format!("Invalid reaction: {:?}", payload.emoji)));
+    }
+
+    let reaction = comment_reaction::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        comment_id: Set(comment_id),
+        emoji: Set(emoji.to_string()),
+        reacted_by: Set(auth_user.username.clone()),
+        user_id: Set(auth_user.id),
+        created_at: Set(Utc::now()),
+    };
+    CommentReaction::insert(reaction)
+        .on_conflict(
+            OnConflict::columns([
+                comment_reaction::Column::CommentId,
+                comment_reaction::Column::Emoji,
+                comment_reaction::Column::UserId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(state.db.as_ref())
+        .await?;
+
+    reactions_changed(&state, comment).await
+}
+
+/// Take back the caller's reaction
+pub async fn remove_reaction(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((id, emoji)): Path<(String, String)>,
+) -> ApiResult<Json<CommentResponse>> {
+    let comment_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid comment ID: {}", id)))?;
+
+    let (comment, _) =
+        access::comment_for_member(state.db.as_ref(), &auth_user, comment_id).await?;
+    ensure_not_removed(&comment)?;
+
+    CommentReaction::delete_many()
+        .filter(comment_reaction::Column::CommentId.eq(comment_id))
+        .filter(comment_reaction::Column::Emoji.eq(emoji.trim()))
+        .filter(comment_reaction::Column::UserId.eq(auth_user.id))
         .exec(state.db.as_ref())
+        .await?;
+
+    reactions_changed(&state, comment).await
+}
+
+async fn reactions_changed(
+    state: &AppState,
+    comment: comment::Model,
+) -> ApiResult<Json<CommentResponse>> {
+    let ticket_id = comment.ticket_id;
+    let response = comment_response(state.db.as_ref(), comment).await?;
+    let ws_message = ServerMessage::CommentUpdated {
+        ticket_id: ticket_id.to_string(),
+        comment: response.clone(),
+    };
+    publish_ticket_event(state, ticket_id, &ws_message).await;
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    #[test]
+    fn test_valid_emoji() {
+        assert!(valid_emoji("👍"));
+        assert!(valid_emoji("👨‍👩‍👧‍👦"));
+        assert!(valid_emoji(":+1:"));
+        assert!(!valid_emoji(""));
+        assert!(!valid_emoji("👍 👍"));
+        assert!(!valid_emoji(&"🎉".repeat(MAX_EMOJI_CHARS + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_a_user_reacts_once_whichever_key_they_use() {
+        let fx = Fixture::new().await;
+        let ticket = fx.ticket("Reacted to", |_| {}).await;
+        let Json(comment) = create_comment(
+            State(fx.state.clone()),
+            Extension(fx.user.clone()),
+            Path(ticket.id.to_string()),
+            Json(CreateCommentRequest { content: "Looks good".to_string(), parent_id: None }),
+        )
         .await
-        .map_err(ApiError::from)?;
+        .unwrap();
 
-    Ok(Json(serde_json::json!({ "success": true })))
+        let mut with_key = fx.user.clone();
+        with_key.api_key_name = Some("ci".to_string());
+        let mut reacted = None;
+        for user in [&fx.user, &with_key] {
+            let Json(response) = add_reaction(
+                State(fx.state.clone()),
+                Extension(user.clone()),
+                Path(comment.id.clone()),
+                Json(AddReactionRequest { emoji: "👍".to_string() }),
+            )
+            .await
+            .unwrap();
+            reacted = Some(response);
+        }
+        let reacted = reacted.unwrap();
+        assert_eq!(reacted.reactions.len(), 1);
+        assert_eq!(reacted.reactions[0].count, 1);
+        assert_eq!(reacted.reactions[0].user_ids, vec![fx.user.id.to_string()]);
+
+        let Json(removed) = remove_reaction(
+            State(fx.state.clone()),
+            Extension(with_key),
+            Path((comment.id.clone(), "👍".to_string())),
+        )
+        .await
+        .unwrap();
+        assert!(removed.reactions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deleting_a_comment_clears_its_excerpts() {
+        let fx = Fixture::new().await;
+        let ticket = fx.ticket("Discussed", |_| {}).await;
+        let Json(comment) = create_comment(
+            State(fx.state.clone()),
+            Extension(fx.user.clone()),
+            Path(ticket.id.to_string()),
+            Json(CreateCommentRequest { content: "The password is hunter2".to_string(), parent_id: None }),
+        )
+        .await
+        .unwrap();
+        let _ = update_comment(
+            State(fx.state.clone()),
+            Extension(fx.user.clone()),
+            Path(comment.id.clone()),
+            Json(UpdateCommentRequest { content: "The password is hunter3".to_string() }),
+        )
+        .await
+        .unwrap();
+        let comment_id = Uuid::parse_str(&comment.id).unwrap();
+        notification::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(fx.user.id),
+            ticket_id: Set(ticket.id),
+            kind: Set("mentioned".to_string()),
+            actor: Set("bob".to_string()),
+            message: Set("The password is hunter2".to_string()),
+            comment_id: Set(Some(comment_id)),
+            read_at: Set(None),
+            created_at: Set(Utc::now()),
+        }
+        .insert(fx.state.db.as_ref())
+        .await
+        .unwrap();
+
+        let _ = delete_comment(State(fx.state.clone()), Extension(fx.user.clone()), Path(comment.id.clone()))
+            .await
+            .unwrap();
+
+        let changes = TicketChange::find()
+            .filter(ticket_change::Column::TicketId.eq(ticket.id))
+            .all(fx.state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 3);
+        assert!(changes.iter().all(|c| c.message.is_none()));
+        let notification = Notification::find()
+            .filter(notification::Column::CommentId.eq(comment_id))
+            .one(fx.state.db.as_ref())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(notification.message, "");
+    }
 }
@@ -1,47 +1,140 @@
-use axum::{extract::{Path, State}, Json};
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use std::collections::{HashMap, HashSet};
+
+use axum::{extract::{Path, Query, State}, Extension, Json};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, QueryFilter,
+    QueryOrder, Set, SqlErr, TransactionTrait,
+};
+use serde::Deserialize;
 use uuid::Uuid;
 use chrono::Utc;
 
 use crate::{
+    api::tickets::{format_ticket_number, resolve_ticket_id},
+    auth::{access, AuthUser},
     error::{ApiError, ApiResult},
-    models::{AddDependencyRequest, DependencyGraphResponse, TicketReference},
+    models::{
+        AddDependencyRequest, DependencyEdge, DependencyGraphResponse, DependencyNode,
+        ScopedDependencyGraphResponse, TicketReference,
+    },
     state::AppState,
 };
-use jility_core::entities::{ticket, ticket_dependency, Ticket, TicketDependency};
+use jility_core::dependency_graph::DependencyGraph;
+use jility_core::entities::{
+    project, sprint_ticket, ticket, ticket_change, ticket_dependency, ChangeType, Project,
+    Sprint, SprintTicket, Ticket, TicketDependency,
+};
 
+/// Add a dependency after checking that both tickets are live, in the same
+/// project, not already linked, and that the new edge does not close a cycle
 pub async fn add_dependency(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<AddDependencyRequest>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let ticket_id = Uuid::parse_str(&id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", id)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    let depends_on_id = resolve_ticket_id(state.db.as_ref(), &payload.depends_on_id).await?;
+
+    if ticket_id == depends_on_id {
+        return Err(ApiError::InvalidInput("A ticket cannot depend on itself".to_string()));
+    }
+
+    let ticket = find_live_ticket(&state, ticket_id, &id).await?;
+
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+    let depends_on = find_live_ticket(&state, depends_on_id, &payload.depends_on_id).await?;
+
+    if ticket.project_id != depends_on.project_id {
+        return Err(ApiError::InvalidInput(
+            "Dependencies must link tickets in the same project".to_string(),
+        ));
+    }
+
+    let now = Utc::now();
+    let txn = state.db.begin().await.map_err(ApiError::from)?;
+
+    // Check against the graph as it is once no other add can change it
+    lock_project(&txn, ticket.project_id).await?;
+    let graph = load_project_graph(&txn, ticket.project_id).await?;
+
+    if graph.contains_edge(ticket_id, depends_on_id) {
+        return Err(ApiError::Conflict(format!(
+            "{} already depends on {}",
+            id, payload.depends_on_id
+        )));
+    }
+
+    if let Some(cycle) = graph.cycle_if_added(ticket_id, depends_on_id) {
+        txn.rollback().await.map_err(ApiError::from)?;
+
+        let tickets: HashMap<Uuid, ticket::Model> = Ticket::find()
+            .filter(ticket::Column::Id.is_in(cycle.clone()))
+            .all(state.db.as_ref())
+            .await
+            .map_err(ApiError::from)?
+            .into_iter()
+            .map(|t| (t.id, t))
+            .collect();
+
+        let mut path = Vec::with_capacity(cycle.len());
+        for node in &cycle {
+            path.push(match tickets.get(node) {
+                Some(t) => format_ticket_number(state.db.as_ref(), t).await?,
+                None => node.to_string(),
+            });
+        }
 
-    let dependency = ticket_dependency::ActiveModel {
+        return Err(ApiError::DependencyCycle(path));
+    }
+
+    ticket_dependency::ActiveModel {
         id: Set(Uuid::new_v4()),
         ticket_id: Set(ticket_id),
-        depends_on_id: Set(payload.depends_on_id),
-        created_at: Set(Utc::now()),
-        created_by: Set("system".to_string()),
-    };
+        depends_on_id: Set(depends_on_id),
+        created_at: Set(now),
+        created_by: Set(auth_user.actor()),
+    }
+    .insert(&txn)
+    .await
+    .map_err(|err| match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => ApiError::Conflict(format!(
+            "{} already depends on {}",
+            id, payload.depends_on_id
+        )),
+        _ => ApiError::from(err),
+    })?;
 
-    dependency
-        .insert(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?;
+    ticket_change::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        ticket_id: Set(ticket_id),
+        change_type: Set(ChangeType::DependencyAdded.as_str().to_string()),
+        field_name: Set(Some("depends_on".to_string())),
+        old_value: Set(None),
+        new_value: Set(Some(depends_on_id.to_string())),
+        changed_by: Set(auth_user.actor()),
+        changed_at: Set(now),
+        message: Set(None),
+    }
+    .insert(&txn)
+    .await
+    .map_err(ApiError::from)?;
+
+    txn.commit().await.map_err(ApiError::from)?;
 
     Ok(Json(serde_json::json!({ "success": true })))
 }
 
 pub async fn remove_dependency(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path((ticket_id, dep_id)): Path<(String, String)>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let ticket_uuid = Uuid::parse_str(&ticket_id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", ticket_id)))?;
-    let dep_uuid = Uuid::parse_str(&dep_id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid dependency ID: {}", dep_id)))?;
+    let ticket_uuid = resolve_ticket_id(state.db.as_ref(), &ticket_id).await?;
+    let dep_uuid = resolve_ticket_id(state.db.as_ref(), &dep_id).await?;
+
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_uuid).await?;
 
     // Find the dependency
     let dependency = TicketDependency::find()
@@ -52,20 +145,101 @@ pub async fn remove_dependency(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound("Dependency not found".to_string()))?;
 
+    let txn = state.db.begin().await.map_err(ApiError::from)?;
+
     TicketDependency::delete_by_id(dependency.id)
-        .exec(state.db.as_ref())
+        .exec(&txn)
         .await
         .map_err(ApiError::from)?;
 
+    ticket_change::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        ticket_id: Set(ticket_uuid),
+        change_type: Set(ChangeType::DependencyRemoved.as_str().to_string()),
+        field_name: Set(Some("depends_on".to_string())),
+        old_value: Set(Some(dep_uuid.to_string())),
+        new_value: Set(None),
+        changed_by: Set(auth_user.actor()),
+        changed_at: Set(Utc::now()),
+        message: Set(None),
+    }
+    .insert(&txn)
+    .await
+    .map_err(ApiError::from)?;
+
+    txn.commit().await.map_err(ApiError::from)?;
+
     Ok(Json(serde_json::json!({ "success": true })))
 }
 
+async fn find_live_ticket(state: &AppState, ticket_id: Uuid, label: &str) -> ApiResult<ticket::Model> {
+    let ticket = Ticket::find_by_id(ticket_id)
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", label)))?;
+
+    if ticket.deleted_at.is_some() {
+        return Err(ApiError::InvalidInput(format!("Ticket {} has been deleted", label)));
+    }
+
+    Ok(ticket)
+}
+
+/// Serialize dependency changes within a project until `txn` ends.
+///
+/// The no-op `UPDATE` takes SQLite's write lock, and a row lock on the project
+/// in Postgres, before the transaction reads the graph.
+async fn lock_project(txn: &DatabaseTransaction, project_id: Uuid) -> ApiResult<()> {
+    Project::update_many()
+        .col_expr(project::Column::UpdatedAt, Expr::col(project::Column::UpdatedAt).into())
+        .filter(project::Column::Id.eq(project_id))
+        .exec(txn)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(())
+}
+
+/// Dependency edges between the live tickets of a project
+async fn load_project_graph<C: ConnectionTrait>(
+    db: &C,
+    project_id: Uuid,
+) -> ApiResult<DependencyGraph> {
+    let ticket_ids: Vec<Uuid> = Ticket::find()
+        .filter(ticket::Column::ProjectId.eq(project_id))
+        .filter(ticket::Column::DeletedAt.is_null())
+        .all(db)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+
+    let edges = TicketDependency::find()
+        .filter(ticket_dependency::Column::TicketId.is_in(ticket_ids.clone()))
+        .filter(ticket_dependency::Column::DependsOnId.is_in(ticket_ids))
+        .all(db)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(DependencyGraph::new(
+        edges.into_iter().map(|e| (e.ticket_id, e.depends_on_id)),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DependencyGraphQuery {
+    /// How many hops of transitive dependencies/dependents to include (default 1)
+    pub depth: Option<usize>,
+}
+
 pub async fn get_dependency_graph(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
+    Query(query): Query<DependencyGraphQuery>,
 ) -> ApiResult<Json<DependencyGraphResponse>> {
-    let ticket_id = Uuid::parse_str(&id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", id)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
 
     let ticket = Ticket::find_by_id(ticket_id)
         .filter(ticket::Column::DeletedAt.is_null())
@@ -74,62 +248,202 @@ pub async fn get_dependency_graph(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
-    // Get dependencies
-    let dependencies = TicketDependency::find()
-        .filter(ticket_dependency::Column::TicketId.eq(ticket_id))
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+
+    let graph = load_project_graph(state.db.as_ref(), ticket.project_id).await?;
+    let distances = graph.neighborhood(ticket_id, query.depth.unwrap_or(1));
+
+    let tickets = Ticket::find()
+        .filter(ticket::Column::Id.is_in(distances.keys().copied().collect::<Vec<_>>()))
         .all(state.db.as_ref())
         .await
         .map_err(ApiError::from)?;
+    let numbers = ticket_numbers(&state, &tickets).await?;
+    let by_id: HashMap<Uuid, &ticket::Model> = tickets.iter().map(|t| (t.id, t)).collect();
 
-    let mut dependency_refs = Vec::new();
-    for dep in dependencies {
-        if let Some(dep_ticket) = Ticket::find_by_id(dep.depends_on_id)
-            .filter(ticket::Column::DeletedAt.is_null())
-            .one(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-        {
-            dependency_refs.push(TicketReference {
-                id: dep_ticket.id.to_string(),
-                number: format!("TASK-{}", dep_ticket.ticket_number),
-                title: dep_ticket.title,
-                status: dep_ticket.status,
-            });
-        }
-    }
+    let reference = |t: &ticket::Model| TicketReference {
+        id: t.id.to_string(),
+        number: numbers[&t.id].clone(),
+        title: t.title.clone(),
+        status: t.status.clone(),
+    };
+
+    let node_ids: HashSet<Uuid> = distances.keys().copied().collect();
+    let edges = graph.edges_within(&node_ids);
+
+    let dependencies = edges
+        .iter()
+        .filter(|(from, _)| *from == ticket_id)
+        .filter_map(|(_, to)| by_id.get(to).map(|t| reference(t)))
+        .collect();
+    let dependents = edges
+        .iter()
+        .filter(|(_, to)| *to == ticket_id)
+        .filter_map(|(from, _)| by_id.get(from).map(|t| reference(t)))
+        .collect();
+
+    let mut nodes: Vec<DependencyNode> = tickets
+        .iter()
+        .map(|t| dependency_node(t, &numbers, distances.get(&t.id).copied()))
+        .collect();
+    nodes.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.number.cmp(&b.number)));
+
+    Ok(Json(DependencyGraphResponse {
+        ticket: reference(&ticket),
+        dependencies,
+        dependents,
+        nodes,
+        edges: edges.into_iter().map(dependency_edge).collect(),
+    }))
+}
+
+/// Dependency graph of the tickets in an epic, with execution order and critical path
+pub async fn get_epic_dependency_graph(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ScopedDependencyGraphResponse>> {
+    let epic_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+
+    let epic = Ticket::find_by_id(epic_id)
+        .filter(ticket::Column::IsEpic.eq(true))
+        .filter(ticket::Column::DeletedAt.is_null())
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Epic not found: {}", id)))?;
+
+    access::require_project(state.db.as_ref(), &auth_user, epic.project_id).await?;
 
-    // Get dependents
-    let dependents = TicketDependency::find()
-        .filter(ticket_dependency::Column::DependsOnId.eq(ticket_id))
+    let tickets = Ticket::find()
+        .filter(ticket::Column::EpicId.eq(epic_id))
+        .filter(ticket::Column::DeletedAt.is_null())
+        .order_by_asc(ticket::Column::TicketNumber)
         .all(state.db.as_ref())
         .await
         .map_err(ApiError::from)?;
 
-    let mut dependent_refs = Vec::new();
-    for dep in dependents {
-        if let Some(dep_ticket) = Ticket::find_by_id(dep.ticket_id)
-            .filter(ticket::Column::DeletedAt.is_null())
-            .one(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-        {
-            dependent_refs.push(TicketReference {
-                id: dep_ticket.id.to_string(),
-                number: format!("TASK-{}", dep_ticket.ticket_number),
-                title: dep_ticket.title,
-                status: dep_ticket.status,
-            });
-        }
+    scoped_graph(&state, epic.project_id, tickets).await.map(Json)
+}
+
+/// Dependency graph of the tickets in a sprint, with execution order and critical path
+pub async fn get_sprint_dependency_graph(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ScopedDependencyGraphResponse>> {
+    let sprint_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", id)))?;
+
+    let sprint = Sprint::find_by_id(sprint_id)
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Sprint {} not found", id)))?;
+
+    access::require_project(state.db.as_ref(), &auth_user, sprint.project_id).await?;
+
+    let ticket_ids: Vec<Uuid> = SprintTicket::find()
+        .filter(sprint_ticket::Column::SprintId.eq(sprint_id))
+        .all(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|st| st.ticket_id)
+        .collect();
+
+    let tickets = Ticket::find()
+        .filter(ticket::Column::Id.is_in(ticket_ids))
+        .filter(ticket::Column::DeletedAt.is_null())
+        .order_by_asc(ticket::Column::TicketNumber)
+        .all(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?;
+
+    scoped_graph(&state, sprint.project_id, tickets).await.map(Json)
+}
+
+async fn scoped_graph(
+    state: &AppState,
+    project_id: Uuid,
+    tickets: Vec<ticket::Model>,
+) -> ApiResult<ScopedDependencyGraphResponse> {
+    let graph = load_project_graph(state.db.as_ref(), project_id).await?;
+    let numbers = ticket_numbers(state, &tickets).await?;
+
+    let ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+    let points: HashMap<Uuid, i32> = tickets
+        .iter()
+        .map(|t| (t.id, t.story_points.unwrap_or(0)))
+        .collect();
+
+    let on_cycle = |nodes: Vec<Uuid>| {
+        let names: Vec<String> = nodes.iter().map(|id| numbers[id].clone()).collect();
+        ApiError::Conflict(format!("Dependency cycle among {}", names.join(", ")))
+    };
+
+    let topological_order = graph.topological_order(&ids).map_err(on_cycle)?;
+    let (critical_path, critical_path_points) = graph
+        .critical_path(&ids, |id| points[&id])
+        .map_err(on_cycle)?;
+
+    let edges = graph.edges_within(&ids.iter().copied().collect());
+
+    Ok(ScopedDependencyGraphResponse {
+        nodes: tickets
+            .iter()
+            .map(|t| dependency_node(t, &numbers, None))
+            .collect(),
+        edges: edges.into_iter().map(dependency_edge).collect(),
+        topological_order: topological_order.iter().map(Uuid::to_string).collect(),
+        critical_path: critical_path.iter().map(Uuid::to_string).collect(),
+        critical_path_points,
+    })
+}
+
+fn dependency_node(
+    ticket: &ticket::Model,
+    numbers: &HashMap<Uuid, String>,
+    depth: Option<usize>,
+) -> DependencyNode {
+    DependencyNode {
+        id: ticket.id.to_string(),
+        number: numbers[&ticket.id].clone(),
+        title: ticket.title.clone(),
+        status: ticket.status.clone(),
+        story_points: ticket.story_points,
+        depth,
     }
+}
 
-    Ok(Json(DependencyGraphResponse {
-        ticket: TicketReference {
-            id: ticket.id.to_string(),
-            number: format!("TASK-{}", ticket.ticket_number),
-            title: ticket.title,
-            status: ticket.status,
-        },
-        dependencies: dependency_refs,
-        dependents: dependent_refs,
-    }))
+fn dependency_edge((ticket_id, depends_on_id): (Uuid, Uuid)) -> DependencyEdge {
+    DependencyEdge {
+        ticket_id: ticket_id.to_string(),
+        depends_on_id: depends_on_id.to_string(),
+    }
+}
+
+/// Display numbers (e.g. "JIL-42") for tickets, using one project lookup
+async fn ticket_numbers(
+    state: &AppState,
+    tickets: &[ticket::Model],
+) -> ApiResult<HashMap<Uuid, String>> {
+    let project_ids: HashSet<Uuid> = tickets.iter().map(|t| t.project_id).collect();
+    let keys: HashMap<Uuid, Option<String>> = Project::find()
+        .filter(project::Column::Id.is_in(project_ids))
+        .all(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|p| (p.id, p.key))
+        .collect();
+
+    Ok(tickets
+        .iter()
+        .map(|t| {
+            let prefix = keys.get(&t.project_id).cloned().flatten();
+            let prefix = prefix.as_deref().unwrap_or("TASK");
+            (t.id, format!("{}-{}", prefix, t.ticket_number))
+        })
+        .collect())
 }
@@ -1,15 +1,17 @@
-use axum::{extract::{Path, Query, State}, Json};
+use axum::{extract::{Path, Query, State}, Extension, Json};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
+    auth::{access, AuthUser},
     error::{ApiError, ApiResult},
     state::AppState,
 };
 use jility_core::entities::{
-    ticket, Ticket,
+    ticket, Ticket, TicketStatus,
 };
+use jility_core::workflow::{self, StatusCategory};
 
 /// Response for epic with progress information
 #[derive(Debug, Serialize)]
@@ -61,10 +63,11 @@ async fn format_ticket_number(
 /// Calculate epic progress from child tickets
 async fn calculate_epic_progress(
     db: &sea_orm::DatabaseConnection,
-    epic_id: Uuid,
+    epic: &ticket::Model,
 ) -> ApiResult<EpicProgress> {
+    let workflow = workflow::load(db, epic.project_id).await?;
     let tickets = Ticket::find()
-        .filter(ticket::Column::EpicId.eq(epic_id))
+        .filter(ticket::Column::EpicId.eq(epic.id))
         .filter(ticket::Column::DeletedAt.is_null())
         .all(db)
         .await
@@ -77,12 +80,15 @@ async fn calculate_epic_progress(
     let mut blocked = 0;
 
     for ticket in tickets {
-        match ticket.status.as_str() {
-            "done" => done += 1,
-            "in_progress" | "review" => in_progress += 1,
-            "todo" | "backlog" => todo += 1,
-            "blocked" => blocked += 1,
-            _ => todo += 1,
+        // Blocked is reported separately whatever category the workflow gives it
+        if ticket.status == TicketStatus::Blocked.as_str() {
+            blocked += 1;
+            continue;
+        }
+        match workflow.category(&ticket.status) {
+            StatusCategory::Done => done += 1,
+            StatusCategory::InProgress => in_progress += 1,
+            StatusCategory::Todo => todo += 1,
         }
     }
 
@@ -105,6 +111,7 @@ async fn calculate_epic_progress(
 /// List all epics with progress stats
 pub async fn list_epics(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(query): Query<ListEpicsQuery>,
 ) -> ApiResult<Json<Vec<EpicResponse>>> {
     let mut find_query = Ticket::find()
@@ -115,7 +122,11 @@ pub async fn list_epics(
     if let Some(project_id_str) = query.project_id {
         let project_uuid = Uuid::parse_str(&project_id_str)
             .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", project_id_str)))?;
+        access::require_project(state.db.as_ref(), &auth_user, project_uuid).await?;
         find_query = find_query.filter(ticket::Column::ProjectId.eq(project_uuid));
+    } else {
+        let project_ids = access::visible_project_ids(state.db.as_ref(), &auth_user).await?;
+        find_query = find_query.filter(ticket::Column::ProjectId.is_in(project_ids));
     }
 
     let epics = find_query
@@ -127,7 +138,7 @@ pub async fn list_epics(
 
     for epic in epics {
         let number = format_ticket_number(state.db.as_ref(), &epic).await?;
-        let progress = calculate_epic_progress(state.db.as_ref(), epic.id).await?;
+        let progress = calculate_epic_progress(state.db.as_ref(), &epic).await?;
 
         responses.push(EpicResponse {
             id: epic.id.to_string(),
@@ -147,6 +158,7 @@ pub async fn list_epics(
 /// Get a specific epic with progress
 pub async fn get_epic(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<EpicResponse>> {
     let uuid = Uuid::parse_str(&id)
@@ -160,8 +172,10 @@ pub async fn get_epic(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Epic not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, epic.project_id).await?;
+
     let number = format_ticket_number(state.db.as_ref(), &epic).await?;
-    let progress = calculate_epic_progress(state.db.as_ref(), epic.id).await?;
+    let progress = calculate_epic_progress(state.db.as_ref(), &epic).await?;
 
     Ok(Json(EpicResponse {
         id: epic.id.to_string(),
@@ -178,6 +192,7 @@ pub async fn get_epic(
 /// Get all tickets for a specific epic
 pub async fn get_epic_tickets(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<crate::models::TicketResponse>>> {
     let uuid = Uuid::parse_str(&id)
@@ -192,6 +207,8 @@ pub async fn get_epic_tickets(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Epic not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, epic.project_id).await?;
+
     // Get all tickets belonging to this epic
     let tickets = Ticket::find()
         .filter(ticket::Column::EpicId.eq(epic.id))
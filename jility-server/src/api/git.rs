@@ -1,22 +1,39 @@
-use axum::{extract::{Path, State}, Json};
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use axum::{body::Bytes, extract::{Path, State}, http::HeaderMap, Extension, Json};
+use hmac::{Hmac, Mac};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use serde::Deserialize;
+use sha2::Sha256;
 use uuid::Uuid;
 use chrono::Utc;
 
 use crate::{
+    api::tickets::resolve_ticket_id,
+    auth::{access, AuthUser},
     error::{ApiError, ApiResult},
-    models::{LinkCommitRequest, CommitLinkResponse},
+    models::{
+        LinkCommitRequest, CommitLinkResponse, GitPushResponse, GitPushSecretResponse,
+        PushedCommitLink, PushedTransition, ServerMessage,
+    },
+    notifications,
     state::AppState,
+    webhooks::generate_secret,
+    websocket::publish_ticket_event,
 };
-use jility_core::entities::{commit_link, CommitLink};
+use jility_core::entities::{
+    commit_link, project, ticket, ticket_change, ChangeType, CommitLink, Project, Ticket,
+};
+use jility_core::policy::ProjectPolicy;
+use jility_core::smart_commit::parse_references;
+use jility_core::workflow::{self, StatusCategory};
 
 pub async fn link_commit(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<LinkCommitRequest>,
 ) -> ApiResult<Json<CommitLinkResponse>> {
-    let ticket_id = Uuid::parse_str(&id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", id)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
 
     let commit_link = commit_link::ActiveModel {
         id: Set(Uuid::new_v4()),
@@ -24,7 +41,7 @@ pub async fn link_commit(
         commit_hash: Set(payload.commit_hash),
         commit_message: Set(payload.commit_message),
         linked_at: Set(Utc::now()),
-        linked_by: Set("system".to_string()),
+        linked_by: Set(auth_user.actor()),
     };
 
     let result = commit_link
@@ -43,10 +60,11 @@ pub async fn link_commit(
 
 pub async fn list_commits(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<CommitLinkResponse>>> {
-    let ticket_id = Uuid::parse_str(&id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", id)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
 
     let commits = CommitLink::find()
         .filter(commit_link::Column::TicketId.eq(ticket_id))
@@ -65,3 +83,341 @@ pub async fn list_commits(
 
     Ok(Json(commits))
 }
+
+/// Generate the secret that verifies this project's push webhooks, replacing
+/// any previous one. The secret is only returned here.
+pub async fn rotate_push_secret(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<GitPushSecretResponse>> {
+    let project_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
+
+    let (project, _) =
+        access::project_for_member(state.db.as_ref(), &auth_user, project_id).await?;
+    access::require_admin(state.db.as_ref(), &auth_user, project.workspace_id).await?;
+
+    let secret = generate_secret();
+    let mut project: project::ActiveModel = project.into();
+    project.git_webhook_secret = Set(Some(secret.clone()));
+    project.updated_at = Set(Utc::now());
+    project.update(state.db.as_ref()).await?;
+
+    Ok(Json(GitPushSecretResponse {
+        secret,
+        push_url: format!("/api/projects/{}/git/push", project_id),
+    }))
+}
+
+/// The parts of a GitHub, GitLab or Gitea push payload we use
+#[derive(Debug, Deserialize)]
+struct PushPayload {
+    #[serde(default)]
+    commits: Vec<PushCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PushCommit {
+    id: String,
+    message: String,
+    #[serde(default)]
+    author: PushAuthor,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PushAuthor {
+    name: Option<String>,
+    email: Option<String>,
+    /// Only GitHub and Gitea send the forge username
+    username: Option<String>,
+}
+
+impl PushAuthor {
+    /// Who to record as linking the commit and changing the ticket
+    fn actor(&self) -> String {
+        [&self.username, &self.name, &self.email]
+            .into_iter()
+            .flatten()
+            .find(|s| !s.is_empty())
+            .cloned()
+            .unwrap_or_else(|| "git".to_string())
+    }
+}
+
+/// Check a push against the project's secret. GitHub and Gitea sign the body
+/// with HMAC-SHA256; GitLab sends the secret itself as a token.
+fn verify_push(headers: &HeaderMap, body: &[u8], secret: &str) -> ApiResult<()> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let valid = if let Some(signature) =
+        header("x-hub-signature-256").or_else(|| header("x-gitea-signature"))
+    {
+        let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        decode_hex(signature).is_some_and(|expected| mac.verify_slice(&expected).is_ok())
+    } else if let Some(token) = header("x-gitlab-token") {
+        constant_time_eq(token.as_bytes(), secret.as_bytes())
+    } else {
+        return Err(ApiError::Unauthorized(
+            "Push is not signed. Expected X-Hub-Signature-256, X-Gitea-Signature or X-Gitlab-Token"
+                .to_string(),
+        ));
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::Unauthorized(
+            "Push signature does not match the project's secret".to_string(),
+        ))
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The event the sender says it delivered. GitLab calls pushes `Push Hook`.
+fn push_event(headers: &HeaderMap) -> Option<&str> {
+    ["x-github-event", "x-gitea-event", "x-gitlab-event"]
+        .into_iter()
+        .find_map(|name| headers.get(name)?.to_str().ok())
+}
+
+fn ignored(reason: String) -> Json<GitPushResponse> {
+    Json(GitPushResponse {
+        ignored: Some(reason),
+        linked: Vec::new(),
+        transitioned: Vec::new(),
+        skipped: Vec::new(),
+    })
+}
+
+/// Receive a push webhook and link each commit to the tickets its message
+/// mentions. A closing verb (`fixes JIL-42`) also moves the ticket to the
+/// workflow's first done status, if the workflow and project policy allow it;
+/// references that can't be applied are reported in `skipped` rather than
+/// failing the push. Not behind login: the request must carry the project's
+/// push secret.
+pub async fn receive_push(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<GitPushResponse>> {
+    let project_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
+
+    let project = Project::find_by_id(project_id)
+        .one(state.db.as_ref())
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", project_id)))?;
+    let secret = project.git_webhook_secret.as_deref().ok_or_else(|| {
+        ApiError::Forbidden(
+            "Push webhooks are not set up for this project. Generate a secret with POST /api/projects/:id/git/secret"
+                .to_string(),
+        )
+    })?;
+    verify_push(&headers, &body, secret)?;
+
+    if let Some(event) = push_event(&headers) {
+        if event != "push" && event != "Push Hook" {
+            return Ok(ignored(format!("Not a push event: {}", event)));
+        }
+    }
+    if !project.auto_link_git {
+        return Ok(ignored(
+            "Commit linking is turned off for this project (auto_link_git)".to_string(),
+        ));
+    }
+
+    let payload: PushPayload = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::InvalidInput(format!("Invalid push payload: {}", e)))?;
+
+    let key = project.key.as_deref().unwrap_or("TASK");
+    let workflow = workflow::load(state.db.as_ref(), project.id).await?;
+    let policy = ProjectPolicy::from(&project);
+    let done = workflow.first_in(StatusCategory::Done);
+
+    let mut linked = Vec::new();
+    let mut transitioned = Vec::new();
+    let mut skipped = Vec::new();
+    let mut actors = Vec::new();
+
+    let txn = state.db.begin().await?;
+    for commit in &payload.commits {
+        let actor = commit.author.actor();
+        let summary = commit.message.lines().next().unwrap_or_default().to_string();
+        let short_hash: String = commit.id.chars().take(7).collect();
+
+        for reference in parse_references(&commit.message) {
+            if reference.key != key {
+                continue;
+            }
+            let number = format!("{}-{}", key, reference.number);
+
+            let Some(ticket) = Ticket::find()
+                .filter(ticket::Column::ProjectId.eq(project.id))
+                .filter(ticket::Column::TicketNumber.eq(reference.number))
+                .filter(ticket::Column::DeletedAt.is_null())
+                .one(&txn)
+                .await?
+            else {
+                skipped.push(format!("{}: ticket not found", number));
+                continue;
+            };
+
+            let now = Utc::now();
+
+            // Redelivered pushes link each commit once
+            let already_linked = CommitLink::find()
+                .filter(commit_link::Column::TicketId.eq(ticket.id))
+                .filter(commit_link::Column::CommitHash.eq(&commit.id))
+                .one(&txn)
+                .await?
+                .is_some();
+            if !already_linked {
+                commit_link::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    ticket_id: Set(ticket.id),
+                    commit_hash: Set(commit.id.clone()),
+                    commit_message: Set(Some(commit.message.clone())),
+                    linked_at: Set(now),
+                    linked_by: Set(actor.clone()),
+                }
+                .insert(&txn)
+                .await?;
+                ticket_change::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    ticket_id: Set(ticket.id),
+                    change_type: Set(ChangeType::CommitLinked.as_str().to_string()),
+                    field_name: Set(Some("commit".to_string())),
+                    old_value: Set(None),
+                    new_value: Set(Some(commit.id.clone())),
+                    changed_by: Set(actor.clone()),
+                    changed_at: Set(now),
+                    message: Set(Some(summary.clone())),
+                }
+                .insert(&txn)
+                .await?;
+                linked.push(PushedCommitLink {
+                    ticket_id: ticket.id.to_string(),
+                    number: number.clone(),
+                    commit_hash: commit.id.clone(),
+                });
+            }
+
+            if !reference.closes || workflow.is_done(&ticket.status) {
+                continue;
+            }
+            let Some(done) = done else {
+                skipped.push(format!("{}: the workflow has no done status", number));
+                continue;
+            };
+            let allowed = workflow.check_transition(&ticket.status, done).and_then(|_| {
+                policy.check_status_change(&workflow, Some(&ticket.status), done, ticket.story_points)
+            });
+            if let Err(err) = allowed {
+                skipped.push(format!("{}: {}", number, err));
+                continue;
+            }
+
+            let old_status = ticket.status.clone();
+            let ticket_id = ticket.id;
+            let mut ticket: ticket::ActiveModel = ticket.into();
+            ticket.status = Set(done.to_string());
+            ticket.updated_at = Set(now);
+            ticket.update(&txn).await?;
+            ticket_change::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                ticket_id: Set(ticket_id),
+                change_type: Set(ChangeType::StatusChanged.as_str().to_string()),
+                field_name: Set(Some("status".to_string())),
+                old_value: Set(Some(old_status.clone())),
+                new_value: Set(Some(done.to_string())),
+                changed_by: Set(actor.clone()),
+                changed_at: Set(now),
+                message: Set(Some(format!("Closed by commit {}", short_hash))),
+            }
+            .insert(&txn)
+            .await?;
+
+            actors.push(actor.clone());
+            transitioned.push(PushedTransition {
+                ticket_id: ticket_id.to_string(),
+                number,
+                old_status,
+                new_status: done.to_string(),
+                commit_hash: commit.id.clone(),
+            });
+        }
+    }
+    txn.commit().await?;
+
+    for (transition, actor) in transitioned.iter().zip(&actors) {
+        let ticket_id = Uuid::parse_str(&transition.ticket_id).unwrap();
+        notifications::status_changed(
+            &state,
+            ticket_id,
+            actor,
+            None,
+            &transition.old_status,
+            &transition.new_status,
+        )
+        .await;
+        let message = ServerMessage::StatusChanged {
+            ticket_id: transition.ticket_id.clone(),
+            old_status: transition.old_status.clone(),
+            new_status: transition.new_status.clone(),
+        };
+        publish_ticket_event(&state, ticket_id, &message).await;
+    }
+
+    Ok(Json(GitPushResponse {
+        ignored: None,
+        linked,
+        transitioned,
+        skipped,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhooks::sign;
+
+    const FIXTURE: &str = include_str!("../../fixtures/github_push.json");
+
+    #[test]
+    fn test_verify_and_parse_push() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-hub-signature-256", sign("whsec_test", FIXTURE).parse().unwrap());
+        assert!(verify_push(&headers, FIXTURE.as_bytes(), "whsec_test").is_ok());
+        assert!(verify_push(&headers, FIXTURE.as_bytes(), "whsec_other").is_err());
+        assert!(verify_push(&headers, b"{}", "whsec_test").is_err());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-gitlab-token", "whsec_test".parse().unwrap());
+        assert!(verify_push(&headers, FIXTURE.as_bytes(), "whsec_test").is_ok());
+        assert!(verify_push(&HeaderMap::new(), FIXTURE.as_bytes(), "whsec_test").is_err());
+
+        let payload: PushPayload = serde_json::from_str(FIXTURE).unwrap();
+        assert_eq!(payload.commits.len(), 2);
+        assert_eq!(payload.commits[0].author.actor(), "octocat");
+        let refs = parse_references(&payload.commits[1].message);
+        assert!(refs.iter().any(|r| r.key == "JIL" && r.number == 2 && r.closes));
+    }
+}
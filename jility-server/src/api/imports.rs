@@ -0,0 +1,137 @@
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, Query, State},
+    Extension, Json,
+};
+use sea_orm::TransactionTrait;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::{
+    auth::{access, AuthUser},
+    error::{ApiError, ApiResult},
+    imports::Import,
+    models::{ImportQuery, ImportReport},
+    state::AppState,
+};
+use jility_core::import::{github, jira, ImportPlan};
+use jility_core::{entities::project, workflow};
+
+/// Most bytes one import request may carry, across all its files
+pub const MAX_IMPORT_BYTES: usize = 64 * 1024 * 1024;
+
+fn multipart_error(err: MultipartError) -> ApiError {
+    ApiError::BadRequest(err.body_text())
+}
+
+/// The project, if the caller is an admin of its workspace
+async fn project_for_admin(
+    state: &AppState,
+    user: &AuthUser,
+    id: &str,
+) -> ApiResult<project::Model> {
+    let project_id = Uuid::parse_str(id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
+    let (project, _) = access::project_for_member(state.db.as_ref(), user, project_id).await?;
+    access::require_admin(state.db.as_ref(), user, project.workspace_id).await?;
+    Ok(project)
+}
+
+/// Report the plan as a dry run, or import it if it has no errors
+async fn run(
+    state: &AppState,
+    auth_user: &AuthUser,
+    project: project::Model,
+    plan: ImportPlan,
+    dry_run: bool,
+) -> ApiResult<Json<ImportReport>> {
+    let import = Import::check(state.db.as_ref(), project, plan).await?;
+    if dry_run {
+        return Ok(Json(import.report(true, Vec::new())));
+    }
+    import.check_errors()?;
+
+    let txn = state.db.begin().await?;
+    let tickets = import.apply(&txn, &auth_user.actor()).await?;
+    txn.commit().await?;
+
+    Ok(Json(import.report(false, tickets)))
+}
+
+/// The `file` fields of an import form, as `(file name, content)`, and its
+/// `mapping` field parsed as JSON
+async fn read_form<M: DeserializeOwned + Default>(
+    mut multipart: Multipart,
+    default_name: &str,
+) -> ApiResult<(Vec<(String, String)>, M)> {
+    let mut files = Vec::new();
+    let mut mapping = M::default();
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        match field.name() {
+            Some("file") => {
+                let name = field.file_name().unwrap_or(default_name).to_string();
+                let bytes = field.bytes().await.map_err(multipart_error)?;
+                let content = String::from_utf8(bytes.to_vec())
+                    .map_err(|_| ApiError::InvalidInput(format!("{} isn't UTF-8 text", name)))?;
+                files.push((name, content));
+            }
+            Some("mapping") => {
+                let text = field.text().await.map_err(multipart_error)?;
+                mapping = serde_json::from_str(&text)
+                    .map_err(|e| ApiError::InvalidInput(format!("Invalid mapping: {}", e)))?;
+            }
+            _ => {}
+        }
+    }
+    if files.is_empty() {
+        return Err(ApiError::InvalidInput("Missing 'file' field".to_string()));
+    }
+    Ok((files, mapping))
+}
+
+/// Import Jira CSV exports into a project, sent as `multipart/form-data`:
+/// one or more `file` fields and an optional `mapping` field holding a
+/// [`jira::JiraMapping`] as JSON. Workspace admins only.
+pub async fn import_jira(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    Query(query): Query<ImportQuery>,
+    multipart: Multipart,
+) -> ApiResult<Json<ImportReport>> {
+    let project = project_for_admin(&state, &auth_user, &id).await?;
+    let (files, mapping) = read_form::<jira::JiraMapping>(multipart, "export.csv").await?;
+
+    let workflow = workflow::load(state.db.as_ref(), project.id).await?;
+    let plan = jira::plan(
+        files
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_str())),
+        &mapping,
+        &workflow,
+    );
+    run(&state, &auth_user, project, plan, query.dry_run).await
+}
+
+/// Import GitHub issue and comment exports (REST API JSON) into a project,
+/// sent like [`import_jira`] with a [`github::GithubMapping`]. Workspace
+/// admins only.
+pub async fn import_github(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    Query(query): Query<ImportQuery>,
+    multipart: Multipart,
+) -> ApiResult<Json<ImportReport>> {
+    let project = project_for_admin(&state, &auth_user, &id).await?;
+    let (files, mapping) = read_form::<github::GithubMapping>(multipart, "issues.json").await?;
+
+    let workflow = workflow::load(state.db.as_ref(), project.id).await?;
+    let plan = github::plan(
+        files
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_str())),
+        &mapping,
+        &workflow,
+    );
+    run(&state, &auth_user, project, plan, query.dry_run).await
+}
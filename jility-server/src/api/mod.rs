@@ -1,16 +1,25 @@
 pub mod activity;
+pub mod archive;
+pub mod attachments;
 pub mod auth;
+pub mod bulk;
 pub mod comments;
 pub mod dependencies;
 pub mod epics;
 pub mod git;
+pub mod imports;
+pub mod notifications;
 pub mod projects;
 pub mod search;
 pub mod sprints;
+pub mod templates;
 pub mod tickets;
+pub mod webhooks;
+pub mod workflows;
 pub mod workspaces;
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{delete, get, patch, post, put},
     Router,
@@ -25,14 +34,10 @@ pub fn api_routes() -> (Router<AppState>, Router<AppState>) {
         // Auth
         .route("/api/auth/register", post(auth::register))
         .route("/api/auth/login", post(auth::login))
-        // Public read-only endpoints
-        .route("/api/projects", get(projects::list_projects))
-        .route("/api/projects/:id", get(projects::get_project))
-        .route("/api/tickets", get(tickets::list_tickets))
-        .route("/api/tickets/:id", get(tickets::get_ticket))
-        .route("/api/tickets/:id/comments", get(comments::list_comments))
         // Public invite endpoint
-        .route("/api/invites/:token", get(workspaces::get_invite_details));
+        .route("/api/invites/:token", get(workspaces::get_invite_details))
+        // Git push webhooks, verified with the project's push secret
+        .route("/api/projects/:id/git/push", post(git::receive_push));
 
     // Protected routes (authentication required)
     let protected_routes = Router::new()
@@ -44,6 +49,25 @@ pub fn api_routes() -> (Router<AppState>, Router<AppState>) {
         .route("/api/workspaces/:slug/members", get(workspaces::list_members))
         .route("/api/workspaces/:slug/members/:user_id", delete(workspaces::remove_member))
         .route("/api/workspaces/:slug/invites", get(workspaces::list_pending_invites))
+        // Backup and restore
+        .route("/api/workspaces/:slug/export", get(archive::export_workspace))
+        .route(
+            "/api/workspaces/restore",
+            post(archive::restore_workspace)
+                .layer(DefaultBodyLimit::max(archive::MAX_ARCHIVE_BYTES)),
+        )
+        // Webhooks
+        .route("/api/workspaces/:slug/webhooks", get(webhooks::list_webhooks))
+        .route("/api/workspaces/:slug/webhooks", post(webhooks::create_webhook))
+        .route("/api/webhooks/:id", get(webhooks::get_webhook))
+        .route("/api/webhooks/:id", put(webhooks::update_webhook))
+        .route("/api/webhooks/:id", delete(webhooks::delete_webhook))
+        .route("/api/webhooks/:id/ping", post(webhooks::ping_webhook))
+        .route("/api/webhooks/:id/deliveries", get(webhooks::list_deliveries))
+        .route(
+            "/api/webhooks/:id/deliveries/:delivery_id/redeliver",
+            post(webhooks::redeliver),
+        )
         // Invite acceptance
         .route("/api/invites/:token/accept", post(workspaces::accept_invite))
         // Epics
@@ -57,12 +81,27 @@ pub fn api_routes() -> (Router<AppState>, Router<AppState>) {
         .route("/api/auth/api-keys", get(auth::list_api_keys))
         .route("/api/auth/api-keys/:id", delete(auth::revoke_api_key))
         .route("/api/auth/sessions", get(auth::list_sessions))
-        // Projects - write operations
+        // Projects
+        .route("/api/projects", get(projects::list_projects))
+        .route("/api/projects/:id", get(projects::get_project))
         .route("/api/projects", post(projects::create_project))
         .route("/api/projects/:id", put(projects::update_project))
         .route("/api/projects/:id", delete(projects::delete_project))
-        // Tickets - write operations
+        .route("/api/projects/:id/workflow", get(workflows::get_workflow))
+        .route("/api/projects/:id/workflow", put(workflows::update_workflow))
+        .route(
+            "/api/projects/:id/import/jira",
+            post(imports::import_jira).layer(DefaultBodyLimit::max(imports::MAX_IMPORT_BYTES)),
+        )
+        .route(
+            "/api/projects/:id/import/github",
+            post(imports::import_github).layer(DefaultBodyLimit::max(imports::MAX_IMPORT_BYTES)),
+        )
+        // Tickets
+        .route("/api/tickets", get(tickets::list_tickets))
+        .route("/api/tickets/:id", get(tickets::get_ticket))
         .route("/api/tickets", post(tickets::create_ticket))
+        .route("/api/tickets/bulk", post(bulk::bulk_update))
         .route("/api/tickets/:id", put(tickets::update_ticket))
         .route("/api/tickets/:id", delete(tickets::delete_ticket))
         .route(
@@ -72,10 +111,36 @@ pub fn api_routes() -> (Router<AppState>, Router<AppState>) {
         .route("/api/tickets/:id/status", patch(tickets::update_status))
         .route("/api/tickets/:id/assign", post(tickets::assign_ticket))
         .route("/api/tickets/:id/unassign", post(tickets::unassign_ticket))
-        // Comments - write operations
+        // Comments
+        .route("/api/tickets/:id/comments", get(comments::list_comments))
         .route("/api/tickets/:id/comments", post(comments::create_comment))
         .route("/api/comments/:id", put(comments::update_comment))
         .route("/api/comments/:id", delete(comments::delete_comment))
+        .route("/api/comments/:id/revisions", get(comments::list_revisions))
+        .route("/api/comments/:id/reactions", post(comments::add_reaction))
+        .route(
+            "/api/comments/:id/reactions/:emoji",
+            delete(comments::remove_reaction),
+        )
+        // Attachments; uploads enforce the configured size limit themselves
+        .route("/api/tickets/:id/attachments", get(attachments::list_attachments))
+        .route(
+            "/api/tickets/:id/attachments",
+            post(attachments::upload_attachment).layer(DefaultBodyLimit::disable()),
+        )
+        .route("/api/attachments/:id", get(attachments::get_attachment))
+        .route("/api/attachments/:id", delete(attachments::delete_attachment))
+        .route(
+            "/api/attachments/:id/content",
+            get(attachments::download_attachment),
+        )
+        // Watchers and notifications
+        .route("/api/tickets/:id/watchers", get(notifications::list_watchers))
+        .route("/api/tickets/:id/watchers", post(notifications::follow_ticket))
+        .route("/api/tickets/:id/watchers", delete(notifications::unfollow_ticket))
+        .route("/api/notifications", get(notifications::list_notifications))
+        .route("/api/notifications/read-all", post(notifications::mark_all_read))
+        .route("/api/notifications/:id", put(notifications::update_notification))
         // Dependencies
         .route(
             "/api/tickets/:id/dependencies",
@@ -89,6 +154,24 @@ pub fn api_routes() -> (Router<AppState>, Router<AppState>) {
             "/api/tickets/:id/dependency-graph",
             get(dependencies::get_dependency_graph),
         )
+        .route(
+            "/api/epics/:id/dependency-graph",
+            get(dependencies::get_epic_dependency_graph),
+        )
+        .route(
+            "/api/sprints/:id/dependency-graph",
+            get(dependencies::get_sprint_dependency_graph),
+        )
+        // Templates
+        .route("/api/templates", get(templates::list_templates))
+        .route("/api/templates", post(templates::create_template))
+        .route("/api/templates/:id", get(templates::get_template))
+        .route("/api/templates/:id", put(templates::update_template))
+        .route("/api/templates/:id", delete(templates::delete_template))
+        .route(
+            "/api/templates/:id/instantiate",
+            post(templates::instantiate_template),
+        )
         // Activity & History
         .route("/api/tickets/:id/activity", get(activity::get_activity))
         .route("/api/tickets/:id/history", get(activity::get_history))
@@ -110,6 +193,7 @@ pub fn api_routes() -> (Router<AppState>, Router<AppState>) {
         // Git Integration
         .route("/api/tickets/:id/commits", post(git::link_commit))
         .route("/api/tickets/:id/commits", get(git::list_commits))
+        .route("/api/projects/:id/git/secret", post(git::rotate_push_secret))
         // Sprints
         .route("/api/projects/:project_id/sprints", get(sprints::list_sprints))
         .route("/api/projects/:project_id/sprints", post(sprints::create_sprint))
@@ -0,0 +1,182 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::tickets::resolve_ticket_id,
+    auth::{access, AuthUser},
+    error::{ApiError, ApiResult},
+    models::{
+        ListNotificationsQuery, NotificationListResponse, NotificationResponse,
+        TicketWatchersResponse, UpdateNotificationRequest, WatcherResponse,
+    },
+    notifications::{notification_responses, watch},
+    state::AppState,
+};
+use jility_core::entities::{
+    notification, ticket_watcher, user, Notification, TicketWatcher, User, WatchReason,
+};
+
+/// Most notifications one `list_notifications` call returns
+const MAX_NOTIFICATIONS: u64 = 200;
+
+/// The caller's notifications, newest first
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<ListNotificationsQuery>,
+) -> ApiResult<Json<NotificationListResponse>> {
+    let mut select = Notification::find()
+        .filter(notification::Column::UserId.eq(auth_user.id))
+        .order_by_desc(notification::Column::CreatedAt)
+        .limit(query.limit.clamp(1, MAX_NOTIFICATIONS));
+    if query.unread {
+        select = select.filter(notification::Column::ReadAt.is_null());
+    }
+    if let Some(before) = query.before {
+        select = select.filter(notification::Column::CreatedAt.lt(before));
+    }
+    let rows = select.all(state.db.as_ref()).await?;
+
+    let unread_count = Notification::find()
+        .filter(notification::Column::UserId.eq(auth_user.id))
+        .filter(notification::Column::ReadAt.is_null())
+        .count(state.db.as_ref())
+        .await?;
+
+    Ok(Json(NotificationListResponse {
+        notifications: notification_responses(state.db.as_ref(), rows).await?,
+        unread_count,
+    }))
+}
+
+/// Mark one of the caller's notifications read or unread
+pub async fn update_notification(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateNotificationRequest>,
+) -> ApiResult<Json<NotificationResponse>> {
+    let notification_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid notification ID: {}", id)))?;
+
+    // Other users' notifications are reported as missing
+    let row = Notification::find_by_id(notification_id)
+        .filter(notification::Column::UserId.eq(auth_user.id))
+        .one(state.db.as_ref())
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Notification not found: {}", id)))?;
+
+    let read_at = match (payload.read, row.read_at) {
+        (true, Some(read_at)) => Some(read_at),
+        (true, None) => Some(Utc::now()),
+        (false, _) => None,
+    };
+    Notification::update_many()
+        .col_expr(notification::Column::ReadAt, Expr::value(read_at))
+        .filter(notification::Column::Id.eq(row.id))
+        .exec(state.db.as_ref())
+        .await?;
+
+    let row = notification::Model { read_at, ..row };
+    notification_responses(state.db.as_ref(), vec![row])
+        .await?
+        .pop()
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Notification not found: {}", id)))
+}
+
+/// Mark all of the caller's notifications read
+pub async fn mark_all_read(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let result = Notification::update_many()
+        .col_expr(notification::Column::ReadAt, Expr::value(Some(Utc::now())))
+        .filter(notification::Column::UserId.eq(auth_user.id))
+        .filter(notification::Column::ReadAt.is_null())
+        .exec(state.db.as_ref())
+        .await?;
+
+    Ok(Json(serde_json::json!({ "updated": result.rows_affected })))
+}
+
+async fn watchers_response(
+    state: &AppState,
+    auth_user: &AuthUser,
+    ticket_id: Uuid,
+) -> ApiResult<TicketWatchersResponse> {
+    let watchers = TicketWatcher::find()
+        .filter(ticket_watcher::Column::TicketId.eq(ticket_id))
+        .order_by_asc(ticket_watcher::Column::CreatedAt)
+        .find_also_related(User)
+        .all(state.db.as_ref())
+        .await?;
+
+    Ok(TicketWatchersResponse {
+        watching: watchers.iter().any(|(w, _)| w.user_id == auth_user.id),
+        watchers: watchers
+            .into_iter()
+            .filter_map(|(w, user): (ticket_watcher::Model, Option<user::Model>)| {
+                Some(WatcherResponse {
+                    user_id: w.user_id.to_string(),
+                    username: user?.username,
+                    reason: w.reason,
+                    created_at: w.created_at.to_rfc3339(),
+                })
+            })
+            .collect(),
+    })
+}
+
+/// Who watches a ticket, and whether the caller does
+pub async fn list_watchers(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<TicketWatchersResponse>> {
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
+
+    Ok(Json(watchers_response(&state, &auth_user, ticket_id).await?))
+}
+
+/// Watch a ticket
+pub async fn follow_ticket(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<TicketWatchersResponse>> {
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
+
+    watch(state.db.as_ref(), ticket_id, auth_user.id, WatchReason::Follower).await?;
+
+    Ok(Json(watchers_response(&state, &auth_user, ticket_id).await?))
+}
+
+/// Stop watching a ticket, whatever the reason for watching it. Commenting
+/// on it or being assigned again starts watching again.
+pub async fn unfollow_ticket(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<TicketWatchersResponse>> {
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_id).await?;
+
+    TicketWatcher::delete_many()
+        .filter(ticket_watcher::Column::TicketId.eq(ticket_id))
+        .filter(ticket_watcher::Column::UserId.eq(auth_user.id))
+        .exec(state.db.as_ref())
+        .await?;
+
+    Ok(Json(watchers_response(&state, &auth_user, ticket_id).await?))
+}
@@ -1,17 +1,24 @@
-use axum::{extract::State, extract::Path, Json};
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use axum::{extract::State, extract::Path, Extension, Json};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use uuid::Uuid;
 use chrono::Utc;
 
 use crate::{
+    auth::{access, AuthUser},
     error::{ApiError, ApiResult},
     models::{CreateProjectRequest, UpdateProjectRequest, ProjectResponse},
     state::AppState,
 };
 use jility_core::entities::{project, Project};
 
-pub async fn list_projects(State(state): State<AppState>) -> ApiResult<Json<Vec<ProjectResponse>>> {
+/// List projects in the caller's workspaces
+pub async fn list_projects(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<Json<Vec<ProjectResponse>>> {
+    let workspace_ids = access::member_workspace_ids(state.db.as_ref(), &auth_user).await?;
     let projects = Project::find()
+        .filter(project::Column::WorkspaceId.is_in(workspace_ids))
         .all(state.db.as_ref())
         .await
         .map_err(ApiError::from)?;
@@ -35,10 +42,14 @@ pub async fn list_projects(State(state): State<AppState>) -> ApiResult<Json<Vec<
     Ok(Json(response))
 }
 
+/// Create a project (workspace admins only)
 pub async fn create_project(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Json(payload): Json<CreateProjectRequest>,
 ) -> ApiResult<Json<ProjectResponse>> {
+    access::require_admin(state.db.as_ref(), &auth_user, payload.workspace_id).await?;
+
     let now = Utc::now();
     let project_id = Uuid::new_v4();
 
@@ -52,6 +63,8 @@ pub async fn create_project(
         ai_planning_enabled: Set(payload.ai_planning_enabled),
         auto_link_git: Set(payload.auto_link_git),
         require_story_points: Set(payload.require_story_points),
+        ticket_sequence: Set(0),
+        git_webhook_secret: Set(None),
         created_at: Set(now),
         updated_at: Set(now),
     };
@@ -77,16 +90,13 @@ pub async fn create_project(
 
 pub async fn get_project(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<ProjectResponse>> {
     let project_id = Uuid::parse_str(&id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
 
-    let project = Project::find_by_id(project_id)
-        .one(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?
-        .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", id)))?;
+    let (project, _) = access::project_for_member(state.db.as_ref(), &auth_user, project_id).await?;
 
     Ok(Json(ProjectResponse {
         id: project.id.to_string(),
@@ -102,19 +112,19 @@ pub async fn get_project(
     }))
 }
 
+/// Update a project (workspace admins only)
 pub async fn update_project(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateProjectRequest>,
 ) -> ApiResult<Json<ProjectResponse>> {
     let project_id = Uuid::parse_str(&id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
 
-    let existing_project = Project::find_by_id(project_id)
-        .one(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?
-        .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", id)))?;
+    let (existing_project, _) =
+        access::project_for_member(state.db.as_ref(), &auth_user, project_id).await?;
+    access::require_admin(state.db.as_ref(), &auth_user, existing_project.workspace_id).await?;
 
     let mut active_model: project::ActiveModel = existing_project.into();
 
@@ -160,13 +170,18 @@ pub async fn update_project(
     }))
 }
 
+/// Delete a project (workspace admins only)
 pub async fn delete_project(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let project_id = Uuid::parse_str(&id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
 
+    let (project, _) = access::project_for_member(state.db.as_ref(), &auth_user, project_id).await?;
+    access::require_admin(state.db.as_ref(), &auth_user, project.workspace_id).await?;
+
     let result = Project::delete_by_id(project_id)
         .exec(state.db.as_ref())
         .await
@@ -14,7 +14,7 @@ use sea_orm::QueryFilter;
 use uuid::Uuid;
 
 use crate::{
-    auth::middleware::AuthUser,
+    auth::{access, middleware::AuthUser},
     error::{ApiError, ApiResult},
     models::{
         CreateSavedViewRequest, SavedViewResponse, SearchQuery, UpdateSavedViewRequest,
@@ -25,9 +25,14 @@ use crate::{
 /// Search tickets with full-text search and advanced filters
 pub async fn search_tickets(
     State(state): State<AppState>,
-    Extension(_auth_user): Extension<AuthUser>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(query): Query<SearchQuery>,
 ) -> ApiResult<Json<CoreSearchResponse>> {
+    if let Some(project_id) = query.project_id {
+        access::require_project(state.db.as_ref(), &auth_user, project_id).await?;
+    }
+    let project_ids = access::visible_project_ids(state.db.as_ref(), &auth_user).await?;
+
     // Parse date filters
     let created_after = query
         .created_after
@@ -80,6 +85,7 @@ pub async fn search_tickets(
         epic_id: query.epic_id,
         parent_id: query.parent_id,
         project_id: query.project_id,
+        project_ids: Some(project_ids),
         search_in: if query.search_in.is_empty() {
             vec![
                 "title".to_string(),
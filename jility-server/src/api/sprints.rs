@@ -1,15 +1,17 @@
-use axum::{extract::{Path, Query, State}, Json};
+use axum::{extract::{Path, Query, State}, Extension, Json};
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
 };
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 use chrono::Utc;
 
 use crate::{
+    auth::{access, AuthUser},
     error::{ApiError, ApiResult},
     models::{
-        CreateSprintRequest, UpdateSprintRequest, StartSprintRequest, AddTicketToSprintRequest,
+        CreateSprintRequest, UpdateSprintRequest, StartSprintRequest,
         SprintResponse, SprintDetailsResponse, SprintStats, BurndownData, BurndownDataPoint,
         SprintHistoryResponse, VelocityData, TicketResponse, format_uuid, format_datetime,
     },
@@ -19,6 +21,8 @@ use jility_core::entities::{
     sprint, sprint_ticket, ticket, ticket_change, project,
     Sprint, SprintTicket, Ticket, TicketChange, ChangeType, Project,
 };
+use jility_core::history::SprintTimeline;
+use jility_core::workflow::{self, StatusCategory};
 
 #[derive(Debug, Deserialize)]
 pub struct ListSprintsQuery {
@@ -28,12 +32,15 @@ pub struct ListSprintsQuery {
 /// List all sprints for a project
 pub async fn list_sprints(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(project_id): Path<String>,
     Query(query): Query<ListSprintsQuery>,
 ) -> ApiResult<Json<Vec<SprintResponse>>> {
     let project_uuid = Uuid::parse_str(&project_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", project_id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, project_uuid).await?;
+
     let mut query_builder = Sprint::find()
         .filter(sprint::Column::ProjectId.eq(project_uuid));
 
@@ -69,12 +76,15 @@ pub async fn list_sprints(
 /// Create a new sprint
 pub async fn create_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(project_id): Path<String>,
     Json(req): Json<CreateSprintRequest>,
 ) -> ApiResult<Json<SprintResponse>> {
     let project_uuid = Uuid::parse_str(&project_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", project_id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, project_uuid).await?;
+
     let start_date = if let Some(date_str) = req.start_date {
         Some(chrono::DateTime::parse_from_rfc3339(&date_str)
             .map_err(|_| ApiError::InvalidInput("Invalid start_date format".to_string()))?
@@ -91,6 +101,12 @@ pub async fn create_sprint(
         None
     };
 
+    // Default capacity to recent velocity when none is given
+    let capacity = match req.capacity {
+        Some(capacity) => Some(capacity),
+        None => suggest_capacity(state.db.as_ref(), project_uuid).await?,
+    };
+
     let now = Utc::now();
     let sprint_id = Uuid::new_v4();
 
@@ -102,7 +118,7 @@ pub async fn create_sprint(
         start_date: Set(start_date),
         end_date: Set(end_date),
         status: Set("planning".to_string()),
-        capacity: Set(req.capacity),
+        capacity: Set(capacity),
         created_at: Set(now),
         updated_at: Set(now),
     };
@@ -126,11 +142,14 @@ pub async fn create_sprint(
 /// Get sprint details with tickets and stats
 pub async fn get_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(sprint_id): Path<String>,
 ) -> ApiResult<Json<SprintDetailsResponse>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
     let sprint = Sprint::find_by_id(sprint_uuid)
         .one(state.db.as_ref())
         .await
@@ -158,6 +177,7 @@ pub async fn get_sprint(
     };
 
     // Calculate stats
+    let workflow = workflow::load(state.db.as_ref(), sprint.project_id).await?;
     let total_tickets = tickets.len();
     let total_points: i32 = tickets
         .iter()
@@ -166,17 +186,17 @@ pub async fn get_sprint(
 
     let completed_tickets: Vec<_> = tickets
         .iter()
-        .filter(|t| t.status == "done")
+        .filter(|t| workflow.category(&t.status) == StatusCategory::Done)
         .collect();
 
     let in_progress_tickets: Vec<_> = tickets
         .iter()
-        .filter(|t| t.status == "in_progress")
+        .filter(|t| workflow.category(&t.status) == StatusCategory::InProgress)
         .collect();
 
     let todo_tickets: Vec<_> = tickets
         .iter()
-        .filter(|t| t.status == "todo" || t.status == "backlog")
+        .filter(|t| workflow.category(&t.status) == StatusCategory::Todo)
         .collect();
 
     let completed_points: i32 = completed_tickets
@@ -254,12 +274,15 @@ pub async fn get_sprint(
 /// Update sprint
 pub async fn update_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(sprint_id): Path<String>,
     Json(req): Json<UpdateSprintRequest>,
 ) -> ApiResult<Json<SprintResponse>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
     let sprint = Sprint::find_by_id(sprint_uuid)
         .one(state.db.as_ref())
         .await
@@ -315,11 +338,14 @@ pub async fn update_sprint(
 /// Delete sprint
 pub async fn delete_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(sprint_id): Path<String>,
 ) -> ApiResult<Json<()>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
     let sprint = Sprint::find_by_id(sprint_uuid)
         .one(state.db.as_ref())
         .await
@@ -343,12 +369,15 @@ pub async fn delete_sprint(
 /// Start a sprint
 pub async fn start_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(sprint_id): Path<String>,
     Json(req): Json<StartSprintRequest>,
 ) -> ApiResult<Json<SprintResponse>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
     let sprint = Sprint::find_by_id(sprint_uuid)
         .one(state.db.as_ref())
         .await
@@ -374,8 +403,9 @@ pub async fn start_sprint(
     active_sprint.updated_at = Set(Utc::now());
 
     let sprint = active_sprint.update(state.db.as_ref()).await.map_err(ApiError::from)?;
+    let project_id = sprint.project_id;
 
-    Ok(Json(SprintResponse {
+    let response = SprintResponse {
         id: format_uuid(&sprint.id),
         project_id: format_uuid(&sprint.project_id),
         name: sprint.name,
@@ -386,17 +416,27 @@ pub async fn start_sprint(
         capacity: sprint.capacity,
         created_at: format_datetime(&sprint.created_at),
         updated_at: format_datetime(&sprint.updated_at),
-    }))
+    };
+
+    state
+        .webhooks
+        .enqueue_project_event(&state.db, project_id, "sprint.started", serde_json::json!({ "sprint": response }))
+        .await;
+
+    Ok(Json(response))
 }
 
 /// Complete a sprint
 pub async fn complete_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(sprint_id): Path<String>,
 ) -> ApiResult<Json<SprintResponse>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
     let sprint = Sprint::find_by_id(sprint_uuid)
         .one(state.db.as_ref())
         .await
@@ -412,8 +452,9 @@ pub async fn complete_sprint(
     active_sprint.updated_at = Set(Utc::now());
 
     let sprint = active_sprint.update(state.db.as_ref()).await.map_err(ApiError::from)?;
+    let project_id = sprint.project_id;
 
-    Ok(Json(SprintResponse {
+    let response = SprintResponse {
         id: format_uuid(&sprint.id),
         project_id: format_uuid(&sprint.project_id),
         name: sprint.name,
@@ -424,18 +465,27 @@ pub async fn complete_sprint(
         capacity: sprint.capacity,
         created_at: format_datetime(&sprint.created_at),
         updated_at: format_datetime(&sprint.updated_at),
-    }))
+    };
+
+    state
+        .webhooks
+        .enqueue_project_event(&state.db, project_id, "sprint.completed", serde_json::json!({ "sprint": response }))
+        .await;
+
+    Ok(Json(response))
 }
 
 /// Add a ticket to a sprint
 pub async fn add_ticket_to_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path((sprint_id, ticket_id)): Path<(String, String)>,
-    Json(req): Json<AddTicketToSprintRequest>,
 ) -> ApiResult<Json<()>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
     // Parse ticket_id (supports both UUID and ticket number like "JIL-42")
     let ticket_uuid = if let Ok(uuid) = Uuid::parse_str(&ticket_id) {
         // Already a UUID
@@ -475,6 +525,8 @@ pub async fn add_ticket_to_sprint(
         ticket.id
     };
 
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_uuid).await?;
+
     // Verify sprint exists
     Sprint::find_by_id(sprint_uuid)
         .one(state.db.as_ref())
@@ -500,7 +552,7 @@ pub async fn add_ticket_to_sprint(
         sprint_id: Set(sprint_uuid),
         ticket_id: Set(ticket_uuid),
         added_at: Set(now),
-        added_by: Set(req.added_by.clone()),
+        added_by: Set(auth_user.actor()),
     };
 
     sprint_ticket.insert(state.db.as_ref()).await.map_err(ApiError::from)?;
@@ -513,7 +565,7 @@ pub async fn add_ticket_to_sprint(
         field_name: Set(None),
         old_value: Set(None),
         new_value: Set(Some(format_uuid(&sprint_uuid))),
-        changed_by: Set(req.added_by),
+        changed_by: Set(auth_user.actor()),
         changed_at: Set(now),
         message: Set(None),
     };
@@ -526,6 +578,7 @@ pub async fn add_ticket_to_sprint(
 /// Remove a ticket from a sprint
 pub async fn remove_ticket_from_sprint(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path((sprint_id, ticket_id)): Path<(String, String)>,
 ) -> ApiResult<Json<()>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
@@ -533,6 +586,9 @@ pub async fn remove_ticket_from_sprint(
     let ticket_uuid = Uuid::parse_str(&ticket_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket ID: {}", ticket_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+    access::require_ticket(state.db.as_ref(), &auth_user, ticket_uuid).await?;
+
     // Delete sprint_ticket entry
     let result = SprintTicket::delete_many()
         .filter(sprint_ticket::Column::SprintId.eq(sprint_uuid))
@@ -553,7 +609,7 @@ pub async fn remove_ticket_from_sprint(
         field_name: Set(None),
         old_value: Set(Some(format_uuid(&sprint_uuid))),
         new_value: Set(None),
-        changed_by: Set("system".to_string()),
+        changed_by: Set(auth_user.actor()),
         changed_at: Set(Utc::now()),
         message: Set(None),
     };
@@ -566,11 +622,21 @@ pub async fn remove_ticket_from_sprint(
 /// Get sprint statistics
 pub async fn get_sprint_stats(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(sprint_id): Path<String>,
 ) -> ApiResult<Json<SprintStats>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
+    let sprint = Sprint::find_by_id(sprint_uuid)
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Sprint {} not found", sprint_id)))?;
+    let workflow = workflow::load(state.db.as_ref(), sprint.project_id).await?;
+
     // Get all tickets in this sprint
     let sprint_tickets = SprintTicket::find()
         .filter(sprint_ticket::Column::SprintId.eq(sprint_uuid))
@@ -599,17 +665,17 @@ pub async fn get_sprint_stats(
 
     let completed_tickets: Vec<_> = tickets
         .iter()
-        .filter(|t| t.status == "done")
+        .filter(|t| workflow.category(&t.status) == StatusCategory::Done)
         .collect();
 
     let in_progress_tickets: Vec<_> = tickets
         .iter()
-        .filter(|t| t.status == "in_progress")
+        .filter(|t| workflow.category(&t.status) == StatusCategory::InProgress)
         .collect();
 
     let todo_tickets: Vec<_> = tickets
         .iter()
-        .filter(|t| t.status == "todo" || t.status == "backlog")
+        .filter(|t| workflow.category(&t.status) == StatusCategory::Todo)
         .collect();
 
     let completed_points: i32 = completed_tickets
@@ -646,14 +712,93 @@ pub async fn get_sprint_stats(
     }))
 }
 
+/// Load every ticket that has ever been in a sprint, with its sprint timeline.
+///
+/// Includes tickets that were removed from the sprint or deleted since, so past
+/// days are reported as they were.
+async fn load_sprint_timelines(
+    db: &sea_orm::DatabaseConnection,
+    sprint: &sprint::Model,
+) -> ApiResult<Vec<(ticket::Model, SprintTimeline)>> {
+    let sprint_uuid = sprint.id;
+    let workflow = workflow::load(db, sprint.project_id).await?;
+    let current_ids: HashSet<Uuid> = SprintTicket::find()
+        .filter(sprint_ticket::Column::SprintId.eq(sprint_uuid))
+        .all(db)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|st| st.ticket_id)
+        .collect();
+
+    let removed_ids = TicketChange::find()
+        .filter(ticket_change::Column::ChangeType.eq(ChangeType::RemovedFromSprint.as_str()))
+        .filter(ticket_change::Column::OldValue.eq(format_uuid(&sprint_uuid)))
+        .all(db)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|c| c.ticket_id);
+
+    let ticket_ids: Vec<Uuid> = current_ids
+        .iter()
+        .copied()
+        .chain(removed_ids)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    if ticket_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let tickets = Ticket::find()
+        .filter(ticket::Column::Id.is_in(ticket_ids.clone()))
+        .all(db)
+        .await
+        .map_err(ApiError::from)?;
+
+    let mut changes_by_ticket: HashMap<Uuid, Vec<ticket_change::Model>> = HashMap::new();
+    for change in TicketChange::find()
+        .filter(ticket_change::Column::TicketId.is_in(ticket_ids))
+        .filter(ticket_change::Column::ChangeType.is_in([
+            ChangeType::StatusChanged.as_str(),
+            ChangeType::StoryPointsChanged.as_str(),
+            ChangeType::AddedToSprint.as_str(),
+            ChangeType::RemovedFromSprint.as_str(),
+        ]))
+        .all(db)
+        .await
+        .map_err(ApiError::from)?
+    {
+        changes_by_ticket.entry(change.ticket_id).or_default().push(change);
+    }
+
+    Ok(tickets
+        .into_iter()
+        .map(|t| {
+            let changes = changes_by_ticket.remove(&t.id).unwrap_or_default();
+            let timeline = SprintTimeline::new(sprint_uuid, &workflow, &t, current_ids.contains(&t.id), changes);
+            (t, timeline)
+        })
+        .collect())
+}
+
 /// Get burndown chart data
+///
+/// Each day is evaluated as of its end by replaying `ticket_change` rows, so
+/// reopened tickets and later edits do not rewrite past days. Points added to the
+/// sprint after it started are reported separately as `scope_added`.
 pub async fn get_burndown(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(sprint_id): Path<String>,
 ) -> ApiResult<Json<BurndownData>> {
     let sprint_uuid = Uuid::parse_str(&sprint_id)
         .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
 
+    access::require_sprint(state.db.as_ref(), &auth_user, sprint_uuid).await?;
+
     let sprint = Sprint::find_by_id(sprint_uuid)
         .one(state.db.as_ref())
         .await
@@ -665,36 +810,28 @@ pub async fn get_burndown(
     let end_date = sprint.end_date.ok_or_else(||
         ApiError::InvalidInput("Sprint has no end date".to_string()))?;
 
-    // Get all tickets in sprint
-    let sprint_tickets = SprintTicket::find()
-        .filter(sprint_ticket::Column::SprintId.eq(sprint_uuid))
-        .all(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?;
+    let timelines = load_sprint_timelines(state.db.as_ref(), &sprint).await?;
 
-    let ticket_ids: Vec<Uuid> = sprint_tickets.iter().map(|st| st.ticket_id).collect();
-
-    let tickets = if ticket_ids.is_empty() {
-        vec![]
-    } else {
-        Ticket::find()
-            .filter(ticket::Column::Id.is_in(ticket_ids))
-            .filter(ticket::Column::DeletedAt.is_null())
-            .all(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-    };
+    // Scope committed when the sprint started
+    let committed: HashSet<Uuid> = timelines
+        .iter()
+        .filter(|(_, timeline)| timeline.state_at(start_date).in_sprint)
+        .map(|(t, _)| t.id)
+        .collect();
 
-    let total_points: i32 = tickets
+    let total_points: i32 = timelines
         .iter()
-        .filter_map(|t| t.story_points)
+        .filter(|(t, _)| committed.contains(&t.id))
+        .filter_map(|(_, timeline)| timeline.state_at(start_date).story_points)
         .sum();
 
     // Calculate number of days
     let days = (end_date - start_date).num_days() + 1;
+    let now = Utc::now();
 
     // Generate data points
     let mut data_points = Vec::new();
+    let mut scope_added = 0;
 
     for day in 0..days {
         let current_date = start_date + chrono::Duration::days(day);
@@ -707,29 +844,36 @@ pub async fn get_burndown(
             total_points
         };
 
-        // Actual remaining points - count tickets that were not "done" by this date
-        // This is simplified - in reality you'd query ticket_changes to see status at that time
-        let actual = if current_date > Utc::now() {
+        let actual = if current_date > now {
             // Future dates - use ideal as estimate
             ideal
         } else {
-            // For simplicity, calculate based on current status
-            // A real implementation would query ticket_changes table
-            let completed_by_date: i32 = tickets
-                .iter()
-                .filter(|t| {
-                    t.status == "done" && t.updated_at <= current_date
-                })
-                .filter_map(|t| t.story_points)
-                .sum();
-
-            total_points - completed_by_date
+            let as_of = (current_date + chrono::Duration::days(1)).min(now);
+
+            let mut remaining = 0;
+            scope_added = 0;
+            for (t, timeline) in &timelines {
+                let ticket_state = timeline.state_at(as_of);
+                if !ticket_state.in_sprint {
+                    continue;
+                }
+                let points = ticket_state.story_points.unwrap_or(0);
+                if !ticket_state.done {
+                    remaining += points;
+                }
+                if !committed.contains(&t.id) {
+                    scope_added += points;
+                }
+            }
+
+            remaining
         };
 
         data_points.push(BurndownDataPoint {
             date: date_str,
             ideal,
             actual,
+            scope_added,
         });
     }
 
@@ -739,61 +883,122 @@ pub async fn get_burndown(
     }))
 }
 
-/// Get sprint history with velocity data
-pub async fn get_sprint_history(
-    State(state): State<AppState>,
-    Path(project_id): Path<String>,
-) -> ApiResult<Json<SprintHistoryResponse>> {
-    let project_uuid = Uuid::parse_str(&project_id)
-        .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", project_id)))?;
+/// Number of recent completed sprints used for the rolling velocity average
+const VELOCITY_WINDOW: usize = 3;
+
+/// Committed vs completed points for a sprint, using `ticket_change` history.
+///
+/// Committed is the scope at `start_date`. Completed only counts tickets that
+/// reached done between `start_date` and `end_date`; tickets still open at the end
+/// are carried over.
+async fn sprint_velocity(
+    db: &sea_orm::DatabaseConnection,
+    sprint: &sprint::Model,
+) -> ApiResult<VelocityData> {
+    let start = sprint.start_date.unwrap_or(sprint.created_at);
+    let end = sprint.end_date.unwrap_or(sprint.updated_at);
+
+    let mut committed_points = 0;
+    let mut completed_points = 0;
+    let mut carried_over_tickets = 0;
+    let mut carried_over_points = 0;
+
+    for (_, timeline) in load_sprint_timelines(db, sprint).await? {
+        let at_start = timeline.state_at(start);
+        if at_start.in_sprint {
+            committed_points += at_start.story_points.unwrap_or(0);
+        }
+
+        let at_end = timeline.state_at(end);
+        if timeline.completed_between(start, end) {
+            completed_points += at_end.story_points.unwrap_or(0);
+        } else if at_end.in_sprint && !at_end.done {
+            carried_over_tickets += 1;
+            carried_over_points += at_end.story_points.unwrap_or(0);
+        }
+    }
+
+    Ok(VelocityData {
+        sprint_name: sprint.name.clone(),
+        committed_points,
+        completed_points,
+        carried_over_tickets,
+        carried_over_points,
+    })
+}
+
+/// Mean and population standard deviation of completed points
+fn velocity_stats(velocity: &[VelocityData]) -> (f64, f64) {
+    if velocity.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let n = velocity.len() as f64;
+    let mean = velocity.iter().map(|v| v.completed_points as f64).sum::<f64>() / n;
+    let variance = velocity
+        .iter()
+        .map(|v| (v.completed_points as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+
+    (mean, variance.sqrt())
+}
 
-    let sprints = Sprint::find()
+/// Completed sprints for a project, most recent first
+async fn completed_sprints(
+    db: &sea_orm::DatabaseConnection,
+    project_uuid: Uuid,
+) -> ApiResult<Vec<sprint::Model>> {
+    Sprint::find()
         .filter(sprint::Column::ProjectId.eq(project_uuid))
         .filter(sprint::Column::Status.eq("completed"))
         .order_by_desc(sprint::Column::CreatedAt)
-        .all(state.db.as_ref())
+        .all(db)
         .await
-        .map_err(ApiError::from)?;
+        .map_err(ApiError::from)
+}
 
-    let mut velocity_data = Vec::new();
-    let mut total_velocity = 0;
+/// Suggested capacity: rolling average velocity over the last few sprints
+async fn suggest_capacity(
+    db: &sea_orm::DatabaseConnection,
+    project_uuid: Uuid,
+) -> ApiResult<Option<i32>> {
+    let mut velocity = Vec::new();
+    for sprint in completed_sprints(db, project_uuid).await?.iter().take(VELOCITY_WINDOW) {
+        velocity.push(sprint_velocity(db, sprint).await?);
+    }
 
-    for sprint in &sprints {
-        // Get completed points for this sprint
-        let sprint_tickets = SprintTicket::find()
-            .filter(sprint_ticket::Column::SprintId.eq(sprint.id))
-            .all(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?;
+    if velocity.is_empty() {
+        return Ok(None);
+    }
 
-        let ticket_ids: Vec<Uuid> = sprint_tickets.iter().map(|st| st.ticket_id).collect();
+    let (rolling_average, _) = velocity_stats(&velocity);
+    Ok(Some(rolling_average.round() as i32))
+}
 
-        let completed_points: i32 = if ticket_ids.is_empty() {
-            0
-        } else {
-            let tickets = Ticket::find()
-                .filter(ticket::Column::Id.is_in(ticket_ids))
-                .filter(ticket::Column::Status.eq("done"))
-                .all(state.db.as_ref())
-                .await
-                .map_err(ApiError::from)?;
-
-            tickets.iter().filter_map(|t| t.story_points).sum()
-        };
+/// Get sprint history with velocity data
+pub async fn get_sprint_history(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(project_id): Path<String>,
+) -> ApiResult<Json<SprintHistoryResponse>> {
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", project_id)))?;
 
-        total_velocity += completed_points;
+    access::require_project(state.db.as_ref(), &auth_user, project_uuid).await?;
 
-        velocity_data.push(VelocityData {
-            sprint_name: sprint.name.clone(),
-            completed_points,
-        });
+    let sprints = completed_sprints(state.db.as_ref(), project_uuid).await?;
+
+    let mut velocity_data = Vec::new();
+    for sprint in &sprints {
+        velocity_data.push(sprint_velocity(state.db.as_ref(), sprint).await?);
     }
 
-    let average_velocity = if !sprints.is_empty() {
-        total_velocity as f64 / sprints.len() as f64
-    } else {
-        0.0
-    };
+    let (average_velocity, _) = velocity_stats(&velocity_data);
+    let window = &velocity_data[..velocity_data.len().min(VELOCITY_WINDOW)];
+    let (rolling_average_velocity, velocity_std_dev) = velocity_stats(window);
+    let suggested_capacity =
+        (!window.is_empty()).then(|| rolling_average_velocity.round() as i32);
 
     let sprint_responses: Vec<SprintResponse> = sprints
         .into_iter()
@@ -815,5 +1020,8 @@ pub async fn get_sprint_history(
         sprints: sprint_responses,
         velocity_data,
         average_velocity,
+        rolling_average_velocity,
+        velocity_std_dev,
+        suggested_capacity,
     }))
 }
@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+    SqlErr, TransactionTrait,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    api::tickets::{insert_ticket, ticket_response},
+    auth::{access, AuthUser},
+    error::{ApiError, ApiResult},
+    models::{
+        CreateTemplateRequest, CreateTicketRequest, InstantiateTemplateRequest,
+        InstantiatedTemplateResponse, TemplateResponse, UpdateTemplateRequest,
+    },
+    notifications,
+    state::AppState,
+    websocket::publish_ticket_event,
+};
+use jility_core::entities::{project, ticket_template, Project, TicketTemplate};
+use jility_core::template::{placeholders, render};
+use jility_core::workflow;
+
+#[derive(Debug, Deserialize)]
+pub struct ListTemplatesQuery {
+    /// Templates for this project plus its workspace-wide templates
+    pub project_id: Option<Uuid>,
+    /// Every template in the workspace
+    pub workspace_id: Option<Uuid>,
+}
+
+fn to_response(template: ticket_template::Model) -> TemplateResponse {
+    let default_labels = template.default_labels();
+    let subtasks = template.subtasks();
+
+    let mut variables = placeholders(&template.title_template);
+    let mut add = |text: &str| {
+        for name in placeholders(text) {
+            if !variables.contains(&name) {
+                variables.push(name);
+            }
+        }
+    };
+    add(&template.description_template);
+    for subtask in &subtasks {
+        add(&subtask.title);
+        add(&subtask.description);
+    }
+
+    TemplateResponse {
+        id: template.id.to_string(),
+        workspace_id: template.workspace_id.to_string(),
+        project_id: template.project_id.map(|id| id.to_string()),
+        name: template.name,
+        description: template.description,
+        title_template: template.title_template,
+        description_template: template.description_template,
+        default_status: template.default_status,
+        default_story_points: template.default_story_points,
+        default_labels,
+        subtasks,
+        variables,
+        created_by: template.created_by,
+        created_at: template.created_at.to_rfc3339(),
+        updated_at: template.updated_at.to_rfc3339(),
+    }
+}
+
+/// Check a default status against the project's workflow. Workspace-wide
+/// templates are checked against the target project when instantiated.
+async fn validate_status(
+    state: &AppState,
+    project_id: Option<Uuid>,
+    status: &Option<String>,
+) -> ApiResult<()> {
+    if let (Some(project_id), Some(status)) = (project_id, status) {
+        workflow::load(state.db.as_ref(), project_id)
+            .await?
+            .check_status(status)?;
+    }
+    Ok(())
+}
+
+async fn find_project(state: &AppState, project_id: Uuid) -> ApiResult<project::Model> {
+    Project::find_by_id(project_id)
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", project_id)))
+}
+
+/// Load a template from one of the caller's workspaces
+async fn find_template(
+    state: &AppState,
+    auth_user: &AuthUser,
+    id: Uuid,
+) -> ApiResult<ticket_template::Model> {
+    let template = TicketTemplate::find_by_id(id)
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Template not found: {}", id)))?;
+    access::require_member(state.db.as_ref(), auth_user, template.workspace_id).await?;
+    Ok(template)
+}
+
+/// Reject a name already used by another template in the same scope
+async fn ensure_unique_name(
+    state: &AppState,
+    workspace_id: Uuid,
+    project_id: Option<Uuid>,
+    name: &str,
+    exclude: Option<Uuid>,
+) -> ApiResult<()> {
+    let mut query = TicketTemplate::find()
+        .filter(ticket_template::Column::WorkspaceId.eq(workspace_id))
+        .filter(ticket_template::Column::Name.eq(name));
+    query = match project_id {
+        Some(project_id) => query.filter(ticket_template::Column::ProjectId.eq(project_id)),
+        None => query.filter(ticket_template::Column::ProjectId.is_null()),
+    };
+    if let Some(id) = exclude {
+        query = query.filter(ticket_template::Column::Id.ne(id));
+    }
+
+    let existing = query.one(state.db.as_ref()).await.map_err(ApiError::from)?;
+    if existing.is_some() {
+        return Err(name_taken(name));
+    }
+    Ok(())
+}
+
+fn name_taken(name: &str) -> ApiError {
+    ApiError::Conflict(format!("Template '{}' already exists", name))
+}
+
+/// Map a save that lost a race for `name` to the same 409 as the check above
+fn save_error(name: &str, err: DbErr) -> ApiError {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => name_taken(name),
+        _ => ApiError::from(err),
+    }
+}
+
+/// List templates for a project or a workspace
+pub async fn list_templates(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<ListTemplatesQuery>,
+) -> ApiResult<Json<Vec<TemplateResponse>>> {
+    let condition = match (query.project_id, query.workspace_id) {
+        (Some(project_id), _) => {
+            let (project, _) =
+                access::project_for_member(state.db.as_ref(), &auth_user, project_id).await?;
+            Condition::any()
+                .add(ticket_template::Column::ProjectId.eq(project_id))
+                .add(
+                    Condition::all()
+                        .add(ticket_template::Column::WorkspaceId.eq(project.workspace_id))
+                        .add(ticket_template::Column::ProjectId.is_null()),
+                )
+        }
+        (None, Some(workspace_id)) => {
+            access::require_member(state.db.as_ref(), &auth_user, workspace_id).await?;
+            Condition::all().add(ticket_template::Column::WorkspaceId.eq(workspace_id))
+        }
+        (None, None) => {
+            return Err(ApiError::InvalidInput(
+                "project_id or workspace_id is required".to_string(),
+            ))
+        }
+    };
+
+    let templates = TicketTemplate::find()
+        .filter(condition)
+        .order_by_asc(ticket_template::Column::Name)
+        .all(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(templates.into_iter().map(to_response).collect()))
+}
+
+/// Get a template
+pub async fn get_template(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<TemplateResponse>> {
+    Ok(Json(to_response(find_template(&state, &auth_user, id).await?)))
+}
+
+/// Create a template
+pub async fn create_template(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<CreateTemplateRequest>,
+) -> ApiResult<Json<TemplateResponse>> {
+    let workspace_id = match (req.project_id, req.workspace_id) {
+        (Some(project_id), workspace_id) => {
+            let project = find_project(&state, project_id).await?;
+            if workspace_id.is_some_and(|id| id != project.workspace_id) {
+                return Err(ApiError::InvalidInput(
+                    "project_id does not belong to workspace_id".to_string(),
+                ));
+            }
+            project.workspace_id
+        }
+        (None, Some(workspace_id)) => workspace_id,
+        (None, None) => {
+            return Err(ApiError::InvalidInput(
+                "project_id or workspace_id is required".to_string(),
+            ))
+        }
+    };
+
+    access::require_member(state.db.as_ref(), &auth_user, workspace_id).await?;
+
+    if req.name.trim().is_empty() || req.title_template.trim().is_empty() {
+        return Err(ApiError::InvalidInput(
+            "name and title_template must not be empty".to_string(),
+        ));
+    }
+    validate_status(&state, req.project_id, &req.default_status).await?;
+    ensure_unique_name(&state, workspace_id, req.project_id, &req.name, None).await?;
+
+    let now = Utc::now();
+    let template = ticket_template::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        workspace_id: Set(workspace_id),
+        project_id: Set(req.project_id),
+        name: Set(req.name.clone()),
+        description: Set(req.description),
+        title_template: Set(req.title_template),
+        description_template: Set(req.description_template),
+        default_status: Set(req.default_status),
+        default_story_points: Set(req.default_story_points),
+        default_labels: Set(serde_json::to_string(&req.default_labels).unwrap()),
+        subtasks: Set(serde_json::to_string(&req.subtasks).unwrap()),
+        created_by: Set(auth_user.actor()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    let template = template
+        .insert(state.db.as_ref())
+        .await
+        .map_err(|err| save_error(&req.name, err))?;
+
+    Ok(Json(to_response(template)))
+}
+
+/// Update a template
+pub async fn update_template(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateTemplateRequest>,
+) -> ApiResult<Json<TemplateResponse>> {
+    let template = find_template(&state, &auth_user, id).await?;
+
+    if let Some(name) = &req.name {
+        ensure_unique_name(&state, template.workspace_id, template.project_id, name, Some(id))
+            .await?;
+    }
+    validate_status(&state, template.project_id, &req.default_status).await?;
+
+    let name = req.name.clone().unwrap_or_else(|| template.name.clone());
+    let mut active: ticket_template::ActiveModel = template.into();
+
+    if let Some(name) = req.name {
+        active.name = Set(name);
+    }
+    if let Some(description) = req.description {
+        active.description = Set(Some(description));
+    }
+    if let Some(title_template) = req.title_template {
+        active.title_template = Set(title_template);
+    }
+    if let Some(description_template) = req.description_template {
+        active.description_template = Set(description_template);
+    }
+    if let Some(default_status) = req.default_status {
+        active.default_status = Set(Some(default_status));
+    }
+    if let Some(default_story_points) = req.default_story_points {
+        active.default_story_points = Set(Some(default_story_points));
+    }
+    if let Some(default_labels) = req.default_labels {
+        active.default_labels = Set(serde_json::to_string(&default_labels).unwrap());
+    }
+    if let Some(subtasks) = req.subtasks {
+        active.subtasks = Set(serde_json::to_string(&subtasks).unwrap());
+    }
+
+    active.updated_at = Set(Utc::now());
+
+    let template = active
+        .update(state.db.as_ref())
+        .await
+        .map_err(|err| save_error(&name, err))?;
+
+    Ok(Json(to_response(template)))
+}
+
+/// Delete a template
+pub async fn delete_template(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<serde_json::Value>> {
+    find_template(&state, &auth_user, id).await?;
+
+    TicketTemplate::delete_by_id(id)
+        .exec(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Create a ticket (and its sub-tasks) from a template
+pub async fn instantiate_template(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<InstantiateTemplateRequest>,
+) -> ApiResult<Json<InstantiatedTemplateResponse>> {
+    let template = find_template(&state, &auth_user, id).await?;
+    let (project, _) =
+        access::project_for_member(state.db.as_ref(), &auth_user, req.project_id).await?;
+
+    let in_scope = match template.project_id {
+        Some(project_id) => project_id == project.id,
+        None => template.workspace_id == project.workspace_id,
+    };
+    if !in_scope {
+        return Err(ApiError::InvalidInput(format!(
+            "Template '{}' is not available in this project",
+            template.name
+        )));
+    }
+
+    let variables: HashMap<String, String> = req
+        .variables
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            (name, value)
+        })
+        .collect();
+
+    let title = render(&template.title_template, &variables)?;
+    let description = render(&template.description_template, &variables)?;
+
+    let actor = auth_user.actor();
+    let txn = state.db.begin().await.map_err(ApiError::from)?;
+
+    let (parent, assignees, labels) = insert_ticket(
+        &txn,
+        CreateTicketRequest {
+            project_id: project.id,
+            title,
+            description: Some(description),
+            story_points: template.default_story_points,
+            status: template.default_status.clone(),
+            assignees: req.assignees,
+            labels: Some(template.default_labels()),
+            parent_id: req.parent_id,
+            epic_id: req.epic_id,
+            is_epic: false,
+            epic_color: None,
+        },
+        &actor,
+    )
+    .await?;
+
+    let mut children = Vec::new();
+    for subtask in template.subtasks() {
+        let child = insert_ticket(
+            &txn,
+            CreateTicketRequest {
+                project_id: project.id,
+                title: render(&subtask.title, &variables)?,
+                description: Some(render(&subtask.description, &variables)?),
+                story_points: subtask.story_points,
+                status: template.default_status.clone(),
+                assignees: None,
+                labels: Some(subtask.labels),
+                parent_id: Some(parent.id),
+                epic_id: req.epic_id,
+                is_epic: false,
+                epic_color: None,
+            },
+            &actor,
+        )
+        .await?;
+        children.push(child);
+    }
+
+    txn.commit().await.map_err(ApiError::from)?;
+
+    notifications::ticket_created(&state, &parent, auth_user.id, &assignees).await;
+    for (child, _, _) in &children {
+        notifications::ticket_created(&state, child, auth_user.id, &[]).await;
+    }
+
+    let ticket = ticket_response(state.db.as_ref(), parent, assignees, labels).await?;
+    let mut subtasks = Vec::with_capacity(children.len());
+    for (child, assignees, labels) in children {
+        subtasks.push(ticket_response(state.db.as_ref(), child, assignees, labels).await?);
+    }
+
+    // Broadcast WebSocket updates
+    for created in std::iter::once(&ticket).chain(&subtasks) {
+        let ws_message = crate::models::ServerMessage::TicketCreated {
+            ticket: created.clone(),
+        };
+        let ticket_id = Uuid::parse_str(&created.id).unwrap();
+        publish_ticket_event(&state, ticket_id, &ws_message).await;
+    }
+
+    Ok(Json(InstantiatedTemplateResponse { ticket, subtasks }))
+}
@@ -1,28 +1,41 @@
-use axum::{extract::{Path, Query, State}, Json};
+use axum::{extract::{Path, Query, State}, Extension, Json};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sea_orm::sea_query::{Expr, Func, Order, Query as SeaQuery, SimpleExpr};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseTransaction, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Select, Set, TransactionTrait,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 use chrono::Utc;
 
 use crate::{
+    api::comments,
+    attachments::ticket_attachments,
+    auth::{access, AuthUser},
     error::{ApiError, ApiResult},
     models::{
         CreateTicketRequest, UpdateTicketRequest, UpdateDescriptionRequest, UpdateStatusRequest,
         AssignTicketRequest, UnassignTicketRequest, TicketResponse, TicketDetailResponse,
-        CommentResponse, TicketReference, CommitLinkResponse, ChangeEventResponse,
+        TicketReference, CommitLinkResponse, ChangeEventResponse,
+        TicketFilter, TicketListResponse,
     },
+    notifications,
     state::AppState,
+    websocket::publish_ticket_event,
 };
 use jility_core::entities::{
-    ticket, ticket_assignee, ticket_label, ticket_change, comment, commit_link, ticket_dependency, project, user,
-    Ticket, TicketAssignee, TicketLabel, TicketChange, Comment, CommitLink,
-    TicketDependency, TicketStatus, ChangeType, Project, User,
+    ticket, ticket_alias, ticket_assignee, ticket_label, ticket_change, commit_link, ticket_dependency,
+    project, user,
+    sprint_ticket, Ticket, TicketAssignee, TicketLabel, TicketChange, CommitLink,
+    TicketDependency, ChangeType, Project, SprintTicket, TicketAlias, User,
 };
+use jility_core::policy::ProjectPolicy;
+use jility_core::workflow;
 
 /// Helper function to format ticket number with project key
-async fn format_ticket_number(
+pub async fn format_ticket_number(
     db: &sea_orm::DatabaseConnection,
     ticket: &ticket::Model,
 ) -> ApiResult<String> {
@@ -36,145 +49,443 @@ async fn format_ticket_number(
     Ok(format!("{}-{}", prefix, ticket.ticket_number))
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ListTicketsQuery {
-    pub project_id: Option<String>,
-    #[serde(default)]
-    #[serde(deserialize_with = "deserialize_string_or_vec")]
-    pub status: Vec<String>,
-    pub assignee: Option<String>,
+/// Resolve a ticket identifier (UUID or PROJECT-NUMBER such as "JIL-42") to its UUID.
+/// A number no ticket has is looked up among the aliases of imported tickets.
+pub async fn resolve_ticket_id(
+    db: &sea_orm::DatabaseConnection,
+    id: &str,
+) -> ApiResult<Uuid> {
+    if let Ok(ticket_id) = Uuid::parse_str(id) {
+        return Ok(ticket_id);
+    }
+
+    let parts: Vec<&str> = id.split('-').collect();
+    if parts.len() != 2 {
+        return Err(ApiError::InvalidInput(
+            format!("Invalid ticket ID format: {}. Use UUID or PROJECT-NUMBER (e.g., JIL-42)", id)
+        ));
+    }
+
+    let project_key = parts[0];
+    let ticket_number: i32 = parts[1]
+        .parse()
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket number: {}", parts[1])))?;
+
+    let project = Project::find()
+        .filter(project::Column::Key.eq(project_key))
+        .one(db)
+        .await
+        .map_err(ApiError::from)?;
+
+    let ticket = match project {
+        Some(project) => Ticket::find()
+            .filter(ticket::Column::ProjectId.eq(project.id))
+            .filter(ticket::Column::TicketNumber.eq(ticket_number))
+            .filter(ticket::Column::DeletedAt.is_null())
+            .one(db)
+            .await
+            .map_err(ApiError::from)?,
+        None => None,
+    };
+    if let Some(ticket) = ticket {
+        return Ok(ticket.id);
+    }
+
+    resolve_alias(db, id).await?.ok_or_else(|| {
+        ApiError::NotFound(format!("Ticket not found: {}-{}", project_key, ticket_number))
+    })
 }
 
-// Helper to deserialize either a single string or a vec of strings
-fn deserialize_string_or_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    use serde::de::{self, Deserialize};
+/// The ticket with alias `alias`, if exactly one ticket has it
+async fn resolve_alias(db: &sea_orm::DatabaseConnection, alias: &str) -> ApiResult<Option<Uuid>> {
+    let ticket_ids: Vec<Uuid> = TicketAlias::find()
+        .filter(ticket_alias::Column::Alias.eq(alias))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| a.ticket_id)
+        .collect();
+    if ticket_ids.is_empty() {
+        return Ok(None);
+    }
 
-    struct StringOrVec;
+    let tickets = Ticket::find()
+        .filter(ticket::Column::Id.is_in(ticket_ids))
+        .filter(ticket::Column::DeletedAt.is_null())
+        .all(db)
+        .await?;
+    match tickets.as_slice() {
+        [] => Ok(None),
+        [ticket] => Ok(Some(ticket.id)),
+        _ => Err(ApiError::InvalidInput(format!(
+            "{} is an alias of several tickets; use the ticket's UUID",
+            alias
+        ))),
+    }
+}
 
-    impl<'de> de::Visitor<'de> for StringOrVec {
-        type Value = Vec<String>;
+/// Most tickets a single page of `list_tickets` returns
+const MAX_PAGE_SIZE: u64 = 500;
+const DEFAULT_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketSort {
+    Created,
+    Updated,
+    #[default]
+    Number,
+    Points,
+    /// Board order of the project's workflow
+    Status,
+}
 
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("string or list of strings")
-        }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
 
-        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(vec![value.to_string()])
-        }
+/// Query string of `GET /api/tickets`. `status` and `label` may be repeated
+/// (`status=todo&status=review`) or comma-separated (`status=todo,review`).
+#[derive(Debug, Default)]
+pub struct ListTicketsQuery {
+    pub filter: TicketFilter,
+    pub sort: TicketSort,
+    pub order: SortOrder,
+    pub limit: u64,
+    pub cursor: Option<String>,
+}
 
-        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-        where
-            A: de::SeqAccess<'de>,
-        {
-            let mut vec = Vec::new();
-            while let Some(value) = seq.next_element()? {
-                vec.push(value);
+impl ListTicketsQuery {
+    fn from_pairs(pairs: Vec<(String, String)>) -> ApiResult<Self> {
+        let mut query = Self {
+            limit: DEFAULT_PAGE_SIZE,
+            ..Self::default()
+        };
+        let split = |value: &str| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let filter = &mut query.filter;
+        for (key, value) in pairs {
+            match key.trim_end_matches("[]") {
+                "project_id" => filter.project_id = Some(value),
+                "status" => filter.status.extend(split(&value)),
+                "assignee" => filter.assignee = Some(value),
+                "unassigned" => filter.unassigned = value == "true" || value == "1",
+                "label" | "labels" => filter.labels.extend(split(&value)),
+                "epic_id" => filter.epic_id = Some(value),
+                "sprint_id" => filter.sprint_id = Some(value),
+                "parent_id" => filter.parent_id = Some(value),
+                "sort" => query.sort = parse_choice("sort", &value)?,
+                "order" => query.order = parse_choice("order", &value)?,
+                "limit" => {
+                    query.limit = value
+                        .parse()
+                        .map_err(|_| ApiError::InvalidInput(format!("Invalid limit: {}", value)))?
+                }
+                "cursor" => query.cursor = Some(value),
+                _ => {}
             }
-            Ok(vec)
         }
+        query.limit = query.limit.clamp(1, MAX_PAGE_SIZE);
+
+        Ok(query)
     }
+}
 
-    deserializer.deserialize_any(StringOrVec)
+/// Non-deleted tickets matching `filter` in projects the user can see, and
+/// the project the filter is limited to
+pub async fn filter_tickets(
+    db: &sea_orm::DatabaseConnection,
+    auth_user: &AuthUser,
+    filter: &TicketFilter,
+) -> ApiResult<(Select<Ticket>, Option<Uuid>)> {
+    let mut query_builder = Ticket::find().filter(ticket::Column::DeletedAt.is_null());
+
+    let project_id = match &filter.project_id {
+        Some(project_id) => {
+            let uuid = Uuid::parse_str(project_id)
+                .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", project_id)))?;
+            access::require_project(db, auth_user, uuid).await?;
+            query_builder = query_builder.filter(ticket::Column::ProjectId.eq(uuid));
+            Some(uuid)
+        }
+        None => {
+            let project_ids = access::visible_project_ids(db, auth_user).await?;
+            query_builder = query_builder.filter(ticket::Column::ProjectId.is_in(project_ids));
+            None
+        }
+    };
+
+    if !filter.status.is_empty() {
+        query_builder = query_builder.filter(ticket::Column::Status.is_in(filter.status.clone()));
+    }
+    if let Some(assignee) = &filter.assignee {
+        query_builder = query_builder.filter(
+            ticket::Column::Id.in_subquery(
+                SeaQuery::select()
+                    .column(ticket_assignee::Column::TicketId)
+                    .from(TicketAssignee)
+                    .and_where(ticket_assignee::Column::Assignee.eq(assignee.as_str()))
+                    .to_owned(),
+            ),
+        );
+    }
+    if filter.unassigned {
+        query_builder = query_builder.filter(
+            ticket::Column::Id.not_in_subquery(
+                SeaQuery::select()
+                    .column(ticket_assignee::Column::TicketId)
+                    .from(TicketAssignee)
+                    .to_owned(),
+            ),
+        );
+    }
+    for label in &filter.labels {
+        query_builder = query_builder.filter(
+            ticket::Column::Id.in_subquery(
+                SeaQuery::select()
+                    .column(ticket_label::Column::TicketId)
+                    .from(TicketLabel)
+                    .and_where(ticket_label::Column::Label.eq(label.as_str()))
+                    .to_owned(),
+            ),
+        );
+    }
+    if let Some(epic_id) = &filter.epic_id {
+        let epic_id = resolve_ticket_id(db, epic_id).await?;
+        query_builder = query_builder.filter(ticket::Column::EpicId.eq(epic_id));
+    }
+    if let Some(parent_id) = &filter.parent_id {
+        let parent_id = resolve_ticket_id(db, parent_id).await?;
+        query_builder = query_builder.filter(ticket::Column::ParentId.eq(parent_id));
+    }
+    if let Some(sprint_id) = &filter.sprint_id {
+        let sprint_id = Uuid::parse_str(sprint_id)
+            .map_err(|_| ApiError::InvalidInput(format!("Invalid sprint ID: {}", sprint_id)))?;
+        query_builder = query_builder.filter(
+            ticket::Column::Id.in_subquery(
+                SeaQuery::select()
+                    .column(sprint_ticket::Column::TicketId)
+                    .from(SprintTicket)
+                    .and_where(sprint_ticket::Column::SprintId.eq(sprint_id))
+                    .to_owned(),
+            ),
+        );
+    }
+
+    Ok((query_builder, project_id))
 }
 
-pub async fn list_tickets(
-    State(state): State<AppState>,
-    Query(query): Query<ListTicketsQuery>,
-) -> ApiResult<Json<Vec<TicketResponse>>> {
-    let mut query_builder = Ticket::find();
+/// A snake_case enum value given as a query parameter
+fn parse_choice<T: serde::de::DeserializeOwned>(name: &str, value: &str) -> ApiResult<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid {}: {}", name, value)))
+}
 
-    // Filter out soft-deleted tickets
-    query_builder = query_builder.filter(ticket::Column::DeletedAt.is_null());
+/// Position of the last ticket on a page. Encoded as URL-safe base64 JSON so
+/// clients can treat it as opaque.
+#[derive(Debug, Serialize, Deserialize)]
+struct ListCursor {
+    sort: TicketSort,
+    order: SortOrder,
+    key: CursorKey,
+    id: Uuid,
+}
 
-    if let Some(project_id) = query.project_id {
-        let uuid = Uuid::parse_str(&project_id)
-            .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", project_id)))?;
-        query_builder = query_builder.filter(ticket::Column::ProjectId.eq(uuid));
+/// Value of the sort column at the cursor
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CursorKey {
+    Int(i64),
+    Time(chrono::DateTime<Utc>),
+}
+
+impl CursorKey {
+    fn into_value(self) -> sea_orm::Value {
+        match self {
+            CursorKey::Int(v) => v.into(),
+            CursorKey::Time(v) => v.into(),
+        }
     }
+}
 
-    if !query.status.is_empty() {
-        query_builder = query_builder.filter(ticket::Column::Status.is_in(query.status));
+impl ListCursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
     }
 
-    let tickets = query_builder
-        .all(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?;
+    fn decode(cursor: &str) -> ApiResult<Self> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| ApiError::InvalidInput(format!("Invalid cursor: {}", cursor)))
+    }
+}
 
-    let mut responses = Vec::new();
+/// Statuses in the order `sort=status` uses
+async fn status_order(
+    db: &sea_orm::DatabaseConnection,
+    project_id: Option<Uuid>,
+) -> ApiResult<Vec<String>> {
+    let workflow = match project_id {
+        Some(project_id) => workflow::load(db, project_id).await?,
+        None => workflow::Workflow::default(),
+    };
+    Ok(workflow.statuses.into_iter().map(|s| s.key).collect())
+}
 
-    for ticket in tickets {
-        // Get assignees
-        let assignees = TicketAssignee::find()
-            .filter(ticket_assignee::Column::TicketId.eq(ticket.id))
-            .all(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-            .into_iter()
-            .map(|a| a.assignee)
-            .collect();
+/// The expression `sort` orders by
+fn sort_expr(sort: TicketSort, statuses: &[String]) -> SimpleExpr {
+    let col = |c: ticket::Column| Expr::col((ticket::Entity, c));
+    match sort {
+        TicketSort::Created => col(ticket::Column::CreatedAt).into(),
+        TicketSort::Updated => col(ticket::Column::UpdatedAt).into(),
+        TicketSort::Number => col(ticket::Column::TicketNumber).into(),
+        // Unestimated tickets sort as -1 points
+        TicketSort::Points => {
+            Func::coalesce([col(ticket::Column::StoryPoints).into(), Expr::val(-1).into()]).into()
+        }
+        // Statuses outside the workflow sort last
+        TicketSort::Status => statuses
+            .iter()
+            .enumerate()
+            .fold(
+                Expr::case(col(ticket::Column::Status).eq(""), statuses.len() as i64),
+                |case, (i, status)| {
+                    case.case(col(ticket::Column::Status).eq(status.as_str()), i as i64)
+                },
+            )
+            .finally(statuses.len() as i64)
+            .into(),
+    }
+}
 
-        // Get labels
-        let labels = TicketLabel::find()
-            .filter(ticket_label::Column::TicketId.eq(ticket.id))
-            .all(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-            .into_iter()
-            .map(|l| l.label)
-            .collect();
+/// Value of [`sort_expr`] for `ticket`
+fn cursor_key(sort: TicketSort, statuses: &[String], ticket: &ticket::Model) -> CursorKey {
+    match sort {
+        TicketSort::Created => CursorKey::Time(ticket.created_at),
+        TicketSort::Updated => CursorKey::Time(ticket.updated_at),
+        TicketSort::Number => CursorKey::Int(ticket.ticket_number.into()),
+        TicketSort::Points => CursorKey::Int(ticket.story_points.unwrap_or(-1).into()),
+        TicketSort::Status => CursorKey::Int(
+            statuses
+                .iter()
+                .position(|s| *s == ticket.status)
+                .unwrap_or(statuses.len()) as i64,
+        ),
+    }
+}
 
-        let number = format_ticket_number(state.db.as_ref(), &ticket).await?;
-
-        responses.push(TicketResponse {
-            id: ticket.id.to_string(),
-            number,
-            title: ticket.title.clone(),
-            description: ticket.description.clone(),
-            status: ticket.status.clone(),
-            story_points: ticket.story_points,
-            assignees,
-            labels,
-            created_at: ticket.created_at.to_rfc3339(),
-            updated_at: ticket.updated_at.to_rfc3339(),
-            created_by: ticket.created_by.clone(),
-            parent_id: ticket.parent_id.map(|id| id.to_string()),
-            epic_id: ticket.epic_id.map(|id| id.to_string()),
-            is_epic: ticket.is_epic,
-            epic_color: ticket.epic_color.clone(),
-        });
+/// A page of tickets matching the query, ordered by `sort` and then by ID
+pub async fn list_tickets(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> ApiResult<Json<TicketListResponse>> {
+    let query = ListTicketsQuery::from_pairs(pairs)?;
+    let db = state.db.as_ref();
+    let (mut query_builder, project_id) = filter_tickets(db, &auth_user, &query.filter).await?;
+
+    let statuses = match query.sort {
+        TicketSort::Status => status_order(db, project_id).await?,
+        _ => Vec::new(),
+    };
+    let sort_expr = sort_expr(query.sort, &statuses);
+    let id_col = Expr::col((ticket::Entity, ticket::Column::Id));
+
+    if let Some(cursor) = &query.cursor {
+        let cursor = ListCursor::decode(cursor)?;
+        if cursor.sort != query.sort || cursor.order != query.order {
+            return Err(ApiError::InvalidInput(
+                "Cursor was issued for a different sort; start again without it".to_string(),
+            ));
+        }
+        let key = cursor.key.into_value();
+        let after = match query.order {
+            SortOrder::Asc => Condition::any()
+                .add(Expr::expr(sort_expr.clone()).gt(key.clone()))
+                .add(
+                    Condition::all()
+                        .add(Expr::expr(sort_expr.clone()).eq(key))
+                        .add(id_col.clone().gt(cursor.id)),
+                ),
+            SortOrder::Desc => Condition::any()
+                .add(Expr::expr(sort_expr.clone()).lt(key.clone()))
+                .add(
+                    Condition::all()
+                        .add(Expr::expr(sort_expr.clone()).eq(key))
+                        .add(id_col.clone().lt(cursor.id)),
+                ),
+        };
+        query_builder = query_builder.filter(after);
     }
 
-    Ok(Json(responses))
+    let order = match query.order {
+        SortOrder::Asc => Order::Asc,
+        SortOrder::Desc => Order::Desc,
+    };
+    // One extra row tells whether there is another page
+    let mut tickets = query_builder
+        .order_by(sort_expr, order.clone())
+        .order_by(ticket::Column::Id, order)
+        .limit(query.limit + 1)
+        .all(db)
+        .await
+        .map_err(ApiError::from)?;
+
+    let next_cursor = if tickets.len() as u64 > query.limit {
+        tickets.truncate(query.limit as usize);
+        tickets.last().map(|last| {
+            ListCursor {
+                sort: query.sort,
+                order: query.order,
+                key: cursor_key(query.sort, &statuses, last),
+                id: last.id,
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+
+    Ok(Json(TicketListResponse {
+        tickets: ticket_responses(db, tickets).await?,
+        next_cursor,
+    }))
 }
 
-pub async fn create_ticket(
-    State(state): State<AppState>,
-    Json(payload): Json<CreateTicketRequest>,
-) -> ApiResult<Json<TicketResponse>> {
+/// Insert a ticket with its assignees, labels and change records.
+///
+/// Runs inside the caller's transaction so several tickets can be created
+/// atomically. Returns the ticket with its assignees and labels.
+pub async fn insert_ticket(
+    txn: &DatabaseTransaction,
+    payload: CreateTicketRequest,
+    actor: &str,
+) -> ApiResult<(ticket::Model, Vec<String>, Vec<String>)> {
     let now = Utc::now();
     let ticket_id = Uuid::new_v4();
 
     // Use project_id from the request
     let project_id = payload.project_id;
 
-    // Get next ticket number for this project
-    let max_number: Option<i32> = Ticket::find()
-        .filter(ticket::Column::ProjectId.eq(project_id))
-        .all(state.db.as_ref())
+    // Reserve the number first: see `project::next_ticket_number`
+    let ticket_number = project::next_ticket_number(txn, project_id)
         .await
-        .map_err(ApiError::from)?
-        .iter()
-        .map(|t| t.ticket_number)
-        .max();
-
-    let ticket_number = max_number.unwrap_or(0) + 1;
+        .map_err(|e| match e {
+            sea_orm::DbErr::RecordNotFound(msg) => ApiError::NotFound(msg),
+            e => ApiError::from(e),
+        })?;
 
     // Validation: Prevent nesting epics (epics cannot have parent epics)
     if payload.is_epic && payload.epic_id.is_some() {
@@ -186,7 +497,7 @@ pub async fn create_ticket(
     // Validation: If epic_id is provided, verify it references an actual epic
     if let Some(parent_epic_id) = payload.epic_id {
         let parent = Ticket::find_by_id(parent_epic_id)
-            .one(state.db.as_ref())
+            .one(txn)
             .await
             .map_err(ApiError::from)?
             .ok_or_else(|| ApiError::NotFound(format!("Parent epic not found: {}", parent_epic_id)))?;
@@ -198,8 +509,20 @@ pub async fn create_ticket(
         }
     }
 
-    // Start transaction
-    let txn = state.db.begin().await.map_err(ApiError::from)?;
+    let workflow = workflow::load(txn, project_id).await?;
+    let status = match &payload.status {
+        Some(status) => {
+            workflow.check_status(status)?;
+            status.clone()
+        }
+        None => workflow.initial_status.clone(),
+    };
+    ProjectPolicy::load(txn, project_id).await?.check_status_change(
+        &workflow,
+        None,
+        &status,
+        payload.story_points,
+    )?;
 
     // Create ticket
     let ticket = ticket::ActiveModel {
@@ -208,7 +531,7 @@ pub async fn create_ticket(
         ticket_number: Set(ticket_number),
         title: Set(payload.title.clone()),
         description: Set(payload.description.clone().unwrap_or_default()),
-        status: Set(payload.status.clone().unwrap_or_else(|| "backlog".to_string())),
+        status: Set(status),
         story_points: Set(payload.story_points),
         epic_id: Set(payload.epic_id),
         parent_id: Set(payload.parent_id),
@@ -218,10 +541,10 @@ pub async fn create_ticket(
         created_at: Set(now),
         updated_at: Set(now),
         deleted_at: Set(None), // Not deleted
-        created_by: Set("system".to_string()), // TODO: Get from auth context
+        created_by: Set(actor.to_string()),
     };
 
-    let result = ticket.insert(&txn).await.map_err(ApiError::from)?;
+    let result = ticket.insert(txn).await.map_err(ApiError::from)?;
 
     // Record creation in ticket_changes
     let change = ticket_change::ActiveModel {
@@ -231,11 +554,11 @@ pub async fn create_ticket(
         field_name: Set(None),
         old_value: Set(None),
         new_value: Set(Some(serde_json::to_string(&result).unwrap())),
-        changed_by: Set("system".to_string()),
+        changed_by: Set(actor.to_string()),
         changed_at: Set(now),
         message: Set(None),
     };
-    change.insert(&txn).await.map_err(ApiError::from)?;
+    change.insert(txn).await.map_err(ApiError::from)?;
 
     // Add assignees if provided
     let mut assignees = Vec::new();
@@ -246,9 +569,9 @@ pub async fn create_ticket(
                 ticket_id: Set(ticket_id),
                 assignee: Set(assignee.clone()),
                 assigned_at: Set(now),
-                assigned_by: Set("system".to_string()),
+                assigned_by: Set(actor.to_string()),
             };
-            assignee_model.insert(&txn).await.map_err(ApiError::from)?;
+            assignee_model.insert(txn).await.map_err(ApiError::from)?;
 
             // Record change
             let change = ticket_change::ActiveModel {
@@ -258,11 +581,11 @@ pub async fn create_ticket(
                 field_name: Set(Some("assignee".to_string())),
                 old_value: Set(None),
                 new_value: Set(Some(assignee.clone())),
-                changed_by: Set("system".to_string()),
+                changed_by: Set(actor.to_string()),
                 changed_at: Set(now),
                 message: Set(None),
             };
-            change.insert(&txn).await.map_err(ApiError::from)?;
+            change.insert(txn).await.map_err(ApiError::from)?;
 
             assignees.push(assignee);
         }
@@ -278,7 +601,7 @@ pub async fn create_ticket(
                 label: Set(label.clone()),
                 created_at: Set(now),
             };
-            label_model.insert(&txn).await.map_err(ApiError::from)?;
+            label_model.insert(txn).await.map_err(ApiError::from)?;
 
             // Record change
             let change = ticket_change::ActiveModel {
@@ -288,21 +611,89 @@ pub async fn create_ticket(
                 field_name: Set(Some("label".to_string())),
                 old_value: Set(None),
                 new_value: Set(Some(label.clone())),
-                changed_by: Set("system".to_string()),
+                changed_by: Set(actor.to_string()),
                 changed_at: Set(now),
                 message: Set(None),
             };
-            change.insert(&txn).await.map_err(ApiError::from)?;
+            change.insert(txn).await.map_err(ApiError::from)?;
 
             labels.push(label);
         }
     }
 
-    txn.commit().await.map_err(ApiError::from)?;
+    Ok((result, assignees, labels))
+}
 
-    let number = format_ticket_number(state.db.as_ref(), &result).await?;
+/// Build the API representation of a ticket
+pub async fn ticket_response(
+    db: &sea_orm::DatabaseConnection,
+    result: ticket::Model,
+    assignees: Vec<String>,
+    labels: Vec<String>,
+) -> ApiResult<TicketResponse> {
+    let number = format_ticket_number(db, &result).await?;
+    Ok(to_response(result, number, assignees, labels))
+}
 
-    let response = TicketResponse {
+/// Build the API representation of several tickets, loading their assignees,
+/// labels and project keys with one query each
+pub async fn ticket_responses(
+    db: &sea_orm::DatabaseConnection,
+    tickets: Vec<ticket::Model>,
+) -> ApiResult<Vec<TicketResponse>> {
+    if tickets.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+    let project_ids: HashSet<Uuid> = tickets.iter().map(|t| t.project_id).collect();
+
+    let mut assignees: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for assignee in TicketAssignee::find()
+        .filter(ticket_assignee::Column::TicketId.is_in(ids.clone()))
+        .order_by_asc(ticket_assignee::Column::AssignedAt)
+        .all(db)
+        .await?
+    {
+        assignees.entry(assignee.ticket_id).or_default().push(assignee.assignee);
+    }
+
+    let mut labels: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for label in TicketLabel::find()
+        .filter(ticket_label::Column::TicketId.is_in(ids))
+        .order_by_asc(ticket_label::Column::CreatedAt)
+        .all(db)
+        .await?
+    {
+        labels.entry(label.ticket_id).or_default().push(label.label);
+    }
+
+    let prefixes: HashMap<Uuid, String> = Project::find()
+        .filter(project::Column::Id.is_in(project_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p.key.unwrap_or_else(|| "TASK".to_string())))
+        .collect();
+
+    Ok(tickets
+        .into_iter()
+        .map(|ticket| {
+            let prefix = prefixes.get(&ticket.project_id).map_or("TASK", String::as_str);
+            let number = format!("{}-{}", prefix, ticket.ticket_number);
+            let assignees = assignees.remove(&ticket.id).unwrap_or_default();
+            let labels = labels.remove(&ticket.id).unwrap_or_default();
+            to_response(ticket, number, assignees, labels)
+        })
+        .collect())
+}
+
+fn to_response(
+    result: ticket::Model,
+    number: String,
+    assignees: Vec<String>,
+    labels: Vec<String>,
+) -> TicketResponse {
+    TicketResponse {
         id: result.id.to_string(),
         number,
         title: result.title,
@@ -317,64 +708,49 @@ pub async fn create_ticket(
         parent_id: result.parent_id.map(|id| id.to_string()),
         epic_id: result.epic_id.map(|id| id.to_string()),
         is_epic: result.is_epic,
-        epic_color: result.epic_color.clone(),
-    };
+        epic_color: result.epic_color,
+    }
+}
+
+pub async fn create_ticket(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<CreateTicketRequest>,
+) -> ApiResult<Json<TicketResponse>> {
+    access::require_project(state.db.as_ref(), &auth_user, payload.project_id).await?;
+
+    let txn = state.db.begin().await.map_err(ApiError::from)?;
+    let (result, assignees, labels) = insert_ticket(&txn, payload, &auth_user.actor()).await?;
+    txn.commit().await.map_err(ApiError::from)?;
+
+    notifications::ticket_created(&state, &result, auth_user.id, &assignees).await;
+
+    let ticket_id = result.id;
+    let response = ticket_response(state.db.as_ref(), result, assignees, labels).await?;
 
     // Broadcast WebSocket update
-    let ws_message = serde_json::to_string(&crate::models::ServerMessage::TicketCreated {
+    let ws_message = crate::models::ServerMessage::TicketCreated {
         ticket: response.clone(),
-    })
-    .unwrap();
-    state.ws_state.broadcast(ws_message).await;
+    };
+    publish_ticket_event(&state, ticket_id, &ws_message).await;
 
     Ok(Json(response))
 }
 
 pub async fn get_ticket(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<TicketDetailResponse>> {
-    // Try to parse as UUID first
-    let ticket = if let Ok(ticket_id) = Uuid::parse_str(&id) {
-        // Lookup by UUID
-        Ticket::find_by_id(ticket_id)
-            .filter(ticket::Column::DeletedAt.is_null())
-            .one(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-            .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?
-    } else {
-        // Try to parse as ticket number (e.g., "VOX-2" or "JIL-42")
-        let parts: Vec<&str> = id.split('-').collect();
-        if parts.len() != 2 {
-            return Err(ApiError::InvalidInput(format!(
-                "Invalid ticket identifier. Expected UUID or ticket number (e.g., VOX-2), got: {}",
-                id
-            )));
-        }
-
-        let project_key = parts[0];
-        let ticket_number: i32 = parts[1].parse()
-            .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket number: {}", id)))?;
-
-        // Find project by key
-        let project = Project::find()
-            .filter(project::Column::Key.eq(project_key))
-            .one(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-            .ok_or_else(|| ApiError::NotFound(format!("Project not found with key: {}", project_key)))?;
+    let ticket_id = resolve_ticket_id(state.db.as_ref(), &id).await?;
+    let ticket = Ticket::find_by_id(ticket_id)
+        .filter(ticket::Column::DeletedAt.is_null())
+        .one(state.db.as_ref())
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
-        // Find ticket by project_id and ticket_number
-        Ticket::find()
-            .filter(ticket::Column::ProjectId.eq(project.id))
-            .filter(ticket::Column::TicketNumber.eq(ticket_number))
-            .filter(ticket::Column::DeletedAt.is_null())
-            .one(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-            .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?
-    };
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
 
     let ticket_id = ticket.id;
 
@@ -399,20 +775,14 @@ pub async fn get_ticket(
         .collect();
 
     // Get comments
-    let comments = Comment::find()
-        .filter(comment::Column::TicketId.eq(ticket_id))
+    let comments = comments::ticket_comments(state.db.as_ref(), ticket_id).await?;
+    let attachments = ticket_attachments(state.db.as_ref(), ticket_id).await?;
+    let aliases = TicketAlias::find()
+        .filter(ticket_alias::Column::TicketId.eq(ticket_id))
         .all(state.db.as_ref())
-        .await
-        .map_err(ApiError::from)?
+        .await?
         .into_iter()
-        .map(|c| CommentResponse {
-            id: c.id.to_string(),
-            ticket_id: c.ticket_id.to_string(),
-            author: c.author,
-            content: c.content,
-            created_at: c.created_at.to_rfc3339(),
-            updated_at: c.updated_at.map(|dt| dt.to_rfc3339()),
-        })
+        .map(|a| a.alias)
         .collect();
 
     // Get dependencies
@@ -554,7 +924,9 @@ pub async fn get_ticket(
 
     Ok(Json(TicketDetailResponse {
         ticket: ticket_response,
+        aliases,
         comments,
+        attachments,
         dependencies: dependency_refs,
         dependents: dependent_refs,
         linked_commits: commits,
@@ -564,6 +936,7 @@ pub async fn get_ticket(
 
 pub async fn update_ticket(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateTicketRequest>,
 ) -> ApiResult<Json<TicketResponse>> {
@@ -577,29 +950,120 @@ pub async fn update_ticket(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+
+    let old_ticket = ticket.clone();
     let mut ticket: ticket::ActiveModel = ticket.into();
     let now = Utc::now();
 
+    // Collect field changes so the ticket history can be replayed later
+    let mut field_changes: Vec<(ChangeType, &str, Option<String>, Option<String>)> = Vec::new();
+
     if let Some(title) = payload.title {
+        if title != old_ticket.title {
+            field_changes.push((
+                ChangeType::TitleChanged,
+                "title",
+                Some(old_ticket.title.clone()),
+                Some(title.clone()),
+            ));
+        }
         ticket.title = Set(title);
     }
+    if let Some(status) = payload.status {
+        if status != old_ticket.status {
+            let workflow = workflow::load(state.db.as_ref(), old_ticket.project_id).await?;
+            workflow.check_transition(&old_ticket.status, &status)?;
+            // Points set in the same request count towards the policy
+            ProjectPolicy::load(state.db.as_ref(), old_ticket.project_id)
+                .await?
+                .check_status_change(
+                    &workflow,
+                    Some(&old_ticket.status),
+                    &status,
+                    payload.story_points.or(old_ticket.story_points),
+                )?;
+            field_changes.push((
+                ChangeType::StatusChanged,
+                "status",
+                Some(old_ticket.status.clone()),
+                Some(status.clone()),
+            ));
+        }
+        ticket.status = Set(status);
+    }
     if let Some(story_points) = payload.story_points {
+        if Some(story_points) != old_ticket.story_points {
+            field_changes.push((
+                ChangeType::StoryPointsChanged,
+                "story_points",
+                old_ticket.story_points.map(|p| p.to_string()),
+                Some(story_points.to_string()),
+            ));
+        }
         ticket.story_points = Set(Some(story_points));
     }
     if let Some(parent_id) = payload.parent_id {
+        if Some(parent_id) != old_ticket.parent_id {
+            field_changes.push((
+                ChangeType::ParentChanged,
+                "parent_id",
+                old_ticket.parent_id.map(|id| id.to_string()),
+                Some(parent_id.to_string()),
+            ));
+        }
         ticket.parent_id = Set(Some(parent_id));
     }
     if let Some(epic_id) = payload.epic_id {
+        if Some(epic_id) != old_ticket.epic_id {
+            field_changes.push((
+                ChangeType::EpicChanged,
+                "epic_id",
+                old_ticket.epic_id.map(|id| id.to_string()),
+                Some(epic_id.to_string()),
+            ));
+        }
         ticket.epic_id = Set(Some(epic_id));
     }
 
     ticket.updated_at = Set(now);
 
+    let txn = state.db.begin().await.map_err(ApiError::from)?;
+
     let result = ticket
-        .update(state.db.as_ref())
+        .update(&txn)
         .await
         .map_err(ApiError::from)?;
 
+    for (change_type, field_name, old_value, new_value) in field_changes {
+        let change = ticket_change::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            ticket_id: Set(ticket_id),
+            change_type: Set(change_type.as_str().to_string()),
+            field_name: Set(Some(field_name.to_string())),
+            old_value: Set(old_value),
+            new_value: Set(new_value),
+            changed_by: Set(auth_user.actor()),
+            changed_at: Set(now),
+            message: Set(None),
+        };
+        change.insert(&txn).await.map_err(ApiError::from)?;
+    }
+
+    txn.commit().await.map_err(ApiError::from)?;
+
+    if result.status != old_ticket.status {
+        notifications::status_changed(
+            &state,
+            ticket_id,
+            &auth_user.actor(),
+            Some(auth_user.id),
+            &old_ticket.status,
+            &result.status,
+        )
+        .await;
+    }
+
     // Get assignees and labels
     let assignees = TicketAssignee::find()
         .filter(ticket_assignee::Column::TicketId.eq(ticket_id))
@@ -640,17 +1104,17 @@ pub async fn update_ticket(
     };
 
     // Broadcast update
-    let ws_message = serde_json::to_string(&crate::models::ServerMessage::TicketUpdated {
+    let ws_message = crate::models::ServerMessage::TicketUpdated {
         ticket: response.clone(),
-    })
-    .unwrap();
-    state.ws_state.broadcast(ws_message).await;
+    };
+    publish_ticket_event(&state, result.id, &ws_message).await;
 
     Ok(Json(response))
 }
 
 pub async fn update_description(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateDescriptionRequest>,
 ) -> ApiResult<Json<TicketResponse>> {
@@ -701,6 +1165,8 @@ pub async fn update_description(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+
     let old_description = ticket.description.clone();
     let mut ticket: ticket::ActiveModel = ticket.into();
     let now = Utc::now();
@@ -721,7 +1187,7 @@ pub async fn update_description(
         field_name: Set(Some("description".to_string())),
         old_value: Set(Some(old_description)),
         new_value: Set(Some(payload.description)),
-        changed_by: Set("system".to_string()),
+        changed_by: Set(auth_user.actor()),
         changed_at: Set(now),
         message: Set(None),
     };
@@ -772,6 +1238,7 @@ pub async fn update_description(
 
 pub async fn update_status(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateStatusRequest>,
 ) -> ApiResult<Json<TicketResponse>> {
@@ -815,10 +1282,6 @@ pub async fn update_status(
         ticket.id
     };
 
-    // Validate status
-    TicketStatus::from_str(&payload.status)
-        .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
-
     let ticket = Ticket::find_by_id(ticket_uuid)
         .filter(ticket::Column::DeletedAt.is_null())
         .one(state.db.as_ref())
@@ -826,6 +1289,19 @@ pub async fn update_status(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+
+    let workflow = workflow::load(state.db.as_ref(), ticket.project_id).await?;
+    workflow.check_transition(&ticket.status, &payload.status)?;
+    ProjectPolicy::load(state.db.as_ref(), ticket.project_id)
+        .await?
+        .check_status_change(
+            &workflow,
+            Some(&ticket.status),
+            &payload.status,
+            ticket.story_points,
+        )?;
+
     let old_status = ticket.status.clone();
     let mut ticket: ticket::ActiveModel = ticket.into();
     let now = Utc::now();
@@ -846,7 +1322,7 @@ pub async fn update_status(
         field_name: Set(Some("status".to_string())),
         old_value: Set(Some(old_status.clone())),
         new_value: Set(Some(payload.status.clone())),
-        changed_by: Set("system".to_string()),
+        changed_by: Set(auth_user.actor()),
         changed_at: Set(now),
         message: Set(None),
     };
@@ -894,20 +1370,30 @@ pub async fn update_status(
         epic_color: result.epic_color.clone(),
     };
 
+    notifications::status_changed(
+        &state,
+        result.id,
+        &auth_user.actor(),
+        Some(auth_user.id),
+        &old_status,
+        &payload.status,
+    )
+    .await;
+
     // Broadcast status change
-    let ws_message = serde_json::to_string(&crate::models::ServerMessage::StatusChanged {
+    let ws_message = crate::models::ServerMessage::StatusChanged {
         ticket_id: result.id.to_string(),
         old_status,
         new_status: payload.status,
-    })
-    .unwrap();
-    state.ws_state.broadcast(ws_message).await;
+    };
+    publish_ticket_event(&state, result.id, &ws_message).await;
 
     Ok(Json(response))
 }
 
 pub async fn assign_ticket(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<AssignTicketRequest>,
 ) -> ApiResult<Json<TicketResponse>> {
@@ -921,6 +1407,8 @@ pub async fn assign_ticket(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+
     let now = Utc::now();
 
     // Add assignee
@@ -929,7 +1417,7 @@ pub async fn assign_ticket(
         ticket_id: Set(ticket_id),
         assignee: Set(payload.assignee.clone()),
         assigned_at: Set(now),
-        assigned_by: Set("system".to_string()),
+        assigned_by: Set(auth_user.actor()),
     };
     assignee
         .insert(state.db.as_ref())
@@ -943,8 +1431,8 @@ pub async fn assign_ticket(
         change_type: Set(ChangeType::AssigneeAdded.as_str().to_string()),
         field_name: Set(Some("assignee".to_string())),
         old_value: Set(None),
-        new_value: Set(Some(payload.assignee)),
-        changed_by: Set("system".to_string()),
+        new_value: Set(Some(payload.assignee.clone())),
+        changed_by: Set(auth_user.actor()),
         changed_at: Set(now),
         message: Set(None),
     };
@@ -953,6 +1441,15 @@ pub async fn assign_ticket(
         .await
         .map_err(ApiError::from)?;
 
+    notifications::assigned(
+        &state,
+        ticket_id,
+        &auth_user.actor(),
+        Some(auth_user.id),
+        std::slice::from_ref(&payload.assignee),
+    )
+    .await;
+
     // Get updated assignees and labels
     let assignees = TicketAssignee::find()
         .filter(ticket_assignee::Column::TicketId.eq(ticket_id))
@@ -995,6 +1492,7 @@ pub async fn assign_ticket(
 
 pub async fn unassign_ticket(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
     Json(payload): Json<UnassignTicketRequest>,
 ) -> ApiResult<Json<TicketResponse>> {
@@ -1008,6 +1506,8 @@ pub async fn unassign_ticket(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+
     // Find and delete assignee
     let assignee = TicketAssignee::find()
         .filter(ticket_assignee::Column::TicketId.eq(ticket_id))
@@ -1032,7 +1532,7 @@ pub async fn unassign_ticket(
         field_name: Set(Some("assignee".to_string())),
         old_value: Set(Some(payload.assignee)),
         new_value: Set(None),
-        changed_by: Set("system".to_string()),
+        changed_by: Set(auth_user.actor()),
         changed_at: Set(now),
         message: Set(None),
     };
@@ -1083,47 +1583,10 @@ pub async fn unassign_ticket(
 
 pub async fn delete_ticket(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    // Accept both UUID and ticket number
-    let ticket_uuid = if let Ok(ticket_id) = Uuid::parse_str(&id) {
-        ticket_id
-    } else {
-        // Parse PROJECT-NUMBER format (e.g., "JIL-42")
-        let parts: Vec<&str> = id.split('-').collect();
-        if parts.len() != 2 {
-            return Err(ApiError::InvalidInput(
-                format!("Invalid ticket ID format: {}. Use UUID or PROJECT-NUMBER (e.g., JIL-42)", id)
-            ));
-        }
-
-        let project_key = parts[0];
-        let ticket_number: i32 = parts[1]
-            .parse()
-            .map_err(|_| ApiError::InvalidInput(format!("Invalid ticket number: {}", parts[1])))?;
-
-        // Find project by key
-        let project = Project::find()
-            .filter(project::Column::Key.eq(project_key))
-            .one(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-            .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", project_key)))?;
-
-        // Find ticket by number
-        let ticket = Ticket::find()
-            .filter(ticket::Column::ProjectId.eq(project.id))
-            .filter(ticket::Column::TicketNumber.eq(ticket_number))
-            .filter(ticket::Column::DeletedAt.is_null())
-            .one(state.db.as_ref())
-            .await
-            .map_err(ApiError::from)?
-            .ok_or_else(|| ApiError::NotFound(
-                format!("Ticket not found: {}-{}", project_key, ticket_number)
-            ))?;
-
-        ticket.id
-    };
+    let ticket_uuid = resolve_ticket_id(state.db.as_ref(), &id).await?;
 
     let ticket = Ticket::find_by_id(ticket_uuid)
         .one(state.db.as_ref())
@@ -1131,13 +1594,15 @@ pub async fn delete_ticket(
         .map_err(ApiError::from)?
         .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
 
+    access::require_project(state.db.as_ref(), &auth_user, ticket.project_id).await?;
+
     // Soft delete: set deleted_at timestamp
     let mut ticket: ticket::ActiveModel = ticket.into();
     let now = Utc::now();
     ticket.deleted_at = Set(Some(now));
     ticket.updated_at = Set(now);
 
-    ticket
+    let deleted = ticket
         .update(state.db.as_ref())
         .await
         .map_err(ApiError::from)?;
@@ -1150,7 +1615,7 @@ pub async fn delete_ticket(
         field_name: Set(None),
         old_value: Set(None),
         new_value: Set(None),
-        changed_by: Set("system".to_string()),
+        changed_by: Set(auth_user.actor()),
         changed_at: Set(now),
         message: Set(Some("Ticket soft deleted".to_string())),
     };
@@ -1159,5 +1624,137 @@ pub async fn delete_ticket(
         .await
         .map_err(ApiError::from)?;
 
+    let data = serde_json::json!({
+        "ticket_id": ticket_uuid,
+        "number": format_ticket_number(state.db.as_ref(), &deleted).await?,
+    });
+    state
+        .webhooks
+        .enqueue_project_event(&state.db, deleted.project_id, "ticket.deleted", data)
+        .await;
+
     Ok(Json(serde_json::json!({ "success": true })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    async fn page(
+        fx: &Fixture,
+        params: &[(&str, &str)],
+        cursor: Option<&str>,
+    ) -> ApiResult<TicketListResponse> {
+        let mut pairs: Vec<(String, String)> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        pairs.push(("project_id".to_string(), fx.project.id.to_string()));
+        if let Some(cursor) = cursor {
+            pairs.push(("cursor".to_string(), cursor.to_string()));
+        }
+        let Json(page) =
+            list_tickets(State(fx.state.clone()), Extension(fx.user.clone()), Query(pairs)).await?;
+        Ok(page)
+    }
+
+    /// Every ticket, following cursors from the first page to the last
+    async fn page_through(fx: &Fixture, sort: &str, order: &str, limit: &str) -> Vec<String> {
+        let params = [("sort", sort), ("order", order), ("limit", limit)];
+        let mut ids = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = page(fx, &params, cursor.as_deref()).await.unwrap();
+            ids.extend(page.tickets.into_iter().map(|t| t.id));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return ids,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cursor_continues_through_tied_sort_keys() {
+        let fx = Fixture::new().await;
+        // Unestimated tickets tie with each other at -1, below 0 points
+        let tickets = [
+            (None, "todo"),
+            (Some(3), "done"),
+            (None, "legacy"),
+            (Some(0), "todo"),
+            (Some(3), "backlog"),
+            (None, "todo"),
+            (Some(3), "legacy"),
+        ];
+        for (i, (points, status)) in tickets.into_iter().enumerate() {
+            fx.ticket(&format!("Ticket {}", i), |t| {
+                t.story_points = Set(points);
+                t.status = Set(status.to_string());
+            })
+            .await;
+        }
+
+        for sort in ["points", "status", "number"] {
+            for order in ["asc", "desc"] {
+                let all = page_through(&fx, sort, order, "500").await;
+                assert_eq!(all.len(), tickets.len());
+                for limit in ["1", "2", "3"] {
+                    let paged = page_through(&fx, sort, order, limit).await;
+                    assert_eq!(paged, all, "sort={} order={} limit={}", sort, order, limit);
+                }
+            }
+        }
+
+        let by_points = page(&fx, &[("sort", "points")], None).await.unwrap().tickets;
+        let points: Vec<Option<i32>> = by_points.iter().map(|t| t.story_points).collect();
+        assert_eq!(points, [None, None, None, Some(0), Some(3), Some(3), Some(3)]);
+
+        // Workflow order, then statuses the workflow doesn't have
+        let by_status = page(&fx, &[("sort", "status")], None).await.unwrap().tickets;
+        let statuses: Vec<&str> = by_status.iter().map(|t| t.status.as_str()).collect();
+        assert_eq!(statuses, ["backlog", "todo", "todo", "todo", "done", "legacy", "legacy"]);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_from_another_sort_is_rejected() {
+        let fx = Fixture::new().await;
+        for i in 0..3 {
+            fx.ticket(&format!("Ticket {}", i), |_| {}).await;
+        }
+        let first = page(&fx, &[("sort", "number"), ("limit", "1")], None).await.unwrap();
+        let cursor = first.next_cursor.unwrap();
+
+        for params in [
+            [("sort", "points"), ("order", "asc")],
+            [("sort", "number"), ("order", "desc")],
+        ] {
+            let result = page(&fx, &params, Some(&cursor)).await;
+            assert!(matches!(result, Err(ApiError::InvalidInput(_))), "{:?}", params);
+        }
+        let result = page(&fx, &[("sort", "number")], Some("not-a-cursor")).await;
+        assert!(matches!(result, Err(ApiError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_limit_is_clamped() {
+        let limit = |value: &str| {
+            ListTicketsQuery::from_pairs(vec![("limit".to_string(), value.to_string())])
+                .unwrap()
+                .limit
+        };
+        assert_eq!(limit("0"), 1);
+        assert_eq!(limit("50"), 50);
+        assert_eq!(limit("100000"), MAX_PAGE_SIZE);
+        assert_eq!(ListTicketsQuery::from_pairs(Vec::new()).unwrap().limit, DEFAULT_PAGE_SIZE);
+        assert!(ListTicketsQuery::from_pairs(vec![("limit".to_string(), "-1".to_string())]).is_err());
+
+        let fx = Fixture::new().await;
+        for i in 0..2 {
+            fx.ticket(&format!("Ticket {}", i), |_| {}).await;
+        }
+        let first = page(&fx, &[("limit", "0")], None).await.unwrap();
+        assert_eq!(first.tickets.len(), 1);
+        assert!(first.next_cursor.is_some());
+    }
+}
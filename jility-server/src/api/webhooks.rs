@@ -0,0 +1,282 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::{access, AuthUser},
+    error::{ApiError, ApiResult},
+    models::{
+        CreateWebhookRequest, ListDeliveriesQuery, UpdateWebhookRequest, WebhookDeliveryResponse,
+        WebhookResponse,
+    },
+    state::AppState,
+    webhooks::{check_target, generate_secret, EVENTS},
+};
+use jility_core::entities::{
+    webhook, webhook_delivery, DeliveryStatus, Project, Webhook, WebhookDelivery,
+};
+
+/// Most deliveries one `list_deliveries` call returns
+const MAX_DELIVERIES: u64 = 200;
+
+fn to_response(hook: webhook::Model, secret: Option<String>) -> WebhookResponse {
+    WebhookResponse {
+        id: hook.id.to_string(),
+        workspace_id: hook.workspace_id.to_string(),
+        project_id: hook.project_id.map(|id| id.to_string()),
+        events: hook.events(),
+        url: hook.url,
+        active: hook.active,
+        secret,
+        created_by: hook.created_by,
+        created_at: hook.created_at.to_rfc3339(),
+        updated_at: hook.updated_at.to_rfc3339(),
+    }
+}
+
+fn delivery_response(delivery: webhook_delivery::Model) -> WebhookDeliveryResponse {
+    WebhookDeliveryResponse {
+        id: delivery.id.to_string(),
+        webhook_id: delivery.webhook_id.to_string(),
+        event: delivery.event,
+        status: delivery.status,
+        attempts: delivery.attempts,
+        next_attempt_at: delivery.next_attempt_at.map(|d| d.to_rfc3339()),
+        response_code: delivery.response_code,
+        response_body: delivery.response_body,
+        payload: serde_json::from_str(&delivery.payload).unwrap_or(serde_json::Value::Null),
+        created_at: delivery.created_at.to_rfc3339(),
+        delivered_at: delivery.delivered_at.map(|d| d.to_rfc3339()),
+    }
+}
+
+async fn check_url(url: &str) -> ApiResult<()> {
+    check_target(url).await.map_err(ApiError::InvalidInput)
+}
+
+fn check_events(events: &[String]) -> ApiResult<()> {
+    match events.iter().find(|e| !EVENTS.contains(&e.as_str())) {
+        Some(event) => Err(ApiError::InvalidInput(format!(
+            "Unknown webhook event '{}'. Valid events: {}",
+            event,
+            EVENTS.join(", ")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// A webhook of a workspace the caller administers
+async fn admin_webhook(db: &DatabaseConnection, user: &AuthUser, id: &str) -> ApiResult<webhook::Model> {
+    let id = Uuid::parse_str(id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid webhook ID: {}", id)))?;
+    let hook = Webhook::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Webhook not found: {}", id)))?;
+    access::require_admin(db, user, hook.workspace_id).await?;
+    Ok(hook)
+}
+
+/// List a workspace's webhooks
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(slug): Path<String>,
+) -> ApiResult<Json<Vec<WebhookResponse>>> {
+    let workspace = access::workspace_for_admin(state.db.as_ref(), &auth_user, &slug).await?;
+
+    let hooks = Webhook::find()
+        .filter(webhook::Column::WorkspaceId.eq(workspace.id))
+        .order_by_asc(webhook::Column::CreatedAt)
+        .all(state.db.as_ref())
+        .await?;
+
+    Ok(Json(hooks.into_iter().map(|h| to_response(h, None)).collect()))
+}
+
+/// Create a webhook. The signing secret is only returned here.
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(slug): Path<String>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> ApiResult<Json<WebhookResponse>> {
+    let workspace = access::workspace_for_admin(state.db.as_ref(), &auth_user, &slug).await?;
+    check_url(&payload.url).await?;
+    check_events(&payload.events)?;
+
+    if let Some(project_id) = payload.project_id {
+        let project = Project::find_by_id(project_id)
+            .one(state.db.as_ref())
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", project_id)))?;
+        if project.workspace_id != workspace.id {
+            return Err(ApiError::InvalidInput(
+                "The project belongs to another workspace".to_string(),
+            ));
+        }
+    }
+
+    let now = Utc::now();
+    let secret = generate_secret();
+    let hook = webhook::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        workspace_id: Set(workspace.id),
+        project_id: Set(payload.project_id),
+        url: Set(payload.url),
+        secret: Set(secret.clone()),
+        events: Set(serde_json::to_string(&payload.events).unwrap()),
+        active: Set(payload.active.unwrap_or(true)),
+        created_by: Set(auth_user.actor()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(state.db.as_ref())
+    .await?;
+
+    Ok(Json(to_response(hook, Some(secret))))
+}
+
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<WebhookResponse>> {
+    let hook = admin_webhook(state.db.as_ref(), &auth_user, &id).await?;
+    Ok(Json(to_response(hook, None)))
+}
+
+/// Change a webhook's URL, events or active flag, or rotate its secret
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> ApiResult<Json<WebhookResponse>> {
+    let hook = admin_webhook(state.db.as_ref(), &auth_user, &id).await?;
+    let mut hook: webhook::ActiveModel = hook.into();
+
+    if let Some(url) = payload.url {
+        check_url(&url).await?;
+        hook.url = Set(url);
+    }
+    if let Some(events) = payload.events {
+        check_events(&events)?;
+        hook.events = Set(serde_json::to_string(&events).unwrap());
+    }
+    if let Some(active) = payload.active {
+        hook.active = Set(active);
+    }
+    let secret = payload.rotate_secret.then(generate_secret);
+    if let Some(secret) = &secret {
+        hook.secret = Set(secret.clone());
+    }
+    hook.updated_at = Set(Utc::now());
+
+    let hook = hook.update(state.db.as_ref()).await?;
+
+    Ok(Json(to_response(hook, secret)))
+}
+
+/// Delete a webhook and its delivery log
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let hook = admin_webhook(state.db.as_ref(), &auth_user, &id).await?;
+
+    WebhookDelivery::delete_many()
+        .filter(webhook_delivery::Column::WebhookId.eq(hook.id))
+        .exec(state.db.as_ref())
+        .await?;
+    Webhook::delete_by_id(hook.id)
+        .exec(state.db.as_ref())
+        .await?;
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Recent deliveries of a webhook, newest first
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> ApiResult<Json<Vec<WebhookDeliveryResponse>>> {
+    let hook = admin_webhook(state.db.as_ref(), &auth_user, &id).await?;
+
+    let mut select = WebhookDelivery::find()
+        .filter(webhook_delivery::Column::WebhookId.eq(hook.id))
+        .order_by_desc(webhook_delivery::Column::CreatedAt)
+        .limit(query.limit.clamp(1, MAX_DELIVERIES));
+    if let Some(status) = query.status {
+        let valid = [
+            DeliveryStatus::Pending,
+            DeliveryStatus::Succeeded,
+            DeliveryStatus::Failed,
+        ];
+        if !valid.iter().any(|s| s.as_str() == status) {
+            return Err(ApiError::InvalidInput(format!(
+                "Invalid delivery status: {}. Use pending, succeeded or failed",
+                status
+            )));
+        }
+        select = select.filter(webhook_delivery::Column::Status.eq(status));
+    }
+
+    let deliveries = select.all(state.db.as_ref()).await?;
+
+    // Responses logged before non-public hosts were refused stay hidden
+    let public = check_target(&hook.url).await.is_ok();
+    Ok(Json(
+        deliveries
+            .into_iter()
+            .map(|mut delivery| {
+                if !public && delivery.response_code.is_some() {
+                    delivery.response_body = None;
+                }
+                delivery_response(delivery)
+            })
+            .collect(),
+    ))
+}
+
+/// Send a delivery's payload again as a new delivery
+pub async fn redeliver(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((id, delivery_id)): Path<(String, String)>,
+) -> ApiResult<Json<WebhookDeliveryResponse>> {
+    let hook = admin_webhook(state.db.as_ref(), &auth_user, &id).await?;
+    let delivery_id = Uuid::parse_str(&delivery_id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid delivery ID: {}", delivery_id)))?;
+
+    let delivery = WebhookDelivery::find_by_id(delivery_id)
+        .filter(webhook_delivery::Column::WebhookId.eq(hook.id))
+        .one(state.db.as_ref())
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Delivery not found: {}", delivery_id)))?;
+
+    let delivery = state.webhooks.redeliver(&state.db, &delivery).await?;
+
+    Ok(Json(delivery_response(delivery)))
+}
+
+/// Queue a `ping` event to check the endpoint and signature
+pub async fn ping_webhook(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<WebhookDeliveryResponse>> {
+    let hook = admin_webhook(state.db.as_ref(), &auth_user, &id).await?;
+    let delivery = state.webhooks.ping(&state.db, &hook).await?;
+    Ok(Json(delivery_response(delivery)))
+}
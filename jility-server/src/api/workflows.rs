@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QuerySelect, Set};
+use uuid::Uuid;
+
+use crate::{
+    auth::{access, AuthUser},
+    error::{ApiError, ApiResult},
+    models::{UpdateWorkflowRequest, WorkflowResponse},
+    state::AppState,
+};
+use jility_core::entities::{project_workflow, ticket, ProjectWorkflow, Ticket};
+use jility_core::workflow::Workflow;
+
+fn to_response(project_id: Uuid, workflow: Workflow, is_default: bool) -> WorkflowResponse {
+    WorkflowResponse {
+        project_id: project_id.to_string(),
+        statuses: workflow.statuses,
+        transitions: workflow.transitions,
+        initial_status: workflow.initial_status,
+        is_default,
+    }
+}
+
+/// The project's workflow (the built-in one if it was never customized)
+pub async fn get_workflow(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<WorkflowResponse>> {
+    let project_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
+    access::require_project(state.db.as_ref(), &auth_user, project_id).await?;
+
+    let stored = ProjectWorkflow::find()
+        .filter(project_workflow::Column::ProjectId.eq(project_id))
+        .one(state.db.as_ref())
+        .await?;
+
+    Ok(Json(match stored {
+        Some(row) => to_response(project_id, row.workflow(), false),
+        None => to_response(project_id, Workflow::default(), true),
+    }))
+}
+
+/// Replace the project's workflow (workspace admins only). Fails if any ticket
+/// is in a status the new workflow drops.
+pub async fn update_workflow(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateWorkflowRequest>,
+) -> ApiResult<Json<WorkflowResponse>> {
+    let project_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::InvalidInput(format!("Invalid project ID: {}", id)))?;
+    let (project, _) =
+        access::project_for_member(state.db.as_ref(), &auth_user, project_id).await?;
+    access::require_admin(state.db.as_ref(), &auth_user, project.workspace_id).await?;
+
+    let workflow = Workflow {
+        statuses: payload.statuses,
+        transitions: payload.transitions,
+        initial_status: payload.initial_status,
+    };
+    workflow.validate()?;
+
+    let orphaned: Vec<String> = Ticket::find()
+        .select_only()
+        .column(ticket::Column::Status)
+        .distinct()
+        .filter(ticket::Column::ProjectId.eq(project_id))
+        .filter(ticket::Column::DeletedAt.is_null())
+        .filter(ticket::Column::Status.is_not_in(workflow.keys()))
+        .into_tuple()
+        .all(state.db.as_ref())
+        .await?;
+    if !orphaned.is_empty() {
+        return Err(ApiError::Conflict(format!(
+            "Tickets are still in statuses this workflow removes: {}. Move them first.",
+            orphaned.join(", ")
+        )));
+    }
+
+    let now = Utc::now();
+    let statuses = serde_json::to_string(&workflow.statuses)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let transitions = serde_json::to_string(&workflow.transitions)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let existing = ProjectWorkflow::find()
+        .filter(project_workflow::Column::ProjectId.eq(project_id))
+        .one(state.db.as_ref())
+        .await?;
+
+    let saved = match existing {
+        Some(row) => {
+            let mut active: project_workflow::ActiveModel = row.into();
+            active.initial_status = Set(workflow.initial_status.clone());
+            active.statuses = Set(statuses);
+            active.transitions = Set(transitions);
+            active.updated_at = Set(now);
+            active.update(state.db.as_ref()).await?
+        }
+        None => {
+            project_workflow::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                project_id: Set(project_id),
+                initial_status: Set(workflow.initial_status.clone()),
+                statuses: Set(statuses),
+                transitions: Set(transitions),
+                created_at: Set(now),
+                updated_at: Set(now),
+            }
+            .insert(state.db.as_ref())
+            .await?
+        }
+    };
+
+    Ok(Json(to_response(project_id, saved.workflow(), false)))
+}
@@ -457,6 +457,10 @@ pub async fn remove_member(
         .remove_member(workspace.id, target_user_id)
         .await
         .map_err(|e| ApiError::Internal(format!("Failed to remove member: {}", e)))?;
+    state
+        .ws_state
+        .leave_workspace(target_user_id, workspace.id)
+        .await;
 
     Ok(StatusCode::NO_CONTENT)
 }
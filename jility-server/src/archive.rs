@@ -0,0 +1,1057 @@
+//! Exporting and restoring workspace archives
+//!
+//! [`export`] writes everything a workspace holds as a
+//! `jility_core::archive`. [`restore`] reads one back as a new workspace,
+//! owned by the user restoring it, with new IDs for every row. Archived
+//! members are invited; anyone can write an archive, so it can't make
+//! other accounts members or hand out blobs of workspaces they can't see.
+//!
+//! Like imports, a restore doesn't notify anyone, broadcast events or run
+//! project policies; the rows already happened.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
+use jility_core::archive::{self, Header, IdMap, Member, Record};
+use jility_core::entities::{
+    attachment, comment, comment_reaction, comment_revision, commit_link, project,
+    project_workflow, saved_view, sprint, sprint_ticket, ticket, ticket_alias, ticket_assignee,
+    ticket_change, ticket_dependency, ticket_label, ticket_template, ticket_watcher, user,
+    workspace, workspace_invite, workspace_member, Attachment, Comment, CommentReaction,
+    CommentRevision, CommitLink, Project, ProjectWorkflow, SavedView, Sprint, SprintTicket, Ticket,
+    TicketAlias, TicketAssignee, TicketChange, TicketDependency, TicketLabel, TicketTemplate,
+    TicketWatcher, User, Workspace, WorkspaceMember, WorkspaceRole,
+};
+use sea_orm::sea_query::{Query, SelectStatement};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, Select, Set,
+};
+use uuid::Uuid;
+
+use crate::attachments::{sanitize_filename, AttachmentLimits};
+use crate::auth::AuthUser;
+use crate::blob_store::{self, BlobStore};
+use crate::error::{ApiError, ApiResult};
+
+/// Rows per `INSERT` when restoring, well under every engine's bind limit
+const BATCH: usize = 100;
+
+/// Days a restore's invites stay valid
+const INVITE_DAYS: i64 = 30;
+
+/// `query`'s rows as records
+async fn add<E: EntityTrait>(
+    records: &mut Vec<Record>,
+    db: &DatabaseConnection,
+    query: Select<E>,
+    record: fn(E::Model) -> Record,
+) -> ApiResult<()> {
+    records.extend(query.all(db).await?.into_iter().map(record));
+    Ok(())
+}
+
+/// Write every row of `workspace` as an archive
+pub async fn export(
+    db: &DatabaseConnection,
+    workspace: workspace::Model,
+    exported_by: &str,
+) -> ApiResult<String> {
+    let workspace_id = workspace.id;
+    let mut records = vec![Record::Workspace(workspace)];
+
+    let members = WorkspaceMember::find()
+        .filter(workspace_member::Column::WorkspaceId.eq(workspace_id))
+        .order_by_asc(workspace_member::Column::JoinedAt)
+        .all(db)
+        .await?;
+    let users: HashMap<Uuid, user::Model> = User::find()
+        .filter(user::Column::Id.is_in(members.iter().map(|m| m.user_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u))
+        .collect();
+    for member in &members {
+        let Some(user) = users.get(&member.user_id) else {
+            continue;
+        };
+        records.push(Record::Member(Member {
+            user_id: user.id,
+            email: user.email.clone(),
+            username: user.username.clone(),
+            full_name: user.full_name.clone(),
+            role: member.role.clone(),
+            joined_at: member.joined_at.to_utc(),
+        }));
+    }
+
+    let project_ids: Vec<Uuid> = Project::find()
+        .filter(project::Column::WorkspaceId.eq(workspace_id))
+        .all(db)
+        .await?
+        .iter()
+        .map(|p| p.id)
+        .collect();
+    let tickets = || -> SelectStatement {
+        Query::select()
+            .column(ticket::Column::Id)
+            .from(Ticket)
+            .and_where(ticket::Column::ProjectId.is_in(project_ids.clone()))
+            .to_owned()
+    };
+    let comments = || -> SelectStatement {
+        Query::select()
+            .column(comment::Column::Id)
+            .from(Comment)
+            .and_where(comment::Column::TicketId.in_subquery(tickets()))
+            .to_owned()
+    };
+
+    add(
+        &mut records,
+        db,
+        Project::find()
+            .filter(project::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(project::Column::CreatedAt),
+        Record::Project,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        ProjectWorkflow::find()
+            .filter(project_workflow::Column::ProjectId.is_in(project_ids.clone())),
+        Record::Workflow,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        TicketTemplate::find()
+            .filter(ticket_template::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(ticket_template::Column::CreatedAt),
+        Record::Template,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        Sprint::find()
+            .filter(sprint::Column::ProjectId.is_in(project_ids.clone()))
+            .order_by_asc(sprint::Column::CreatedAt),
+        Record::Sprint,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        Ticket::find()
+            .filter(ticket::Column::ProjectId.is_in(project_ids.clone()))
+            .order_by_asc(ticket::Column::ProjectId)
+            .order_by_asc(ticket::Column::TicketNumber),
+        Record::Ticket,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        TicketAlias::find().filter(ticket_alias::Column::ProjectId.is_in(project_ids.clone())),
+        Record::TicketAlias,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        TicketAssignee::find()
+            .filter(ticket_assignee::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(ticket_assignee::Column::AssignedAt),
+        Record::Assignee,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        TicketLabel::find()
+            .filter(ticket_label::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(ticket_label::Column::CreatedAt),
+        Record::Label,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        TicketDependency::find()
+            .filter(ticket_dependency::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(ticket_dependency::Column::CreatedAt),
+        Record::Dependency,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        SprintTicket::find()
+            .filter(sprint_ticket::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(sprint_ticket::Column::AddedAt),
+        Record::SprintTicket,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        Comment::find()
+            .filter(comment::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(comment::Column::CreatedAt),
+        Record::Comment,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        CommentRevision::find()
+            .filter(comment_revision::Column::CommentId.in_subquery(comments()))
+            .order_by_asc(comment_revision::Column::EditedAt),
+        Record::CommentRevision,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        CommentReaction::find()
+            .filter(comment_reaction::Column::CommentId.in_subquery(comments()))
+            .order_by_asc(comment_reaction::Column::CreatedAt),
+        Record::CommentReaction,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        Attachment::find()
+            .filter(attachment::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(attachment::Column::CreatedAt),
+        Record::Attachment,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        CommitLink::find()
+            .filter(commit_link::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(commit_link::Column::LinkedAt),
+        Record::CommitLink,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        TicketWatcher::find()
+            .filter(ticket_watcher::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(ticket_watcher::Column::CreatedAt),
+        Record::Watcher,
+    )
+    .await?;
+    add(
+        &mut records,
+        db,
+        TicketChange::find()
+            .filter(ticket_change::Column::TicketId.in_subquery(tickets()))
+            .order_by_asc(ticket_change::Column::ChangedAt),
+        Record::Change,
+    )
+    .await?;
+
+    // Saved views belong to users; archive the members' views of this
+    // workspace's projects
+    let views = SavedView::find()
+        .filter(saved_view::Column::UserId.is_in(members.iter().map(|m| m.user_id)))
+        .order_by_asc(saved_view::Column::CreatedAt)
+        .all(db)
+        .await?;
+    records.extend(
+        views
+            .into_iter()
+            .filter(|view| {
+                view_project(view).is_some_and(|project_id| project_ids.contains(&project_id))
+            })
+            .map(Record::SavedView),
+    );
+
+    Ok(archive::write(Header::new(exported_by), &records))
+}
+
+/// The project a saved view's filters are limited to
+fn view_project(view: &saved_view::Model) -> Option<Uuid> {
+    let filters: serde_json::Value = serde_json::from_str(&view.filters).ok()?;
+    filters.get("project_id")?.as_str()?.parse().ok()
+}
+
+/// What a restore did, or would do
+pub struct Restored {
+    pub workspace: workspace::Model,
+    /// Records restored, by type
+    pub records: BTreeMap<String, usize>,
+    /// Emails of the members invited, everyone archived but the owner
+    pub invited: Vec<String>,
+    /// Attachments left out because their content isn't available to the
+    /// owner
+    pub missing_attachments: usize,
+    pub warnings: Vec<String>,
+}
+
+/// The records of an archive, by type
+#[derive(Default)]
+struct Rows {
+    workspaces: Vec<workspace::Model>,
+    members: Vec<Member>,
+    projects: Vec<project::Model>,
+    workflows: Vec<project_workflow::Model>,
+    templates: Vec<ticket_template::Model>,
+    sprints: Vec<sprint::Model>,
+    tickets: Vec<ticket::Model>,
+    aliases: Vec<ticket_alias::Model>,
+    assignees: Vec<ticket_assignee::Model>,
+    labels: Vec<ticket_label::Model>,
+    dependencies: Vec<ticket_dependency::Model>,
+    sprint_tickets: Vec<sprint_ticket::Model>,
+    comments: Vec<comment::Model>,
+    revisions: Vec<comment_revision::Model>,
+    reactions: Vec<comment_reaction::Model>,
+    attachments: Vec<attachment::Model>,
+    commit_links: Vec<commit_link::Model>,
+    watchers: Vec<ticket_watcher::Model>,
+    changes: Vec<ticket_change::Model>,
+    views: Vec<saved_view::Model>,
+}
+
+impl Rows {
+    fn new(records: Vec<Record>) -> Self {
+        let mut rows = Self::default();
+        for record in records {
+            match record {
+                Record::Header(_) | Record::End(_) => {}
+                Record::Workspace(r) => rows.workspaces.push(r),
+                Record::Member(r) => rows.members.push(r),
+                Record::Project(r) => rows.projects.push(r),
+                Record::Workflow(r) => rows.workflows.push(r),
+                Record::Template(r) => rows.templates.push(r),
+                Record::Sprint(r) => rows.sprints.push(r),
+                Record::Ticket(r) => rows.tickets.push(r),
+                Record::TicketAlias(r) => rows.aliases.push(r),
+                Record::Assignee(r) => rows.assignees.push(r),
+                Record::Label(r) => rows.labels.push(r),
+                Record::Dependency(r) => rows.dependencies.push(r),
+                Record::SprintTicket(r) => rows.sprint_tickets.push(r),
+                Record::Comment(r) => rows.comments.push(r),
+                Record::CommentRevision(r) => rows.revisions.push(r),
+                Record::CommentReaction(r) => rows.reactions.push(r),
+                Record::Attachment(r) => rows.attachments.push(r),
+                Record::CommitLink(r) => rows.commit_links.push(r),
+                Record::Watcher(r) => rows.watchers.push(r),
+                Record::Change(r) => rows.changes.push(r),
+                Record::SavedView(r) => rows.views.push(r),
+            }
+        }
+        rows
+    }
+}
+
+/// New IDs for restored rows, and the accounts archived users map to
+struct Remap {
+    ids: IdMap,
+    users: HashMap<Uuid, Uuid>,
+}
+
+impl Remap {
+    /// The new ID of a row the archive holds; `InvalidInput` if it doesn't
+    fn id(&self, old: Uuid, what: &str) -> ApiResult<Uuid> {
+        self.ids.get(old).ok_or_else(|| {
+            ApiError::InvalidInput(format!(
+                "The archive refers to {} {}, which it doesn't contain",
+                what, old
+            ))
+        })
+    }
+
+    fn optional(&self, old: Option<Uuid>, what: &str) -> ApiResult<Option<Uuid>> {
+        old.map(|old| self.id(old, what)).transpose()
+    }
+
+    /// The account here for an archived user; `None` leaves the row unowned
+    fn user(&self, old: Option<Uuid>) -> Option<Uuid> {
+        old.and_then(|old| self.users.get(&old).copied())
+    }
+}
+
+/// Insert `rows`, a batch at a time
+async fn insert_all<M, A>(txn: &DatabaseTransaction, rows: Vec<M>) -> ApiResult<()>
+where
+    M: IntoActiveModel<A>,
+    A: ActiveModelTrait + Send,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+{
+    let mut rows = rows.into_iter().peekable();
+    while rows.peek().is_some() {
+        let batch: Vec<A> = rows
+            .by_ref()
+            .take(BATCH)
+            .map(IntoActiveModel::into_active_model)
+            .collect();
+        A::Entity::insert_many(batch)
+            .exec_without_returning(txn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Restore an archive's records as a new workspace owned by `owner`.
+/// Attachments pass the checks uploads do under `limits`.
+pub async fn restore(
+    txn: &DatabaseTransaction,
+    blob_store: &dyn BlobStore,
+    limits: &AttachmentLimits,
+    records: Vec<Record>,
+    owner: &AuthUser,
+) -> ApiResult<Restored> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for record in &records {
+        *counts.entry(record.kind().to_string()).or_default() += 1;
+    }
+    let rows = Rows::new(records);
+    let mut warnings = Vec::new();
+
+    let [archived] = <[workspace::Model; 1]>::try_from(rows.workspaces).map_err(|found| {
+        ApiError::InvalidInput(format!(
+            "The archive should hold one workspace, not {}",
+            found.len()
+        ))
+    })?;
+
+    let keys: Vec<&str> = rows
+        .projects
+        .iter()
+        .filter_map(|p| p.key.as_deref())
+        .collect();
+    let taken: Vec<String> = Project::find()
+        .filter(project::Column::Key.is_in(keys))
+        .all(txn)
+        .await?
+        .into_iter()
+        .filter_map(|p| p.key)
+        .collect();
+    if !taken.is_empty() {
+        return Err(ApiError::Conflict(format!(
+            "Project keys already used on this server: {}",
+            taken.join(", ")
+        )));
+    }
+
+    let mut slug = archived.slug.clone();
+    let mut n = 1;
+    while Workspace::find()
+        .filter(workspace::Column::Slug.eq(&slug))
+        .one(txn)
+        .await?
+        .is_some()
+    {
+        n += 1;
+        slug = format!("{}-{}", archived.slug, n);
+    }
+    if slug != archived.slug {
+        warnings.push(format!(
+            "Workspace slug '{}' is taken; restored as '{}'",
+            archived.slug, slug
+        ));
+    }
+
+    let mut remap = Remap {
+        ids: IdMap::new(),
+        users: HashMap::new(),
+    };
+    let now = Utc::now().fixed_offset();
+    let workspace = workspace::Model {
+        id: remap.ids.assign(archived.id),
+        slug,
+        created_by_user_id: owner.id,
+        ..archived
+    }
+    .into_active_model()
+    .insert(txn)
+    .await?;
+
+    // The owner is the only member; everyone else archived is invited, and
+    // joins only by accepting
+    workspace_member::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        workspace_id: Set(workspace.id),
+        user_id: Set(owner.id),
+        role: Set(WorkspaceRole::Admin),
+        invited_by_user_id: Set(None),
+        invited_at: Set(None),
+        joined_at: Set(now),
+    }
+    .insert(txn)
+    .await?;
+    let mut invited = Vec::new();
+    for member in &rows.members {
+        if member.email == owner.email {
+            remap.users.insert(member.user_id, owner.id);
+            continue;
+        }
+        workspace_invite::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            workspace_id: Set(workspace.id),
+            email: Set(member.email.clone()),
+            role: Set(member.role.clone()),
+            invited_by_user_id: Set(owner.id),
+            token: Set(Uuid::new_v4().to_string()),
+            expires_at: Set(now + chrono::Duration::days(INVITE_DAYS)),
+            accepted_at: Set(None),
+            created_at: Set(now),
+        }
+        .insert(txn)
+        .await?;
+        invited.push(member.email.clone());
+    }
+
+    let mut projects = Vec::new();
+    for p in rows.projects {
+        projects.push(project::Model {
+            id: remap.ids.assign(p.id),
+            workspace_id: remap.id(p.workspace_id, "workspace")?,
+            // Pushes stay refused until a new secret is generated
+            git_webhook_secret: None,
+            ..p
+        });
+    }
+    insert_all(txn, projects).await?;
+
+    let mut workflows = Vec::new();
+    for w in rows.workflows {
+        workflows.push(project_workflow::Model {
+            id: remap.ids.assign(w.id),
+            project_id: remap.id(w.project_id, "project")?,
+            ..w
+        });
+    }
+    insert_all(txn, workflows).await?;
+
+    let mut templates = Vec::new();
+    for t in rows.templates {
+        templates.push(ticket_template::Model {
+            id: remap.ids.assign(t.id),
+            workspace_id: remap.id(t.workspace_id, "workspace")?,
+            project_id: remap.optional(t.project_id, "project")?,
+            ..t
+        });
+    }
+    insert_all(txn, templates).await?;
+
+    let mut sprints = Vec::new();
+    for s in rows.sprints {
+        sprints.push(sprint::Model {
+            id: remap.ids.assign(s.id),
+            project_id: remap.id(s.project_id, "project")?,
+            ..s
+        });
+    }
+    insert_all(txn, sprints).await?;
+
+    // Tickets link to each other; assign every ID first, and set the links
+    // once all tickets exist
+    for t in &rows.tickets {
+        remap.ids.assign(t.id);
+    }
+    let mut tickets = Vec::new();
+    let mut links = Vec::new();
+    for t in rows.tickets {
+        let id = remap.id(t.id, "ticket")?;
+        let epic_id = remap.optional(t.epic_id, "ticket")?;
+        let parent_id = remap.optional(t.parent_id, "ticket")?;
+        let parent_epic_id = remap.optional(t.parent_epic_id, "ticket")?;
+        if epic_id.is_some() || parent_id.is_some() || parent_epic_id.is_some() {
+            links.push(ticket::ActiveModel {
+                id: Set(id),
+                epic_id: Set(epic_id),
+                parent_id: Set(parent_id),
+                parent_epic_id: Set(parent_epic_id),
+                ..Default::default()
+            });
+        }
+        tickets.push(ticket::Model {
+            id,
+            project_id: remap.id(t.project_id, "project")?,
+            epic_id: None,
+            parent_id: None,
+            parent_epic_id: None,
+            ..t
+        });
+    }
+    insert_all(txn, tickets).await?;
+    for link in links {
+        link.update(txn).await?;
+    }
+
+    let mut aliases = Vec::new();
+    for a in rows.aliases {
+        aliases.push(ticket_alias::Model {
+            id: remap.ids.assign(a.id),
+            project_id: remap.id(a.project_id, "project")?,
+            ticket_id: remap.id(a.ticket_id, "ticket")?,
+            ..a
+        });
+    }
+    insert_all(txn, aliases).await?;
+
+    let mut assignees = Vec::new();
+    for a in rows.assignees {
+        assignees.push(ticket_assignee::Model {
+            id: remap.ids.assign(a.id),
+            ticket_id: remap.id(a.ticket_id, "ticket")?,
+            ..a
+        });
+    }
+    insert_all(txn, assignees).await?;
+
+    let mut labels = Vec::new();
+    for l in rows.labels {
+        labels.push(ticket_label::Model {
+            id: remap.ids.assign(l.id),
+            ticket_id: remap.id(l.ticket_id, "ticket")?,
+            ..l
+        });
+    }
+    insert_all(txn, labels).await?;
+
+    let mut dependencies = Vec::new();
+    for d in rows.dependencies {
+        dependencies.push(ticket_dependency::Model {
+            id: remap.ids.assign(d.id),
+            ticket_id: remap.id(d.ticket_id, "ticket")?,
+            depends_on_id: remap.id(d.depends_on_id, "ticket")?,
+            ..d
+        });
+    }
+    insert_all(txn, dependencies).await?;
+
+    let mut sprint_tickets = Vec::new();
+    for s in rows.sprint_tickets {
+        sprint_tickets.push(sprint_ticket::Model {
+            id: remap.ids.assign(s.id),
+            sprint_id: remap.id(s.sprint_id, "sprint")?,
+            ticket_id: remap.id(s.ticket_id, "ticket")?,
+            ..s
+        });
+    }
+    insert_all(txn, sprint_tickets).await?;
+
+    // Replies come after the comments they answer
+    let mut archived_comments = rows.comments;
+    archived_comments.sort_by_key(|c| c.created_at);
+    for c in &archived_comments {
+        remap.ids.assign(c.id);
+    }
+    let mut comments = Vec::new();
+    for c in archived_comments {
+        comments.push(comment::Model {
+            id: remap.id(c.id, "comment")?,
+            ticket_id: remap.id(c.ticket_id, "ticket")?,
+            parent_id: remap.optional(c.parent_id, "comment")?,
+            author_user_id: remap.user(c.author_user_id),
+            ..c
+        });
+    }
+    insert_all(txn, comments).await?;
+
+    let mut revisions = Vec::new();
+    for r in rows.revisions {
+        revisions.push(comment_revision::Model {
+            id: remap.ids.assign(r.id),
+            comment_id: remap.id(r.comment_id, "comment")?,
+            ..r
+        });
+    }
+    insert_all(txn, revisions).await?;
+
+    let mut reactions = Vec::new();
+    let mut dropped_reactions = 0;
+    for r in rows.reactions {
+        let Some(user_id) = remap.user(Some(r.user_id)) else {
+            dropped_reactions += 1;
+            continue;
+        };
+        reactions.push(comment_reaction::Model {
+            id: remap.ids.assign(r.id),
+            comment_id: remap.id(r.comment_id, "comment")?,
+            user_id,
+            ..r
+        });
+    }
+    insert_all(txn, reactions).await?;
+    if dropped_reactions > 0 {
+        warnings.push(format!(
+            "{} reaction(s) of invited members weren't restored",
+            dropped_reactions
+        ));
+    }
+
+    // Archived rows are only as trustworthy as their writer: the hash
+    // becomes a blob key and the rest goes out in download headers
+    let (attachments, refused): (Vec<_>, Vec<_>) =
+        rows.attachments.into_iter().partition(|a| {
+            blob_store::is_key(&a.sha256)
+                && a.size_bytes > 0
+                && a.size_bytes as u64 <= limits.max_bytes as u64
+                && limits.allows(&a.content_type)
+                && sanitize_filename(&a.filename) == a.filename
+        });
+    if !refused.is_empty() {
+        warnings.push(format!(
+            "{} attachment(s) that uploads would refuse weren't restored",
+            refused.len()
+        ));
+    }
+
+    // An archive names blobs by hash without holding them. Link one the
+    // owner can already download, or one no attachment here uses, which
+    // was copied over for this restore; others stay out of reach.
+    let hashes: HashSet<String> = attachments.iter().map(|a| a.sha256.clone()).collect();
+    let owner_tickets = Query::select()
+        .column(ticket::Column::Id)
+        .from(Ticket)
+        .and_where(
+            ticket::Column::ProjectId.in_subquery(
+                Query::select()
+                    .column(project::Column::Id)
+                    .from(Project)
+                    .and_where(
+                        project::Column::WorkspaceId.in_subquery(
+                            Query::select()
+                                .column(workspace_member::Column::WorkspaceId)
+                                .from(WorkspaceMember)
+                                .and_where(workspace_member::Column::UserId.eq(owner.id))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            ),
+        )
+        .to_owned();
+    let mut used = HashSet::new();
+    let mut reachable = HashSet::new();
+    for a in Attachment::find()
+        .filter(attachment::Column::Sha256.is_in(hashes.iter().cloned()))
+        .filter(attachment::Column::TicketId.in_subquery(owner_tickets))
+        .all(txn)
+        .await?
+    {
+        reachable.insert(a.sha256);
+    }
+    for a in Attachment::find()
+        .filter(attachment::Column::Sha256.is_in(hashes.iter().cloned()))
+        .all(txn)
+        .await?
+    {
+        used.insert(a.sha256);
+    }
+    let mut available = HashSet::new();
+    for sha256 in hashes {
+        let exists = blob_store.exists(&sha256).await.map_err(|e| {
+            ApiError::Internal(format!("Failed to check attachment content: {}", e))
+        })?;
+        if exists && (reachable.contains(&sha256) || !used.contains(&sha256)) {
+            available.insert(sha256);
+        }
+    }
+
+    let mut restored_attachments = Vec::new();
+    let mut missing_attachments = 0;
+    for a in attachments {
+        if !available.contains(&a.sha256) {
+            missing_attachments += 1;
+            continue;
+        }
+        restored_attachments.push(attachment::Model {
+            id: remap.ids.assign(a.id),
+            ticket_id: remap.id(a.ticket_id, "ticket")?,
+            comment_id: remap.optional(a.comment_id, "comment")?,
+            uploaded_by_user_id: remap.user(a.uploaded_by_user_id),
+            ..a
+        });
+    }
+    insert_all(txn, restored_attachments).await?;
+
+    let mut commit_links = Vec::new();
+    for c in rows.commit_links {
+        commit_links.push(commit_link::Model {
+            id: remap.ids.assign(c.id),
+            ticket_id: remap.id(c.ticket_id, "ticket")?,
+            ..c
+        });
+    }
+    insert_all(txn, commit_links).await?;
+
+    let mut watchers = Vec::new();
+    let mut dropped_watchers = 0;
+    for w in rows.watchers {
+        let Some(&user_id) = remap.users.get(&w.user_id) else {
+            dropped_watchers += 1;
+            continue;
+        };
+        watchers.push(ticket_watcher::Model {
+            id: remap.ids.assign(w.id),
+            ticket_id: remap.id(w.ticket_id, "ticket")?,
+            user_id,
+            ..w
+        });
+    }
+    insert_all(txn, watchers).await?;
+    if dropped_watchers > 0 {
+        warnings.push(format!(
+            "{} watch(es) of invited members weren't restored",
+            dropped_watchers
+        ));
+    }
+
+    // Changes embed the IDs of tickets, comments, sprints and attachments
+    // in their values, so they go last
+    let mut changes = Vec::new();
+    for c in rows.changes {
+        changes.push(ticket_change::Model {
+            id: remap.ids.assign(c.id),
+            ticket_id: remap.id(c.ticket_id, "ticket")?,
+            old_value: c.old_value.as_deref().map(|v| remap.ids.rewrite(v)),
+            new_value: c.new_value.as_deref().map(|v| remap.ids.rewrite(v)),
+            ..c
+        });
+    }
+    insert_all(txn, changes).await?;
+
+    let mut views = Vec::new();
+    let mut dropped_views = 0;
+    for v in rows.views {
+        let Some(&user_id) = remap.users.get(&v.user_id) else {
+            dropped_views += 1;
+            continue;
+        };
+        views.push(saved_view::Model {
+            id: remap.ids.assign(v.id),
+            user_id,
+            filters: remap.ids.rewrite(&v.filters),
+            ..v
+        });
+    }
+    insert_all(txn, views).await?;
+    if dropped_views > 0 {
+        warnings.push(format!(
+            "{} saved view(s) of invited members weren't restored",
+            dropped_views
+        ));
+    }
+
+    Ok(Restored {
+        workspace,
+        records: counts,
+        invited,
+        missing_attachments,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob_store::LocalStore;
+    use crate::test_support::Fixture;
+    use sea_orm::{sea_query::Expr, TransactionTrait};
+
+    #[tokio::test]
+    async fn test_restore_invites_members_and_keeps_other_blobs_out() {
+        let fx = Fixture::new().await;
+        let db = fx.state.db.as_ref();
+        let dir = std::env::temp_dir().join(format!("jility-restore-{}", Uuid::new_v4()));
+        let blobs = LocalStore::new(&dir);
+
+        let ticket = fx.ticket("Screenshots", |_| {}).await;
+        let attach = |name: &str, sha256: &str| attachment::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            ticket_id: Set(ticket.id),
+            comment_id: Set(None),
+            filename: Set(name.to_string()),
+            content_type: Set("image/png".to_string()),
+            size_bytes: Set(4),
+            sha256: Set(sha256.to_string()),
+            uploaded_by: Set("alice".to_string()),
+            uploaded_by_user_id: Set(Some(fx.user.id)),
+            created_at: Set(Utc::now()),
+        };
+        let private = attach("private.png", &"a".repeat(64)).insert(db).await.unwrap();
+        let copied = attach("copied.png", &"b".repeat(64)).insert(db).await.unwrap();
+        for sha256 in [&private.sha256, &copied.sha256] {
+            blobs.put(sha256, b"data".to_vec()).await.unwrap();
+        }
+
+        let workspace = Workspace::find_by_id(fx.project.workspace_id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let body = export(db, workspace, "alice").await.unwrap();
+        // As on a server the copied blob was brought to, where nothing uses it
+        Attachment::delete_by_id(copied.id).exec(db).await.unwrap();
+        let mut renamed: project::ActiveModel = fx.project.clone().into();
+        renamed.key = Set(Some("OLD".to_string()));
+        renamed.update(db).await.unwrap();
+
+        let now = Utc::now();
+        let bob: AuthUser = user::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            email: Set("bob@example.com".to_string()),
+            username: Set("bob".to_string()),
+            password_hash: Set(String::new()),
+            full_name: Set(None),
+            avatar_url: Set(None),
+            is_active: Set(true),
+            is_verified: Set(true),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+            last_login_at: Set(None),
+        }
+        .insert(db)
+        .await
+        .unwrap()
+        .into();
+
+        let (_, records) = archive::read(&body).unwrap();
+        let txn = db.begin().await.unwrap();
+        let restored = restore(&txn, &blobs, &AttachmentLimits::default(), records, &bob)
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+
+        let members: Vec<Uuid> = WorkspaceMember::find()
+            .filter(workspace_member::Column::WorkspaceId.eq(restored.workspace.id))
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.user_id)
+            .collect();
+        assert_eq!(members, [bob.id]);
+        assert_eq!(restored.invited, ["alice@example.com"]);
+
+        let attachments: Vec<(String, Option<Uuid>)> = Attachment::find()
+            .filter(attachment::Column::Id.ne(private.id))
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.filename, a.uploaded_by_user_id))
+            .collect();
+        assert_eq!(attachments, [("copied.png".to_string(), None)]);
+        assert_eq!(restored.missing_attachments, 1);
+
+        // Alice can download the private blob, but the copied one is Bob's now
+        Project::update_many()
+            .col_expr(project::Column::Key, Expr::value(Option::<String>::None))
+            .filter(project::Column::WorkspaceId.eq(restored.workspace.id))
+            .exec(db)
+            .await
+            .unwrap();
+        let (_, records) = archive::read(&body).unwrap();
+        let txn = db.begin().await.unwrap();
+        let restored = restore(&txn, &blobs, &AttachmentLimits::default(), records, &fx.user)
+            .await
+            .unwrap();
+        txn.rollback().await.unwrap();
+        assert_eq!(restored.missing_attachments, 1);
+        assert!(restored.invited.is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_refuses_attachments_uploads_would() {
+        let fx = Fixture::new().await;
+        let db = fx.state.db.as_ref();
+        let dir = std::env::temp_dir().join(format!("jility-restore-{}", Uuid::new_v4()));
+        let blobs = LocalStore::new(dir.join("blobs"));
+
+        let ticket = fx.ticket("Screenshots", |_| {}).await;
+        let shot = attachment::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            ticket_id: Set(ticket.id),
+            comment_id: Set(None),
+            filename: Set("shot.png".to_string()),
+            content_type: Set("image/png".to_string()),
+            size_bytes: Set(4),
+            sha256: Set("c".repeat(64)),
+            uploaded_by: Set("alice".to_string()),
+            uploaded_by_user_id: Set(Some(fx.user.id)),
+            created_at: Set(Utc::now()),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        blobs.put(&shot.sha256, b"data".to_vec()).await.unwrap();
+        std::fs::write(dir.join("secret"), b"secret").unwrap();
+
+        let workspace = Workspace::find_by_id(fx.project.workspace_id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let body = export(db, workspace, "alice").await.unwrap();
+        let mut renamed: project::ActiveModel = fx.project.clone().into();
+        renamed.key = Set(Some("OLD".to_string()));
+        renamed.update(db).await.unwrap();
+
+        // The exported row, plus tampered copies of it
+        let (_, mut records) = archive::read(&body).unwrap();
+        let tampered = |edit: fn(&mut attachment::Model)| {
+            let mut a = shot.clone();
+            a.id = Uuid::new_v4();
+            edit(&mut a);
+            Record::Attachment(a)
+        };
+        let end = records.len() - 1;
+        records.splice(
+            end..end,
+            [
+                tampered(|a| a.sha256 = "../secret".to_string()),
+                tampered(|a| a.sha256 = "../../../etc/passwd".to_string()),
+                tampered(|a| a.content_type = "text/html; charset=utf-8".to_string()),
+                tampered(|a| a.filename = "../shot.png".to_string()),
+                tampered(|a| a.size_bytes = i64::MAX),
+            ],
+        );
+
+        let txn = db.begin().await.unwrap();
+        let restored = restore(&txn, &blobs, &AttachmentLimits::default(), records, &fx.user)
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+
+        let restored_ticket = Ticket::find()
+            .filter(ticket::Column::ProjectId.ne(fx.project.id))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let attachments: Vec<String> = Attachment::find()
+            .filter(attachment::Column::TicketId.eq(restored_ticket.id))
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| a.sha256)
+            .collect();
+        assert_eq!(attachments, [shot.sha256]);
+        assert_eq!(restored.missing_attachments, 0);
+        assert!(restored
+            .warnings
+            .contains(&"5 attachment(s) that uploads would refuse weren't restored".to_string()));
+        assert!(dir.join("secret").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+//! Attachment limits and bookkeeping
+//!
+//! Uploads are checked against a size limit and a list of allowed MIME types,
+//! both configurable from the environment. Attachment rows share blobs by
+//! content hash; a blob is removed from the store with the last row that
+//! points at it.
+
+use std::collections::HashSet;
+
+use jility_core::entities::{attachment, Attachment};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+use crate::error::ApiResult;
+use crate::models::AttachmentResponse;
+use crate::state::AppState;
+
+/// Used when `ATTACHMENTS_MAX_BYTES` isn't set: 25 MiB
+pub const DEFAULT_MAX_BYTES: usize = 25 * 1024 * 1024;
+
+/// Used when `ATTACHMENTS_ALLOWED_TYPES` isn't set. Screenshots, logs,
+/// patches and the usual document and archive formats.
+pub const DEFAULT_ALLOWED_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "text/*",
+    "application/json",
+    "application/pdf",
+    "application/zip",
+    "application/gzip",
+    "application/x-patch",
+];
+
+/// Longest filename kept, in characters
+const MAX_FILENAME: usize = 255;
+
+#[derive(Debug, Clone)]
+pub struct AttachmentLimits {
+    pub max_bytes: usize,
+    /// MIME types, or `type/*` for every subtype
+    pub allowed_types: Vec<String>,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            allowed_types: DEFAULT_ALLOWED_TYPES.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
+impl AttachmentLimits {
+    /// `ATTACHMENTS_MAX_BYTES` and the comma-separated
+    /// `ATTACHMENTS_ALLOWED_TYPES`, falling back to the defaults
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut limits = Self::default();
+        if let Ok(max) = std::env::var("ATTACHMENTS_MAX_BYTES") {
+            limits.max_bytes = max
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid ATTACHMENTS_MAX_BYTES: {}", max))?;
+        }
+        if let Ok(types) = std::env::var("ATTACHMENTS_ALLOWED_TYPES") {
+            limits.allowed_types = types
+                .split(',')
+                .map(|t| t.trim().to_ascii_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect();
+        }
+        Ok(limits)
+    }
+
+    pub fn allows(&self, content_type: &str) -> bool {
+        // Also keeps the stored type a valid header value
+        let well_formed = content_type
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"/!#$&^_.+-".contains(&b));
+        well_formed && self.allowed_types.iter().any(|allowed| match allowed.strip_suffix("/*") {
+            Some(prefix) => content_type
+                .split_once('/')
+                .is_some_and(|(kind, _)| kind == prefix),
+            None => allowed == content_type,
+        })
+    }
+}
+
+/// The MIME type to store for an upload: the declared one without
+/// parameters, or a guess from the extension when the client only sent
+/// `application/octet-stream`
+pub fn content_type_for(declared: Option<&str>, filename: &str) -> String {
+    let declared = declared
+        .and_then(|t| t.split(';').next())
+        .map(|t| t.trim().to_ascii_lowercase())
+        .filter(|t| !t.is_empty() && t != "application/octet-stream");
+    if let Some(declared) = declared {
+        return declared;
+    }
+
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "diff" | "patch" => "text/x-diff",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// The last path component of an uploaded filename, without control
+/// characters
+pub fn sanitize_filename(name: &str) -> String {
+    let name: String = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILENAME)
+        .collect();
+    match name.trim() {
+        "" | "." | ".." => "file".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Types a browser may show inline; everything else is downloaded, so an
+/// uploaded page or SVG can't run script on the API's origin
+pub fn is_inline(content_type: &str) -> bool {
+    matches!(
+        content_type,
+        "image/png" | "image/jpeg" | "image/gif" | "image/webp"
+    )
+}
+
+pub fn attachment_response(row: attachment::Model) -> AttachmentResponse {
+    AttachmentResponse {
+        url: format!("/api/attachments/{}/content", row.id),
+        id: row.id.to_string(),
+        ticket_id: row.ticket_id.to_string(),
+        comment_id: row.comment_id.map(|id| id.to_string()),
+        filename: row.filename,
+        content_type: row.content_type,
+        size_bytes: row.size_bytes,
+        sha256: row.sha256,
+        uploaded_by: row.uploaded_by,
+        uploaded_by_user_id: row.uploaded_by_user_id.map(|id| id.to_string()),
+        created_at: row.created_at.to_rfc3339(),
+    }
+}
+
+/// A ticket's attachments, oldest first
+pub async fn ticket_attachments<C: ConnectionTrait>(
+    db: &C,
+    ticket_id: Uuid,
+) -> Result<Vec<AttachmentResponse>, DbErr> {
+    Ok(Attachment::find()
+        .filter(attachment::Column::TicketId.eq(ticket_id))
+        .order_by_asc(attachment::Column::CreatedAt)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(attachment_response)
+        .collect())
+}
+
+/// Delete attachment rows, then the blobs no other attachment shares
+pub async fn remove(state: &AppState, rows: Vec<attachment::Model>) -> ApiResult<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let hashes: HashSet<String> = rows.iter().map(|a| a.sha256.clone()).collect();
+    Attachment::delete_many()
+        .filter(attachment::Column::Id.is_in(rows.iter().map(|a| a.id)))
+        .exec(state.db.as_ref())
+        .await?;
+
+    let _guard = state.blob_lock.write().await;
+    for hash in hashes {
+        let still_used = Attachment::find()
+            .filter(attachment::Column::Sha256.eq(&hash))
+            .count(state.db.as_ref())
+            .await?
+            > 0;
+        if !still_used {
+            // A leftover blob only wastes space, so the rows stay deleted
+            if let Err(e) = state.blob_store.delete(&hash).await {
+                tracing::warn!("Failed to delete blob {}: {}", hash, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_and_content_types() {
+        let limits = AttachmentLimits::default();
+        assert!(limits.allows("image/png"));
+        assert!(limits.allows("text/x-diff"));
+        assert!(!limits.allows("image/svg+xml"));
+        assert!(!limits.allows("application/octet-stream"));
+        assert!(!limits.allows("text/plain\r\nx-evil: 1"));
+
+        assert_eq!(content_type_for(Some("image/png"), "a.bin"), "image/png");
+        assert_eq!(content_type_for(Some("Text/Plain; charset=utf-8"), "a"), "text/plain");
+        assert_eq!(content_type_for(Some("application/octet-stream"), "fix.patch"), "text/x-diff");
+        assert_eq!(content_type_for(None, "agent.LOG"), "text/plain");
+        assert_eq!(content_type_for(None, "blob"), "application/octet-stream");
+
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename("C:\\Users\\me\\shot.png"), "shot.png");
+        assert_eq!(sanitize_filename("a\nb.txt"), "ab.txt");
+        assert_eq!(sanitize_filename("dir/"), "file");
+    }
+}
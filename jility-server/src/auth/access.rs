@@ -0,0 +1,259 @@
+//! Workspace authorization
+//!
+//! Every project, ticket, comment, sprint and template belongs to exactly one
+//! workspace. These helpers resolve that workspace and check the user's
+//! membership (and role, for admin-only operations) before a handler touches
+//! the resource.
+
+use jility_core::entities::{
+    comment, project, workspace, workspace_member, Comment, Project, Sprint, Ticket, Workspace,
+    WorkspaceMember, WorkspaceRole,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use super::AuthUser;
+use crate::error::{ApiError, ApiResult};
+
+/// The caller's role in `workspace_id`, or `Forbidden` if they aren't a member
+pub async fn require_member(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    workspace_id: Uuid,
+) -> ApiResult<WorkspaceRole> {
+    WorkspaceMember::find()
+        .filter(workspace_member::Column::WorkspaceId.eq(workspace_id))
+        .filter(workspace_member::Column::UserId.eq(user.id))
+        .one(db)
+        .await?
+        .map(|member| member.role)
+        .ok_or_else(|| ApiError::Forbidden("You are not a member of this workspace".to_string()))
+}
+
+/// `Forbidden` unless the caller is an admin of `workspace_id`
+pub async fn require_admin(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    workspace_id: Uuid,
+) -> ApiResult<()> {
+    match require_member(db, user, workspace_id).await? {
+        WorkspaceRole::Admin => Ok(()),
+        WorkspaceRole::Member => Err(ApiError::Forbidden(
+            "Only workspace admins can perform this action".to_string(),
+        )),
+    }
+}
+
+/// Load a workspace by slug, if the caller is one of its admins
+pub async fn workspace_for_admin(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    slug: &str,
+) -> ApiResult<workspace::Model> {
+    let workspace = Workspace::find()
+        .filter(workspace::Column::Slug.eq(slug))
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Workspace not found".to_string()))?;
+    require_admin(db, user, workspace.id).await?;
+    Ok(workspace)
+}
+
+/// IDs of every workspace the caller belongs to
+pub async fn member_workspace_ids(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+) -> ApiResult<Vec<Uuid>> {
+    Ok(WorkspaceMember::find()
+        .filter(workspace_member::Column::UserId.eq(user.id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|member| member.workspace_id)
+        .collect())
+}
+
+/// IDs of every project in a workspace the caller belongs to
+pub async fn visible_project_ids(db: &DatabaseConnection, user: &AuthUser) -> ApiResult<Vec<Uuid>> {
+    let workspace_ids = member_workspace_ids(db, user).await?;
+    Ok(Project::find()
+        .filter(project::Column::WorkspaceId.is_in(workspace_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|project| project.id)
+        .collect())
+}
+
+/// Load a project the caller can see
+pub async fn project_for_member(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    project_id: Uuid,
+) -> ApiResult<(project::Model, WorkspaceRole)> {
+    let project = Project::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", project_id)))?;
+    let role = require_member(db, user, project.workspace_id).await?;
+    Ok((project, role))
+}
+
+/// `Forbidden` unless the caller belongs to the project's workspace
+pub async fn require_project(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    project_id: Uuid,
+) -> ApiResult<WorkspaceRole> {
+    Ok(project_for_member(db, user, project_id).await?.1)
+}
+
+/// `Forbidden` unless the caller belongs to the workspace of the ticket's project
+pub async fn require_ticket(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    ticket_id: Uuid,
+) -> ApiResult<WorkspaceRole> {
+    let ticket = Ticket::find_by_id(ticket_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", ticket_id)))?;
+    require_project(db, user, ticket.project_id).await
+}
+
+/// `Forbidden` unless the caller belongs to the workspace of the sprint's project
+pub async fn require_sprint(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    sprint_id: Uuid,
+) -> ApiResult<WorkspaceRole> {
+    let sprint = Sprint::find_by_id(sprint_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Sprint not found: {}", sprint_id)))?;
+    require_project(db, user, sprint.project_id).await
+}
+
+/// Load a comment the caller can see, with their role in its workspace
+pub async fn comment_for_member(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    comment_id: Uuid,
+) -> ApiResult<(comment::Model, WorkspaceRole)> {
+    let comment = Comment::find_by_id(comment_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Comment not found: {}", comment_id)))?;
+    let role = require_ticket(db, user, comment.ticket_id).await?;
+    Ok((comment, role))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::{Path, Query, State};
+    use axum::{Extension, Json};
+
+    use crate::api::{comments, search, sprints, tickets};
+    use crate::models::{CreateCommentRequest, CreateSprintRequest, UpdateCommentRequest};
+    use crate::test_support::Fixture;
+
+    use super::*;
+
+    fn refused<T>(result: ApiResult<T>) -> bool {
+        matches!(result, Err(ApiError::Forbidden(_) | ApiError::NotFound(_)))
+    }
+
+    #[tokio::test]
+    async fn test_outsiders_cant_reach_another_workspace() {
+        let fx = Fixture::new().await;
+        let bob = fx.outsider().await;
+        let ticket = fx.ticket("Secret launch plan", |_| {}).await;
+        let bobs = bob.ticket("Secret bake sale", |_| {}).await;
+
+        let Json(comment) = comments::create_comment(
+            State(fx.state.clone()),
+            Extension(fx.user.clone()),
+            Path(ticket.id.to_string()),
+            Json(CreateCommentRequest { content: "Launch on Friday".to_string(), parent_id: None }),
+        )
+        .await
+        .unwrap();
+        let _ = sprints::create_sprint(
+            State(fx.state.clone()),
+            Extension(fx.user.clone()),
+            Path(fx.project.id.to_string()),
+            Json(CreateSprintRequest {
+                name: "Launch".to_string(),
+                goal: None,
+                start_date: None,
+                end_date: None,
+                capacity: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+        for id in [ticket.id.to_string(), "JIL-1".to_string()] {
+            let result = tickets::get_ticket(
+                State(fx.state.clone()),
+                Extension(bob.user.clone()),
+                Path(id.clone()),
+            )
+            .await;
+            assert!(refused(result), "{}", id);
+        }
+        let result = comments::update_comment(
+            State(fx.state.clone()),
+            Extension(bob.user.clone()),
+            Path(comment.id.clone()),
+            Json(UpdateCommentRequest { content: "Launch never".to_string() }),
+        )
+        .await;
+        assert!(refused(result));
+        let result = sprints::list_sprints(
+            State(fx.state.clone()),
+            Extension(bob.user.clone()),
+            Path(fx.project.id.to_string()),
+            Query(sprints::ListSprintsQuery { status: None }),
+        )
+        .await;
+        assert!(refused(result));
+
+        // Lists and searches only hold the caller's own tickets
+        let list = |pairs: Vec<(String, String)>| {
+            tickets::list_tickets(
+                State(fx.state.clone()),
+                Extension(bob.user.clone()),
+                Query(pairs),
+            )
+        };
+        let Json(listed) = list(Vec::new()).await.unwrap();
+        let listed: Vec<String> = listed.tickets.into_iter().map(|t| t.id).collect();
+        assert_eq!(listed, [bobs.id.to_string()]);
+        let pairs = vec![("project_id".to_string(), fx.project.id.to_string())];
+        assert!(refused(list(pairs).await));
+
+        let query = serde_json::from_value(serde_json::json!({ "q": "secret" })).unwrap();
+        let Json(found) = search::search_tickets(
+            State(fx.state.clone()),
+            Extension(bob.user.clone()),
+            Query(query),
+        )
+        .await
+        .unwrap();
+        let found: Vec<Uuid> = found.tickets.into_iter().map(|t| t.id).collect();
+        assert_eq!(found, [bobs.id]);
+        let query = serde_json::from_value(serde_json::json!({
+            "q": "secret",
+            "project_id": fx.project.id,
+        }))
+        .unwrap();
+        let result = search::search_tickets(
+            State(fx.state.clone()),
+            Extension(bob.user.clone()),
+            Query(query),
+        )
+        .await;
+        assert!(refused(result));
+    }
+}
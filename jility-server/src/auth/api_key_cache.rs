@@ -0,0 +1,181 @@
+//! API key verification cache
+//!
+//! Agents call the API in tight loops with the same key. Verified keys are
+//! remembered for a short while so those calls skip the key and user lookups,
+//! and `last_used_at` is recorded in memory and written back in batches
+//! instead of once per request.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use jility_core::{api_key, ApiKey};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+use super::AuthUser;
+
+/// How long a verified key is trusted without going back to the database.
+/// Revocations on this server take effect immediately; this bounds how long
+/// other server instances, or a deactivated user, keep working.
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// How often buffered `last_used_at` timestamps are written back
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+struct CachedKey {
+    key_id: Uuid,
+    user: AuthUser,
+    expires_at: Option<DateTime<Utc>>,
+    verified_at: Instant,
+}
+
+#[derive(Default)]
+pub struct ApiKeyCache {
+    /// Verified keys by digest
+    keys: RwLock<HashMap<String, CachedKey>>,
+    /// Pending `last_used_at` updates by key ID
+    last_used: Mutex<HashMap<Uuid, DateTime<Utc>>>,
+}
+
+impl ApiKeyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The user a key digest was verified for, if it is still fresh.
+    /// Records the use of the key.
+    pub async fn get(&self, digest: &str) -> Option<AuthUser> {
+        let (key_id, user) = {
+            let keys = self.keys.read().await;
+            let cached = keys.get(digest)?;
+            if cached.verified_at.elapsed() > CACHE_TTL
+                || cached.expires_at.is_some_and(|at| at < Utc::now())
+            {
+                return None;
+            }
+            (cached.key_id, cached.user.clone())
+        };
+
+        self.record_use(key_id).await;
+        Some(user)
+    }
+
+    pub async fn insert(
+        &self,
+        digest: String,
+        key_id: Uuid,
+        user: AuthUser,
+        expires_at: Option<DateTime<Utc>>,
+    ) {
+        let mut keys = self.keys.write().await;
+        keys.retain(|_, cached| cached.verified_at.elapsed() <= CACHE_TTL);
+        keys.insert(
+            digest,
+            CachedKey {
+                key_id,
+                user,
+                expires_at,
+                verified_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Forget a key, e.g. after it is revoked
+    pub async fn invalidate(&self, key_id: Uuid) {
+        self.keys
+            .write()
+            .await
+            .retain(|_, cached| cached.key_id != key_id);
+        self.last_used.lock().await.remove(&key_id);
+    }
+
+    pub async fn record_use(&self, key_id: Uuid) {
+        self.last_used.lock().await.insert(key_id, Utc::now());
+    }
+
+    /// Write buffered `last_used_at` timestamps in one transaction, returning
+    /// how many keys were updated
+    pub async fn flush_last_used(&self, db: &DatabaseConnection) -> Result<usize, DbErr> {
+        let pending = std::mem::take(&mut *self.last_used.lock().await);
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let txn = db.begin().await?;
+        for (key_id, used_at) in &pending {
+            ApiKey::update_many()
+                .col_expr(
+                    api_key::Column::LastUsedAt,
+                    Expr::value(Some(DateTime::<chrono::FixedOffset>::from(*used_at))),
+                )
+                .filter(api_key::Column::Id.eq(*key_id))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+
+        Ok(pending.len())
+    }
+
+    /// Periodically flush `last_used_at` updates in the background
+    pub fn spawn_flush_task(self: Arc<Self>, db: Arc<DatabaseConnection>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.flush_last_used(&db).await {
+                    tracing::warn!("Failed to record API key usage: {}", e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> AuthUser {
+        AuthUser {
+            id: Uuid::new_v4(),
+            email: "agent@example.com".to_string(),
+            username: "agent".to_string(),
+            full_name: None,
+            scopes: Some(vec![]),
+            api_key_name: Some("agent".to_string()),
+            api_key_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_until_invalidated() {
+        let cache = ApiKeyCache::new();
+        let key_id = Uuid::new_v4();
+        cache.insert("digest".to_string(), key_id, user(), None).await;
+
+        assert!(cache.get("digest").await.is_some());
+        assert!(cache.get("other").await.is_none());
+        assert!(cache.last_used.lock().await.contains_key(&key_id));
+
+        cache.invalidate(key_id).await;
+        assert!(cache.get("digest").await.is_none());
+        assert!(cache.last_used.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cache_respects_key_expiry() {
+        let cache = ApiKeyCache::new();
+        let expired = Utc::now() - chrono::Duration::seconds(1);
+        cache
+            .insert("digest".to_string(), Uuid::new_v4(), user(), Some(expired))
+            .await;
+
+        assert!(cache.get("digest").await.is_none());
+    }
+}
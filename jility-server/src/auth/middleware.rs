@@ -6,13 +6,13 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use jility_core::{ApiKey, Session, User};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
 
 use crate::error::AppError;
 use crate::state::AppState;
 
+use super::scope::{required_scope, Scope};
 use super::service::AuthService;
 
 /// Authenticated user information that gets added to request extensions
@@ -22,6 +22,37 @@ pub struct AuthUser {
     pub email: String,
     pub username: String,
     pub full_name: Option<String>,
+    /// Scopes of the API key used to authenticate; `None` for session logins
+    #[serde(skip)]
+    pub scopes: Option<Vec<Scope>>,
+    /// Name of the API key used to authenticate; `None` for session logins
+    #[serde(skip)]
+    pub api_key_name: Option<String>,
+    /// ID of the API key used to authenticate; `None` for session logins
+    #[serde(skip)]
+    pub api_key_id: Option<uuid::Uuid>,
+}
+
+impl AuthUser {
+    /// `MissingScope` if the caller authenticated with an API key lacking `scope`
+    pub fn require_scope(&self, scope: Scope) -> Result<(), AppError> {
+        match &self.scopes {
+            Some(scopes) if !scopes.iter().any(|s| s.grants(scope)) => {
+                Err(AppError::MissingScope(scope.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Who to record in audit fields (`changed_by`, `author`, `created_by`, ...).
+    /// Requests made with an API key are attributed to `username (key name)`, so
+    /// the activity log tells a user's agents apart from the user.
+    pub fn actor(&self) -> String {
+        match &self.api_key_name {
+            Some(key_name) => format!("{} ({})", self.username, key_name),
+            None => self.username.clone(),
+        }
+    }
 }
 
 impl From<jility_core::UserModel> for AuthUser {
@@ -31,6 +62,9 @@ impl From<jility_core::UserModel> for AuthUser {
             email: user.email,
             username: user.username,
             full_name: user.full_name,
+            scopes: None,
+            api_key_name: None,
+            api_key_id: None,
         }
     }
 }
@@ -46,26 +80,33 @@ pub async fn auth_middleware(
         .get(AUTHORIZATION)
         .and_then(|h| h.to_str().ok());
 
-    let user = match auth_header {
+    let user = authenticate(auth_header, &state).await?;
+
+    if let Some(scope) = required_scope(req.method(), req.uri().path()) {
+        user.require_scope(scope)?;
+    }
+
+    // Add user to request extensions
+    req.extensions_mut().insert(user);
+
+    Ok(next.run(req).await)
+}
+
+/// Resolve an `Authorization` header value (`Bearer <jwt>` or `ApiKey <key>`) to a user
+pub async fn authenticate(header: Option<&str>, state: &AppState) -> Result<AuthUser, AppError> {
+    match header {
         Some(header) if header.starts_with("Bearer ") => {
             let token = &header[7..];
-            validate_jwt_token(token, &state).await?
+            validate_jwt_token(token, state).await
         }
         Some(header) if header.starts_with("ApiKey ") => {
             let key = &header[7..];
-            validate_api_key(key, &state.db).await?
+            validate_api_key(key, state).await
         }
-        _ => {
-            return Err(AppError::Unauthorized(
-                "Missing or invalid authorization header".to_string(),
-            ))
-        }
-    };
-
-    // Add user to request extensions
-    req.extensions_mut().insert(user);
-
-    Ok(next.run(req).await)
+        _ => Err(AppError::Unauthorized(
+            "Missing or invalid authorization header".to_string(),
+        )),
+    }
 }
 
 /// Validate JWT token and return user
@@ -124,33 +165,23 @@ async fn validate_jwt_token(token: &str, state: &AppState) -> Result<AuthUser, A
 }
 
 /// Validate API key and return user
-async fn validate_api_key(
-    key: &str,
-    db: &Arc<DatabaseConnection>,
-) -> Result<AuthUser, AppError> {
-    // Get key prefix
-    let prefix = AuthService::get_key_prefix(key)
-        .map_err(|e| AppError::Unauthorized(format!("Invalid API key format: {}", e)))?;
+async fn validate_api_key(key: &str, state: &AppState) -> Result<AuthUser, AppError> {
+    let digest = state.auth_service.digest_api_key(key);
 
-    // Find API key by prefix
-    let api_keys = ApiKey::find()
-        .filter(jility_core::api_key::Column::Prefix.eq(&prefix))
-        .all(db.as_ref())
-        .await
-        .map_err(|e| AppError::Database(e))?;
-
-    // Try to find matching key
-    let auth_service = AuthService::new("".to_string()); // Only used for verification
-    let mut matched_key: Option<jility_core::ApiKeyModel> = None;
-
-    for api_key in api_keys {
-        if auth_service.verify_password(key, &api_key.key_hash).unwrap_or(false) {
-            matched_key = Some(api_key);
-            break;
-        }
+    if let Some(user) = state.api_key_cache.get(&digest).await {
+        return Ok(user);
     }
 
-    let api_key = matched_key.ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()))?;
+    // Find API key by digest, falling back to keys created before digests
+    let api_key = ApiKey::find()
+        .filter(jility_core::api_key::Column::KeyHash.eq(&digest))
+        .one(state.db.as_ref())
+        .await
+        .map_err(AppError::Database)?;
+    let api_key = match api_key {
+        Some(api_key) => api_key,
+        None => upgrade_legacy_api_key(key, &digest, state).await?,
+    };
 
     // Check if key is revoked
     if api_key.revoked_at.is_some() {
@@ -164,23 +195,9 @@ async fn validate_api_key(
         }
     }
 
-    // Update last_used_at (fire and forget)
-    let api_key_id = api_key.id;
-    let db_clone = Arc::clone(db);
-    tokio::spawn(async move {
-        use sea_orm::ActiveModelTrait;
-        use sea_orm::Set;
-
-        if let Ok(Some(key)) = ApiKey::find_by_id(api_key_id).one(&*db_clone).await {
-            let mut active_key: jility_core::api_key::ActiveModel = key.into();
-            active_key.last_used_at = Set(Some(chrono::Utc::now().into()));
-            let _ = active_key.update(&*db_clone).await;
-        }
-    });
-
     // Fetch user
     let user = User::find_by_id(api_key.user_id)
-        .one(db.as_ref())
+        .one(state.db.as_ref())
         .await
         .map_err(|e| AppError::Database(e))?
         .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
@@ -192,5 +209,60 @@ async fn validate_api_key(
         ));
     }
 
-    Ok(user.into())
+    let mut user: AuthUser = user.into();
+    user.scopes = Some(
+        serde_json::from_str::<Vec<String>>(&api_key.scopes)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|scope| scope.parse().ok())
+            .collect(),
+    );
+    user.api_key_name = Some(api_key.name.clone());
+    user.api_key_id = Some(api_key.id);
+
+    state
+        .api_key_cache
+        .insert(
+            digest,
+            api_key.id,
+            user.clone(),
+            api_key.expires_at.map(Into::into),
+        )
+        .await;
+    state.api_key_cache.record_use(api_key.id).await;
+
+    Ok(user)
+}
+
+/// Keys created before digests were introduced are stored as bcrypt hashes.
+/// Verify against those by prefix and, on a match, replace the hash with the
+/// key's digest so later requests take the direct lookup.
+async fn upgrade_legacy_api_key(
+    key: &str,
+    digest: &str,
+    state: &AppState,
+) -> Result<jility_core::ApiKeyModel, AppError> {
+    let prefix = AuthService::get_key_prefix(key)
+        .map_err(|e| AppError::Unauthorized(format!("Invalid API key format: {}", e)))?;
+
+    let api_keys = ApiKey::find()
+        .filter(jility_core::api_key::Column::Prefix.eq(&prefix))
+        .all(state.db.as_ref())
+        .await
+        .map_err(AppError::Database)?;
+
+    let api_key = api_keys
+        .into_iter()
+        .filter(|api_key| AuthService::is_legacy_key_hash(&api_key.key_hash))
+        .find(|api_key| {
+            state
+                .auth_service
+                .verify_password(key, &api_key.key_hash)
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()))?;
+
+    let mut active_key: jility_core::api_key::ActiveModel = api_key.into();
+    active_key.key_hash = Set(digest.to_string());
+    Ok(active_key.update(state.db.as_ref()).await?)
 }
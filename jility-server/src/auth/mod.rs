@@ -1,5 +1,10 @@
+pub mod access;
+pub mod api_key_cache;
+pub mod scope;
 pub mod service;
 pub mod middleware;
 
+pub use api_key_cache::ApiKeyCache;
 pub use service::{AuthService, Claims};
-pub use middleware::{auth_middleware, AuthUser};
+pub use middleware::{auth_middleware, authenticate, AuthUser};
+pub use scope::Scope;
@@ -0,0 +1,183 @@
+//! API key scopes
+//!
+//! Session logins carry the user's full permissions; API keys only get the
+//! scopes chosen when they were created. A `*:write` scope includes the
+//! matching `*:read` scope, and `admin` includes everything.
+
+use std::fmt;
+use std::str::FromStr;
+
+use axum::http::Method;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// Read projects, tickets, comments, attachments, epics, history, dependencies and templates
+    TicketsRead,
+    /// Create and update tickets, comments, attachments, dependencies, templates and commit links
+    TicketsWrite,
+    /// Delete tickets, comments, attachments and templates
+    TicketsDelete,
+    SprintsRead,
+    SprintsWrite,
+    /// Create, update and delete projects
+    ProjectsWrite,
+    /// Workspaces, members, invites, webhooks, imports, sessions and API keys
+    Admin,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 7] = [
+        Scope::TicketsRead,
+        Scope::TicketsWrite,
+        Scope::TicketsDelete,
+        Scope::SprintsRead,
+        Scope::SprintsWrite,
+        Scope::ProjectsWrite,
+        Scope::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::TicketsRead => "tickets:read",
+            Scope::TicketsWrite => "tickets:write",
+            Scope::TicketsDelete => "tickets:delete",
+            Scope::SprintsRead => "sprints:read",
+            Scope::SprintsWrite => "sprints:write",
+            Scope::ProjectsWrite => "projects:write",
+            Scope::Admin => "admin",
+        }
+    }
+
+    /// Whether a key holding `self` may perform an action that requires `required`
+    pub fn grants(self, required: Scope) -> bool {
+        self == required
+            || self == Scope::Admin
+            || matches!(
+                (self, required),
+                (Scope::TicketsWrite, Scope::TicketsRead)
+                    | (Scope::SprintsWrite, Scope::SprintsRead)
+            )
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Scope::ALL.iter().map(Scope::as_str).collect();
+                format!("Unknown scope '{}'. Valid scopes: {}", s, valid.join(", "))
+            })
+    }
+}
+
+/// The scope an API key needs to call `method path`, or `None` if any
+/// authenticated caller may use the route
+pub fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let read = *method == Method::GET;
+
+    match segments.as_slice() {
+        ["api", "auth", "me" | "logout"] => None,
+        ["api", "auth", ..]
+        | ["api", "workspaces", ..]
+        | ["api", "invites", ..]
+        | ["api", "webhooks", ..]
+        | ["api", "projects", _, "import", ..] => Some(Scope::Admin),
+        ["api", "sprints", ..] | ["api", "projects", _, "sprints" | "sprint-history", ..] => {
+            Some(if read {
+                Scope::SprintsRead
+            } else {
+                Scope::SprintsWrite
+            })
+        }
+        ["api", "projects", ..] if !read => Some(Scope::ProjectsWrite),
+        ["api", "tickets" | "comments" | "attachments" | "templates", _]
+            if *method == Method::DELETE =>
+        {
+            Some(Scope::TicketsDelete)
+        }
+        _ => Some(if read {
+            Scope::TicketsRead
+        } else {
+            Scope::TicketsWrite
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope_by_route() {
+        assert_eq!(
+            required_scope(&Method::GET, "/api/tickets/JIL-1"),
+            Some(Scope::TicketsRead)
+        );
+        assert_eq!(
+            required_scope(&Method::PUT, "/api/tickets/JIL-1"),
+            Some(Scope::TicketsWrite)
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/tickets/JIL-1"),
+            Some(Scope::TicketsDelete)
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/attachments/a"),
+            Some(Scope::TicketsDelete)
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/api/projects/p/import/jira"),
+            Some(Scope::Admin)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/api/workspaces/acme/export"),
+            Some(Scope::Admin)
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/tickets/JIL-1/dependencies/JIL-2"),
+            Some(Scope::TicketsWrite)
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/api/projects/p/sprints"),
+            Some(Scope::SprintsWrite)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/api/projects/p"),
+            Some(Scope::TicketsRead)
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/projects/p"),
+            Some(Scope::ProjectsWrite)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/api/webhooks/w/deliveries"),
+            Some(Scope::Admin)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/api/auth/api-keys"),
+            Some(Scope::Admin)
+        );
+        assert_eq!(required_scope(&Method::GET, "/api/auth/me"), None);
+    }
+
+    #[test]
+    fn test_write_grants_read() {
+        assert!(Scope::TicketsWrite.grants(Scope::TicketsRead));
+        assert!(!Scope::TicketsWrite.grants(Scope::TicketsDelete));
+        assert!(!Scope::TicketsRead.grants(Scope::TicketsWrite));
+        assert!(Scope::Admin.grants(Scope::SprintsWrite));
+        assert_eq!("sprints:write".parse(), Ok(Scope::SprintsWrite));
+        assert!("tickets:everything".parse::<Scope>().is_err());
+    }
+}
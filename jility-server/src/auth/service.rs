@@ -1,7 +1,9 @@
 use anyhow::{anyhow, Result};
 use bcrypt::{hash, verify, DEFAULT_COST};
+use hmac::{Hmac, Mac};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use uuid::Uuid;
 
 const JWT_EXPIRATION_DAYS: i64 = 7;
@@ -9,6 +11,7 @@ const JWT_EXPIRATION_DAYS: i64 = 7;
 #[derive(Clone)]
 pub struct AuthService {
     jwt_secret: String,
+    api_key_secret: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -19,8 +22,20 @@ pub struct Claims {
 }
 
 impl AuthService {
+    /// API key digests are keyed with `jwt_secret` too, so rotating it
+    /// invalidates every API key unless [`Self::with_api_key_secret`] is used
     pub fn new(jwt_secret: String) -> Self {
-        Self { jwt_secret }
+        Self {
+            api_key_secret: jwt_secret.clone(),
+            jwt_secret,
+        }
+    }
+
+    /// Key API key digests with a separate secret. Only then can the JWT
+    /// secret be rotated without invalidating every API key.
+    pub fn with_api_key_secret(mut self, api_key_secret: String) -> Self {
+        self.api_key_secret = api_key_secret;
+        self
     }
 
     /// Hash a password using bcrypt
@@ -65,7 +80,7 @@ impl AuthService {
     }
 
     /// Generate an API key with the format "jil_live_" + 32 random characters
-    /// Returns (key, hash) tuple
+    /// Returns (key, digest) tuple
     pub fn generate_api_key(&self) -> Result<(String, String)> {
         use rand::Rng;
         const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
@@ -80,9 +95,24 @@ impl AuthService {
             .collect();
 
         let key = format!("jil_live_{}", random_part);
-        let key_hash = self.hash_password(&key)?;
+        let key_digest = self.digest_api_key(&key);
+
+        Ok((key, key_digest))
+    }
+
+    /// HMAC-SHA256 of an API key, stored in `api_key.key_hash` and used to look
+    /// the key up directly. Keys carry 190 bits of randomness, so a slow
+    /// password hash adds nothing but latency.
+    pub fn digest_api_key(&self, key: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_key_secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(key.as_bytes());
+        format!("{:x}", mac.finalize().into_bytes())
+    }
 
-        Ok((key, key_hash))
+    /// Whether a stored key hash predates digests and must be checked with bcrypt
+    pub fn is_legacy_key_hash(key_hash: &str) -> bool {
+        key_hash.starts_with("$2")
     }
 
     /// Get prefix from API key (first 8 chars after "jil_live_")
@@ -137,12 +167,21 @@ mod tests {
     fn test_api_key_generation() {
         let service = AuthService::new("test_secret".to_string());
 
-        let (key, hash) = service.generate_api_key().unwrap();
+        let (key, digest) = service.generate_api_key().unwrap();
         assert!(key.starts_with("jil_live_"));
         assert_eq!(key.len(), 9 + 32); // "jil_live_" + 32 chars
 
-        // Verify the hash
-        assert!(service.verify_password(&key, &hash).unwrap());
+        // Verify the digest
+        assert_eq!(digest, service.digest_api_key(&key));
+        assert!(!AuthService::is_legacy_key_hash(&digest));
+        assert!(AuthService::is_legacy_key_hash(
+            &service.hash_password(&key).unwrap()
+        ));
+
+        // Digests depend on the server secret
+        let other = AuthService::new("test_secret".to_string())
+            .with_api_key_secret("other_secret".to_string());
+        assert_ne!(digest, other.digest_api_key(&key));
 
         // Test prefix extraction
         let prefix = AuthService::get_key_prefix(&key).unwrap();
@@ -0,0 +1,95 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::fs;
+use uuid::Uuid;
+
+use super::{is_key, BlobError, BlobStore};
+
+/// Blobs as files under a directory, fanned out by the key's first two
+/// characters
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Where the blob under `key` lives. Anything but a hex digest is
+    /// refused, so no key can name a file outside the root.
+    fn path(&self, key: &str) -> Result<PathBuf, BlobError> {
+        if !is_key(key) {
+            return Err(BlobError::InvalidKey(key.to_string()));
+        }
+        Ok(self.root.join(&key[..2]).join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), BlobError> {
+        let path = self.path(key)?;
+        let dir = path.parent().expect("blob paths have a shard directory");
+        fs::create_dir_all(dir).await?;
+
+        // Write then rename, so readers never see a partial blob
+        let tmp = dir.join(format!(".{}.tmp", Uuid::new_v4()));
+        fs::write(&tmp, data).await?;
+        if let Err(e) = fs::rename(&tmp, &path).await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, BlobError> {
+        match fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, BlobError> {
+        Ok(fs::try_exists(self.path(key)?).await?)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobError> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_refuses_keys_outside_the_root() {
+        let dir = std::env::temp_dir().join(format!("jility-blobs-{}", Uuid::new_v4()));
+        let store = LocalStore::new(dir.join("blobs"));
+        fs::create_dir_all(&dir).await.unwrap();
+        fs::write(dir.join("secret"), b"secret").await.unwrap();
+
+        for key in ["../secret", "../../secret", "/etc/passwd", "ab/../../secret", "ABC"] {
+            assert!(matches!(store.get(key).await, Err(BlobError::InvalidKey(_))), "{}", key);
+            assert!(matches!(store.exists(key).await, Err(BlobError::InvalidKey(_))), "{}", key);
+            assert!(matches!(store.delete(key).await, Err(BlobError::InvalidKey(_))), "{}", key);
+            assert!(matches!(
+                store.put(key, b"x".to_vec()).await,
+                Err(BlobError::InvalidKey(_))
+            ));
+        }
+        assert!(fs::try_exists(dir.join("secret")).await.unwrap());
+
+        let key = "a".repeat(64);
+        store.put(&key, b"blob".to_vec()).await.unwrap();
+        assert_eq!(store.get(&key).await.unwrap(), Some(b"blob".to_vec()));
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+//! Storage for attachment contents
+//!
+//! Blobs are immutable and keyed by the hex SHA-256 of their content, so an
+//! upload that matches an existing blob is stored once. [`LocalStore`] keeps
+//! them under a directory, `.jility/attachments` by default; [`S3Store`] talks
+//! to any S3-compatible service (AWS S3, MinIO, R2, ...). [`from_env`] picks
+//! one from the environment.
+
+mod local;
+mod s3;
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+pub use local::LocalStore;
+pub use s3::{S3Config, S3Store};
+
+/// Used when `ATTACHMENTS_DIR` isn't set
+pub const DEFAULT_DIR: &str = ".jility/attachments";
+
+#[derive(Debug, thiserror::Error)]
+pub enum BlobError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The object store answered with an unexpected status
+    #[error("blob store returned {status}: {body}")]
+    Status { status: u16, body: String },
+
+    /// The key isn't a hex SHA-256 digest
+    #[error("invalid blob key: {0:?}")]
+    InvalidKey(String),
+}
+
+/// Whether `key` is a blob key: 64 lowercase hex digits
+pub fn is_key(key: &str) -> bool {
+    key.len() == 64 && key.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Store `data` under `key`, replacing any blob already there
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), BlobError>;
+
+    /// The blob under `key`, or `None` if there is none
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, BlobError>;
+
+    async fn exists(&self, key: &str) -> Result<bool, BlobError>;
+
+    /// Remove the blob under `key`; removing a missing blob succeeds
+    async fn delete(&self, key: &str) -> Result<(), BlobError>;
+}
+
+/// The S3 store when `ATTACHMENTS_S3_BUCKET` is set, otherwise the local
+/// store under `ATTACHMENTS_DIR`
+pub fn from_env() -> anyhow::Result<Arc<dyn BlobStore>> {
+    let Ok(bucket) = std::env::var("ATTACHMENTS_S3_BUCKET") else {
+        let dir = std::env::var("ATTACHMENTS_DIR").unwrap_or_else(|_| DEFAULT_DIR.to_string());
+        tracing::info!("Storing attachments in {}", dir);
+        return Ok(Arc::new(LocalStore::new(dir)));
+    };
+
+    let region = std::env::var("ATTACHMENTS_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+    let config = S3Config {
+        endpoint: std::env::var("ATTACHMENTS_S3_ENDPOINT")
+            .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", region)),
+        bucket,
+        region,
+        access_key: std::env::var("ATTACHMENTS_S3_ACCESS_KEY")
+            .context("ATTACHMENTS_S3_ACCESS_KEY must be set with ATTACHMENTS_S3_BUCKET")?,
+        secret_key: std::env::var("ATTACHMENTS_S3_SECRET_KEY")
+            .context("ATTACHMENTS_S3_SECRET_KEY must be set with ATTACHMENTS_S3_BUCKET")?,
+    };
+    tracing::info!("Storing attachments in bucket {} at {}", config.bucket, config.endpoint);
+    Ok(Arc::new(S3Store::new(config)?))
+}
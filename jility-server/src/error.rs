@@ -6,6 +6,8 @@ use axum::{
 use serde::Serialize;
 use thiserror::Error;
 
+use jility_core::policy::PolicyViolation;
+
 #[derive(Debug, Error)]
 pub enum ApiError {
     #[error("Resource not found: {0}")]
@@ -31,6 +33,21 @@ pub enum ApiError {
 
     #[error("Forbidden: {0}")]
     Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// An API key lacks the scope a route requires
+    #[error("API key is missing the '{0}' scope")]
+    MissingScope(String),
+
+    /// Ticket numbers along the cycle, starting and ending at the same ticket
+    #[error("Dependency would create a cycle: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
+
+    /// The change breaks one of the project's policies
+    #[error("Policy violation: {0}")]
+    PolicyViolation(PolicyViolation),
 }
 
 impl From<jility_core::CoreError> for ApiError {
@@ -39,6 +56,9 @@ impl From<jility_core::CoreError> for ApiError {
             jility_core::CoreError::NotFound(msg) => ApiError::NotFound(msg),
             jility_core::CoreError::InvalidInput(msg) => ApiError::InvalidInput(msg),
             jility_core::CoreError::Database(err) => ApiError::Database(err),
+            jility_core::CoreError::PolicyViolation(violation) => {
+                ApiError::PolicyViolation(violation)
+            }
             jility_core::CoreError::Serialization(err) => {
                 ApiError::Internal(format!("Serialization error: {}", err))
             }
@@ -46,6 +66,12 @@ impl From<jility_core::CoreError> for ApiError {
     }
 }
 
+impl From<crate::blob_store::BlobError> for ApiError {
+    fn from(err: crate::blob_store::BlobError) -> Self {
+        ApiError::Internal(format!("Blob store error: {}", err))
+    }
+}
+
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
@@ -66,12 +92,27 @@ impl IntoResponse for ApiError {
             }
             ApiError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "unauthorized"),
             ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "forbidden"),
+            ApiError::Conflict(_) => (StatusCode::CONFLICT, "conflict"),
+            ApiError::MissingScope(_) => (StatusCode::FORBIDDEN, "insufficient_scope"),
+            ApiError::DependencyCycle(_) => (StatusCode::CONFLICT, "dependency_cycle"),
+            ApiError::PolicyViolation(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "policy_violation")
+            }
+        };
+
+        let details = match &self {
+            ApiError::DependencyCycle(cycle) => Some(serde_json::json!({ "cycle": cycle })),
+            ApiError::MissingScope(scope) => {
+                Some(serde_json::json!({ "required_scope": scope }))
+            }
+            ApiError::PolicyViolation(violation) => serde_json::to_value(violation).ok(),
+            _ => None,
         };
 
         let body = Json(ErrorResponse {
             error: error_type.to_string(),
             message: self.to_string(),
-            details: None,
+            details,
         });
 
         (status, body).into_response()
@@ -0,0 +1,612 @@
+//! Applying import plans
+//!
+//! [`Import::check`] resolves a plan read by `jility_core::import` against
+//! the target project: issues an earlier import already created, found by
+//! alias or by their ID in the source, are skipped except for comments not
+//! imported yet, links to them resolve through their aliases, and sprints
+//! are matched by name. The result is reported as a dry run, or written in one transaction
+//! by [`Import::apply`].
+//!
+//! Imported tickets and comments keep their original dates and don't notify
+//! watchers or mentioned users, broadcast events or run project policies;
+//! they record what the source tracker already decided.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use jility_core::entities::{
+    comment, project, sprint, sprint_ticket, ticket, ticket_alias, ticket_assignee, ticket_change,
+    ticket_label, ChangeType, Comment, Sprint, Ticket, TicketAlias,
+};
+use jility_core::import::{ImportPlan, ImportedComment, ImportedIssue, Severity};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    QueryFilter, Set,
+};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::models::{ImportReport, ImportedTicketResponse};
+
+pub struct Import {
+    project: project::Model,
+    plan: ImportPlan,
+    /// Keys of the plan's issues an earlier import already created, with
+    /// their ticket if it still exists
+    earlier: HashMap<String, Option<Target>>,
+    /// Indexes of the comments to add to each issue: all of a new issue's,
+    /// and those of an earlier one that weren't imported yet
+    comments: HashMap<String, Vec<usize>>,
+    /// IDs of the tickets to create, by key
+    ids: HashMap<String, Uuid>,
+    /// Epic and parent of each issue to create, by key
+    links: HashMap<String, (Option<Uuid>, Option<Uuid>)>,
+    /// The project's sprints, by name
+    sprints: HashMap<String, Uuid>,
+    /// Sprints to create, in order of first use
+    new_sprints: Vec<String>,
+}
+
+/// A ticket a link can point at
+#[derive(Debug, Clone, Copy)]
+struct Target {
+    id: Uuid,
+    is_epic: bool,
+}
+
+impl Import {
+    pub async fn check(
+        db: &DatabaseConnection,
+        project: project::Model,
+        mut plan: ImportPlan,
+    ) -> ApiResult<Self> {
+        let tickets: HashMap<Uuid, bool> = Ticket::find()
+            .filter(ticket::Column::ProjectId.eq(project.id))
+            .filter(ticket::Column::DeletedAt.is_null())
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|t| (t.id, t.is_epic))
+            .collect();
+        let aliases = TicketAlias::find()
+            .filter(ticket_alias::Column::ProjectId.eq(project.id))
+            .all(db)
+            .await?;
+        let target = |alias: &ticket_alias::Model| {
+            tickets.get(&alias.ticket_id).map(|&is_epic| Target {
+                id: alias.ticket_id,
+                is_epic,
+            })
+        };
+        let existing: HashMap<&str, Option<Target>> = aliases
+            .iter()
+            .map(|alias| (alias.alias.as_str(), target(alias)))
+            .collect();
+        // Issue IDs outlast keys, which change when an issue moves
+        let by_source_id: HashMap<&str, Option<Target>> = aliases
+            .iter()
+            .filter(|alias| alias.source == plan.source)
+            .filter_map(|alias| Some((alias.source_id.as_deref()?, target(alias))))
+            .collect();
+        let earlier: HashMap<String, Option<Target>> = plan
+            .issues
+            .iter()
+            .filter_map(|issue| {
+                let found = existing.get(issue.key.as_str()).or_else(|| {
+                    issue
+                        .source_id
+                        .as_deref()
+                        .and_then(|id| by_source_id.get(id))
+                })?;
+                Some((issue.key.clone(), *found))
+            })
+            .collect();
+
+        let new: Vec<&ImportedIssue> = plan
+            .issues
+            .iter()
+            .filter(|issue| !earlier.contains_key(&issue.key))
+            .collect();
+        let ids: HashMap<String, Uuid> = new
+            .iter()
+            .map(|issue| (issue.key.clone(), Uuid::new_v4()))
+            .collect();
+        let targets: HashMap<&str, Target> = new
+            .iter()
+            .map(|issue| {
+                let target = Target {
+                    id: ids[&issue.key],
+                    is_epic: issue.is_epic,
+                };
+                (issue.key.as_str(), target)
+            })
+            .chain(
+                existing
+                    .iter()
+                    .chain(&by_source_id)
+                    .filter_map(|(&reference, target)| Some((reference, (*target)?))),
+            )
+            .collect();
+
+        let mut links = HashMap::new();
+        let mut dropped = Vec::new();
+        for issue in &new {
+            let mut epic_id = None;
+            let mut parent_id = None;
+            if let Some(epic) = &issue.epic {
+                match targets.get(epic.as_str()) {
+                    Some(target) if target.is_epic => epic_id = Some(target.id),
+                    Some(_) => {
+                        dropped.push((*issue, format!("{} isn't an epic; epic link dropped", epic)))
+                    }
+                    None => {
+                        dropped.push((*issue, format!("Epic {} not found; link dropped", epic)))
+                    }
+                }
+            }
+            if let Some(parent) = &issue.parent {
+                match targets.get(parent.as_str()) {
+                    Some(target) if !target.is_epic => parent_id = Some(target.id),
+                    Some(target) if epic_id.is_none() => epic_id = Some(target.id),
+                    Some(_) => {}
+                    None => {
+                        dropped.push((*issue, format!("Parent {} not found; link dropped", parent)))
+                    }
+                }
+            }
+            links.insert(issue.key.clone(), (epic_id, parent_id));
+        }
+
+        let sprints: HashMap<String, Uuid> = Sprint::find()
+            .filter(sprint::Column::ProjectId.eq(project.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|s| (s.name, s.id))
+            .collect();
+        let mut new_sprints: Vec<String> = Vec::new();
+        for name in new.iter().filter_map(|issue| issue.sprint.as_ref()) {
+            if !sprints.contains_key(name) && !new_sprints.contains(name) {
+                new_sprints.push(name.clone());
+            }
+        }
+
+        let mut comments: HashMap<String, Vec<usize>> = new
+            .iter()
+            .map(|issue| (issue.key.clone(), (0..issue.comments.len()).collect()))
+            .collect();
+        let live: HashMap<Uuid, &ImportedIssue> = plan
+            .issues
+            .iter()
+            .filter_map(|issue| Some((earlier.get(&issue.key).copied()??.id, issue)))
+            .collect();
+        let imported = Comment::find()
+            .filter(comment::Column::TicketId.is_in(live.keys().copied()))
+            .filter(comment::Column::AuthorUserId.is_null())
+            .all(db)
+            .await?;
+        let seen_ids: HashSet<(Uuid, &str)> = imported
+            .iter()
+            .filter_map(|c| Some((c.ticket_id, c.source_id.as_deref()?)))
+            .collect();
+        // Comments imported before their IDs were kept
+        let seen_content: HashSet<(Uuid, &str, &str)> = imported
+            .iter()
+            .filter(|c| c.source_id.is_none())
+            .map(|c| (c.ticket_id, c.author.as_str(), c.content.as_str()))
+            .collect();
+        for (&ticket_id, issue) in &live {
+            let unseen = issue
+                .comments
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| {
+                    !c.source_id
+                        .as_deref()
+                        .is_some_and(|id| seen_ids.contains(&(ticket_id, id)))
+                        && !seen_content.contains(&(ticket_id, c.author.as_str(), c.body.as_str()))
+                })
+                .map(|(i, _)| i)
+                .collect();
+            comments.insert(issue.key.clone(), unseen);
+        }
+
+        let dropped: Vec<_> = dropped
+            .into_iter()
+            .map(|(issue, message)| (issue.origin.clone(), issue.key.clone(), message))
+            .collect();
+        for (origin, key, message) in dropped {
+            plan.problem(Severity::Warning, &origin, Some(&key), message);
+        }
+
+        Ok(Self {
+            project,
+            plan,
+            earlier,
+            comments,
+            ids,
+            links,
+            sprints,
+            new_sprints,
+        })
+    }
+
+    fn is_new(&self, issue: &ImportedIssue) -> bool {
+        !self.earlier.contains_key(&issue.key)
+    }
+
+    /// The comments of `issue` to add
+    fn comments_of<'a>(
+        &'a self,
+        issue: &'a ImportedIssue,
+    ) -> impl Iterator<Item = &'a ImportedComment> {
+        self.comments
+            .get(&issue.key)
+            .into_iter()
+            .flatten()
+            .map(|&i| &issue.comments[i])
+    }
+
+    /// `InvalidInput` naming the first error, if the plan has any
+    pub fn check_errors(&self) -> ApiResult<()> {
+        let mut errors = self
+            .plan
+            .problems
+            .iter()
+            .filter(|p| p.severity == Severity::Error);
+        let Some(first) = errors.next() else {
+            return Ok(());
+        };
+        let location = match (&first.file, first.line, &first.key) {
+            (_, _, Some(key)) => format!("{}: ", key),
+            (Some(file), Some(line), None) => format!("{} line {}: ", file, line),
+            (None, Some(line), None) => format!("line {}: ", line),
+            (Some(file), None, None) => format!("{}: ", file),
+            (None, None, None) => String::new(),
+        };
+        Err(ApiError::InvalidInput(format!(
+            "Nothing was imported; the export has {} error(s). First: {}{} (a dry run reports them all)",
+            errors.count() + 1,
+            location,
+            first.message
+        )))
+    }
+
+    pub fn report(&self, dry_run: bool, tickets: Vec<ImportedTicketResponse>) -> ImportReport {
+        let new: Vec<&ImportedIssue> = self.plan.issues.iter().filter(|i| self.is_new(i)).collect();
+        let mut statuses = BTreeMap::new();
+        for issue in &new {
+            *statuses.entry(issue.status.clone()).or_default() += 1;
+        }
+        let (errors, warnings) = self
+            .plan
+            .problems
+            .iter()
+            .cloned()
+            .partition(|p| p.severity == Severity::Error);
+
+        ImportReport {
+            source: self.plan.source.clone(),
+            dry_run,
+            issues: self.plan.issues.len(),
+            created: new.len(),
+            epics: new.iter().filter(|i| i.is_epic).count(),
+            comments: self.comments.values().map(Vec::len).sum(),
+            skipped: self
+                .plan
+                .issues
+                .iter()
+                .filter(|i| !self.is_new(i))
+                .map(|i| i.key.clone())
+                .collect(),
+            new_sprints: self.new_sprints.clone(),
+            statuses,
+            errors,
+            warnings,
+            tickets,
+        }
+    }
+
+    /// Create the sprints and tickets, and add new comments to tickets of
+    /// earlier imports. Every change recorded for a ticket says which issue
+    /// it was imported from.
+    pub async fn apply(
+        &self,
+        txn: &DatabaseTransaction,
+        actor: &str,
+    ) -> ApiResult<Vec<ImportedTicketResponse>> {
+        let now = Utc::now();
+        let prefix = self.project.key.as_deref().unwrap_or("TASK");
+        let mut sprints = self.sprints.clone();
+        let mut created = Vec::new();
+
+        for issue in &self.plan.issues {
+            let message = format!("Imported from {} ({})", issue.key, self.plan.source);
+            if let Some(earlier) = self.earlier.get(&issue.key) {
+                if let Some(target) = earlier {
+                    for imported in self.comments_of(issue) {
+                        add_comment(txn, target.id, imported, now, actor, &message).await?;
+                    }
+                }
+                continue;
+            }
+
+            // Reserve the number first: see `project::next_ticket_number`
+            let ticket_number = project::next_ticket_number(txn, self.project.id).await?;
+
+            if let Some(name) = &issue.sprint {
+                if !sprints.contains_key(name) {
+                    let details = self.plan.sprint(name);
+                    let completed = details.is_some_and(|s| s.completed);
+                    let sprint = sprint::ActiveModel {
+                        id: Set(Uuid::new_v4()),
+                        project_id: Set(self.project.id),
+                        name: Set(name.clone()),
+                        goal: Set(details.and_then(|s| s.goal.clone())),
+                        start_date: Set(None),
+                        end_date: Set(details.and_then(|s| s.end_date)),
+                        status: Set(if completed { "completed" } else { "planning" }.to_string()),
+                        capacity: Set(None),
+                        created_at: Set(now),
+                        updated_at: Set(now),
+                    }
+                    .insert(txn)
+                    .await?;
+                    sprints.insert(name.clone(), sprint.id);
+                }
+            }
+
+            let ticket_id = self.ids[&issue.key];
+            let (epic_id, parent_id) = self.links[&issue.key];
+            let created_at = issue.created_at.unwrap_or(now);
+            let ticket = ticket::ActiveModel {
+                id: Set(ticket_id),
+                project_id: Set(self.project.id),
+                ticket_number: Set(ticket_number),
+                title: Set(issue.title.clone()),
+                description: Set(issue.description.clone()),
+                status: Set(issue.status.clone()),
+                story_points: Set(issue.story_points),
+                epic_id: Set(epic_id),
+                parent_id: Set(parent_id),
+                is_epic: Set(issue.is_epic),
+                epic_color: Set(None),
+                parent_epic_id: Set(None),
+                created_at: Set(created_at),
+                updated_at: Set(issue.updated_at.unwrap_or(created_at).max(created_at)),
+                deleted_at: Set(None),
+                created_by: Set(issue.reporter.clone().unwrap_or_else(|| actor.to_string())),
+            }
+            .insert(txn)
+            .await?;
+
+            let record =
+                |change_type: ChangeType, field: Option<&str>, new_value: String, changed_at| {
+                    ticket_change::ActiveModel {
+                        id: Set(Uuid::new_v4()),
+                        ticket_id: Set(ticket_id),
+                        change_type: Set(change_type.as_str().to_string()),
+                        field_name: Set(field.map(str::to_string)),
+                        old_value: Set(None),
+                        new_value: Set(Some(new_value)),
+                        changed_by: Set(actor.to_string()),
+                        changed_at: Set(changed_at),
+                        message: Set(Some(message.clone())),
+                    }
+                    .insert(txn)
+                };
+            record(
+                ChangeType::Imported,
+                None,
+                serde_json::to_string(&ticket).unwrap(),
+                created_at,
+            )
+            .await?;
+
+            for assignee in &issue.assignees {
+                ticket_assignee::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    ticket_id: Set(ticket_id),
+                    assignee: Set(assignee.clone()),
+                    assigned_at: Set(created_at),
+                    assigned_by: Set(actor.to_string()),
+                }
+                .insert(txn)
+                .await?;
+                record(
+                    ChangeType::AssigneeAdded,
+                    Some("assignee"),
+                    assignee.clone(),
+                    created_at,
+                )
+                .await?;
+            }
+
+            for label in &issue.labels {
+                ticket_label::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    ticket_id: Set(ticket_id),
+                    label: Set(label.clone()),
+                    created_at: Set(created_at),
+                }
+                .insert(txn)
+                .await?;
+                record(
+                    ChangeType::LabelAdded,
+                    Some("label"),
+                    label.clone(),
+                    created_at,
+                )
+                .await?;
+            }
+
+            if let Some(sprint_id) = issue.sprint.as_ref().map(|name| sprints[name]) {
+                sprint_ticket::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    sprint_id: Set(sprint_id),
+                    ticket_id: Set(ticket_id),
+                    added_at: Set(created_at),
+                    added_by: Set(actor.to_string()),
+                }
+                .insert(txn)
+                .await?;
+                record(
+                    ChangeType::AddedToSprint,
+                    None,
+                    sprint_id.to_string(),
+                    created_at,
+                )
+                .await?;
+            }
+
+            for imported in self.comments_of(issue) {
+                add_comment(txn, ticket_id, imported, created_at, actor, &message).await?;
+            }
+
+            ticket_alias::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                project_id: Set(self.project.id),
+                ticket_id: Set(ticket_id),
+                alias: Set(issue.key.clone()),
+                source: Set(self.plan.source.clone()),
+                source_id: Set(issue.source_id.clone()),
+                created_at: Set(now),
+            }
+            .insert(txn)
+            .await?;
+
+            created.push(ImportedTicketResponse {
+                key: issue.key.clone(),
+                id: ticket_id.to_string(),
+                number: format!("{}-{}", prefix, ticket_number),
+            });
+        }
+
+        Ok(created)
+    }
+}
+
+/// Add an imported comment to a ticket; `default_at` dates it when the
+/// source didn't
+async fn add_comment(
+    txn: &DatabaseTransaction,
+    ticket_id: Uuid,
+    imported: &ImportedComment,
+    default_at: DateTime<Utc>,
+    actor: &str,
+    message: &str,
+) -> ApiResult<()> {
+    let comment_at = imported.created_at.unwrap_or(default_at);
+    let comment = comment::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        ticket_id: Set(ticket_id),
+        parent_id: Set(None),
+        author: Set(imported.author.clone()),
+        author_user_id: Set(None),
+        content: Set(imported.body.clone()),
+        created_at: Set(comment_at),
+        updated_at: Set(imported.updated_at.filter(|at| *at > comment_at)),
+        deleted_at: Set(None),
+        deleted_by: Set(None),
+        source_id: Set(imported.source_id.clone()),
+    }
+    .insert(txn)
+    .await?;
+    ticket_change::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        ticket_id: Set(ticket_id),
+        change_type: Set(ChangeType::CommentAdded.as_str().to_string()),
+        field_name: Set(Some("comment".to_string())),
+        old_value: Set(None),
+        new_value: Set(Some(comment.id.to_string())),
+        changed_by: Set(actor.to_string()),
+        changed_at: Set(comment_at),
+        message: Set(Some(message.to_string())),
+    }
+    .insert(txn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+    use jility_core::import::github;
+    use jility_core::workflow::Workflow;
+    use sea_orm::{sea_query::Expr, QueryOrder, TransactionTrait};
+
+    fn export(repository: &str, comments: &[u64]) -> ImportPlan {
+        let issue = format!(
+            r#"{{"number": 1, "id": 901, "title": "Crash on save", "state": "open",
+               "repository_url": "https://api.github.com/repos/{}"}}"#,
+            repository
+        );
+        let comments: Vec<String> = comments
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"{{"id": {id}, "issue_url": "https://api.github.com/repos/{repository}/issues/1",
+                       "user": {{"login": "octocat"}}, "body": "Comment {id}"}}"#
+                )
+            })
+            .collect();
+        let content = format!("[{}, {}]", issue, comments.join(", "));
+        github::plan(
+            [("issues.json", content.as_str())],
+            &github::GithubMapping::default(),
+            &Workflow::default(),
+        )
+    }
+
+    async fn import(fx: &Fixture, plan: ImportPlan) -> ImportReport {
+        let import = Import::check(fx.state.db.as_ref(), fx.project.clone(), plan)
+            .await
+            .unwrap();
+        let txn = fx.state.db.begin().await.unwrap();
+        let tickets = import.apply(&txn, "alice").await.unwrap();
+        txn.commit().await.unwrap();
+        import.report(false, tickets)
+    }
+
+    #[tokio::test]
+    async fn test_importing_again_adds_only_new_comments() {
+        let fx = Fixture::new().await;
+        let first = import(&fx, export("acme/app", &[10])).await;
+        assert_eq!((first.created, first.comments), (1, 1));
+
+        // Comments imported before their IDs were kept match by content
+        Comment::update_many()
+            .col_expr(
+                comment::Column::SourceId,
+                Expr::value(Option::<String>::None),
+            )
+            .exec(fx.state.db.as_ref())
+            .await
+            .unwrap();
+
+        // The repository was renamed since; the issue keeps its ID
+        let second = import(&fx, export("acme/renamed", &[10, 11])).await;
+        assert_eq!((second.created, second.comments), (0, 1));
+        assert_eq!(second.skipped, ["acme/renamed#1"]);
+
+        let third = import(&fx, export("acme/renamed", &[10, 11])).await;
+        assert_eq!((third.created, third.comments), (0, 0));
+
+        let comments: Vec<(String, Option<String>)> = Comment::find()
+            .order_by_asc(comment::Column::CreatedAt)
+            .all(fx.state.db.as_ref())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.content, c.source_id))
+            .collect();
+        assert_eq!(
+            comments,
+            [
+                ("Comment 10".to_string(), None),
+                ("Comment 11".to_string(), Some("11".to_string())),
+            ]
+        );
+    }
+}
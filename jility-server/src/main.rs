@@ -1,9 +1,17 @@
 mod api;
+mod archive;
+mod attachments;
 mod auth;
+mod blob_store;
 mod error;
+mod imports;
 mod models;
+mod notifications;
 mod services;
 mod state;
+#[cfg(test)]
+mod test_support;
+mod webhooks;
 mod websocket;
 
 use anyhow::Result;
@@ -16,7 +24,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
     api::api_routes,
-    auth::auth_middleware,
+    auth::{auth_middleware, AuthService},
     state::{connect_database, AppState},
     websocket::websocket_routes,
 };
@@ -54,8 +62,30 @@ async fn main() -> Result<()> {
             "insecure_default_secret_change_in_production".to_string()
         });
 
+    // API key digests are keyed separately when API_KEY_SECRET is set;
+    // otherwise rotating JWT_SECRET invalidates every API key
+    let mut auth_service = AuthService::new(jwt_secret);
+    match std::env::var("API_KEY_SECRET") {
+        Ok(api_key_secret) => auth_service = auth_service.with_api_key_secret(api_key_secret),
+        Err(_) => tracing::warn!(
+            "API_KEY_SECRET not set, digesting API keys with JWT_SECRET; rotating it will invalidate every API key"
+        ),
+    }
+
+    // Attachments go to S3 when a bucket is configured, else to disk
+    let blob_store = blob_store::from_env()?;
+    let attachment_limits = attachments::AttachmentLimits::from_env()?;
+
     // Create app state
-    let state = AppState::new(db, jwt_secret);
+    let state = AppState::new(db, auth_service).with_attachments(blob_store, attachment_limits);
+    state
+        .api_key_cache
+        .clone()
+        .spawn_flush_task(state.db.clone());
+    state
+        .webhooks
+        .clone()
+        .spawn_delivery_task(state.db.clone());
 
     // Build router
     let (public_routes, protected_routes) = api_routes();
@@ -1,4 +1,8 @@
-use serde::Deserialize;
+use std::collections::HashMap;
+
+use jility_core::entities::TemplateSubtask;
+use jility_core::workflow::{WorkflowStatus, WorkflowTransition};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 // Project requests
@@ -49,6 +53,7 @@ pub struct CreateTicketRequest {
 #[derive(Debug, Deserialize)]
 pub struct UpdateTicketRequest {
     pub title: Option<String>,
+    pub status: Option<String>,
     pub story_points: Option<i32>,
     pub parent_id: Option<Uuid>,
     pub epic_id: Option<Uuid>,
@@ -79,6 +84,8 @@ pub struct UnassignTicketRequest {
 #[derive(Debug, Deserialize)]
 pub struct CreateCommentRequest {
     pub content: String,
+    /// Reply to this comment on the same ticket
+    pub parent_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -86,10 +93,16 @@ pub struct UpdateCommentRequest {
     pub content: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AddReactionRequest {
+    pub emoji: String,
+}
+
 // Dependency requests
 #[derive(Debug, Deserialize)]
 pub struct AddDependencyRequest {
-    pub depends_on_id: Uuid,
+    /// UUID or ticket number (e.g., "JIL-42")
+    pub depends_on_id: String,
 }
 
 // Git integration requests
@@ -188,7 +201,195 @@ pub struct StartSprintRequest {
     pub end_date: String,
 }
 
+// Template requests
+#[derive(Debug, Deserialize)]
+pub struct CreateTemplateRequest {
+    /// Required unless project_id is given (the project's workspace is used)
+    pub workspace_id: Option<Uuid>,
+    /// Restrict the template to one project
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub title_template: String,
+    #[serde(default)]
+    pub description_template: String,
+    pub default_status: Option<String>,
+    pub default_story_points: Option<i32>,
+    #[serde(default)]
+    pub default_labels: Vec<String>,
+    #[serde(default)]
+    pub subtasks: Vec<TemplateSubtask>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTemplateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub title_template: Option<String>,
+    pub description_template: Option<String>,
+    pub default_status: Option<String>,
+    pub default_story_points: Option<i32>,
+    pub default_labels: Option<Vec<String>>,
+    pub subtasks: Option<Vec<TemplateSubtask>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstantiateTemplateRequest {
+    pub project_id: Uuid,
+    /// Values for `{{variable}}` placeholders; non-string values are rendered as JSON
+    #[serde(default)]
+    pub variables: HashMap<String, serde_json::Value>,
+    pub assignees: Option<Vec<String>>,
+    pub parent_id: Option<Uuid>,
+    pub epic_id: Option<Uuid>,
+}
+
+// Workflow requests
+#[derive(Debug, Deserialize)]
+pub struct UpdateWorkflowRequest {
+    /// In board order
+    pub statuses: Vec<WorkflowStatus>,
+    /// Allowed moves; leave empty to allow every move
+    #[serde(default)]
+    pub transitions: Vec<WorkflowTransition>,
+    pub initial_status: String,
+}
+
+// WebSocket messages
+/// Topics are `workspace:<id>`, `project:<id>`, `sprint:<id>` or `ticket:<id or number>`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe { topics: Vec<String> },
+    Unsubscribe { topics: Vec<String> },
+}
+
+// Bulk requests
+/// Ticket filters shared by `GET /api/tickets` and bulk operations
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TicketFilter {
+    pub project_id: Option<String>,
+    pub status: Vec<String>,
+    pub assignee: Option<String>,
+    /// Only tickets nobody is assigned to
+    pub unassigned: bool,
+    /// Tickets must carry every one of these labels
+    pub labels: Vec<String>,
+    /// Epic UUID or number, e.g. `JIL-5`
+    pub epic_id: Option<String>,
+    pub sprint_id: Option<String>,
+    /// Parent ticket UUID or number
+    pub parent_id: Option<String>,
+}
+
+/// Apply `operations` to the tickets named in `tickets`, or to every ticket
+/// matching `filter` (which must name a project)
 #[derive(Debug, Deserialize)]
-pub struct AddTicketToSprintRequest {
-    pub added_by: String,
+pub struct BulkTicketRequest {
+    /// Ticket UUIDs or numbers such as `JIL-42`
+    #[serde(default)]
+    pub tickets: Vec<String>,
+    pub filter: Option<TicketFilter>,
+    pub operations: BulkOperations,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BulkOperations {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub add_assignees: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remove_assignees: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub add_labels: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remove_labels: Vec<String>,
+    /// Epic UUID or number to move the tickets into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_id: Option<String>,
+    /// Take the tickets out of their epic
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub remove_epic: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_to_sprint: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove_from_sprint: Option<Uuid>,
+    /// Soft-delete the tickets; can't be combined with other operations
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub delete: bool,
+}
+
+// Webhook requests
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Event types to receive; empty for all of them
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Limit the hook to one project of the workspace
+    pub project_id: Option<Uuid>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+    /// Replace the signing secret; the new one is returned once
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    /// `pending`, `succeeded` or `failed`
+    pub status: Option<String>,
+    #[serde(default = "default_delivery_limit")]
+    pub limit: u64,
+}
+
+fn default_delivery_limit() -> u64 {
+    50
+}
+
+// Notification requests
+#[derive(Debug, Deserialize)]
+pub struct ListNotificationsQuery {
+    /// Only unread notifications
+    #[serde(default)]
+    pub unread: bool,
+    #[serde(default = "default_notification_limit")]
+    pub limit: u64,
+    /// Only notifications older than this RFC 3339 time, to page back
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn default_notification_limit() -> u64 {
+    50
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationRequest {
+    pub read: bool,
+}
+
+// Import requests
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Report what the import would do without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// Backup requests
+#[derive(Debug, Deserialize)]
+pub struct RestoreQuery {
+    /// Check the archive and report what the restore would do, then roll it
+    /// back
+    #[serde(default)]
+    pub dry_run: bool,
 }
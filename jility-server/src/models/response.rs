@@ -1,7 +1,14 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
+use jility_core::entities::TemplateSubtask;
+use jility_core::import::ImportProblem;
+use jility_core::workflow::{WorkflowStatus, WorkflowTransition};
 use serde::Serialize;
 use uuid::Uuid;
 
+use super::BulkOperations;
+
 // Project responses
 #[derive(Debug, Serialize)]
 pub struct ProjectResponse {
@@ -37,10 +44,32 @@ pub struct TicketResponse {
     pub epic_color: Option<String>,
 }
 
+/// Result of `POST /api/tickets/bulk`
+#[derive(Debug, Serialize)]
+pub struct BulkTicketResponse {
+    /// Number of tickets the operations were applied to
+    pub matched: usize,
+    /// Number of change records written
+    pub changes: usize,
+    /// The tickets after the change; empty for deletions
+    pub tickets: Vec<TicketResponse>,
+}
+
+/// A page of `GET /api/tickets`
+#[derive(Debug, Serialize)]
+pub struct TicketListResponse {
+    pub tickets: Vec<TicketResponse>,
+    /// Pass as `cursor` to get the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TicketDetailResponse {
     pub ticket: TicketResponse,
+    /// Keys the ticket had in trackers it was imported from
+    pub aliases: Vec<String>,
     pub comments: Vec<CommentResponse>,
+    pub attachments: Vec<AttachmentResponse>,
     pub dependencies: Vec<TicketReference>,
     pub dependents: Vec<TicketReference>,
     pub linked_commits: Vec<CommitLinkResponse>,
@@ -60,10 +89,56 @@ pub struct TicketReference {
 pub struct CommentResponse {
     pub id: String,
     pub ticket_id: String,
+    /// The comment this one replies to
+    pub parent_id: Option<String>,
     pub author: String,
+    /// The user who wrote it; unset for imported comments
+    pub author_user_id: Option<String>,
+    /// Empty once the comment is removed
     pub content: String,
     pub created_at: String,
     pub updated_at: Option<String>,
+    /// Earlier versions kept from edits
+    pub revision_count: u64,
+    pub reactions: Vec<ReactionSummary>,
+    /// Set on the tombstone of a removed comment
+    pub deleted_at: Option<String>,
+    pub deleted_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReactionSummary {
+    pub emoji: String,
+    pub count: usize,
+    pub users: Vec<String>,
+    pub user_ids: Vec<String>,
+}
+
+// Attachment responses
+#[derive(Debug, Serialize, Clone)]
+pub struct AttachmentResponse {
+    pub id: String,
+    pub ticket_id: String,
+    /// Set when the file belongs to a comment
+    pub comment_id: Option<String>,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub uploaded_by: String,
+    pub uploaded_by_user_id: Option<String>,
+    pub created_at: String,
+    /// Where to download the content
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentRevisionResponse {
+    pub id: String,
+    /// The text before the edit
+    pub content: String,
+    pub edited_by: String,
+    pub edited_at: String,
 }
 
 // Dependency responses
@@ -72,6 +147,40 @@ pub struct DependencyGraphResponse {
     pub ticket: TicketReference,
     pub dependencies: Vec<TicketReference>,
     pub dependents: Vec<TicketReference>,
+    /// Transitive graph around the ticket, up to the requested depth
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyNode {
+    pub id: String,
+    pub number: String,
+    pub title: String,
+    pub status: String,
+    pub story_points: Option<i32>,
+    /// Hops from the requested ticket (absent for epic/sprint graphs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+}
+
+/// `ticket_id` depends on `depends_on_id`
+#[derive(Debug, Serialize)]
+pub struct DependencyEdge {
+    pub ticket_id: String,
+    pub depends_on_id: String,
+}
+
+/// Dependency graph of an epic or sprint with an execution plan
+#[derive(Debug, Serialize)]
+pub struct ScopedDependencyGraphResponse {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    /// Ticket IDs ordered so dependencies come first
+    pub topological_order: Vec<String>,
+    /// Ticket IDs along the chain with the most story points
+    pub critical_path: Vec<String>,
+    pub critical_path_points: i32,
 }
 
 // Activity & History
@@ -105,6 +214,41 @@ pub struct CommitLinkResponse {
     pub linked_by: String,
 }
 
+/// Returned once when a project's push secret is generated
+#[derive(Debug, Serialize, Clone)]
+pub struct GitPushSecretResponse {
+    pub secret: String,
+    /// Path to configure as the repository's push webhook
+    pub push_url: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GitPushResponse {
+    /// Why the push was not processed, e.g. a non-push event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignored: Option<String>,
+    pub linked: Vec<PushedCommitLink>,
+    pub transitioned: Vec<PushedTransition>,
+    /// References that could not be applied, with the reason
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PushedCommitLink {
+    pub ticket_id: String,
+    pub number: String,
+    pub commit_hash: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PushedTransition {
+    pub ticket_id: String,
+    pub number: String,
+    pub old_status: String,
+    pub new_status: String,
+    pub commit_hash: String,
+}
+
 // WebSocket messages
 #[derive(Debug, Serialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -120,10 +264,33 @@ pub enum ServerMessage {
         ticket_id: String,
         comment: CommentResponse,
     },
+    /// Edited or reacted to
+    CommentUpdated {
+        ticket_id: String,
+        comment: CommentResponse,
+    },
+    /// Carries the tombstone
+    CommentDeleted {
+        ticket_id: String,
+        comment: CommentResponse,
+    },
     DescriptionEdited {
         ticket_id: String,
         version: i32,
     },
+    /// One event for a whole bulk operation
+    TicketsBulkUpdated {
+        project_id: String,
+        ticket_ids: Vec<String>,
+        operations: BulkOperations,
+    },
+    /// Sent only to the recipient, without subscribing
+    Notification {
+        notification: NotificationResponse,
+    },
+    Subscribed { topics: Vec<String> },
+    Unsubscribed { topics: Vec<String> },
+    Error { message: String },
 }
 
 // Utility function to format UUID as string
@@ -176,6 +343,8 @@ pub struct BurndownDataPoint {
     pub date: String,
     pub ideal: i32,
     pub actual: i32,
+    /// Points added to the sprint after it started, as of this day
+    pub scope_added: i32,
 }
 
 #[derive(Debug, Serialize)]
@@ -187,7 +356,12 @@ pub struct BurndownData {
 #[derive(Debug, Serialize)]
 pub struct VelocityData {
     pub sprint_name: String,
+    /// Points in the sprint when it started
+    pub committed_points: i32,
+    /// Points that reached done between the sprint's start and end dates
     pub completed_points: i32,
+    pub carried_over_tickets: usize,
+    pub carried_over_points: i32,
 }
 
 #[derive(Debug, Serialize)]
@@ -195,6 +369,10 @@ pub struct SprintHistoryResponse {
     pub sprints: Vec<SprintResponse>,
     pub velocity_data: Vec<VelocityData>,
     pub average_velocity: f64,
+    /// Average over the most recent completed sprints
+    pub rolling_average_velocity: f64,
+    pub velocity_std_dev: f64,
+    pub suggested_capacity: Option<i32>,
 }
 
 // Saved view responses
@@ -210,3 +388,171 @@ pub struct SavedViewResponse {
     pub created_at: String,
     pub updated_at: String,
 }
+
+// Template responses
+#[derive(Debug, Serialize)]
+pub struct TemplateResponse {
+    pub id: String,
+    pub workspace_id: String,
+    pub project_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub title_template: String,
+    pub description_template: String,
+    pub default_status: Option<String>,
+    pub default_story_points: Option<i32>,
+    pub default_labels: Vec<String>,
+    pub subtasks: Vec<TemplateSubtask>,
+    /// Placeholders used by the title, description and sub-tasks
+    pub variables: Vec<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstantiatedTemplateResponse {
+    pub ticket: TicketResponse,
+    pub subtasks: Vec<TicketResponse>,
+}
+
+// Workflow responses
+#[derive(Debug, Serialize)]
+pub struct WorkflowResponse {
+    pub project_id: String,
+    pub statuses: Vec<WorkflowStatus>,
+    pub transitions: Vec<WorkflowTransition>,
+    pub initial_status: String,
+    /// True while the project uses the built-in workflow
+    pub is_default: bool,
+}
+
+// Webhook responses
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub workspace_id: String,
+    pub project_id: Option<String>,
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    /// Only returned when the hook is created or its secret is rotated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<String>,
+    pub response_code: Option<i32>,
+    pub response_body: Option<String>,
+    pub payload: serde_json::Value,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+// Notification responses
+#[derive(Debug, Serialize, Clone)]
+pub struct NotificationResponse {
+    pub id: String,
+    /// `mentioned`, `assigned`, `commented` or `status_changed`
+    pub kind: String,
+    pub ticket_id: String,
+    pub ticket_number: String,
+    pub ticket_title: String,
+    pub actor: String,
+    pub message: String,
+    pub comment_id: Option<String>,
+    pub read: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<NotificationResponse>,
+    /// Across the whole inbox, not just this page
+    pub unread_count: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatcherResponse {
+    pub user_id: String,
+    pub username: String,
+    /// `creator`, `assignee`, `commenter` or `follower`
+    pub reason: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TicketWatchersResponse {
+    /// Whether the caller watches the ticket
+    pub watching: bool,
+    pub watchers: Vec<WatcherResponse>,
+}
+
+// Import responses
+/// What an import did or, for a dry run, would do
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    /// `jira` or `github`
+    pub source: String,
+    pub dry_run: bool,
+    /// Issues read from the export
+    pub issues: usize,
+    /// Tickets created, or to be created
+    pub created: usize,
+    /// How many of the new tickets are epics
+    pub epics: usize,
+    /// Comments added, to the new tickets and to tickets an earlier import
+    /// created
+    pub comments: usize,
+    /// Keys of issues an earlier import already created; only their new
+    /// comments are imported
+    pub skipped: Vec<String>,
+    /// Sprints created, or to be created
+    pub new_sprints: Vec<String>,
+    /// New tickets per status
+    pub statuses: BTreeMap<String, usize>,
+    /// Nothing is imported while there are errors
+    pub errors: Vec<ImportProblem>,
+    pub warnings: Vec<ImportProblem>,
+    /// The created tickets; empty for a dry run
+    pub tickets: Vec<ImportedTicketResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportedTicketResponse {
+    /// Key in the source tracker, now an alias of the ticket
+    pub key: String,
+    pub id: String,
+    pub number: String,
+}
+
+// Backup responses
+/// What a restore did or, for a dry run, would do
+#[derive(Debug, Serialize)]
+pub struct RestoreReport {
+    pub dry_run: bool,
+    /// The new workspace; for a dry run, the ID it would have had
+    pub workspace_id: String,
+    pub name: String,
+    pub slug: String,
+    /// Archive version
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Records restored, by type
+    pub records: BTreeMap<String, usize>,
+    /// Emails of the archived members invited to the workspace
+    pub invited: Vec<String>,
+    /// Attachments left out because their content isn't available
+    pub missing_attachments: usize,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,356 @@
+//! Ticket watchers and the per-user notification inbox
+//!
+//! Users watch the tickets they create, are assigned to or comment on, plus
+//! any ticket they follow. Comments and status changes on a watched ticket
+//! land in each watcher's inbox; assignments and `@username` mentions reach
+//! the people concerned whether or not they watch. Nobody is notified about
+//! their own changes, including those made with their API keys, so a user's
+//! agents don't bury them. New notifications are also pushed to the
+//! recipient's open websockets.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use jility_core::entities::{
+    comment, notification, project, ticket, ticket_watcher, user, workspace_member, NotificationKind,
+    Project, Ticket, TicketWatcher, User, WatchReason, WorkspaceMember,
+};
+use jility_core::mentions::parse_mentions;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
+use uuid::Uuid;
+
+use crate::models::{NotificationResponse, ServerMessage};
+use crate::state::AppState;
+
+/// Longest comment excerpt kept in a notification
+const MAX_EXCERPT: usize = 200;
+
+/// What happened to a ticket, as told to the people it concerns
+struct Activity {
+    kind: NotificationKind,
+    actor: String,
+    /// The user behind `actor`, who isn't told about their own changes
+    actor_id: Option<Uuid>,
+    message: String,
+    comment_id: Option<Uuid>,
+}
+
+/// Start watching `ticket_id`. A user who already watches keeps their
+/// original reason.
+pub async fn watch<C: ConnectionTrait>(
+    db: &C,
+    ticket_id: Uuid,
+    user_id: Uuid,
+    reason: WatchReason,
+) -> Result<(), DbErr> {
+    let watcher = ticket_watcher::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        ticket_id: Set(ticket_id),
+        user_id: Set(user_id),
+        reason: Set(reason.as_str().to_string()),
+        created_at: Set(Utc::now()),
+    };
+    TicketWatcher::insert(watcher)
+        .on_conflict(
+            OnConflict::columns([ticket_watcher::Column::TicketId, ticket_watcher::Column::UserId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+pub fn excerpt(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_EXCERPT) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// A ticket was created: watch it for the creator, and for and notify the
+/// assignees
+pub async fn ticket_created(
+    state: &AppState,
+    ticket: &ticket::Model,
+    creator_id: Uuid,
+    assignees: &[String],
+) {
+    if let Err(e) = watch(state.db.as_ref(), ticket.id, creator_id, WatchReason::Creator).await {
+        tracing::warn!("Failed to watch ticket {}: {}", ticket.id, e);
+    }
+    assigned(state, ticket.id, &ticket.created_by, Some(creator_id), assignees).await;
+}
+
+/// `assignees` were added to a ticket: they watch it from now on and are
+/// told about it
+pub async fn assigned(
+    state: &AppState,
+    ticket_id: Uuid,
+    actor: &str,
+    actor_id: Option<Uuid>,
+    assignees: &[String],
+) {
+    if assignees.is_empty() {
+        return;
+    }
+    let result = async {
+        // Assignees that aren't users (e.g. `agent-1`) have no inbox
+        let users = User::find()
+            .filter(user::Column::Username.is_in(assignees.iter().cloned()))
+            .all(state.db.as_ref())
+            .await?;
+        for user in &users {
+            watch(state.db.as_ref(), ticket_id, user.id, WatchReason::Assignee).await?;
+        }
+        let activity = Activity {
+            kind: NotificationKind::Assigned,
+            actor: actor.to_string(),
+            actor_id,
+            message: "assigned you".to_string(),
+            comment_id: None,
+        };
+        let user_ids: Vec<Uuid> = users.iter().map(|u| u.id).collect();
+        notify(state, ticket_id, &user_ids, &activity).await
+    };
+    if let Err(e) = result.await {
+        tracing::warn!("Failed to notify assignees of ticket {}: {}", ticket_id, e);
+    }
+}
+
+/// A ticket's status changed: tell its watchers
+pub async fn status_changed(
+    state: &AppState,
+    ticket_id: Uuid,
+    actor: &str,
+    actor_id: Option<Uuid>,
+    old_status: &str,
+    new_status: &str,
+) {
+    let activity = Activity {
+        kind: NotificationKind::StatusChanged,
+        actor: actor.to_string(),
+        actor_id,
+        message: format!("moved from {} to {}", old_status, new_status),
+        comment_id: None,
+    };
+    let result = async {
+        let watchers = watcher_ids(state, ticket_id).await?;
+        notify(state, ticket_id, &watchers, &activity).await
+    };
+    if let Err(e) = result.await {
+        tracing::warn!("Failed to notify watchers of ticket {}: {}", ticket_id, e);
+    }
+}
+
+/// A comment was added: the author watches the ticket, mentioned members get
+/// a mention and the other watchers a comment notification
+pub async fn comment_added(state: &AppState, comment: &comment::Model, author_id: Uuid) {
+    let result = async {
+        watch(state.db.as_ref(), comment.ticket_id, author_id, WatchReason::Commenter).await?;
+
+        let mentioned = mentioned_users(state, &comment.content, None).await?;
+        let mut activity = Activity {
+            kind: NotificationKind::Mentioned,
+            actor: comment.author.clone(),
+            actor_id: Some(author_id),
+            message: excerpt(&comment.content),
+            comment_id: Some(comment.id),
+        };
+        notify(state, comment.ticket_id, &mentioned, &activity).await?;
+
+        let watchers: Vec<Uuid> = watcher_ids(state, comment.ticket_id)
+            .await?
+            .into_iter()
+            .filter(|id| !mentioned.contains(id))
+            .collect();
+        activity.kind = NotificationKind::Commented;
+        notify(state, comment.ticket_id, &watchers, &activity).await
+    };
+    if let Err(e) = result.await {
+        tracing::warn!("Failed to notify about comment {}: {}", comment.id, e);
+    }
+}
+
+/// A comment was edited: only users it newly mentions are told
+pub async fn comment_edited(state: &AppState, comment: &comment::Model, old_content: &str) {
+    let result = async {
+        let mentioned = mentioned_users(state, &comment.content, Some(old_content)).await?;
+        let activity = Activity {
+            kind: NotificationKind::Mentioned,
+            actor: comment.author.clone(),
+            actor_id: comment.author_user_id,
+            message: excerpt(&comment.content),
+            comment_id: Some(comment.id),
+        };
+        notify(state, comment.ticket_id, &mentioned, &activity).await
+    };
+    if let Err(e) = result.await {
+        tracing::warn!("Failed to notify about comment {}: {}", comment.id, e);
+    }
+}
+
+/// Users mentioned in `content` but not in `previous`
+async fn mentioned_users(
+    state: &AppState,
+    content: &str,
+    previous: Option<&str>,
+) -> Result<Vec<Uuid>, DbErr> {
+    let already: Vec<String> = previous.map(parse_mentions).unwrap_or_default();
+    let usernames: Vec<String> = parse_mentions(content)
+        .into_iter()
+        .filter(|name| !already.contains(name))
+        .collect();
+    if usernames.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(User::find()
+        .filter(user::Column::Username.is_in(usernames))
+        .all(state.db.as_ref())
+        .await?
+        .into_iter()
+        .map(|u| u.id)
+        .collect())
+}
+
+async fn watcher_ids(state: &AppState, ticket_id: Uuid) -> Result<Vec<Uuid>, DbErr> {
+    Ok(TicketWatcher::find()
+        .filter(ticket_watcher::Column::TicketId.eq(ticket_id))
+        .all(state.db.as_ref())
+        .await?
+        .into_iter()
+        .map(|w| w.user_id)
+        .collect())
+}
+
+/// Store a notification for each of `user_ids` who is still a member of the
+/// ticket's workspace and didn't cause the activity, and push it to them
+async fn notify(
+    state: &AppState,
+    ticket_id: Uuid,
+    user_ids: &[Uuid],
+    activity: &Activity,
+) -> Result<(), DbErr> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+    let db = state.db.as_ref();
+    let Some(ticket) = Ticket::find_by_id(ticket_id).one(db).await? else {
+        return Ok(());
+    };
+    let Some(project) = Project::find_by_id(ticket.project_id).one(db).await? else {
+        return Ok(());
+    };
+
+    let members: HashSet<Uuid> = WorkspaceMember::find()
+        .filter(workspace_member::Column::WorkspaceId.eq(project.workspace_id))
+        .filter(workspace_member::Column::UserId.is_in(user_ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| m.user_id)
+        .collect();
+    let recipients: Vec<user::Model> = User::find()
+        .filter(user::Column::Id.is_in(members))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|u| Some(u.id) != activity.actor_id)
+        .collect();
+
+    let key = project.key.as_deref().unwrap_or("TASK");
+    let now = Utc::now();
+    for recipient in recipients {
+        let row = notification::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(recipient.id),
+            ticket_id: Set(ticket_id),
+            kind: Set(activity.kind.as_str().to_string()),
+            actor: Set(activity.actor.clone()),
+            message: Set(activity.message.clone()),
+            comment_id: Set(activity.comment_id),
+            read_at: Set(None),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await?;
+
+        let message = ServerMessage::Notification {
+            notification: notification_response(row, &ticket, key),
+        };
+        state
+            .ws_state
+            .send_to_user(recipient.id, serde_json::to_string(&message).unwrap())
+            .await;
+    }
+
+    Ok(())
+}
+
+pub fn notification_response(
+    row: notification::Model,
+    ticket: &ticket::Model,
+    key: &str,
+) -> NotificationResponse {
+    NotificationResponse {
+        id: row.id.to_string(),
+        kind: row.kind,
+        ticket_id: row.ticket_id.to_string(),
+        ticket_number: format!("{}-{}", key, ticket.ticket_number),
+        ticket_title: ticket.title.clone(),
+        actor: row.actor,
+        message: row.message,
+        comment_id: row.comment_id.map(|id| id.to_string()),
+        read: row.read_at.is_some(),
+        created_at: row.created_at.to_rfc3339(),
+    }
+}
+
+/// Responses for a page of notifications, loading their tickets and project
+/// keys in two queries
+pub async fn notification_responses<C: ConnectionTrait>(
+    db: &C,
+    rows: Vec<notification::Model>,
+) -> Result<Vec<NotificationResponse>, DbErr> {
+    let ticket_ids: HashSet<Uuid> = rows.iter().map(|n| n.ticket_id).collect();
+    let tickets: HashMap<Uuid, ticket::Model> = Ticket::find()
+        .filter(ticket::Column::Id.is_in(ticket_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|t| (t.id, t))
+        .collect();
+    let project_ids: HashSet<Uuid> = tickets.values().map(|t| t.project_id).collect();
+    let keys: HashMap<Uuid, Option<String>> = Project::find()
+        .filter(project::Column::Id.is_in(project_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p.key))
+        .collect();
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let ticket = tickets.get(&row.ticket_id)?;
+            let key = keys.get(&ticket.project_id).cloned().flatten();
+            Some(notification_response(row, ticket, key.as_deref().unwrap_or("TASK")))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excerpt() {
+        assert_eq!(excerpt("  short  "), "short");
+        let long = "é".repeat(MAX_EXCERPT + 5);
+        assert_eq!(excerpt(&long).chars().count(), MAX_EXCERPT + 1);
+    }
+}
@@ -1,49 +1,169 @@
 use jility_core::search::SearchService;
 use sea_orm::{Database, DatabaseConnection, DbErr};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc::UnboundedSender, RwLock};
+use uuid::Uuid;
 
-use crate::auth::AuthService;
+use crate::attachments::AttachmentLimits;
+use crate::auth::{ApiKeyCache, AuthService};
+use crate::blob_store::{BlobStore, LocalStore, DEFAULT_DIR};
+use crate::webhooks::WebhookDispatcher;
+use crate::websocket::{EventScope, Topic};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
     pub ws_state: Arc<WebSocketState>,
     pub auth_service: AuthService,
+    pub api_key_cache: Arc<ApiKeyCache>,
     pub search_service: Arc<SearchService>,
+    pub webhooks: Arc<WebhookDispatcher>,
+    /// Attachment contents
+    pub blob_store: Arc<dyn BlobStore>,
+    /// Uploads store blobs under the read lock and removals delete unused
+    /// ones under the write lock, so a blob can't go while a new row uses it
+    pub blob_lock: Arc<RwLock<()>>,
+    pub attachment_limits: AttachmentLimits,
 }
 
 impl AppState {
-    pub fn new(db: DatabaseConnection, jwt_secret: String) -> Self {
+    pub fn new(db: DatabaseConnection, auth_service: AuthService) -> Self {
         let db = Arc::new(db);
         Self {
             search_service: Arc::new(SearchService::new(db.clone())),
             db,
             ws_state: Arc::new(WebSocketState::new()),
-            auth_service: AuthService::new(jwt_secret),
+            auth_service,
+            api_key_cache: Arc::new(ApiKeyCache::new()),
+            webhooks: Arc::new(WebhookDispatcher::new()),
+            blob_store: Arc::new(LocalStore::new(DEFAULT_DIR)),
+            blob_lock: Arc::new(RwLock::new(())),
+            attachment_limits: AttachmentLimits::default(),
         }
     }
+
+    pub fn with_attachments(
+        mut self,
+        blob_store: Arc<dyn BlobStore>,
+        limits: AttachmentLimits,
+    ) -> Self {
+        self.blob_store = blob_store;
+        self.attachment_limits = limits;
+        self
+    }
 }
 
-// WebSocket state for broadcasting updates
+// WebSocket state for delivering updates to subscribed clients
 pub struct WebSocketState {
-    pub subscribers: RwLock<Vec<tokio::sync::mpsc::UnboundedSender<String>>>,
+    clients: RwLock<HashMap<Uuid, WebSocketClient>>,
+}
+
+struct WebSocketClient {
+    user_id: Uuid,
+    /// API key the client connected with; `None` for session logins
+    api_key_id: Option<Uuid>,
+    /// Subscribed topics, with the workspace each belongs to
+    topics: HashMap<Topic, Uuid>,
+    sender: UnboundedSender<String>,
 }
 
 impl WebSocketState {
     pub fn new() -> Self {
         Self {
-            subscribers: RwLock::new(Vec::new()),
+            clients: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Register a client of `user_id` with no subscriptions and return its id
+    pub async fn connect(
+        &self,
+        user_id: Uuid,
+        api_key_id: Option<Uuid>,
+        sender: UnboundedSender<String>,
+    ) -> Uuid {
+        let client_id = Uuid::new_v4();
+        self.clients.write().await.insert(
+            client_id,
+            WebSocketClient {
+                user_id,
+                api_key_id,
+                topics: HashMap::new(),
+                sender,
+            },
+        );
+        client_id
+    }
+
+    pub async fn disconnect(&self, client_id: Uuid) {
+        self.clients.write().await.remove(&client_id);
+    }
+
+    /// Send `message` to one client; `false` once it has been disconnected
+    pub async fn send_to_client(&self, client_id: Uuid, message: String) -> bool {
+        match self.clients.read().await.get(&client_id) {
+            Some(client) => client.sender.send(message).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Subscribe a client to topics, each given with its workspace
+    pub async fn subscribe(&self, client_id: Uuid, topics: &[(Topic, Uuid)]) {
+        if let Some(client) = self.clients.write().await.get_mut(&client_id) {
+            client.topics.extend(topics.iter().copied());
+        }
+    }
+
+    pub async fn unsubscribe(&self, client_id: Uuid, topics: &[Topic]) {
+        if let Some(client) = self.clients.write().await.get_mut(&client_id) {
+            for topic in topics {
+                client.topics.remove(topic);
+            }
+        }
+    }
+
+    /// Drop a user's subscriptions to a workspace they no longer belong to
+    pub async fn leave_workspace(&self, user_id: Uuid, workspace_id: Uuid) {
+        for client in self.clients.write().await.values_mut() {
+            if client.user_id == user_id {
+                client.topics.retain(|_, workspace| *workspace != workspace_id);
+            }
         }
     }
 
-    pub async fn subscribe(&self, tx: tokio::sync::mpsc::UnboundedSender<String>) {
-        self.subscribers.write().await.push(tx);
+    /// Close the clients connected with a revoked API key
+    pub async fn revoke_api_key(&self, api_key_id: Uuid) {
+        self.clients
+            .write()
+            .await
+            .retain(|_, client| client.api_key_id != Some(api_key_id));
+    }
+
+    /// Close a user's clients that connected with a session rather than a key
+    pub async fn revoke_sessions(&self, user_id: Uuid) {
+        self.clients
+            .write()
+            .await
+            .retain(|_, client| client.user_id != user_id || client.api_key_id.is_some());
+    }
+
+    /// Send `message` to every client subscribed to a topic covering `scope`
+    pub async fn publish(&self, scope: &EventScope, message: String) {
+        let mut clients = self.clients.write().await;
+        clients.retain(|_, client| {
+            if !client.topics.keys().any(|topic| scope.matches(topic)) {
+                return true;
+            }
+            client.sender.send(message.clone()).is_ok()
+        });
     }
 
-    pub async fn broadcast(&self, message: String) {
-        let mut subscribers = self.subscribers.write().await;
-        subscribers.retain(|tx| tx.send(message.clone()).is_ok());
+    /// Send `message` to every client of `user_id`, whatever its subscriptions
+    pub async fn send_to_user(&self, user_id: Uuid, message: String) {
+        let mut clients = self.clients.write().await;
+        clients.retain(|_, client| {
+            client.user_id != user_id || client.sender.send(message.clone()).is_ok()
+        });
     }
 }
 
@@ -0,0 +1,126 @@
+//! Database fixtures for handler tests
+
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, Database, Set, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{
+    auth::{AuthService, AuthUser},
+    state::AppState,
+};
+use jility_core::entities::{project, ticket, user, workspace, workspace_member, WorkspaceRole};
+
+pub struct Fixture {
+    pub state: AppState,
+    /// Admin of the workspace that owns `project`
+    pub user: AuthUser,
+    pub project: project::Model,
+}
+
+impl Fixture {
+    /// A migrated in-memory database holding one user, their workspace and
+    /// a project in it
+    pub async fn new() -> Self {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        jility_core::run_migrations(&db).await.unwrap();
+        let state = AppState::new(db, AuthService::new("test-secret".to_string()));
+        Self::with_workspace(state, "alice", "Jility", "JIL").await
+    }
+
+    /// Another user in the same database, admin of a workspace and project
+    /// of their own that this fixture's user doesn't belong to
+    pub async fn outsider(&self) -> Self {
+        Self::with_workspace(self.state.clone(), "bob", "Bobbin", "BOB").await
+    }
+
+    async fn with_workspace(state: AppState, username: &str, project: &str, key: &str) -> Self {
+        let db = state.db.as_ref();
+        let now = Utc::now();
+        let user = user::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            email: Set(format!("{}@example.com", username)),
+            username: Set(username.to_string()),
+            password_hash: Set(String::new()),
+            full_name: Set(None),
+            avatar_url: Set(None),
+            is_active: Set(true),
+            is_verified: Set(true),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+            last_login_at: Set(None),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        let workspace = workspace::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(format!("{}'s Workspace", username)),
+            slug: Set(username.to_string()),
+            created_by_user_id: Set(user.id),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        workspace_member::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            workspace_id: Set(workspace.id),
+            user_id: Set(user.id),
+            role: Set(WorkspaceRole::Admin),
+            invited_by_user_id: Set(None),
+            invited_at: Set(None),
+            joined_at: Set(now.into()),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        let project = project::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            workspace_id: Set(workspace.id),
+            name: Set(project.to_string()),
+            key: Set(Some(key.to_string())),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        Self {
+            state,
+            user: user.into(),
+            project,
+        }
+    }
+
+    /// Insert the project's next ticket; `edit` sets anything beyond the title
+    pub async fn ticket(
+        &self,
+        title: &str,
+        edit: impl FnOnce(&mut ticket::ActiveModel),
+    ) -> ticket::Model {
+        let txn = self.state.db.begin().await.unwrap();
+        let number = project::next_ticket_number(&txn, self.project.id)
+            .await
+            .unwrap();
+        let now = Utc::now();
+        let mut ticket = ticket::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            project_id: Set(self.project.id),
+            ticket_number: Set(number),
+            title: Set(title.to_string()),
+            description: Set(String::new()),
+            status: Set("backlog".to_string()),
+            created_by: Set(self.user.username.clone()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        edit(&mut ticket);
+        let ticket = ticket.insert(&txn).await.unwrap();
+        txn.commit().await.unwrap();
+        ticket
+    }
+}
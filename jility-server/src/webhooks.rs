@@ -0,0 +1,546 @@
+//! Outbound webhooks
+//!
+//! Events are written to `webhook_delivery` for every subscribed hook and a
+//! background task posts them, so deliveries survive restarts. Each request
+//! carries an HMAC-SHA256 signature of its body made with the hook's secret.
+//! Failed attempts are retried with exponential backoff until the delivery
+//! runs out of attempts and is marked `failed`; admins can redeliver it.
+//!
+//! Hooks are set up by workspace admins, not by whoever runs the server, so
+//! they may only reach public addresses: a URL whose host is or resolves to a
+//! loopback, private or link-local address is refused when the hook is saved
+//! and again when each request is made. Redirects aren't followed.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use hmac::{Hmac, Mac};
+use hyper::client::connect::dns::Name;
+use jility_core::entities::{
+    webhook, webhook_delivery, DeliveryStatus, Project, Webhook, WebhookDelivery,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::models::ServerMessage;
+
+/// Event types a webhook can subscribe to
+pub const EVENTS: [&str; 11] = [
+    "ticket.created",
+    "ticket.updated",
+    "ticket.status_changed",
+    "ticket.description_edited",
+    "ticket.deleted",
+    "tickets.bulk_updated",
+    "comment.created",
+    "comment.updated",
+    "comment.deleted",
+    "sprint.started",
+    "sprint.completed",
+];
+
+/// Sent by `POST /api/webhooks/:id/ping` whatever the hook subscribes to
+pub const PING: &str = "ping";
+
+/// Attempts before a delivery is marked `failed`
+const MAX_ATTEMPTS: i32 = 8;
+/// Wait before the first retry; doubles with every failed attempt
+const RETRY_BASE: Duration = Duration::from_secs(30);
+/// How often the queue is checked for retries that came due
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries sent concurrently per poll
+const BATCH_SIZE: u64 = 20;
+/// Longest response body kept in the delivery log
+const MAX_LOGGED_BODY: usize = 2000;
+
+/// Webhook event type of a websocket message, if it has one
+pub fn event_for(message: &ServerMessage) -> Option<&'static str> {
+    match message {
+        ServerMessage::TicketCreated { .. } => Some("ticket.created"),
+        ServerMessage::TicketUpdated { .. } => Some("ticket.updated"),
+        ServerMessage::StatusChanged { .. } => Some("ticket.status_changed"),
+        ServerMessage::DescriptionEdited { .. } => Some("ticket.description_edited"),
+        ServerMessage::TicketsBulkUpdated { .. } => Some("tickets.bulk_updated"),
+        ServerMessage::CommentAdded { .. } => Some("comment.created"),
+        ServerMessage::CommentUpdated { .. } => Some("comment.updated"),
+        ServerMessage::CommentDeleted { .. } => Some("comment.deleted"),
+        ServerMessage::Notification { .. }
+        | ServerMessage::Subscribed { .. }
+        | ServerMessage::Unsubscribed { .. }
+        | ServerMessage::Error { .. } => None,
+    }
+}
+
+/// A fresh signing secret, for outbound webhooks and inbound git pushes
+pub fn generate_secret() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    format!("whsec_{}", random)
+}
+
+/// `sha256=<hex>` signature of `body`, sent as `X-Jility-Signature-256`
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Whether webhooks may be sent to `ip`: anything but the server's own
+/// machine and network
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(ip.into()),
+            None => {
+                !(ip.is_unspecified()
+                    || ip.is_loopback()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// The addresses `host` resolves to, if every one of them is public
+async fn public_addrs(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Can't resolve {}: {}", host, e))?
+        .collect();
+    match addrs.iter().find(|addr| !is_public(addr.ip())) {
+        Some(_) if host.parse::<IpAddr>().is_ok() => {
+            Err(format!("{} isn't a public address", host))
+        }
+        Some(addr) => Err(format!(
+            "{} resolves to {}, which isn't a public address",
+            host,
+            addr.ip()
+        )),
+        None if addrs.is_empty() => Err(format!("{} doesn't resolve to any address", host)),
+        None => Ok(addrs),
+    }
+}
+
+/// Check that `url` is an http(s) URL whose host is public
+pub async fn check_target(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid webhook URL {}: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Webhook URL must start with http:// or https://: {}", url));
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| format!("Webhook URL has no host: {}", url))?;
+    // IPv6 hosts come bracketed
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    public_addrs(host, port).await.map(|_| ())
+}
+
+/// Resolves hostnames for the delivery client, refusing non-public
+/// addresses. Checking at connect time means a host can't pass
+/// `check_target` and then be re-pointed at the server's network.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = public_addrs(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Wait after the `attempts`-th failed attempt
+fn backoff(attempts: i32) -> Duration {
+    RETRY_BASE * 2u32.pow(attempts.clamp(1, MAX_ATTEMPTS) as u32 - 1)
+}
+
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    wake: Notify,
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookDispatcher {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent("Jility-Webhooks")
+                .redirect(reqwest::redirect::Policy::none())
+                .dns_resolver(Arc::new(PublicResolver))
+                .build()
+                .expect("default TLS backend is available"),
+            wake: Notify::new(),
+        }
+    }
+
+    /// Queue a websocket message as a webhook event of `project_id`
+    pub async fn enqueue_message(
+        &self,
+        db: &DatabaseConnection,
+        workspace_id: Uuid,
+        project_id: Uuid,
+        message: &ServerMessage,
+    ) {
+        let Some(event) = event_for(message) else {
+            return;
+        };
+        let mut data = serde_json::to_value(message).unwrap_or(Value::Null);
+        if let Some(data) = data.as_object_mut() {
+            data.remove("type");
+        }
+        self.enqueue(db, workspace_id, Some(project_id), event, data)
+            .await;
+    }
+
+    /// Queue an event of `project_id`, looking up its workspace
+    pub async fn enqueue_project_event(
+        &self,
+        db: &DatabaseConnection,
+        project_id: Uuid,
+        event: &str,
+        data: Value,
+    ) {
+        match Project::find_by_id(project_id).one(db).await {
+            Ok(Some(project)) => {
+                self.enqueue(db, project.workspace_id, Some(project.id), event, data)
+                    .await
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to queue {} webhooks: {}", event, e),
+        }
+    }
+
+    /// Queue `event` for every active hook of the workspace (or of
+    /// `project_id`) that subscribes to it. Failures are logged, never
+    /// returned: a webhook problem shouldn't fail the change that caused it.
+    pub async fn enqueue(
+        &self,
+        db: &DatabaseConnection,
+        workspace_id: Uuid,
+        project_id: Option<Uuid>,
+        event: &str,
+        data: Value,
+    ) {
+        if let Err(e) = self
+            .try_enqueue(db, workspace_id, project_id, event, data)
+            .await
+        {
+            tracing::warn!("Failed to queue {} webhooks: {}", event, e);
+        }
+    }
+
+    async fn try_enqueue(
+        &self,
+        db: &DatabaseConnection,
+        workspace_id: Uuid,
+        project_id: Option<Uuid>,
+        event: &str,
+        data: Value,
+    ) -> Result<(), DbErr> {
+        let mut scope = Condition::any().add(webhook::Column::ProjectId.is_null());
+        if let Some(project_id) = project_id {
+            scope = scope.add(webhook::Column::ProjectId.eq(project_id));
+        }
+        let hooks: Vec<webhook::Model> = Webhook::find()
+            .filter(webhook::Column::WorkspaceId.eq(workspace_id))
+            .filter(webhook::Column::Active.eq(true))
+            .filter(scope)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter(|hook| hook.wants(event))
+            .collect();
+        if hooks.is_empty() {
+            return Ok(());
+        }
+
+        let payload = envelope(event, workspace_id, project_id, data);
+        for hook in hooks {
+            queue(db, hook.id, event, &payload).await?;
+        }
+        self.wake.notify_one();
+
+        Ok(())
+    }
+
+    /// Queue a `ping` for one hook, whether or not it is active
+    pub async fn ping(&self, db: &DatabaseConnection, hook: &webhook::Model) -> Result<webhook_delivery::Model, DbErr> {
+        let data = json!({ "webhook_id": hook.id, "events": hook.events() });
+        let payload = envelope(PING, hook.workspace_id, hook.project_id, data);
+        let delivery = queue(db, hook.id, PING, &payload).await?;
+        self.wake.notify_one();
+        Ok(delivery)
+    }
+
+    /// Queue another attempt at a logged delivery, with the same payload
+    pub async fn redeliver(
+        &self,
+        db: &DatabaseConnection,
+        delivery: &webhook_delivery::Model,
+    ) -> Result<webhook_delivery::Model, DbErr> {
+        let delivery = queue(db, delivery.webhook_id, &delivery.event, &delivery.payload).await?;
+        self.wake.notify_one();
+        Ok(delivery)
+    }
+
+    /// Post due deliveries in the background, picking up whatever was
+    /// pending when the server stopped
+    pub fn spawn_delivery_task(self: Arc<Self>, db: Arc<DatabaseConnection>) {
+        tokio::spawn(async move {
+            loop {
+                match self.deliver_due(&db).await {
+                    // A full batch means more may be waiting
+                    Ok(sent) if sent as u64 == BATCH_SIZE => continue,
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to deliver webhooks: {}", e),
+                }
+                tokio::select! {
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                }
+            }
+        });
+    }
+
+    /// Attempt every pending delivery that is due, returning how many were tried
+    async fn deliver_due(&self, db: &DatabaseConnection) -> Result<usize, DbErr> {
+        let due = WebhookDelivery::find()
+            .filter(webhook_delivery::Column::Status.eq(DeliveryStatus::Pending.as_str()))
+            .filter(webhook_delivery::Column::NextAttemptAt.lte(Utc::now()))
+            .order_by_asc(webhook_delivery::Column::NextAttemptAt)
+            .limit(BATCH_SIZE)
+            .all(db)
+            .await?;
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let hook_ids: Vec<Uuid> = due.iter().map(|d| d.webhook_id).collect();
+        let hooks: HashMap<Uuid, webhook::Model> = Webhook::find()
+            .filter(webhook::Column::Id.is_in(hook_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|hook| (hook.id, hook))
+            .collect();
+
+        let count = due.len();
+        let results = join_all(due.into_iter().map(|delivery| {
+            let hook = hooks.get(&delivery.webhook_id);
+            self.attempt(db, hook, delivery)
+        }))
+        .await;
+        for result in results {
+            result?;
+        }
+
+        Ok(count)
+    }
+
+    async fn attempt(
+        &self,
+        db: &DatabaseConnection,
+        hook: Option<&webhook::Model>,
+        delivery: webhook_delivery::Model,
+    ) -> Result<(), DbErr> {
+        let now = Utc::now();
+        let attempts = delivery.attempts + 1;
+        let mut row: webhook_delivery::ActiveModel = delivery.clone().into();
+        row.attempts = Set(attempts);
+
+        // Pings go out even while the hook is switched off, so it can be tested
+        let hook = match hook {
+            Some(hook) if hook.active || delivery.event == PING => hook,
+            _ => {
+                row.status = Set(DeliveryStatus::Failed.to_string());
+                row.next_attempt_at = Set(None);
+                row.response_body = Set(Some("Webhook is disabled".to_string()));
+                row.update(db).await?;
+                return Ok(());
+            }
+        };
+
+        // The resolver only sees hostnames; this also catches IP addresses.
+        // A refused host fails the attempt like an unreachable one.
+        let succeeded = match check_target(&hook.url).await {
+            Err(reason) => {
+                row.response_code = Set(None);
+                row.response_body = Set(Some(reason));
+                false
+            }
+            Ok(()) => {
+                let result = self
+                    .client
+                    .post(&hook.url)
+                    .header("content-type", "application/json")
+                    .header("x-jility-event", &delivery.event)
+                    .header("x-jility-delivery", delivery.id.to_string())
+                    .header("x-jility-signature-256", sign(&hook.secret, &delivery.payload))
+                    .body(delivery.payload.clone())
+                    .send()
+                    .await;
+                match result {
+                    Ok(response) => {
+                        let status = response.status();
+                        let body = response.text().await.unwrap_or_default();
+                        row.response_code = Set(Some(status.as_u16() as i32));
+                        row.response_body = Set(Some(truncate(body)));
+                        status.is_success()
+                    }
+                    Err(e) => {
+                        row.response_code = Set(None);
+                        row.response_body = Set(Some(e.to_string()));
+                        false
+                    }
+                }
+            }
+        };
+
+        if succeeded {
+            row.status = Set(DeliveryStatus::Succeeded.to_string());
+            row.next_attempt_at = Set(None);
+            row.delivered_at = Set(Some(now));
+        } else if attempts >= MAX_ATTEMPTS {
+            row.status = Set(DeliveryStatus::Failed.to_string());
+            row.next_attempt_at = Set(None);
+        } else {
+            row.next_attempt_at = Set(Some(retry_at(now, attempts)));
+        }
+        row.update(db).await?;
+
+        Ok(())
+    }
+}
+
+fn retry_at(now: DateTime<Utc>, attempts: i32) -> DateTime<Utc> {
+    now + chrono::Duration::from_std(backoff(attempts)).unwrap_or(chrono::Duration::hours(1))
+}
+
+/// The JSON body posted for an event; the same for every hook it goes to
+fn envelope(event: &str, workspace_id: Uuid, project_id: Option<Uuid>, data: Value) -> String {
+    json!({
+        "id": Uuid::new_v4(),
+        "event": event,
+        "created_at": Utc::now().to_rfc3339(),
+        "workspace_id": workspace_id,
+        "project_id": project_id,
+        "data": data,
+    })
+    .to_string()
+}
+
+async fn queue(
+    db: &DatabaseConnection,
+    webhook_id: Uuid,
+    event: &str,
+    payload: &str,
+) -> Result<webhook_delivery::Model, DbErr> {
+    let now = Utc::now();
+    webhook_delivery::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        webhook_id: Set(webhook_id),
+        event: Set(event.to_string()),
+        payload: Set(payload.to_string()),
+        status: Set(DeliveryStatus::Pending.to_string()),
+        attempts: Set(0),
+        next_attempt_at: Set(Some(now)),
+        response_code: Set(None),
+        response_body: Set(None),
+        created_at: Set(now),
+        delivered_at: Set(None),
+    }
+    .insert(db)
+    .await
+}
+
+fn truncate(mut body: String) -> String {
+    if body.len() > MAX_LOGGED_BODY {
+        let mut end = MAX_LOGGED_BODY;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_and_backoff() {
+        // Matches `echo -n '{}' | openssl dgst -sha256 -hmac secret`
+        assert_eq!(
+            sign("secret", "{}"),
+            "sha256=77325902caca812dc259733aacd046b73817372c777b8d95b402647474516e13"
+        );
+
+        assert_eq!(backoff(1), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(60));
+        assert_eq!(backoff(7), Duration::from_secs(30 * 64));
+    }
+
+    #[tokio::test]
+    async fn test_only_public_targets_are_allowed() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://0.0.0.0/hook",
+            "http://10.1.2.3/hook",
+            "http://172.20.0.5/hook",
+            "https://192.168.1.10/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.100.1.1/hook",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[fe80::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+            "ftp://93.184.216.34/hook",
+            "not a url",
+        ] {
+            assert!(check_target(url).await.is_err(), "{}", url);
+        }
+
+        assert!(check_target("https://93.184.216.34/hook").await.is_ok());
+        assert!(check_target("http://[2606:2800:220:1::1]:8443/hook").await.is_ok());
+    }
+}
@@ -1,47 +1,92 @@
+mod topic;
+
+pub use topic::{EventScope, Topic};
+
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Query, State,
     },
+    http::{header::AUTHORIZATION, HeaderMap},
     response::Response,
 };
 use futures::{sink::SinkExt, stream::StreamExt};
+use jility_core::entities::{sprint_ticket, Project, Sprint, SprintTicket, Ticket};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
 use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::{
+    api::tickets::resolve_ticket_id,
+    auth::{access, authenticate, AuthUser, Scope},
+    error::{ApiError, ApiResult},
+    models::{ClientMessage, ServerMessage},
+    state::AppState,
+};
 
-use crate::state::AppState;
+/// Browsers can't set headers on a websocket handshake, so credentials may also
+/// be passed in the query string
+#[derive(Debug, Deserialize)]
+pub struct WebSocketAuthQuery {
+    pub token: Option<String>,
+    pub api_key: Option<String>,
+}
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
-) -> Response {
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+    Query(query): Query<WebSocketAuthQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let header = match (query.token, query.api_key) {
+        (Some(token), _) => Some(format!("Bearer {}", token)),
+        (None, Some(key)) => Some(format!("ApiKey {}", key)),
+        (None, None) => headers
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .map(str::to_string),
+    };
+    let user = authenticate(header.as_deref(), &state).await?;
+    user.require_scope(Scope::TicketsRead)?;
+
+    Ok(ws.on_upgrade(|socket| handle_websocket(socket, state, user)))
 }
 
-async fn handle_websocket(socket: WebSocket, state: AppState) {
+async fn handle_websocket(socket: WebSocket, state: AppState, user: AuthUser) {
     let (mut sender, mut receiver) = socket.split();
 
-    // Create a channel for this websocket client
+    // Create a channel for this websocket client. The state holds the only
+    // sender, so the channel closes when the state drops the client, e.g.
+    // because its API key was revoked.
     let (tx, mut rx) = mpsc::unbounded_channel::<String>();
 
-    // Subscribe this client to broadcasts
-    state.ws_state.subscribe(tx).await;
+    // Only the user's notifications are delivered until the client subscribes
+    // to a topic
+    let client_id = state.ws_state.connect(user.id, user.api_key_id, tx).await;
 
     // Spawn a task to send messages to this client
     let mut send_task = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
             if sender.send(Message::Text(msg)).await.is_err() {
-                break;
+                return;
             }
         }
+        let _ = sender.send(Message::Close(None)).await;
     });
 
     // Handle incoming messages from this client
+    let recv_state = state.clone();
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = receiver.next().await {
             match msg {
                 Message::Text(text) => {
                     tracing::debug!("Received WebSocket message: {}", text);
-                    // TODO: Handle client messages (e.g., subscriptions, filters)
+                    let reply = handle_client_message(&recv_state, client_id, &user, &text).await;
+                    let reply = serde_json::to_string(&reply).unwrap();
+                    if !recv_state.ws_state.send_to_client(client_id, reply).await {
+                        break;
+                    }
                 }
                 Message::Close(_) => {
                     tracing::debug!("WebSocket client disconnected");
@@ -58,9 +103,201 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
         _ = (&mut recv_task) => send_task.abort(),
     }
 
+    state.ws_state.disconnect(client_id).await;
     tracing::debug!("WebSocket connection closed");
 }
 
+async fn handle_client_message(
+    state: &AppState,
+    client_id: Uuid,
+    user: &AuthUser,
+    text: &str,
+) -> ServerMessage {
+    let message: ClientMessage = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(e) => {
+            return ServerMessage::Error {
+                message: format!("Invalid message: {}", e),
+            }
+        }
+    };
+
+    let (topics, subscribe) = match message {
+        ClientMessage::Subscribe { topics } => (topics, true),
+        ClientMessage::Unsubscribe { topics } => (topics, false),
+    };
+
+    let mut resolved = Vec::with_capacity(topics.len());
+    for topic in &topics {
+        match resolve_topic(state, user, topic).await {
+            Ok(topic) => resolved.push(topic),
+            Err(e) => {
+                return ServerMessage::Error {
+                    message: e.to_string(),
+                }
+            }
+        }
+    }
+
+    let topics = resolved.iter().map(|(topic, _)| topic.to_string()).collect();
+    if subscribe {
+        state.ws_state.subscribe(client_id, &resolved).await;
+        ServerMessage::Subscribed { topics }
+    } else {
+        let resolved: Vec<Topic> = resolved.into_iter().map(|(topic, _)| topic).collect();
+        state.ws_state.unsubscribe(client_id, &resolved).await;
+        ServerMessage::Unsubscribed { topics }
+    }
+}
+
+/// Parse a `<kind>:<id>` topic and check the user belongs to its workspace,
+/// which is returned with it
+async fn resolve_topic(
+    state: &AppState,
+    user: &AuthUser,
+    raw: &str,
+) -> ApiResult<(Topic, Uuid)> {
+    let db = state.db.as_ref();
+    let (kind, id) = raw
+        .split_once(':')
+        .ok_or_else(|| ApiError::InvalidInput(format!("Invalid topic: {}", raw)))?;
+    let parse = |id: &str| {
+        Uuid::parse_str(id).map_err(|_| ApiError::InvalidInput(format!("Invalid topic: {}", raw)))
+    };
+
+    let (topic, workspace_id) = match kind {
+        "workspace" => {
+            let id = parse(id)?;
+            (Topic::Workspace(id), id)
+        }
+        "project" => {
+            let id = parse(id)?;
+            (Topic::Project(id), project_workspace(state, id).await?)
+        }
+        "sprint" => {
+            let sprint = Sprint::find_by_id(parse(id)?)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::NotFound(format!("Sprint not found: {}", id)))?;
+            (
+                Topic::Sprint(sprint.id),
+                project_workspace(state, sprint.project_id).await?,
+            )
+        }
+        "ticket" => {
+            let ticket_id = resolve_ticket_id(db, id).await?;
+            let ticket = Ticket::find_by_id(ticket_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", id)))?;
+            (
+                Topic::Ticket(ticket.id),
+                project_workspace(state, ticket.project_id).await?,
+            )
+        }
+        _ => {
+            return Err(ApiError::InvalidInput(format!(
+                "Unknown topic kind '{}': expected workspace, project, sprint or ticket",
+                kind
+            )))
+        }
+    };
+
+    access::require_member(db, user, workspace_id).await?;
+
+    Ok((topic, workspace_id))
+}
+
+async fn project_workspace(state: &AppState, project_id: Uuid) -> ApiResult<Uuid> {
+    let project = Project::find_by_id(project_id)
+        .one(state.db.as_ref())
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Project not found: {}", project_id)))?;
+    Ok(project.workspace_id)
+}
+
+async fn ticket_scope(state: &AppState, ticket_id: Uuid) -> ApiResult<EventScope> {
+    let db = state.db.as_ref();
+    let ticket = Ticket::find_by_id(ticket_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Ticket not found: {}", ticket_id)))?;
+    let workspace_id = project_workspace(state, ticket.project_id).await?;
+    let sprint_ids = SprintTicket::find()
+        .filter(sprint_ticket::Column::TicketId.eq(ticket_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| row.sprint_id)
+        .collect();
+
+    Ok(EventScope {
+        workspace_id,
+        project_id: ticket.project_id,
+        sprint_ids,
+        ticket_ids: vec![ticket_id],
+    })
+}
+
+/// Deliver an event about a ticket to clients subscribed to the ticket, one of
+/// its sprints, its project or its workspace, and to the workspace's webhooks
+pub async fn publish_ticket_event(state: &AppState, ticket_id: Uuid, message: &ServerMessage) {
+    match ticket_scope(state, ticket_id).await {
+        Ok(scope) => {
+            state
+                .webhooks
+                .enqueue_message(&state.db, scope.workspace_id, scope.project_id, message)
+                .await;
+            let message = serde_json::to_string(message).unwrap();
+            state.ws_state.publish(&scope, message).await;
+        }
+        Err(e) => tracing::warn!("Failed to publish event for ticket {}: {}", ticket_id, e),
+    }
+}
+
+/// Deliver one event about many tickets of a project. `sprint_ids` are sprints
+/// the tickets just left, which no longer show up in `sprint_ticket`.
+pub async fn publish_tickets_event(
+    state: &AppState,
+    project_id: Uuid,
+    ticket_ids: Vec<Uuid>,
+    sprint_ids: Vec<Uuid>,
+    message: &ServerMessage,
+) {
+    let scope = async {
+        let workspace_id = project_workspace(state, project_id).await?;
+        let mut sprint_ids = sprint_ids;
+        sprint_ids.extend(
+            SprintTicket::find()
+                .filter(sprint_ticket::Column::TicketId.is_in(ticket_ids.clone()))
+                .all(state.db.as_ref())
+                .await?
+                .into_iter()
+                .map(|row| row.sprint_id),
+        );
+        sprint_ids.sort();
+        sprint_ids.dedup();
+        ApiResult::Ok(EventScope {
+            workspace_id,
+            project_id,
+            sprint_ids,
+            ticket_ids,
+        })
+    };
+
+    match scope.await {
+        Ok(scope) => {
+            state
+                .webhooks
+                .enqueue_message(&state.db, scope.workspace_id, project_id, message)
+                .await;
+            let message = serde_json::to_string(message).unwrap();
+            state.ws_state.publish(&scope, message).await;
+        }
+        Err(e) => tracing::warn!("Failed to publish event for project {}: {}", project_id, e),
+    }
+}
+
 pub fn websocket_routes() -> axum::Router<AppState> {
     axum::Router::new().route("/ws", axum::routing::get(websocket_handler))
 }
@@ -0,0 +1,68 @@
+use std::fmt;
+
+use uuid::Uuid;
+
+/// Something a websocket client can subscribe to, written `<kind>:<id>` on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    Workspace(Uuid),
+    Project(Uuid),
+    Sprint(Uuid),
+    Ticket(Uuid),
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topic::Workspace(id) => write!(f, "workspace:{}", id),
+            Topic::Project(id) => write!(f, "project:{}", id),
+            Topic::Sprint(id) => write!(f, "sprint:{}", id),
+            Topic::Ticket(id) => write!(f, "ticket:{}", id),
+        }
+    }
+}
+
+/// Where a ticket event happened, used to pick the clients that receive it
+#[derive(Debug, Clone)]
+pub struct EventScope {
+    pub workspace_id: Uuid,
+    pub project_id: Uuid,
+    pub sprint_ids: Vec<Uuid>,
+    /// Every ticket the event touches; one for most events, many for bulk changes
+    pub ticket_ids: Vec<Uuid>,
+}
+
+impl EventScope {
+    pub fn matches(&self, topic: &Topic) -> bool {
+        match topic {
+            Topic::Workspace(id) => *id == self.workspace_id,
+            Topic::Project(id) => *id == self.project_id,
+            Topic::Sprint(id) => self.sprint_ids.contains(id),
+            Topic::Ticket(id) => self.ticket_ids.contains(id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_matches_each_level() {
+        let scope = EventScope {
+            workspace_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            sprint_ids: vec![Uuid::new_v4()],
+            ticket_ids: vec![Uuid::new_v4()],
+        };
+
+        assert!(scope.matches(&Topic::Workspace(scope.workspace_id)));
+        assert!(scope.matches(&Topic::Project(scope.project_id)));
+        assert!(scope.matches(&Topic::Sprint(scope.sprint_ids[0])));
+        assert!(scope.matches(&Topic::Ticket(scope.ticket_ids[0])));
+
+        assert!(!scope.matches(&Topic::Workspace(scope.project_id)));
+        assert!(!scope.matches(&Topic::Ticket(Uuid::new_v4())));
+        assert!(!scope.matches(&Topic::Sprint(Uuid::new_v4())));
+    }
+}
@@ -72,7 +72,7 @@ function ActiveSprintContent() {
         // Move incomplete tickets to next sprint
         for (const ticket of incompleteTickets) {
           await api.removeTicketFromSprint(sprint.sprint.id, ticket.id)
-          await api.addTicketToSprint(nextSprint.id, ticket.id, user.email)
+          await api.addTicketToSprint(nextSprint.id, ticket.id)
         }
       } else if (action === 'backlog' && incompleteTickets.length > 0) {
         // Remove from sprint and set to backlog status
@@ -151,6 +151,11 @@ function SprintHistoryContent() {
                       <div className="text-right">
                         <div className="text-sm text-muted-foreground">Velocity</div>
                         <div className="text-lg font-bold">{velocity.completed_points} pts</div>
+                        <div className="text-xs text-muted-foreground">
+                          of {velocity.committed_points} committed
+                          {velocity.carried_over_tickets > 0 &&
+                            ` · ${velocity.carried_over_tickets} carried over`}
+                        </div>
                       </div>
                     )}
                   </div>
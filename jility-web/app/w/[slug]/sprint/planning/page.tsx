@@ -76,7 +76,7 @@ function SprintPlanningContent() {
     if (!selectedSprint || !user) return
 
     try {
-      await api.addTicketToSprint(selectedSprint.id, ticketId, user.email)
+      await api.addTicketToSprint(selectedSprint.id, ticketId)
       // Move ticket from backlog to sprint
       const ticket = backlogTickets.find(t => t.id === ticketId)
       if (ticket) {
@@ -18,6 +18,7 @@ import { ArrowLeft, Trash2, Layers, Edit2, X, Check } from 'lucide-react'
 import { useAuth } from '@/lib/auth-context'
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select'
 import { getStatusLabel } from '@/lib/utils'
+import { useWorkflow, reachableStatuses } from '@/lib/use-workflow'
 import Link from 'next/link'
 import {
   AlertDialog,
@@ -43,6 +44,7 @@ export default function TicketPage() {
   const [loading, setLoading] = useState(true)
   const [members, setMembers] = useState<WorkspaceMember[]>([])
   const [isLoadingMembers, setIsLoadingMembers] = useState(true)
+  const { workflow } = useWorkflow(ticketDetails?.ticket.project_id)
   const [epic, setEpic] = useState<Epic | null>(null)
   const [epics, setEpics] = useState<Epic[]>([])
   const [isEditingEpic, setIsEditingEpic] = useState(false)
@@ -50,13 +52,14 @@ export default function TicketPage() {
 
   // WebSocket handler for real-time updates
   const handleWebSocketMessage = useCallback((message: WebSocketMessage) => {
-    // Refresh ticket when a comment is added to this ticket
-    if (message.type === 'comment_added' && message.ticket_id === ticketId) {
+    // Refresh ticket when one of its comments is added, changed or removed
+    const commentEvents = ['comment_added', 'comment_updated', 'comment_deleted']
+    if (commentEvents.includes(message.type) && message.ticket_id === ticketId) {
       loadTicket()
     }
   }, [ticketId])
 
-  useWebSocket(handleWebSocketMessage)
+  useWebSocket(handleWebSocketMessage, [`ticket:${ticketId}`])
 
   useEffect(() => {
     loadTicket()
@@ -148,9 +151,9 @@ export default function TicketPage() {
     }
   }
 
-  const handleAddComment = async (content: string) => {
+  const handleAddComment = async (content: string, parentId?: string) => {
     try {
-      await api.createComment(ticketId, content)
+      await api.createComment(ticketId, content, parentId)
       await loadTicket()
     } catch (error) {
       console.error('Failed to add comment:', error)
@@ -172,10 +175,23 @@ export default function TicketPage() {
     }
   }
 
+  const handleToggleReaction = async (id: string, emoji: string, reacted: boolean) => {
+    try {
+      if (reacted) {
+        await api.removeReaction(id, emoji)
+      } else {
+        await api.addReaction(id, emoji)
+      }
+      await loadTicket()
+    } catch (error) {
+      console.error('Failed to react to comment:', error)
+    }
+  }
+
   const handleDeleteComment = async (id: string) => {
     try {
       await api.deleteComment(id)
-      await loadTicket()  // Refresh to remove deleted comment
+      await loadTicket()  // Refresh to show the tombstone
     } catch (error) {
       console.error('Failed to delete comment:', error)
       toast({
@@ -446,10 +462,11 @@ export default function TicketPage() {
 
           <CommentsSection
             comments={ticketDetails.comments}
-            currentUser={user?.email || 'system'}
+            currentUserId={user?.id}
             onAddComment={handleAddComment}
             onEditComment={handleEditComment}
             onDeleteComment={handleDeleteComment}
+            onToggleReaction={handleToggleReaction}
           />
         </div>
 
@@ -459,6 +476,7 @@ export default function TicketPage() {
             <div className="flex items-center gap-2">
               <StatusSelector
                 currentStatus={ticketDetails.ticket.status}
+                statuses={reachableStatuses(workflow, ticketDetails.ticket.status)}
                 onStatusChange={handleStatusChange}
               />
               <AlertDialog>
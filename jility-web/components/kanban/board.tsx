@@ -16,12 +16,11 @@ import { api } from '@/lib/api'
 import { useWebSocket } from '@/lib/websocket'
 import { useWorkspace } from '@/lib/workspace-context'
 import { useProject } from '@/lib/project-context'
+import { useWorkflow } from '@/lib/use-workflow'
 import { Column } from './column'
 import { TicketCard } from './ticket-card'
 import { CreateTicketDialog } from '../ticket/create-ticket-dialog'
 
-const STATUSES: TicketStatus[] = ['backlog', 'todo', 'in_progress', 'review', 'done', 'blocked']
-
 interface KanbanBoardProps {
   filterFn?: (tickets: Ticket[]) => Ticket[]
   epics?: Epic[]
@@ -37,6 +36,7 @@ export function KanbanBoard({ filterFn, epics = [] }: KanbanBoardProps) {
   const [activeTicket, setActiveTicket] = useState<Ticket | null>(null)
   const [loading, setLoading] = useState(true)
   const [showCreateDialog, setShowCreateDialog] = useState(false)
+  const { statuses } = useWorkflow(currentProject?.id)
 
   // Check for create parameter
   useEffect(() => {
@@ -100,10 +100,18 @@ export function KanbanBoard({ filterFn, epics = [] }: KanbanBoardProps) {
           )
         }
         break
+
+      case 'tickets_bulk_updated':
+        // One event for many tickets; reload rather than patch each one
+        loadTickets()
+        break
     }
-  }, [])
+  }, [loadTickets])
 
-  useWebSocket(handleWebSocketMessage)
+  useWebSocket(
+    handleWebSocketMessage,
+    currentProject ? [`project:${currentProject.id}`] : []
+  )
 
   const handleDragStart = (event: DragStartEvent) => {
     const ticket = tickets.find((t) => t.id === event.active.id)
@@ -131,7 +139,7 @@ export function KanbanBoard({ filterFn, epics = [] }: KanbanBoardProps) {
 
     // Check if over.id is a valid status or a ticket ID
     let newStatus: TicketStatus
-    if (STATUSES.includes(over.id as TicketStatus)) {
+    if (statuses.some((s) => s.key === over.id)) {
       // Dropped on column
       newStatus = over.id as TicketStatus
     } else {
@@ -198,11 +206,12 @@ export function KanbanBoard({ filterFn, epics = [] }: KanbanBoardProps) {
         onDragEnd={handleDragEnd}
       >
         <div className="flex gap-3 md:gap-4 overflow-x-auto pb-4 px-4 md:px-6 pt-6 h-full">
-          {STATUSES.map((status) => (
+          {statuses.map((status) => (
             <Column
-              key={status}
-              status={status}
-              tickets={displayedTickets.filter((t) => t.status === status)}
+              key={status.key}
+              status={status.key}
+              label={status.name}
+              tickets={displayedTickets.filter((t) => t.status === status.key)}
               epics={epics}
             />
           ))}
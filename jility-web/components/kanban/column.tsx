@@ -9,12 +9,13 @@ import { Plus } from 'lucide-react'
 
 interface ColumnProps {
   status: TicketStatus
+  label?: string
   tickets: Ticket[]
   epics?: Epic[]
   onCreateTicket?: (status: TicketStatus) => void
 }
 
-export function Column({ status, tickets, epics = [], onCreateTicket }: ColumnProps) {
+export function Column({ status, label, tickets, epics = [], onCreateTicket }: ColumnProps) {
   const { setNodeRef, isOver } = useDroppable({
     id: status,
   })
@@ -26,7 +27,7 @@ export function Column({ status, tickets, epics = [], onCreateTicket }: ColumnPr
       <div className="flex items-center justify-between mb-3 px-2">
         <div className="flex items-center gap-2">
           <h2 className="text-sm font-semibold">
-            {getStatusLabel(status)}
+            {label ?? getStatusLabel(status)}
           </h2>
           <span className="text-xs text-muted-foreground bg-muted px-2 py-0.5 rounded-full">
             {tickets.length}
@@ -13,6 +13,18 @@ interface ApiKey {
   last_used_at: string | null
 }
 
+const SCOPES = [
+  { value: 'tickets:read', label: 'Read tickets, projects and comments' },
+  { value: 'tickets:write', label: 'Create and update tickets and comments' },
+  { value: 'tickets:delete', label: 'Delete tickets and comments' },
+  { value: 'sprints:read', label: 'Read sprints' },
+  { value: 'sprints:write', label: 'Manage sprints' },
+  { value: 'projects:write', label: 'Manage projects' },
+  { value: 'admin', label: 'Full access, including workspaces and API keys' },
+]
+
+const DEFAULT_SCOPES = ['tickets:read', 'tickets:write', 'sprints:read']
+
 interface ApiKeysTabProps {
   apiKeys: ApiKey[]
   onUpdate: () => Promise<void>
@@ -21,6 +33,7 @@ interface ApiKeysTabProps {
 export function ApiKeysTab({ apiKeys, onUpdate }: ApiKeysTabProps) {
   const [showNewKeyModal, setShowNewKeyModal] = useState(false)
   const [newKeyName, setNewKeyName] = useState('')
+  const [newKeyScopes, setNewKeyScopes] = useState<string[]>(DEFAULT_SCOPES)
   const [newKeyData, setNewKeyData] = useState<{ key: string; api_key: ApiKey } | null>(null)
 
   const handleCreateApiKey = async () => {
@@ -28,15 +41,20 @@ export function ApiKeysTab({ apiKeys, onUpdate }: ApiKeysTabProps) {
       alert('Please enter a key name')
       return
     }
+    if (newKeyScopes.length === 0) {
+      alert('Please select at least one scope')
+      return
+    }
 
     try {
       const result = await api.createApiKey({
         name: newKeyName,
-        scopes: ['tickets:read', 'tickets:write'],
+        scopes: newKeyScopes,
         expires_in_days: 365,
       })
       setNewKeyData(result)
       setNewKeyName('')
+      setNewKeyScopes(DEFAULT_SCOPES)
       await onUpdate()
     } catch (error) {
       alert('Failed to create API key: ' + (error instanceof Error ? error.message : 'Unknown error'))
@@ -182,6 +200,32 @@ export function ApiKeysTab({ apiKeys, onUpdate }: ApiKeysTabProps) {
                   />
                 </div>
 
+                <div>
+                  <span className="block text-sm font-medium mb-2">Scopes</span>
+                  <div className="space-y-2">
+                    {SCOPES.map((scope) => (
+                      <label key={scope.value} className="flex items-start gap-2 text-sm">
+                        <input
+                          type="checkbox"
+                          className="mt-1"
+                          checked={newKeyScopes.includes(scope.value)}
+                          onChange={(e) =>
+                            setNewKeyScopes((prev) =>
+                              e.target.checked
+                                ? [...prev, scope.value]
+                                : prev.filter((s) => s !== scope.value)
+                            )
+                          }
+                        />
+                        <span>
+                          <span className="font-mono">{scope.value}</span>
+                          <span className="block text-xs text-muted-foreground">{scope.label}</span>
+                        </span>
+                      </label>
+                    ))}
+                  </div>
+                </div>
+
                 <div className="flex gap-2">
                   <button
                     onClick={handleCreateApiKey}
@@ -6,6 +6,7 @@ export interface BurndownDataPoint {
   date: string
   ideal: number
   actual: number
+  scope_added: number
 }
 
 export interface BurndownData {
@@ -18,13 +19,14 @@ interface BurndownChartProps {
 }
 
 export function BurndownChart({ data }: BurndownChartProps) {
-  const { maxPoints, points } = useMemo(() => {
+  const { maxPoints, points, hasScopeChange } = useMemo(() => {
     const max = Math.max(
-      ...data.data_points.map(d => Math.max(d.ideal, d.actual))
+      ...data.data_points.map(d => Math.max(d.ideal, d.actual, d.scope_added))
     )
     return {
       maxPoints: max,
-      points: data.data_points
+      points: data.data_points,
+      hasScopeChange: data.data_points.some(d => d.scope_added > 0)
     }
   }, [data])
 
@@ -48,6 +50,10 @@ export function BurndownChart({ data }: BurndownChartProps) {
     .map((d, i) => `${i === 0 ? 'M' : 'L'} ${xScale(i)} ${yScale(d.actual)}`)
     .join(' ')
 
+  const scopePath = points
+    .map((d, i) => `${i === 0 ? 'M' : 'L'} ${xScale(i)} ${yScale(d.scope_added)}`)
+    .join(' ')
+
   return (
     <div className="w-full overflow-x-auto">
       <svg
@@ -122,6 +128,16 @@ export function BurndownChart({ data }: BurndownChartProps) {
           strokeDasharray="5,5"
         />
 
+        {/* Scope added mid-sprint */}
+        {hasScopeChange && (
+          <path
+            d={scopePath}
+            fill="none"
+            stroke="#f59e0b"
+            strokeWidth={2}
+          />
+        )}
+
         {/* Actual burndown line */}
         <path
           d={actualPath}
@@ -172,6 +188,12 @@ export function BurndownChart({ data }: BurndownChartProps) {
           <div className="w-8 h-0.5 bg-blue-500" />
           <span className="text-sm text-gray-600 dark:text-gray-400">Actual</span>
         </div>
+        {hasScopeChange && (
+          <div className="flex items-center gap-2">
+            <div className="w-8 h-0.5 bg-amber-500" />
+            <span className="text-sm text-gray-600 dark:text-gray-400">Scope added</span>
+          </div>
+        )}
       </div>
     </div>
   )
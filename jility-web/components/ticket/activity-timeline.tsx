@@ -20,6 +20,7 @@ interface ActivityTimelineProps {
 function getChangeIcon(changeType: string) {
   switch (changeType) {
     case 'created':
+    case 'imported':
       return <Circle className="h-4 w-4" />
     case 'status_changed':
       return <AlertCircle className="h-4 w-4" />
@@ -36,6 +37,8 @@ function getChangeIcon(changeType: string) {
     case 'commit_linked':
       return <GitCommit className="h-4 w-4" />
     case 'comment_added':
+    case 'comment_edited':
+    case 'comment_deleted':
       return <MessageSquare className="h-4 w-4" />
     default:
       return <Circle className="h-4 w-4" />
@@ -46,6 +49,8 @@ function getChangeDescription(change: TicketChange): string {
   switch (change.change_type) {
     case 'created':
       return 'created this ticket'
+    case 'imported':
+      return 'imported this ticket'
     case 'status_changed':
       return `changed status from ${getStatusLabel(change.old_value || '')} to ${getStatusLabel(change.new_value || '')}`
     case 'title_changed':
@@ -66,6 +71,10 @@ function getChangeDescription(change: TicketChange): string {
       return `linked commit`
     case 'comment_added':
       return `added a comment`
+    case 'comment_edited':
+      return `edited a comment`
+    case 'comment_deleted':
+      return `removed a comment`
     default:
       return change.change_type.replace(/_/g, ' ')
   }
@@ -6,7 +6,8 @@ import { Button } from '@/components/ui/button'
 import { Textarea } from '@/components/ui/textarea'
 import { formatDate } from '@/lib/utils'
 import { useToast } from '@/hooks/use-toast'
-import { MoreHorizontal, Pencil, Trash2 } from 'lucide-react'
+import { MoreHorizontal, Pencil, Reply, Trash2 } from 'lucide-react'
+import type { Comment } from '@/lib/types'
 import {
   DropdownMenu,
   DropdownMenuContent,
@@ -24,28 +25,42 @@ import {
   AlertDialogTitle,
 } from '@/components/ui/alert-dialog'
 
+const QUICK_REACTIONS = ['👍', '🎉', '👀']
+
 interface CommentItemProps {
-  comment: {
-    id: string
-    author: string
-    content: string
-    created_at: string
-    updated_at?: string
-  }
-  currentUser: string
+  comment: Comment
+  currentUserId?: string
   onEdit?: (id: string, content: string) => Promise<void>
   onDelete?: (id: string) => Promise<void>
+  onReply?: (comment: Comment) => void
+  onToggleReaction?: (id: string, emoji: string, reacted: boolean) => Promise<void>
 }
 
-export function CommentItem({ comment, currentUser, onEdit, onDelete }: CommentItemProps) {
+export function CommentItem({
+  comment,
+  currentUserId,
+  onEdit,
+  onDelete,
+  onReply,
+  onToggleReaction,
+}: CommentItemProps) {
   const { toast } = useToast()
   const [isEditing, setIsEditing] = useState(false)
   const [editedContent, setEditedContent] = useState(comment.content)
   const [isSaving, setIsSaving] = useState(false)
   const [showDeleteDialog, setShowDeleteDialog] = useState(false)
 
-  const isAuthor = comment.author === currentUser
-  const isEdited = comment.updated_at && comment.updated_at !== comment.created_at
+  const isAuthor = !!currentUserId && comment.author_user_id === currentUserId
+  const isEdited = comment.revision_count > 0
+
+  if (comment.deleted_at) {
+    return (
+      <div className="flex gap-3">
+        <div className="h-8 w-8" />
+        <p className="text-sm italic text-muted-foreground">Comment removed</p>
+      </div>
+    )
+  }
 
   const handleSave = async () => {
     if (!editedContent.trim() || !onEdit) return
@@ -129,6 +144,48 @@ export function CommentItem({ comment, currentUser, onEdit, onDelete }: CommentI
             <p className="text-sm whitespace-pre-wrap">{comment.content}</p>
           </div>
         )}
+
+        {!isEditing && (
+          <div className="flex flex-wrap items-center gap-1 mt-1">
+            {comment.reactions.map((reaction) => {
+              const reacted = !!currentUserId && reaction.user_ids.includes(currentUserId)
+              return (
+                <Button
+                  key={reaction.emoji}
+                  variant={reacted ? 'secondary' : 'ghost'}
+                  size="sm"
+                  className="h-6 px-2 text-xs"
+                  title={reaction.users.join(', ')}
+                  onClick={() => onToggleReaction?.(comment.id, reaction.emoji, reacted)}
+                >
+                  {reaction.emoji} {reaction.count}
+                </Button>
+              )
+            })}
+            <div className="flex gap-1 opacity-0 group-hover:opacity-100">
+              {onToggleReaction &&
+                QUICK_REACTIONS.filter((emoji) => !comment.reactions.some((r) => r.emoji === emoji)).map(
+                  (emoji) => (
+                    <Button
+                      key={emoji}
+                      variant="ghost"
+                      size="sm"
+                      className="h-6 px-2 text-xs"
+                      onClick={() => onToggleReaction(comment.id, emoji, false)}
+                    >
+                      {emoji}
+                    </Button>
+                  )
+                )}
+              {onReply && (
+                <Button variant="ghost" size="sm" className="h-6 px-2 text-xs" onClick={() => onReply(comment)}>
+                  <Reply className="h-3 w-3 mr-1" />
+                  Reply
+                </Button>
+              )}
+            </div>
+          </div>
+        )}
       </div>
 
       {isAuthor && !isEditing && (
@@ -161,7 +218,7 @@ export function CommentItem({ comment, currentUser, onEdit, onDelete }: CommentI
           <AlertDialogHeader>
             <AlertDialogTitle>Delete comment?</AlertDialogTitle>
             <AlertDialogDescription>
-              This action cannot be undone.
+              The comment will be replaced by a &quot;comment removed&quot; note in its thread.
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
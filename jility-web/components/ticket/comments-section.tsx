@@ -10,21 +10,41 @@ import { MessageSquare, Send } from 'lucide-react'
 
 interface CommentsSectionProps {
   comments: Comment[]
-  currentUser?: string
-  onAddComment: (content: string) => Promise<void>
+  currentUserId?: string
+  onAddComment: (content: string, parentId?: string) => Promise<void>
   onEditComment?: (id: string, content: string) => Promise<void>
   onDeleteComment?: (id: string) => Promise<void>
+  onToggleReaction?: (id: string, emoji: string, reacted: boolean) => Promise<void>
+}
+
+// Depth-first order: each reply follows the comment it answers
+function threaded(comments: Comment[]): { comment: Comment; depth: number }[] {
+  const ids = new Set(comments.map((c) => c.id))
+  const result: { comment: Comment; depth: number }[] = []
+  const visit = (parentId: string | null, depth: number) => {
+    for (const comment of comments) {
+      const parent = comment.parent_id && ids.has(comment.parent_id) ? comment.parent_id : null
+      if (parent === parentId) {
+        result.push({ comment, depth })
+        visit(comment.id, depth + 1)
+      }
+    }
+  }
+  visit(null, 0)
+  return result
 }
 
 export function CommentsSection({
   comments,
-  currentUser = 'system',
+  currentUserId,
   onAddComment,
   onEditComment,
   onDeleteComment,
+  onToggleReaction,
 }: CommentsSectionProps) {
   const { toast } = useToast()
   const [newComment, setNewComment] = useState('')
+  const [replyTo, setReplyTo] = useState<Comment | null>(null)
   const [isSubmitting, setIsSubmitting] = useState(false)
 
   const handleSubmit = async (e: React.FormEvent) => {
@@ -33,8 +53,9 @@ export function CommentsSection({
 
     setIsSubmitting(true)
     try {
-      await onAddComment(newComment.trim())
+      await onAddComment(newComment.trim(), replyTo?.id)
       setNewComment('')
+      setReplyTo(null)
     } catch (error) {
       console.error('Failed to add comment:', error)
       toast({
@@ -63,22 +84,33 @@ export function CommentsSection({
             No comments yet. Start the conversation!
           </p>
         ) : (
-          comments.map((comment) => (
-            <CommentItem
-              key={comment.id}
-              comment={comment}
-              currentUser={currentUser}
-              onEdit={onEditComment}
-              onDelete={onDeleteComment}
-            />
+          threaded(comments).map(({ comment, depth }) => (
+            <div key={comment.id} style={{ marginLeft: `${Math.min(depth, 4) * 2.75}rem` }}>
+              <CommentItem
+                comment={comment}
+                currentUserId={currentUserId}
+                onEdit={onEditComment}
+                onDelete={onDeleteComment}
+                onReply={setReplyTo}
+                onToggleReaction={onToggleReaction}
+              />
+            </div>
           ))
         )}
       </div>
 
       {/* New Comment Form */}
       <form onSubmit={handleSubmit} className="space-y-2 md:space-y-3">
+        {replyTo && (
+          <div className="flex items-center justify-between text-xs text-muted-foreground">
+            <span>Replying to {replyTo.author}</span>
+            <Button type="button" variant="ghost" size="sm" onClick={() => setReplyTo(null)}>
+              Cancel
+            </Button>
+          </div>
+        )}
         <Textarea
-          placeholder="Add a comment..."
+          placeholder={replyTo ? 'Write a reply...' : 'Add a comment...'}
           value={newComment}
           onChange={(e) => setNewComment(e.target.value)}
           className="min-h-20 md:min-h-24 text-sm"
@@ -1,22 +1,34 @@
 'use client'
 
-import type { TicketStatus } from '@/lib/types'
+import type { TicketStatus, WorkflowStatus } from '@/lib/types'
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select'
 import { getStatusLabel } from '@/lib/utils'
+import { DEFAULT_STATUSES } from '@/lib/use-workflow'
 
 interface StatusSelectorProps {
   currentStatus: TicketStatus
   onStatusChange: (newStatus: TicketStatus) => Promise<void>
+  // Statuses the ticket can move to; defaults to every built-in status
+  statuses?: WorkflowStatus[]
   disabled?: boolean
 }
 
-const STATUSES: TicketStatus[] = ['backlog', 'todo', 'in_progress', 'review', 'done', 'blocked']
-
-export function StatusSelector({ currentStatus, onStatusChange, disabled = false }: StatusSelectorProps) {
+export function StatusSelector({
+  currentStatus,
+  onStatusChange,
+  statuses = DEFAULT_STATUSES,
+  disabled = false,
+}: StatusSelectorProps) {
   const handleChange = async (value: string) => {
     await onStatusChange(value as TicketStatus)
   }
 
+  const labelFor = (key: string) =>
+    statuses.find((s) => s.key === key)?.name ?? getStatusLabel(key)
+  const options = statuses.some((s) => s.key === currentStatus)
+    ? statuses
+    : [{ key: currentStatus, name: labelFor(currentStatus), category: 'todo' as const }, ...statuses]
+
   return (
     <Select value={currentStatus} onValueChange={handleChange} disabled={disabled}>
       <SelectTrigger className="flex-1">
@@ -26,19 +38,19 @@ export function StatusSelector({ currentStatus, onStatusChange, disabled = false
               className="w-2 h-2 rounded-full flex-shrink-0"
               style={{ backgroundColor: `var(--status-${currentStatus})` }}
             />
-            <span>{getStatusLabel(currentStatus)}</span>
+            <span>{labelFor(currentStatus)}</span>
           </div>
         </SelectValue>
       </SelectTrigger>
       <SelectContent>
-        {STATUSES.map((status) => (
-          <SelectItem key={status} value={status}>
+        {options.map((status) => (
+          <SelectItem key={status.key} value={status.key}>
             <div className="flex items-center gap-2">
               <div
                 className="w-2 h-2 rounded-full flex-shrink-0"
-                style={{ backgroundColor: `var(--status-${status})` }}
+                style={{ backgroundColor: `var(--status-${status.key})` }}
               />
-              <span>{getStatusLabel(status)}</span>
+              <span>{status.name}</span>
             </div>
           </SelectItem>
         ))}
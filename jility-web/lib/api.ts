@@ -3,12 +3,25 @@ import type {
   TicketDetails,
   CreateTicketRequest,
   UpdateTicketRequest,
+  Attachment,
   Comment,
+  CommentRevision,
   Project,
   CreateProjectRequest,
   UpdateProjectRequest,
+  Workflow,
+  UpdateWorkflowRequest,
+  JiraMapping,
+  GithubMapping,
+  ImportReport,
   TicketFilters,
+  TicketPage,
+  BulkTicketRequest,
+  BulkTicketResponse,
   LinkedCommit,
+  Notification,
+  NotificationList,
+  TicketWatchers,
   TicketChange,
   SearchFilters,
   SearchResponse,
@@ -19,6 +32,7 @@ import type {
   InviteMemberRequest,
   InviteResponse,
   PendingInvite,
+  RestoreReport,
   InviteDetails,
   WorkspaceResponse,
   Sprint,
@@ -58,7 +72,7 @@ async function handleResponse<T>(response: Response): Promise<T> {
 export const api = {
   // Projects
   listProjects: async (): Promise<Project[]> => {
-    const res = await fetch(`${API_BASE}/projects`)
+    const res = await fetch(`${API_BASE}/projects`, { headers: getAuthHeaders() })
     return handleResponse<Project[]>(res)
   },
 
@@ -75,7 +89,7 @@ export const api = {
   },
 
   getProject: async (id: string): Promise<Project> => {
-    const res = await fetch(`${API_BASE}/projects/${id}`)
+    const res = await fetch(`${API_BASE}/projects/${id}`, { headers: getAuthHeaders() })
     return handleResponse<Project>(res)
   },
 
@@ -91,6 +105,57 @@ export const api = {
     return handleResponse<Project>(res)
   },
 
+  getProjectWorkflow: async (projectId: string): Promise<Workflow> => {
+    const res = await fetch(`${API_BASE}/projects/${projectId}/workflow`, { headers: getAuthHeaders() })
+    return handleResponse<Workflow>(res)
+  },
+
+  updateProjectWorkflow: async (projectId: string, data: UpdateWorkflowRequest): Promise<Workflow> => {
+    const res = await fetch(`${API_BASE}/projects/${projectId}/workflow`, {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+        ...getAuthHeaders(),
+      },
+      body: JSON.stringify(data),
+    })
+    return handleResponse<Workflow>(res)
+  },
+
+  importJira: async (
+    projectId: string,
+    files: File[],
+    mapping?: JiraMapping,
+    dryRun = false,
+  ): Promise<ImportReport> => {
+    const form = new FormData()
+    files.forEach((file) => form.append('file', file))
+    if (mapping) form.append('mapping', JSON.stringify(mapping))
+    const res = await fetch(`${API_BASE}/projects/${projectId}/import/jira?dry_run=${dryRun}`, {
+      method: 'POST',
+      headers: getAuthHeaders(),
+      body: form,
+    })
+    return handleResponse<ImportReport>(res)
+  },
+
+  importGithub: async (
+    projectId: string,
+    files: File[],
+    mapping?: GithubMapping,
+    dryRun = false,
+  ): Promise<ImportReport> => {
+    const form = new FormData()
+    files.forEach((file) => form.append('file', file))
+    if (mapping) form.append('mapping', JSON.stringify(mapping))
+    const res = await fetch(`${API_BASE}/projects/${projectId}/import/github?dry_run=${dryRun}`, {
+      method: 'POST',
+      headers: getAuthHeaders(),
+      body: form,
+    })
+    return handleResponse<ImportReport>(res)
+  },
+
   deleteProject: async (id: string): Promise<{ success: boolean }> => {
     const res = await fetch(`${API_BASE}/projects/${id}`, {
       method: 'DELETE',
@@ -99,8 +164,20 @@ export const api = {
     return handleResponse<{ success: boolean }>(res)
   },
 
+  bulkUpdateTickets: async (data: BulkTicketRequest): Promise<BulkTicketResponse> => {
+    const res = await fetch(`${API_BASE}/tickets/bulk`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        ...getAuthHeaders(),
+      },
+      body: JSON.stringify(data),
+    })
+    return handleResponse<BulkTicketResponse>(res)
+  },
+
   // Tickets
-  listTickets: async (filters?: TicketFilters): Promise<Ticket[]> => {
+  listTicketPage: async (filters?: TicketFilters, cursor?: string): Promise<TicketPage> => {
     const params = new URLSearchParams()
     if (filters?.project_id) params.append('project_id', filters.project_id)
     if (filters?.status) {
@@ -109,13 +186,34 @@ export const api = {
       statuses.forEach(status => params.append('status', status))
     }
     if (filters?.assignee) params.append('assignee', filters.assignee)
+    if (filters?.unassigned) params.append('unassigned', 'true')
+    filters?.labels?.forEach(label => params.append('label', label))
+    if (filters?.epic_id) params.append('epic_id', filters.epic_id)
+    if (filters?.sprint_id) params.append('sprint_id', filters.sprint_id)
+    if (filters?.parent_id) params.append('parent_id', filters.parent_id)
+    if (filters?.sort) params.append('sort', filters.sort)
+    if (filters?.order) params.append('order', filters.order)
+    if (filters?.limit) params.append('limit', String(filters.limit))
+    if (cursor) params.append('cursor', cursor)
 
-    const res = await fetch(`${API_BASE}/tickets?${params}`)
-    return handleResponse<Ticket[]>(res)
+    const res = await fetch(`${API_BASE}/tickets?${params}`, { headers: getAuthHeaders() })
+    return handleResponse<TicketPage>(res)
+  },
+
+  // Every matching ticket, fetched page by page
+  listTickets: async (filters?: TicketFilters): Promise<Ticket[]> => {
+    const tickets: Ticket[] = []
+    let cursor: string | undefined
+    do {
+      const page = await api.listTicketPage({ limit: 500, ...filters }, cursor)
+      tickets.push(...page.tickets)
+      cursor = page.next_cursor ?? undefined
+    } while (cursor)
+    return tickets
   },
 
   getTicket: async (id: string): Promise<TicketDetails> => {
-    const res = await fetch(`${API_BASE}/tickets/${id}`)
+    const res = await fetch(`${API_BASE}/tickets/${id}`, { headers: getAuthHeaders() })
     return handleResponse<TicketDetails>(res)
   },
 
@@ -207,18 +305,18 @@ export const api = {
 
   // Comments
   listComments: async (ticketId: string): Promise<Comment[]> => {
-    const res = await fetch(`${API_BASE}/tickets/${ticketId}/comments`)
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/comments`, { headers: getAuthHeaders() })
     return handleResponse<Comment[]>(res)
   },
 
-  createComment: async (ticketId: string, content: string): Promise<Comment> => {
+  createComment: async (ticketId: string, content: string, parentId?: string): Promise<Comment> => {
     const res = await fetch(`${API_BASE}/tickets/${ticketId}/comments`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
         ...getAuthHeaders(),
       },
-      body: JSON.stringify({ content }),
+      body: JSON.stringify({ content, parent_id: parentId }),
     })
     return handleResponse<Comment>(res)
   },
@@ -243,6 +341,117 @@ export const api = {
     return handleResponse<{ success: boolean }>(res)
   },
 
+  listCommentRevisions: async (id: string): Promise<CommentRevision[]> => {
+    const res = await fetch(`${API_BASE}/comments/${id}/revisions`, { headers: getAuthHeaders() })
+    return handleResponse<CommentRevision[]>(res)
+  },
+
+  addReaction: async (id: string, emoji: string): Promise<Comment> => {
+    const res = await fetch(`${API_BASE}/comments/${id}/reactions`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        ...getAuthHeaders(),
+      },
+      body: JSON.stringify({ emoji }),
+    })
+    return handleResponse<Comment>(res)
+  },
+
+  removeReaction: async (id: string, emoji: string): Promise<Comment> => {
+    const res = await fetch(`${API_BASE}/comments/${id}/reactions/${encodeURIComponent(emoji)}`, {
+      method: 'DELETE',
+      headers: getAuthHeaders(),
+    })
+    return handleResponse<Comment>(res)
+  },
+
+  // Attachments
+  listAttachments: async (ticketId: string): Promise<Attachment[]> => {
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/attachments`, { headers: getAuthHeaders() })
+    return handleResponse<Attachment[]>(res)
+  },
+
+  uploadAttachment: async (ticketId: string, file: File, commentId?: string): Promise<Attachment> => {
+    const form = new FormData()
+    form.append('file', file)
+    if (commentId) form.append('comment_id', commentId)
+    // The browser sets the multipart boundary itself
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/attachments`, {
+      method: 'POST',
+      headers: getAuthHeaders(),
+      body: form,
+    })
+    return handleResponse<Attachment>(res)
+  },
+
+  downloadAttachment: async (id: string): Promise<Blob> => {
+    const res = await fetch(`${API_BASE}/attachments/${id}/content`, { headers: getAuthHeaders() })
+    if (!res.ok) return handleResponse<Blob>(res)
+    return res.blob()
+  },
+
+  deleteAttachment: async (id: string): Promise<{ success: boolean }> => {
+    const res = await fetch(`${API_BASE}/attachments/${id}`, {
+      method: 'DELETE',
+      headers: getAuthHeaders(),
+    })
+    return handleResponse<{ success: boolean }>(res)
+  },
+
+  // Watchers and notifications
+  listWatchers: async (ticketId: string): Promise<TicketWatchers> => {
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/watchers`, { headers: getAuthHeaders() })
+    return handleResponse<TicketWatchers>(res)
+  },
+
+  followTicket: async (ticketId: string): Promise<TicketWatchers> => {
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/watchers`, {
+      method: 'POST',
+      headers: getAuthHeaders(),
+    })
+    return handleResponse<TicketWatchers>(res)
+  },
+
+  unfollowTicket: async (ticketId: string): Promise<TicketWatchers> => {
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/watchers`, {
+      method: 'DELETE',
+      headers: getAuthHeaders(),
+    })
+    return handleResponse<TicketWatchers>(res)
+  },
+
+  listNotifications: async (
+    params: { unread?: boolean; limit?: number; before?: string } = {}
+  ): Promise<NotificationList> => {
+    const query = new URLSearchParams()
+    if (params.unread) query.append('unread', 'true')
+    if (params.limit) query.append('limit', params.limit.toString())
+    if (params.before) query.append('before', params.before)
+    const res = await fetch(`${API_BASE}/notifications?${query}`, { headers: getAuthHeaders() })
+    return handleResponse<NotificationList>(res)
+  },
+
+  markNotification: async (id: string, read: boolean): Promise<Notification> => {
+    const res = await fetch(`${API_BASE}/notifications/${id}`, {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+        ...getAuthHeaders(),
+      },
+      body: JSON.stringify({ read }),
+    })
+    return handleResponse<Notification>(res)
+  },
+
+  markAllNotificationsRead: async (): Promise<{ updated: number }> => {
+    const res = await fetch(`${API_BASE}/notifications/read-all`, {
+      method: 'POST',
+      headers: getAuthHeaders(),
+    })
+    return handleResponse<{ updated: number }>(res)
+  },
+
   // Dependencies
   addDependency: async (ticketId: string, dependsOnId: string): Promise<{ success: boolean }> => {
     const res = await fetch(`${API_BASE}/tickets/${ticketId}/dependencies`, {
@@ -265,7 +474,7 @@ export const api = {
 
   // Activity
   getActivity: async (ticketId: string): Promise<TicketChange[]> => {
-    const res = await fetch(`${API_BASE}/tickets/${ticketId}/activity`)
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/activity`, { headers: getAuthHeaders() })
     return handleResponse<TicketChange[]>(res)
   },
 
@@ -287,7 +496,7 @@ export const api = {
   },
 
   listCommits: async (ticketId: string): Promise<LinkedCommit[]> => {
-    const res = await fetch(`${API_BASE}/tickets/${ticketId}/commits`)
+    const res = await fetch(`${API_BASE}/tickets/${ticketId}/commits`, { headers: getAuthHeaders() })
     return handleResponse<LinkedCommit[]>(res)
   },
 
@@ -296,7 +505,7 @@ export const api = {
     const params = new URLSearchParams({ q: query })
     if (limit) params.append('limit', limit.toString())
 
-    const res = await fetch(`${API_BASE}/search?${params}`)
+    const res = await fetch(`${API_BASE}/search?${params}`, { headers: getAuthHeaders() })
     return handleResponse<Ticket[]>(res)
   },
 
@@ -324,18 +533,18 @@ export const api = {
     if (filters.limit) params.append('limit', filters.limit.toString())
     if (filters.offset) params.append('offset', filters.offset.toString())
 
-    const res = await fetch(`${API_BASE}/search?${params}`)
+    const res = await fetch(`${API_BASE}/search?${params}`, { headers: getAuthHeaders() })
     return handleResponse<SearchResponse>(res)
   },
 
   // Saved views
   listSavedViews: async (): Promise<SavedView[]> => {
-    const res = await fetch(`${API_BASE}/search/views`)
+    const res = await fetch(`${API_BASE}/search/views`, { headers: getAuthHeaders() })
     return handleResponse<SavedView[]>(res)
   },
 
   getSavedView: async (id: string): Promise<SavedView> => {
-    const res = await fetch(`${API_BASE}/search/views/${id}`)
+    const res = await fetch(`${API_BASE}/search/views/${id}`, { headers: getAuthHeaders() })
     return handleResponse<SavedView>(res)
   },
 
@@ -475,6 +684,24 @@ export const api = {
     return response.json()
   },
 
+  // Workspace archives; admins only
+  exportWorkspace: async (workspaceSlug: string): Promise<Blob> => {
+    const res = await fetch(`${API_BASE}/workspaces/${workspaceSlug}/export`, {
+      headers: getAuthHeaders(),
+    })
+    if (!res.ok) return handleResponse<Blob>(res)
+    return res.blob()
+  },
+
+  restoreWorkspace: async (archive: Blob, dryRun = false): Promise<RestoreReport> => {
+    const res = await fetch(`${API_BASE}/workspaces/restore?dry_run=${dryRun}`, {
+      method: 'POST',
+      headers: { ...getAuthHeaders(), 'Content-Type': 'application/x-ndjson' },
+      body: archive,
+    })
+    return handleResponse<RestoreReport>(res)
+  },
+
   getInviteDetails: async (token: string): Promise<InviteDetails> => {
     const response = await fetch(`${API_BASE}/invites/${token}`)
     if (!response.ok) {
@@ -601,14 +828,10 @@ export const api = {
     return handleResponse<Sprint>(res)
   },
 
-  addTicketToSprint: async (sprintId: string, ticketId: string, addedBy: string): Promise<void> => {
+  addTicketToSprint: async (sprintId: string, ticketId: string): Promise<void> => {
     const res = await fetch(`${API_BASE}/sprints/${sprintId}/tickets/${ticketId}`, {
       method: 'POST',
-      headers: {
-        'Content-Type': 'application/json',
-        ...getAuthHeaders(),
-      },
-      body: JSON.stringify({ added_by: addedBy }),
+      headers: getAuthHeaders(),
     })
     await handleResponse<void>(res)
   },
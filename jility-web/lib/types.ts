@@ -1,4 +1,5 @@
-export type TicketStatus = 'backlog' | 'todo' | 'in_progress' | 'review' | 'done' | 'blocked'
+// Built-in statuses; projects with a custom workflow may use any status key
+export type TicketStatus = 'backlog' | 'todo' | 'in_progress' | 'review' | 'done' | 'blocked' | (string & {})
 
 export interface Ticket {
   id: string
@@ -26,7 +27,10 @@ export interface TicketReference {
 
 export interface TicketDetails {
   ticket: Ticket
+  // Keys the ticket had in trackers it was imported from
+  aliases: string[]
   comments: Comment[]
+  attachments: Attachment[]
   dependencies: TicketReference[]
   dependents: TicketReference[]
   linked_commits: LinkedCommit[]
@@ -36,10 +40,83 @@ export interface TicketDetails {
 export interface Comment {
   id: string
   ticket_id: string
+  // The comment this one replies to
+  parent_id?: string | null
   author: string
+  // The user who wrote it; unset for imported comments
+  author_user_id?: string | null
+  // Empty once the comment is removed
   content: string
   created_at: string
   updated_at?: string
+  revision_count: number
+  reactions: CommentReaction[]
+  // Set on the tombstone of a removed comment
+  deleted_at?: string | null
+  deleted_by?: string | null
+}
+
+export interface Attachment {
+  id: string
+  ticket_id: string
+  // Set when the file belongs to a comment
+  comment_id?: string | null
+  filename: string
+  content_type: string
+  size_bytes: number
+  sha256: string
+  uploaded_by: string
+  uploaded_by_user_id?: string | null
+  created_at: string
+  // API path of the content; needs the auth header, see api.downloadAttachment
+  url: string
+}
+
+export interface CommentReaction {
+  emoji: string
+  count: number
+  users: string[]
+  user_ids: string[]
+}
+
+export interface CommentRevision {
+  id: string
+  // The text before the edit
+  content: string
+  edited_by: string
+  edited_at: string
+}
+
+export type NotificationKind = 'mentioned' | 'assigned' | 'commented' | 'status_changed'
+
+export interface Notification {
+  id: string
+  kind: NotificationKind
+  ticket_id: string
+  ticket_number: string
+  ticket_title: string
+  actor: string
+  message: string
+  comment_id?: string
+  read: boolean
+  created_at: string
+}
+
+export interface NotificationList {
+  notifications: Notification[]
+  unread_count: number
+}
+
+export interface TicketWatcher {
+  user_id: string
+  username: string
+  reason: 'creator' | 'assignee' | 'commenter' | 'follower'
+  created_at: string
+}
+
+export interface TicketWatchers {
+  watching: boolean
+  watchers: TicketWatcher[]
 }
 
 export interface LinkedCommit {
@@ -74,6 +151,80 @@ export interface Project {
   updated_at: string
 }
 
+export type StatusCategory = 'todo' | 'in_progress' | 'done'
+
+export interface WorkflowStatus {
+  key: string
+  name: string
+  category: StatusCategory
+}
+
+export interface WorkflowTransition {
+  from: string // status key or '*'
+  to: string
+}
+
+export interface Workflow {
+  project_id: string
+  statuses: WorkflowStatus[]
+  transitions: WorkflowTransition[] // empty = any move allowed
+  initial_status: string
+  is_default: boolean
+}
+
+export interface UpdateWorkflowRequest {
+  statuses: WorkflowStatus[]
+  transitions: WorkflowTransition[]
+  initial_status: string
+}
+
+// Column names and value translations for a Jira CSV import; everything is optional
+export interface JiraMapping {
+  columns?: Partial<Record<
+    | 'key' | 'id' | 'summary' | 'description' | 'issue_type' | 'status' | 'assignee'
+    | 'reporter' | 'story_points' | 'epic_link' | 'parent' | 'sprint' | 'created' | 'updated',
+    string
+  >> & { labels?: string[] }
+  statuses?: Record<string, string>
+  users?: Record<string, string>
+  epic_types?: string[]
+  date_format?: string
+}
+
+// Status, label and user translations for a GitHub Issues import
+export interface GithubMapping {
+  open?: string
+  closed?: string
+  status_labels?: Record<string, string>
+  // null drops the label
+  labels?: Record<string, string | null>
+  users?: Record<string, string>
+}
+
+export interface ImportProblem {
+  severity: 'error' | 'warning'
+  file?: string
+  line?: number
+  key?: string
+  message: string
+}
+
+export interface ImportReport {
+  source: string
+  dry_run: boolean
+  issues: number
+  created: number
+  epics: number
+  comments: number
+  skipped: string[]
+  new_sprints: string[]
+  statuses: Record<string, number>
+  errors: ImportProblem[]
+  warnings: ImportProblem[]
+  // Empty for a dry run
+  tickets: { key: string; id: string; number: string }[]
+}
+
 export interface CreateProjectRequest {
   workspace_id: string
   name: string
@@ -111,25 +262,94 @@ export interface CreateTicketRequest {
 
 export interface UpdateTicketRequest {
   title?: string
+  status?: TicketStatus
   story_points?: number
   parent_id?: string
   epic_id?: string
 }
 
 export interface WebSocketMessage {
-  type: 'ticket_created' | 'ticket_updated' | 'status_changed' | 'comment_added' | 'description_edited'
+  type:
+    | 'ticket_created'
+    | 'ticket_updated'
+    | 'status_changed'
+    | 'comment_added'
+    | 'comment_updated'
+    | 'comment_deleted'
+    | 'description_edited'
+    | 'tickets_bulk_updated'
+    | 'notification'
+    | 'subscribed'
+    | 'unsubscribed'
+    | 'error'
   ticket?: Ticket
   ticket_id?: string
+  // Set on tickets_bulk_updated
+  ticket_ids?: string[]
+  operations?: BulkOperations
+  // Set on notification, sent only to its recipient
+  notification?: Notification
   old_status?: TicketStatus
   new_status?: TicketStatus
   comment?: Comment
   version?: number
+  topics?: string[]
+  message?: string
 }
 
+export type TicketSort = 'created' | 'updated' | 'number' | 'points' | 'status'
+
 export interface TicketFilters {
   project_id?: string
   status?: TicketStatus | TicketStatus[]
   assignee?: string
+  unassigned?: boolean
+  // Tickets must carry every label
+  labels?: string[]
+  epic_id?: string
+  sprint_id?: string
+  parent_id?: string
+  sort?: TicketSort
+  order?: 'asc' | 'desc'
+  limit?: number
+}
+
+export interface TicketPage {
+  tickets: Ticket[]
+  // Pass as `cursor` to fetch the next page; null on the last page
+  next_cursor: string | null
+}
+
+// Bulk operations, applied to every selected ticket in one transaction
+export interface BulkOperations {
+  status?: TicketStatus
+  add_assignees?: string[]
+  remove_assignees?: string[]
+  add_labels?: string[]
+  remove_labels?: string[]
+  epic_id?: string
+  remove_epic?: boolean
+  add_to_sprint?: string
+  remove_from_sprint?: string
+  // Can't be combined with other operations
+  delete?: boolean
+}
+
+export interface BulkTicketRequest {
+  // Ticket UUIDs or numbers such as JIL-42
+  tickets?: string[]
+  // Alternative to `tickets`; must name a project
+  filter?: Omit<TicketFilters, 'status' | 'sort' | 'order' | 'limit'> & {
+    project_id: string
+    status?: TicketStatus[]
+  }
+  operations: BulkOperations
+}
+
+export interface BulkTicketResponse {
+  matched: number
+  changes: number
+  tickets: Ticket[]
 }
 
 // Search types
@@ -251,6 +471,21 @@ export interface InviteDetails {
   is_expired: boolean
 }
 
+export interface RestoreReport {
+  dry_run: boolean
+  // For a dry run, the ID the workspace would have had
+  workspace_id: string
+  name: string
+  slug: string
+  version: number
+  exported_at: string
+  records: Record<string, number>
+  // Emails of the archived members invited to the workspace
+  invited: string[]
+  missing_attachments: number
+  warnings: string[]
+}
+
 export interface WorkspaceResponse {
   id: string
   name: string
@@ -293,6 +528,7 @@ export interface BurndownDataPoint {
   date: string
   ideal: number
   actual: number
+  scope_added: number
 }
 
 export interface BurndownData {
@@ -302,13 +538,19 @@ export interface BurndownData {
 
 export interface VelocityData {
   sprint_name: string
+  committed_points: number
   completed_points: number
+  carried_over_tickets: number
+  carried_over_points: number
 }
 
 export interface SprintHistory {
   sprints: Sprint[]
   velocity_data: VelocityData[]
   average_velocity: number
+  rolling_average_velocity: number
+  velocity_std_dev: number
+  suggested_capacity: number | null
 }
 
 export interface WorkspaceSettings {
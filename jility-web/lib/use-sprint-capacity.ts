@@ -28,9 +28,9 @@ export function useSprintCapacity() {
 
       try {
         const history = await api.getSprintHistory(slug)
-        if (history.average_velocity > 0) {
-          // Use average velocity as default capacity
-          return Math.round(history.average_velocity)
+        if (history.suggested_capacity && history.suggested_capacity > 0) {
+          // Use the server's suggestion (rolling average velocity)
+          return history.suggested_capacity
         }
       } catch (error) {
         console.error('Failed to fetch sprint history:', error)
@@ -0,0 +1,60 @@
+'use client'
+
+import { useState, useEffect } from 'react'
+import { api } from './api'
+import type { Workflow, WorkflowStatus } from './types'
+
+/**
+ * Statuses of the built-in workflow, used until the project's workflow loads
+ */
+export const DEFAULT_STATUSES: WorkflowStatus[] = [
+  { key: 'backlog', name: 'Backlog', category: 'todo' },
+  { key: 'todo', name: 'To Do', category: 'todo' },
+  { key: 'in_progress', name: 'In Progress', category: 'in_progress' },
+  { key: 'review', name: 'Review', category: 'in_progress' },
+  { key: 'done', name: 'Done', category: 'done' },
+  { key: 'blocked', name: 'Blocked', category: 'in_progress' },
+]
+
+/**
+ * `from` plus the statuses a ticket in `from` may move to, in board order
+ */
+export function reachableStatuses(workflow: Workflow | null, from: string): WorkflowStatus[] {
+  const statuses = workflow?.statuses ?? DEFAULT_STATUSES
+  if (!workflow || workflow.transitions.length === 0 || !statuses.some((s) => s.key === from)) {
+    return statuses
+  }
+  return statuses.filter(
+    (s) =>
+      s.key === from ||
+      workflow.transitions.some((t) => t.to === s.key && (t.from === from || t.from === '*'))
+  )
+}
+
+/**
+ * Load the workflow (statuses and allowed transitions) of a project
+ */
+export function useWorkflow(projectId?: string) {
+  const [workflow, setWorkflow] = useState<Workflow | null>(null)
+
+  useEffect(() => {
+    if (!projectId) return
+    let mounted = true
+
+    api
+      .getProjectWorkflow(projectId)
+      .then((data) => {
+        if (mounted) setWorkflow(data)
+      })
+      .catch((error) => console.error('Failed to load workflow:', error))
+
+    return () => {
+      mounted = false
+    }
+  }, [projectId])
+
+  return {
+    workflow,
+    statuses: workflow?.statuses ?? DEFAULT_STATUSES,
+  }
+}
@@ -5,24 +5,48 @@ import type { WebSocketMessage } from './types'
 
 const WS_URL = process.env.NEXT_PUBLIC_WS_URL || 'ws://localhost:3900/ws'
 
-export function useWebSocket(onMessage: (message: WebSocketMessage) => void) {
+type ClientMessage = { type: 'subscribe' | 'unsubscribe'; topics: string[] }
+
+/**
+ * Subscribe to real-time events for the given topics, e.g. `project:<id>`,
+ * `sprint:<id>` or `ticket:<id>`. Only events matching a topic are delivered.
+ */
+export function useWebSocket(
+  onMessage: (message: WebSocketMessage) => void,
+  topics: string[]
+) {
   const ws = useRef<WebSocket | null>(null)
+  const topicsRef = useRef<string[]>([])
   const reconnectTimeoutRef = useRef<NodeJS.Timeout>()
   const reconnectAttempts = useRef(0)
 
+  const send = (message: ClientMessage) => {
+    if (message.topics.length && ws.current?.readyState === WebSocket.OPEN) {
+      ws.current.send(JSON.stringify(message))
+    }
+  }
+
   const connect = useCallback(() => {
     try {
-      ws.current = new WebSocket(WS_URL)
+      const token = localStorage.getItem('jility_token')
+      if (!token) return
+
+      ws.current = new WebSocket(`${WS_URL}?token=${encodeURIComponent(token)}`)
 
       ws.current.onopen = () => {
         console.log('WebSocket connected')
         reconnectAttempts.current = 0
+        send({ type: 'subscribe', topics: topicsRef.current })
       }
 
       ws.current.onmessage = (event) => {
         try {
           const message = JSON.parse(event.data) as WebSocketMessage
-          onMessage(message)
+          if (message.type === 'error') {
+            console.error('WebSocket error:', message.message)
+          } else {
+            onMessage(message)
+          }
         } catch (error) {
           console.error('Failed to parse WebSocket message:', error)
         }
@@ -49,6 +73,18 @@ export function useWebSocket(onMessage: (message: WebSocketMessage) => void) {
     }
   }, [onMessage])
 
+  // Keep the server-side subscriptions in sync with `topics`
+  const topicsKey = topics.filter(Boolean).sort().join(',')
+  useEffect(() => {
+    const next = topicsKey ? topicsKey.split(',') : []
+    const removed = topicsRef.current.filter((t) => !next.includes(t))
+    const added = next.filter((t) => !topicsRef.current.includes(t))
+    topicsRef.current = next
+
+    if (removed.length) send({ type: 'unsubscribe', topics: removed })
+    if (added.length) send({ type: 'subscribe', topics: added })
+  }, [topicsKey])
+
   useEffect(() => {
     connect()
 